@@ -1 +1,2 @@
-RUST_LOG=vived RUST_BACKTRACE=1 cargo run
\ No newline at end of file
+# usage: GUILDED_TOKEN=... ./run.sh [example], runs ping_pong by default
+RUST_LOG=vived RUST_BACKTRACE=1 cargo run -p vived --example "${1:-ping_pong}"
@@ -0,0 +1,71 @@
+//! Measures how long it takes to deliver events to several receivers
+//!
+//! Every receiver of the event channel gets its own clone of each event,
+//! this sends 10k synthetic message events to 4 receivers and times it.
+//!
+//! Run with `cargo run --release --example broadcast_clone`
+
+use std::time::Instant;
+
+use tokio::sync::broadcast;
+use vived::ws::events::GuildedEvent;
+
+/// How many events are sent
+const EVENTS: usize = 10_000;
+/// How many receivers each event is delivered to
+const SUBSCRIBERS: usize = 4;
+
+/// A message event with a realistic amount of content
+fn event(index: usize) -> GuildedEvent {
+    serde_json::from_value(serde_json::json!({
+        "t": "ChatMessageCreated",
+        "d": {
+            "serverId": "wlVr3Ggl",
+            "message": {
+                "id": format!("00000000-0000-0000-0000-{index:012}"),
+                "type": "default",
+                "serverId": "wlVr3Ggl",
+                "channelId": "00000000-0000-0000-0000-000000000001",
+                "content": "some chat message with a bit of text in it, like people send ".repeat(3),
+                "embeds": [{"title": "title", "description": "description ".repeat(10)}],
+                "mentions": {"users": [{"id": "Ann6LewA"}]},
+                "createdAt": "2022-10-26T00:00:00.000Z",
+                "createdBy": "Ann6LewA"
+            }
+        }
+    }))
+    .expect("the synthetic event should be valid")
+}
+
+#[tokio::main]
+async fn main() {
+    let events: Vec<GuildedEvent> = (0..EVENTS).map(event).collect();
+
+    let (sender, _) = broadcast::channel(EVENTS);
+    let receivers: Vec<_> = (0..SUBSCRIBERS).map(|_| sender.subscribe()).collect();
+
+    let start = Instant::now();
+    for event in events {
+        sender.send(event).expect("the receivers are alive");
+    }
+    drop(sender);
+
+    let tasks = receivers.into_iter().map(|mut receiver| {
+        tokio::spawn(async move {
+            let mut received = 0;
+            while receiver.recv().await.is_ok() {
+                received += 1;
+            }
+            received
+        })
+    });
+    let mut received = 0;
+    for task in tasks {
+        received += task.await.expect("the receiver task shouldn't panic");
+    }
+
+    println!(
+        "delivered {received} events to {SUBSCRIBERS} receivers in {:?}",
+        start.elapsed()
+    );
+}
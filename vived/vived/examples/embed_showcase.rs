@@ -0,0 +1,48 @@
+//! Sends an embed using every embed field
+//!
+//! Run with `GUILDED_TOKEN=... GUILDED_CHANNEL=... cargo run --example embed_showcase`
+
+use vived::prelude::*;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    env_logger::init();
+    let token = std::env::var("GUILDED_TOKEN")
+        .map_err(|_| "set the GUILDED_TOKEN environment variable to your bot token")?;
+    let channel = std::env::var("GUILDED_CHANNEL")
+        .map_err(|_| "set the GUILDED_CHANNEL environment variable to the id of a chat channel")?;
+
+    let client = ApiClient::new(&token)?;
+
+    let embed = Embed::new()
+        .title("Embed showcase")
+        .description("Every field an embed can have")
+        .url("https://www.guilded.gg")
+        .color(Color::GUILDED_GOLD)
+        .timestamp(chrono::Utc::now())
+        .footer(EmbedFooter::from("This is a footer").icon_url(
+            "https://img.guildedcdn.com/asset/DefaultUserAvatars/profile_1.png",
+        ))
+        .thumbnail("https://img.guildedcdn.com/asset/DefaultUserAvatars/profile_2.png")
+        .image("https://img.guildedcdn.com/asset/DefaultUserAvatars/profile_3.png")
+        .author(
+            EmbedAuthor::from("This is an author")
+                .url("https://www.guilded.gg")
+                .icon_url("https://img.guildedcdn.com/asset/DefaultUserAvatars/profile_4.png"),
+        )
+        .field(EmbedField::new("Field 1", "This is field 1"))
+        .field(EmbedField::new("Field 2", "This is field 2"))
+        .field(EmbedField::new("Inline 1", "This is inline").inline(true))
+        .field(EmbedField::new("Inline 2", "This is inline too").inline(true));
+
+    match client
+        .make_request(MessageCreate::new_with_embed(channel, embed))
+        .await
+    {
+        Ok(message) => println!("sent message {}", message.id),
+        Err(ApiError::Guilded(error)) => eprintln!("guilded rejected the embed: {error:?}"),
+        Err(error) => return Err(error.into()),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,52 @@
+//! Deletes messages containing blocked words and warns the author
+//!
+//! Run with `GUILDED_TOKEN=... cargo run --example moderation`
+
+use vived::prelude::*;
+
+/// Words that aren't allowed
+const BLOCKED_WORDS: [&str; 2] = ["spam", "scam"];
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    env_logger::init();
+    let token = std::env::var("GUILDED_TOKEN")
+        .map_err(|_| "set the GUILDED_TOKEN environment variable to your bot token")?;
+
+    let client = ApiClient::new(&token)?;
+    let mut events = connect_to_websocket(&token, 10).await?;
+
+    while let Ok(event) = events.recv().await {
+        let GuildedEvent::ChatMessageCreated { message, .. } = event else {
+            continue;
+        };
+
+        let content = message.content_or_empty().to_lowercase();
+        if !BLOCKED_WORDS.iter().any(|word| content.contains(word)) {
+            continue;
+        }
+
+        // the bot might lack permissions in some channels, so keep going on errors
+        let deleted = client
+            .make_request(MessageDelete::new(
+                message.channel_id.clone(),
+                message.id.clone(),
+            ))
+            .await;
+        if let Err(error) = deleted {
+            eprintln!("failed to delete message {}: {error}", message.id);
+            continue;
+        }
+
+        if let Some(user) = message.created_by.creator().as_user() {
+            let warning = MessageCreate::new(message.channel_id.clone())
+                .content(format!("A message by {user} was removed for containing a blocked word"))
+                .silent(true);
+            if let Err(error) = client.make_request(warning).await {
+                eprintln!("failed to send warning: {error}");
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,46 @@
+//! Prints the message history of a channel, newest first
+//!
+//! Run with `GUILDED_TOKEN=... GUILDED_CHANNEL=... cargo run --example paginated_history`
+
+use vived::prelude::*;
+
+/// How many messages to print at most
+const MAX_MESSAGES: usize = 500;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    env_logger::init();
+    let token = std::env::var("GUILDED_TOKEN")
+        .map_err(|_| "set the GUILDED_TOKEN environment variable to your bot token")?;
+    let channel = std::env::var("GUILDED_CHANNEL")
+        .map_err(|_| "set the GUILDED_CHANNEL environment variable to the id of a chat channel")?;
+
+    let client = ApiClient::new(&token)?;
+
+    let mut printed = 0;
+    let mut before = None;
+    while printed < MAX_MESSAGES {
+        let mut request = ChannelGetMessages::new(channel.as_str()).limit(100);
+        if let Some(before) = before {
+            request = request.before(before);
+        }
+
+        let page = client.make_request(request).await?;
+        // messages come newest first, so the last one is where the next page starts
+        let Some(oldest) = page.last() else {
+            break;
+        };
+        before = Some(oldest.created_at);
+
+        for message in &page {
+            println!("[{}] {}", message.created_at, message.text_preview(80));
+        }
+        printed += page.len();
+
+        if page.has_more == Some(false) {
+            break;
+        }
+    }
+
+    Ok(())
+}
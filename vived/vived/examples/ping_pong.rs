@@ -0,0 +1,39 @@
+//! Replies "pong" to every "!ping"
+//!
+//! Run with `GUILDED_TOKEN=... cargo run --example ping_pong`
+
+use vived::prelude::*;
+
+#[tokio::main]
+async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    env_logger::init();
+    let token = std::env::var("GUILDED_TOKEN")
+        .map_err(|_| "set the GUILDED_TOKEN environment variable to your bot token")?;
+
+    let client = ApiClient::new(&token)?;
+    let me = client.make_request(GetUser::me()).await?;
+    println!("logged in as {}", me.name);
+
+    let mut events = connect_to_websocket(&token, 10).await?;
+    while let Ok(event) = events.recv().await {
+        let GuildedEvent::ChatMessageCreated { message, .. } = event else {
+            continue;
+        };
+
+        // don't reply to ourself
+        if message.created_by.creator().as_user() == Some(&me.id) {
+            continue;
+        }
+
+        if message.content_or_empty().trim() == "!ping" {
+            if let Err(error) = client
+                .make_request(MessageCreate::as_reply_to(&message, "pong"))
+                .await
+            {
+                eprintln!("failed to reply: {error}");
+            }
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,35 @@
+//! Echo bot written using only the prelude
+//!
+//! This also guards against name collisions in the prelude, as it stops compiling if two exports clash.
+//! Run with `GUILDED_TOKEN=... cargo run --example prelude`
+
+use vived::prelude::*;
+
+#[tokio::main]
+async fn main() {
+    let token = std::env::var("GUILDED_TOKEN").expect("GUILDED_TOKEN should be set");
+
+    let client = ApiClient::new(&token).unwrap();
+    let mut events = connect_to_websocket(&token, 10).await.unwrap();
+
+    while let Ok(event) = events.recv().await {
+        let GuildedEvent::ChatMessageCreated { message, .. } = event else {
+            continue;
+        };
+
+        if let Some(text) = message
+            .content
+            .as_deref()
+            .and_then(|content| content.strip_prefix("!echo "))
+        {
+            let embed = Embed::new()
+                .title("Echo")
+                .description(text)
+                .color(Color(0, 200, 255));
+            client
+                .make_request(MessageCreate::new_with_embed(message.channel_id.clone(), embed))
+                .await
+                .unwrap();
+        }
+    }
+}
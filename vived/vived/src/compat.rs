@@ -0,0 +1,228 @@
+//! The old top level paths, from before the sub crates got their own modules
+//!
+//! These only exist so old code keeps compiling with a warning, and will be removed in the next release.
+//! `#[deprecated]` does nothing on a `use`, so every old item is a type alias or function here,
+//! which are deprecated along with this module.
+//! Traits and tuple struct constructors can't be aliased, `vived::Endpoint` is still re-exported as is
+//! and ids have to be created with `From` (`ChannelId::from("...")`) instead of `vived::ChannelId("...")`.
+//!
+//! ```compile_fail
+//! #![deny(deprecated)]
+//! use vived::endpoints::GetChannel;
+//! ```
+//!
+//! ```compile_fail
+//! #![deny(deprecated)]
+//! let _ = vived::Embed::new();
+//! ```
+//!
+//! ```
+//! #![allow(deprecated)]
+//! use vived::endpoints::GetChannel;
+//!
+//! let embed: vived::Embed = vived::embed::Embed::new().title("still works");
+//! let _ = GetChannel::new(vived::ChannelId::from("channel"));
+//! assert_eq!(embed.title.as_deref(), Some("still works"));
+//! ```
+
+/// Define an alias for each type in `module`
+macro_rules! aliases {
+    ($module:ident: $($name:ident),* $(,)?) => {
+        $(
+            #[doc = concat!("Deprecated path of [`", stringify!($name), "`](", stringify!($module), "::", stringify!($name), ")")]
+            pub type $name = $module::$name;
+        )*
+    };
+}
+
+/// Deprecated path of [`vived_models::message`]
+#[deprecated(note = "use `vived::models::message` instead")]
+pub mod message {
+    use vived_models::message as source;
+
+    aliases!(source: Message, MessageType, Mentions);
+
+    /// Deprecated name of [`vived_models::Creator`]
+    pub type CreatedBy = vived_models::Creator;
+    /// Deprecated name of [`vived_models::CreatorRawFields`]
+    pub type CreatedByRawFields = vived_models::CreatorRawFields;
+}
+
+/// Deprecated path of [`vived_models::ids`]
+#[deprecated(note = "use `vived::models::ids` instead")]
+pub mod ids {
+    use vived_models::ids as source;
+
+    aliases!(source:
+        ServerId, ChannelId, MessageId, UserId, WebhookId, GroupId, ListItemId,
+        RoleId, CalendarEventId, DocId, ForumTopicId, EmoteId, CategoryId,
+    );
+}
+
+/// Deprecated path of [`vived_models::embed`]
+#[deprecated(note = "use `vived::models::embed` instead")]
+pub mod embed {
+    use vived_models::embed as source;
+
+    aliases!(source: Embed, EmbedFooter, EmbedImage, EmbedAuthor, EmbedField);
+}
+
+/// Deprecated path of [`vived_models::color`]
+#[deprecated(note = "use `vived::models::color` instead")]
+pub mod color {
+    use vived_models::color as source;
+
+    aliases!(source: Color);
+}
+
+aliases!(vived_models:
+    Message, Color,
+    ServerId, ChannelId, MessageId, UserId, WebhookId, GroupId, ListItemId,
+    RoleId, CalendarEventId, DocId, ForumTopicId, EmoteId, CategoryId,
+    Embed, EmbedFooter, EmbedImage, EmbedAuthor, EmbedField,
+    Server, ServerType, Channel, ChannelType, ThreadArchivedInfo,
+    ServerGroup, ServerCategory, CategoryNode, GroupNode, ServerTree,
+    User, UserType, ServerMember,
+);
+
+/// Deprecated path of [`vived_api::endpoints`]
+#[cfg(feature = "api")]
+#[deprecated(note = "use `vived::api::endpoints` or `vived::prelude` instead")]
+pub mod endpoints {
+    use vived_api::endpoints as source;
+
+    aliases!(source:
+        MessageCreate, MessageCreateArguments, MessageEdit, MessageDelete, BulkDeleteMessages,
+        ChannelGetMessage, ChannelGetMessages, GetChannel, GetServerChannels,
+        GetServer, GetServerGroups, GetServerCategories,
+        GetServerMember, MemberNicknameSet, MemberNicknameDelete,
+    );
+}
+
+/// Deprecated path of [`vived_api::helpers`]
+#[cfg(feature = "api")]
+#[deprecated(note = "use `vived::api::helpers` instead")]
+pub mod helpers {
+    /// Deprecated path of [`vived_api::helpers::get_server_tree`]
+    ///
+    /// # Errors
+    /// Same as [`vived_api::helpers::get_server_tree`]
+    pub async fn get_server_tree(
+        client: &vived_api::ApiClient,
+        server: impl Into<vived_models::ServerId>,
+    ) -> Result<vived_models::ServerTree, vived_api::ApiError> {
+        vived_api::helpers::get_server_tree(client, server).await
+    }
+}
+
+#[cfg(feature = "api")]
+aliases!(vived_api:
+    ApiClient, ApiClientConfig, ApiError, GuildedError, RatelimitStats, RatelimitWeight,
+);
+
+#[cfg(feature = "cache")]
+aliases!(vived_api: CachedClient);
+
+#[cfg(feature = "api")]
+pub use vived_api::Endpoint;
+
+/// Deprecated path of [`vived_api::get_server_tree`]
+///
+/// # Errors
+/// Same as [`vived_api::get_server_tree`]
+#[cfg(feature = "api")]
+#[deprecated(note = "use `vived::api::get_server_tree` instead")]
+pub async fn get_server_tree(
+    client: &vived_api::ApiClient,
+    server: impl Into<vived_models::ServerId>,
+) -> Result<vived_models::ServerTree, vived_api::ApiError> {
+    vived_api::get_server_tree(client, server).await
+}
+
+/// Deprecated path of [`vived_websocket::events`]
+#[cfg(feature = "websocket")]
+#[deprecated(note = "use `vived::ws::events` or `vived::prelude` instead")]
+pub mod events {
+    use vived_websocket::events as source;
+
+    aliases!(source: GuildedEvent, EventKind, MessageDeleteData);
+}
+
+/// Deprecated path of [`vived_websocket::client`]
+#[cfg(feature = "websocket")]
+#[deprecated(note = "use `vived::ws::client` instead")]
+pub mod client {
+    /// Deprecated path of [`vived_websocket::client::connect_to_websocket`]
+    ///
+    /// # Errors
+    /// Same as [`vived_websocket::client::connect_to_websocket`]
+    pub async fn connect_to_websocket(
+        token: &str,
+        event_capacity: usize,
+    ) -> Result<
+        tokio::sync::broadcast::Receiver<vived_websocket::events::GuildedEvent>,
+        vived_websocket::tungstenite::Error,
+    > {
+        vived_websocket::client::connect_to_websocket(token, event_capacity).await
+    }
+}
+
+/// Deprecated path of [`vived_websocket::wait`]
+#[cfg(feature = "websocket")]
+#[deprecated(note = "use `vived::ws::wait` or `vived::prelude` instead")]
+pub mod wait {
+    use std::future::Future;
+    use std::time::Duration;
+
+    use tokio::sync::broadcast::Receiver;
+    use vived_websocket::events::GuildedEvent;
+    use vived_websocket::wait as source;
+
+    aliases!(source: WaitError);
+
+    /// Deprecated path of [`vived_websocket::wait::wait_for_event`]
+    ///
+    /// # Errors
+    /// Same as [`vived_websocket::wait::wait_for_event`]
+    pub fn wait_for_event<T, F>(
+        events: &Receiver<GuildedEvent>,
+        filter: F,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<T, vived_websocket::wait::WaitError>>
+    where
+        F: FnMut(&GuildedEvent) -> Option<T>,
+    {
+        vived_websocket::wait::wait_for_event(events, filter, timeout)
+    }
+
+    /// Deprecated path of [`vived_websocket::wait::wait_for_message`]
+    ///
+    /// # Errors
+    /// Same as [`vived_websocket::wait::wait_for_message`]
+    pub fn wait_for_message<F>(
+        events: &Receiver<GuildedEvent>,
+        predicate: F,
+        timeout: Duration,
+    ) -> impl Future<Output = Result<vived_models::Message, vived_websocket::wait::WaitError>>
+    where
+        F: FnMut(&vived_models::Message) -> bool,
+    {
+        vived_websocket::wait::wait_for_message(events, predicate, timeout)
+    }
+}
+
+/// Deprecated path of [`vived_websocket::connect_to_websocket`]
+///
+/// # Errors
+/// Same as [`vived_websocket::connect_to_websocket`]
+#[cfg(feature = "websocket")]
+#[deprecated(note = "use `vived::ws::connect_to_websocket` or `vived::prelude` instead")]
+pub async fn connect_to_websocket(
+    token: &str,
+    event_capacity: usize,
+) -> Result<
+    tokio::sync::broadcast::Receiver<vived_websocket::events::GuildedEvent>,
+    vived_websocket::tungstenite::Error,
+> {
+    vived_websocket::connect_to_websocket(token, event_capacity).await
+}
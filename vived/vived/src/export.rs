@@ -0,0 +1,338 @@
+//! Export the history of a channel, for example to archive it before deleting it
+//!
+//! Messages are written newest first, as [`ApiClient::message_history`] returns them.
+//! [`ExportFormat::JsonLines`] writes every message in full, one json object a line.
+//! [`ExportFormat::Csv`] flattens each message to the columns in [`CSV_HEADER`].
+//!
+//! ```
+//! use futures_util::stream;
+//! use vived::export::{write_messages, ExportFormat};
+//! use vived::models::Message;
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let message: Message = serde_json::from_str(r#"{
+//!     "id": "m1", "type": "default", "serverId": "s", "channelId": "c",
+//!     "content": "first line\nsecond, with \"quotes\"",
+//!     "embeds": [{"title": "Title"}, {}],
+//!     "replyMessageIds": ["r1", "r2"],
+//!     "createdAt": "2022-10-01T12:00:00Z", "createdBy": "Ann6LewA", "createdByWebhookId": "w"
+//! }"#).unwrap();
+//!
+//! let mut exported = Vec::new();
+//! let mut progress = Vec::new();
+//! let count = write_messages(&mut exported, stream::iter([Ok(message)]), ExportFormat::Csv, |done| progress.push(done))
+//!     .await
+//!     .unwrap();
+//!
+//! assert_eq!(count, 1);
+//! assert_eq!(progress, [1]);
+//! assert_eq!(
+//!     String::from_utf8(exported).unwrap(),
+//!     "id,created_at,author_id,is_webhook,content,embed_count,first_embed_title,reply_ids\n\
+//!      m1,2022-10-01T12:00:00+00:00,w,true,\"first line\nsecond, with \"\"quotes\"\"\",2,Title,r1;r2\n"
+//! );
+//! # }
+//! ```
+
+use futures_util::{Stream, StreamExt};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+use vived_api::search::SearchOptions;
+use vived_api::{ApiClient, ApiError};
+use vived_models::{ChannelId, Creator, Message, MessageId};
+
+/// Columns of a csv export
+pub const CSV_HEADER: &str =
+    "id,created_at,author_id,is_webhook,content,embed_count,first_embed_title,reply_ids";
+
+/// How exported messages are written
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportFormat {
+    /// One json object a line, containing the whole message
+    JsonLines,
+    /// One row a message, see [`CSV_HEADER`] for the columns
+    Csv,
+}
+
+/// What part of a channel to export
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ExportOptions {
+    /// How the messages are written
+    pub format: ExportFormat,
+    /// Only export messages sent at or after this, for example the newest message of an earlier export
+    pub after: Option<chrono::DateTime<chrono::Utc>>,
+    /// Only export messages sent before this, see [`ExportOptions::resume_after`] to continue an interrupted export
+    pub before: Option<chrono::DateTime<chrono::Utc>>,
+    /// Messages sent exactly at `before` that were already written, set with [`ExportOptions::resume_after`]
+    pub seen_at_before: Option<Vec<MessageId>>,
+    /// Export at most this many messages
+    pub max_messages: usize,
+    /// Include private messages
+    pub include_private: bool,
+    /// Start a csv export with [`CSV_HEADER`], turned off by [`ExportOptions::resume_after`]
+    pub csv_header: bool,
+}
+
+impl ExportOptions {
+    /// Export every message in the given format
+    #[must_use]
+    pub fn new(format: ExportFormat) -> Self {
+        Self {
+            format,
+            after: None,
+            before: None,
+            seen_at_before: None,
+            max_messages: usize::MAX,
+            include_private: false,
+            csv_header: true,
+        }
+    }
+
+    /// Only export messages sent after `after`
+    #[must_use]
+    pub fn after(mut self, after: chrono::DateTime<chrono::Utc>) -> Self {
+        self.after = Some(after);
+        self
+    }
+
+    /// Only export messages sent before `before`
+    #[must_use]
+    pub fn before(mut self, before: chrono::DateTime<chrono::Utc>) -> Self {
+        self.before = Some(before);
+        self.seen_at_before = None;
+        self
+    }
+
+    /// Continue an interrupted export
+    ///
+    /// `oldest` is the time of the last message it wrote, and `written` are the ids of the messages it wrote sent at that time.
+    /// Other messages sent at that same time are still exported, which [`ExportOptions::before`] would leave out.
+    /// The rows are meant to be appended to the interrupted export, so no csv header is written,
+    /// call [`ExportOptions::csv_header`] afterwards to write one anyway.
+    #[must_use]
+    pub fn resume_after<I>(mut self, oldest: chrono::DateTime<chrono::Utc>, written: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<MessageId>,
+    {
+        self.before = Some(oldest);
+        self.seen_at_before = Some(written.into_iter().map(Into::into).collect());
+        self.csv_header = false;
+        self
+    }
+
+    /// Export at most `max_messages` messages
+    #[must_use]
+    pub fn max_messages(mut self, max_messages: usize) -> Self {
+        self.max_messages = max_messages;
+        self
+    }
+
+    /// Set if private messages are exported
+    #[must_use]
+    pub fn include_private(mut self, include_private: bool) -> Self {
+        self.include_private = include_private;
+        self
+    }
+
+    /// Set if a csv export starts with [`CSV_HEADER`], for example to append to an existing file
+    #[must_use]
+    pub fn csv_header(mut self, csv_header: bool) -> Self {
+        self.csv_header = csv_header;
+        self
+    }
+}
+
+/// An error that stopped an export
+#[derive(Debug)]
+pub enum ExportError {
+    /// Getting the messages failed
+    Api(ApiError),
+    /// Writing the messages failed
+    Io(std::io::Error),
+    /// A message could not be turned into json
+    Json(serde_json::Error),
+}
+
+impl From<ApiError> for ExportError {
+    fn from(v: ApiError) -> Self {
+        Self::Api(v)
+    }
+}
+
+impl From<std::io::Error> for ExportError {
+    fn from(v: std::io::Error) -> Self {
+        Self::Io(v)
+    }
+}
+
+impl From<serde_json::Error> for ExportError {
+    fn from(v: serde_json::Error) -> Self {
+        Self::Json(v)
+    }
+}
+
+impl std::fmt::Display for ExportError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Api(ref e) => write!(f, "Export failed to get messages: {e}"),
+            Self::Io(ref e) => write!(f, "Export failed to write messages: {e}"),
+            Self::Json(ref e) => write!(f, "Export failed to serialize a message: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            Self::Api(ref e) => Some(e),
+            Self::Io(ref e) => Some(e),
+            Self::Json(ref e) => Some(e),
+        }
+    }
+}
+
+/// Export the history of `channel` to `writer`, returning how many messages were written
+///
+/// `progress` is called with the number of messages written so far after every message,
+/// throttle it yourself if it does something expensive like editing a message.
+///
+/// # Errors
+/// If getting or writing a message fails, the messages before it are already written
+pub async fn export_channel<W>(
+    client: &ApiClient,
+    channel: impl Into<ChannelId>,
+    mut writer: W,
+    options: ExportOptions,
+    progress: impl FnMut(usize),
+) -> Result<usize, ExportError>
+where
+    W: AsyncWrite + Unpin,
+{
+    let mut search = SearchOptions::default()
+        .max_messages(options.max_messages)
+        .include_private(options.include_private);
+    if let Some(after) = options.after {
+        search = search.oldest(after);
+    }
+    match (options.before, options.seen_at_before) {
+        (Some(before), Some(written)) => search = search.resume_after(before, written),
+        (Some(before), None) => search = search.before(before),
+        (None, _) => {}
+    }
+
+    if options.format == ExportFormat::Csv && options.csv_header {
+        write_csv_header(&mut writer).await?;
+    }
+    let messages = client.message_history(channel, search);
+    write_rows(writer, messages, options.format, progress).await
+}
+
+/// Write `messages` to `writer` in `format`, returning how many messages were written
+///
+/// This is what [`export_channel`] uses, it can be given any stream of messages.
+/// A csv export always starts with [`CSV_HEADER`].
+///
+/// # Errors
+/// If the stream yields an error, or writing fails
+pub async fn write_messages<W, S>(
+    mut writer: W,
+    messages: S,
+    format: ExportFormat,
+    progress: impl FnMut(usize),
+) -> Result<usize, ExportError>
+where
+    W: AsyncWrite + Unpin,
+    S: Stream<Item = Result<Message, ApiError>>,
+{
+    if format == ExportFormat::Csv {
+        write_csv_header(&mut writer).await?;
+    }
+    write_rows(writer, messages, format, progress).await
+}
+
+/// Write [`CSV_HEADER`] as the first line
+async fn write_csv_header<W>(writer: &mut W) -> Result<(), ExportError>
+where
+    W: AsyncWrite + Unpin,
+{
+    writer
+        .write_all(format!("{CSV_HEADER}\n").as_bytes())
+        .await?;
+    Ok(())
+}
+
+/// Write every message in `messages` as a line in `format`, without a header
+async fn write_rows<W, S>(
+    mut writer: W,
+    messages: S,
+    format: ExportFormat,
+    mut progress: impl FnMut(usize),
+) -> Result<usize, ExportError>
+where
+    W: AsyncWrite + Unpin,
+    S: Stream<Item = Result<Message, ApiError>>,
+{
+    let mut messages = Box::pin(messages);
+    let mut written = 0;
+    while let Some(message) = messages.next().await {
+        let message = message?;
+        let line = match format {
+            ExportFormat::JsonLines => serde_json::to_string(&message)?,
+            ExportFormat::Csv => csv_row(&message),
+        };
+        writer.write_all(format!("{line}\n").as_bytes()).await?;
+
+        written += 1;
+        progress(written);
+    }
+
+    writer.flush().await?;
+    Ok(written)
+}
+
+/// Flatten a message to the columns in [`CSV_HEADER`]
+fn csv_row(message: &Message) -> String {
+    let (author, is_webhook) = match message.created_by.creator() {
+        Creator::User(user) => (user.0, false),
+        Creator::Webhook(webhook) => (webhook.0, true),
+    };
+    let first_embed_title = message
+        .embeds
+        .first()
+        .and_then(|embed| embed.title.clone())
+        .unwrap_or_default();
+    let reply_ids = message
+        .reply_message_ids
+        .iter()
+        .flatten()
+        .map(|id| id.0.as_str())
+        .collect::<Vec<_>>()
+        .join(";");
+
+    [
+        message.id.0.clone(),
+        message.created_at.to_rfc3339(),
+        author,
+        is_webhook.to_string(),
+        message.content_or_empty().to_owned(),
+        message.embeds.len().to_string(),
+        first_embed_title,
+        reply_ids,
+    ]
+    .iter()
+    .map(String::as_str)
+    .map(csv_field)
+    .collect::<Vec<_>>()
+    .join(",")
+}
+
+/// Quote a csv field if it contains a separator, quote or newline
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
@@ -0,0 +1,478 @@
+//! Forward websocket events to an http endpoint, for example to bridge them into a dashboard
+//!
+//! [`forward_events`] posts the events it receives as json batches to a configured url.
+//! A batch is sent once it has [`ForwarderConfig::batch_size`] events,
+//! or [`ForwarderConfig::batch_interval`] after the last batch, whichever comes first.
+//!
+//! Every request has this body, where each event is serialized the same way guilded sends it:
+//! ```json
+//! {"version": 1, "events": [{"t": "ChatMessageCreated", "d": {"serverId": "...", "message": {...}}}]}
+//! ```
+//! If a secret is configured the body is signed with HMAC-SHA256,
+//! and the hex digest is sent in the [`SIGNATURE_HEADER`] header as `sha256=<digest>`.
+//!
+//! A batch that fails with a network error or a 5xx status is retried with exponential backoff until it goes through.
+//! A batch the sink rejects with a 4xx status would be rejected again, so it is dropped and counted in [`ForwarderStats::rejected`].
+//! Meanwhile new events wait in a buffer of at most [`ForwarderConfig::buffer_capacity`] events,
+//! once it is full the oldest events are dropped and counted in [`ForwarderStats::dropped`],
+//! so a sink that is down for a long time can't run the bot out of memory.
+//!
+//! ```no_run
+//! use vived::forwarder::{forward_events, ForwarderConfig};
+//! use vived::ws::events::GuildedEvent;
+//!
+//! # async fn run(ws: vived::ws::WebSocketClient) {
+//! let config = ForwarderConfig::new("http://localhost:8080/guilded")
+//!     .secret("shared secret")
+//!     .filter(|event| matches!(event, GuildedEvent::ChatMessageCreated { .. }));
+//!
+//! let forwarder = forward_events(ws.subscribe(), config);
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
+use hmac::{Hmac, Mac};
+use serde::Serialize;
+use sha2::Sha256;
+use tokio::sync::{broadcast, Notify};
+use tokio::time::Instant;
+use vived_api::reqwest;
+use vived_websocket::events::GuildedEvent;
+
+/// Version of the request body, bumped when its shape changes
+pub const FORWARD_SCHEMA_VERSION: u32 = 1;
+
+/// Header the signature of the body is sent in
+pub const SIGNATURE_HEADER: &str = "X-Vived-Signature";
+
+/// Delay before the first retry of a failed batch
+const INITIAL_RETRY_DELAY: Duration = Duration::from_millis(500);
+/// Longest delay between retries of a failed batch
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(60);
+
+/// Decides which events are forwarded
+type EventFilter = Arc<dyn Fn(&GuildedEvent) -> bool + Send + Sync>;
+
+/// Settings for [`forward_events`]
+#[derive(Clone)]
+#[non_exhaustive]
+pub struct ForwarderConfig {
+    /// Url the batches are posted to
+    pub url: String,
+    /// Secret the body is signed with, no signature is sent if this is `None`
+    pub secret: Option<Vec<u8>>,
+    /// Most events sent in one request
+    pub batch_size: usize,
+    /// Longest time between batches while there are events waiting
+    pub batch_interval: Duration,
+    /// Most events kept while a batch is being sent, older events are dropped beyond this
+    pub buffer_capacity: usize,
+    /// Only events this returns `true` for are forwarded, every event if `None`
+    filter: Option<EventFilter>,
+}
+
+impl std::fmt::Debug for ForwarderConfig {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ForwarderConfig")
+            .field("url", &self.url)
+            .field("signed", &self.secret.is_some())
+            .field("batch_size", &self.batch_size)
+            .field("batch_interval", &self.batch_interval)
+            .field("buffer_capacity", &self.buffer_capacity)
+            .field("filtered", &self.filter.is_some())
+            .finish()
+    }
+}
+
+impl ForwarderConfig {
+    /// Forward every event to `url`, in batches of up to 50 events at least every second
+    #[must_use]
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            secret: None,
+            batch_size: 50,
+            batch_interval: Duration::from_secs(1),
+            buffer_capacity: 10_000,
+            filter: None,
+        }
+    }
+
+    /// Sign every body with `secret`
+    #[must_use]
+    pub fn secret(mut self, secret: impl Into<Vec<u8>>) -> Self {
+        self.secret = Some(secret.into());
+        self
+    }
+
+    /// Set the most events sent in one request, at least 1
+    #[must_use]
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Set the longest time between batches
+    #[must_use]
+    pub fn batch_interval(mut self, batch_interval: Duration) -> Self {
+        self.batch_interval = batch_interval;
+        self
+    }
+
+    /// Set the most events kept while the sink is slow or down, at least 1
+    #[must_use]
+    pub fn buffer_capacity(mut self, buffer_capacity: usize) -> Self {
+        self.buffer_capacity = buffer_capacity.max(1);
+        self
+    }
+
+    /// Only forward events `filter` returns `true` for
+    #[must_use]
+    pub fn filter(mut self, filter: impl Fn(&GuildedEvent) -> bool + Send + Sync + 'static) -> Self {
+        self.filter = Some(Arc::new(filter));
+        self
+    }
+}
+
+/// Counters of a running forwarder, see [`Forwarder::stats`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ForwarderStats {
+    /// Events the sink accepted
+    pub forwarded: u64,
+    /// Events dropped because the buffer was full, or because the websocket receiver lagged behind
+    pub dropped: u64,
+    /// Requests that failed and were retried
+    pub failed_requests: u64,
+    /// Events in batches the sink rejected with a 4xx status, these are not retried
+    pub rejected: u64,
+}
+
+/// Events waiting to be forwarded, dropping the oldest once it is full
+///
+/// ```
+/// use vived::forwarder::EventBuffer;
+///
+/// let mut buffer = EventBuffer::new(2);
+/// buffer.push(1);
+/// buffer.push(2);
+/// buffer.push(3); // drops 1
+///
+/// assert_eq!(buffer.dropped(), 1);
+/// assert_eq!(buffer.take(5), [2, 3]);
+/// assert!(buffer.is_empty());
+/// ```
+#[derive(Debug)]
+pub struct EventBuffer<T> {
+    /// Most events kept
+    capacity: usize,
+    /// Waiting events, oldest first
+    events: VecDeque<T>,
+    /// Events dropped so far
+    dropped: u64,
+}
+
+impl<T> EventBuffer<T> {
+    /// Create an empty buffer holding at most `capacity` events, at least 1
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            events: VecDeque::new(),
+            dropped: 0,
+        }
+    }
+
+    /// Add an event, dropping the oldest one if the buffer is full
+    pub fn push(&mut self, event: T) {
+        if self.events.len() >= self.capacity {
+            self.events.pop_front();
+            self.dropped += 1;
+        }
+        self.events.push_back(event);
+    }
+
+    /// Remove and return up to `max` of the oldest events
+    pub fn take(&mut self, max: usize) -> Vec<T> {
+        let count = max.min(self.events.len());
+        self.events.drain(..count).collect()
+    }
+
+    /// How many events are waiting
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.events.len()
+    }
+
+    /// Are there no events waiting
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.events.is_empty()
+    }
+
+    /// How many events were dropped because the buffer was full
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.dropped
+    }
+}
+
+/// Body of a forwarded batch
+#[derive(Debug, Serialize)]
+pub struct ForwardBatch<'a> {
+    /// See [`FORWARD_SCHEMA_VERSION`]
+    pub version: u32,
+    /// The events, oldest first
+    pub events: &'a [GuildedEvent],
+}
+
+/// Sign `body` with `secret`, returning the value of the [`SIGNATURE_HEADER`] header
+///
+/// Receivers should compute the same value over the raw body and compare them in constant time.
+/// ```
+/// use vived::forwarder::sign;
+///
+/// assert_eq!(
+///     sign(b"Jefe", b"what do ya want for nothing?"),
+///     "sha256=5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843"
+/// );
+/// ```
+#[must_use]
+pub fn sign(secret: &[u8], body: &[u8]) -> String {
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(secret)
+        .unwrap_or_else(|_| unreachable!("hmac accepts keys of any length"));
+    mac.update(body);
+
+    let digest = mac.finalize().into_bytes();
+    let mut signature = String::with_capacity(7 + digest.len() * 2);
+    signature.push_str("sha256=");
+    for byte in digest {
+        signature.push_str(&format!("{byte:02x}"));
+    }
+    signature
+}
+
+/// State shared between the forwarder tasks and its handle
+#[derive(Debug)]
+struct Shared {
+    /// Events waiting to be sent
+    buffer: Mutex<EventBuffer<GuildedEvent>>,
+    /// Woken when a full batch is waiting, or the event stream ended
+    wake: Notify,
+    /// Set once the event stream ended
+    closed: AtomicBool,
+    /// Events the sink accepted
+    forwarded: AtomicU64,
+    /// Events lost because the receiver lagged behind
+    lagged: AtomicU64,
+    /// Requests that failed
+    failed_requests: AtomicU64,
+    /// Events the sink rejected
+    rejected: AtomicU64,
+}
+
+/// Handle to a running forwarder, the forwarder keeps running if this is dropped
+#[derive(Debug, Clone)]
+pub struct Forwarder {
+    /// State shared with the tasks
+    shared: Arc<Shared>,
+}
+
+impl Forwarder {
+    /// Current counters
+    #[must_use]
+    pub fn stats(&self) -> ForwarderStats {
+        let buffer_dropped = self
+            .shared
+            .buffer
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .dropped();
+
+        ForwarderStats {
+            forwarded: self.shared.forwarded.load(Ordering::Relaxed),
+            dropped: buffer_dropped + self.shared.lagged.load(Ordering::Relaxed),
+            failed_requests: self.shared.failed_requests.load(Ordering::Relaxed),
+            rejected: self.shared.rejected.load(Ordering::Relaxed),
+        }
+    }
+
+    /// How many events are waiting to be sent
+    #[must_use]
+    pub fn buffered(&self) -> usize {
+        self.shared
+            .buffer
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .len()
+    }
+}
+
+/// Forward the events from `events` as configured in `config`, see the [module docs](self)
+///
+/// Stops once the sender of `events` is dropped and everything buffered was sent.
+///
+/// # Panics
+/// If called outside of a tokio runtime
+pub fn forward_events(
+    events: broadcast::Receiver<GuildedEvent>,
+    config: ForwarderConfig,
+) -> Forwarder {
+    let shared = Arc::new(Shared {
+        buffer: Mutex::new(EventBuffer::new(config.buffer_capacity)),
+        wake: Notify::new(),
+        closed: AtomicBool::new(false),
+        forwarded: AtomicU64::new(0),
+        lagged: AtomicU64::new(0),
+        failed_requests: AtomicU64::new(0),
+        rejected: AtomicU64::new(0),
+    });
+
+    tokio::spawn(collect(
+        events,
+        Arc::clone(&shared),
+        config.filter.clone(),
+        config.batch_size,
+    ));
+    tokio::spawn(send(Arc::clone(&shared), config));
+
+    Forwarder { shared }
+}
+
+/// Move received events into the buffer
+async fn collect(
+    mut events: broadcast::Receiver<GuildedEvent>,
+    shared: Arc<Shared>,
+    filter: Option<EventFilter>,
+    batch_size: usize,
+) {
+    loop {
+        match events.recv().await {
+            Ok(event) => {
+                if filter.as_ref().is_none_or(|filter| filter(&event)) {
+                    let mut buffer = shared.buffer.lock().unwrap_or_else(PoisonError::into_inner);
+                    buffer.push(event);
+                    if buffer.len() >= batch_size {
+                        shared.wake.notify_one();
+                    }
+                }
+            }
+            Err(broadcast::error::RecvError::Lagged(missed)) => {
+                log::warn!("event forwarder lagged behind, {missed} events were lost");
+                shared.lagged.fetch_add(missed, Ordering::Relaxed);
+            }
+            Err(broadcast::error::RecvError::Closed) => {
+                shared.closed.store(true, Ordering::Relaxed);
+                shared.wake.notify_one();
+                return;
+            }
+        }
+    }
+}
+
+/// Send batches from the buffer until the event stream ended and the buffer is empty
+///
+/// A batch goes out once it is full, `batch_interval` after the last one, or when the stream ended.
+async fn send(shared: Arc<Shared>, config: ForwarderConfig) {
+    let client = reqwest::Client::new();
+    let mut last_batch = Instant::now();
+
+    loop {
+        let closed = shared.closed.load(Ordering::Relaxed);
+        let (due, batch) = {
+            let mut buffer = shared.buffer.lock().unwrap_or_else(PoisonError::into_inner);
+            let due = closed
+                || buffer.len() >= config.batch_size
+                || last_batch.elapsed() >= config.batch_interval;
+            let batch = if due {
+                buffer.take(config.batch_size)
+            } else {
+                Vec::new()
+            };
+            (due, batch)
+        };
+
+        if batch.is_empty() {
+            if closed {
+                return;
+            }
+            // nothing was waiting, so the next batch is an interval from now
+            if due {
+                last_batch = Instant::now();
+            }
+            let wait = config.batch_interval.saturating_sub(last_batch.elapsed());
+            tokio::select! {
+                () = shared.wake.notified() => {}
+                () = tokio::time::sleep(wait) => {}
+            }
+            continue;
+        }
+
+        send_batch(&client, &config, &shared, &batch).await;
+        last_batch = Instant::now();
+    }
+}
+
+/// Post a batch, retrying until the sink accepts or rejects it
+async fn send_batch(
+    client: &reqwest::Client,
+    config: &ForwarderConfig,
+    shared: &Shared,
+    batch: &[GuildedEvent],
+) {
+    let body = match serde_json::to_vec(&ForwardBatch {
+        version: FORWARD_SCHEMA_VERSION,
+        events: batch,
+    }) {
+        Ok(body) => body,
+        Err(error) => {
+            log::error!("failed to serialize {} events, dropping them: {error}", batch.len());
+            return;
+        }
+    };
+    let signature = config.secret.as_ref().map(|secret| sign(secret, &body));
+
+    let mut delay = INITIAL_RETRY_DELAY;
+    loop {
+        let mut request = client
+            .post(&config.url)
+            .header(reqwest::header::CONTENT_TYPE, "application/json")
+            .body(body.clone());
+        if let Some(ref signature) = signature {
+            request = request.header(SIGNATURE_HEADER, signature);
+        }
+
+        let count = u64::try_from(batch.len()).unwrap_or(u64::MAX);
+        let error = match request.send().await {
+            Ok(response) if response.status().is_success() => {
+                shared.forwarded.fetch_add(count, Ordering::Relaxed);
+                return;
+            }
+            Ok(response) if response.status().is_client_error() => {
+                // sending the same body again would get the same answer
+                shared.rejected.fetch_add(count, Ordering::Relaxed);
+                log::error!(
+                    "{} rejected {} events with {}, dropping them",
+                    config.url,
+                    batch.len(),
+                    response.status()
+                );
+                return;
+            }
+            Ok(response) => response.status().to_string(),
+            Err(error) => error.to_string(),
+        };
+
+        shared.failed_requests.fetch_add(1, Ordering::Relaxed);
+        log::warn!(
+            "failed to forward {} events to {}, retrying in {delay:?}: {error}",
+            batch.len(),
+            config.url
+        );
+        tokio::time::sleep(delay).await;
+        delay = (delay * 2).min(MAX_RETRY_DELAY);
+    }
+}
@@ -0,0 +1,192 @@
+//! Xp leaderboards
+//!
+//! Guilded has no leaderboard endpoint, so [`Leaderboard`] mirrors the xp totals returned when awarding xp
+//! in an [`XpStore`] and ranks members from that.
+//!
+//! Members with the same xp share a rank, and the next rank is skipped:
+//! ```
+//! use vived::leaderboard::{rank, MemoryXpStore, XpStore};
+//! use vived::models::UserId;
+//!
+//! let store = MemoryXpStore::default();
+//! store.set_total(&UserId::from("a"), 10);
+//! store.set_total(&UserId::from("b"), 10);
+//! store.set_total(&UserId::from("c"), 5);
+//!
+//! let ranks: Vec<usize> = rank(store.totals()).iter().map(|entry| entry.rank).collect();
+//! assert_eq!(ranks, [1, 1, 3]);
+//!
+//! assert!(rank(Vec::new()).is_empty());
+//! ```
+
+use std::collections::HashMap;
+use std::sync::{PoisonError, RwLock};
+
+use tokio::sync::Mutex;
+use vived_api::{
+    endpoints::{rank_by_xp, MemberXpAward},
+    ApiClient, ApiError,
+};
+use vived_models::{Color, Embed, ServerId, UserId};
+
+/// Medals shown in front of the top three ranks
+const MEDALS: [&str; 3] = ["🥇", "🥈", "🥉"];
+
+/// Storage for xp totals, implement this to persist totals (for example in sqlite)
+pub trait XpStore: Send + Sync {
+    /// Set the xp total of a user
+    fn set_total(&self, user: &UserId, total: i64);
+
+    /// Get the xp total of a user
+    fn total(&self, user: &UserId) -> Option<i64>;
+
+    /// Get the xp totals of all users
+    fn totals(&self) -> Vec<(UserId, i64)>;
+}
+
+/// In memory [`XpStore`], totals are lost when the bot restarts
+#[derive(Debug, Default)]
+pub struct MemoryXpStore {
+    /// Xp totals
+    totals: RwLock<HashMap<UserId, i64>>,
+}
+
+impl XpStore for MemoryXpStore {
+    fn set_total(&self, user: &UserId, total: i64) {
+        self.totals
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(user.clone(), total);
+    }
+
+    fn total(&self, user: &UserId) -> Option<i64> {
+        self.totals
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(user)
+            .copied()
+    }
+
+    fn totals(&self) -> Vec<(UserId, i64)> {
+        self.totals
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .map(|(user, &total)| (user.clone(), total))
+            .collect()
+    }
+}
+
+/// A ranked user on the leaderboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LeaderboardEntry {
+    /// Rank of the user, starting at 1
+    pub rank: usize,
+    /// The user
+    pub user: UserId,
+    /// Xp total of the user
+    pub xp: i64,
+}
+
+/// Rank xp totals, highest first
+///
+/// Users with the same xp share a rank and are ordered by id.
+#[must_use]
+pub fn rank(totals: Vec<(UserId, i64)>) -> Vec<LeaderboardEntry> {
+    rank_by_xp(totals, |user| &user.0)
+        .into_iter()
+        .map(|(rank, user, xp)| LeaderboardEntry { rank, user, xp })
+        .collect()
+}
+
+/// Xp leaderboard for a server
+#[derive(Debug)]
+pub struct Leaderboard<S = MemoryXpStore> {
+    /// Client used to award xp
+    client: ApiClient,
+    /// The server the leaderboard is for
+    server: ServerId,
+    /// Mirrored xp totals
+    store: S,
+    /// Held while awarding, so an older total can't overwrite a newer one
+    award_lock: Mutex<()>,
+}
+
+impl Leaderboard {
+    /// Create a leaderboard that keeps its totals in memory
+    pub fn new(client: ApiClient, server: impl Into<ServerId>) -> Self {
+        Self::with_store(client, server, MemoryXpStore::default())
+    }
+}
+
+impl<S: XpStore> Leaderboard<S> {
+    /// Create a leaderboard using the given store
+    pub fn with_store(client: ApiClient, server: impl Into<ServerId>, store: S) -> Self {
+        Self {
+            client,
+            server: server.into(),
+            store,
+            award_lock: Mutex::new(()),
+        }
+    }
+
+    /// The store the totals are mirrored in
+    pub fn store(&self) -> &S {
+        &self.store
+    }
+
+    /// Award xp to a member (negative to take it away), returning their new total
+    ///
+    /// # Errors
+    /// If the amount is out of range or the request fails, the store is left untouched in that case
+    pub async fn award(&self, user: impl Into<UserId>, amount: i32) -> Result<i64, ApiError> {
+        let user = user.into();
+        let endpoint = MemberXpAward::new(self.server.clone(), user.clone(), amount)?;
+
+        let _guard = self.award_lock.lock().await;
+        let total = self.client.make_request(endpoint).await?;
+        self.store.set_total(&user, total);
+
+        Ok(total)
+    }
+
+    /// The `n` highest ranked users
+    pub fn top_n(&self, n: usize) -> Vec<LeaderboardEntry> {
+        let mut entries = rank(self.store.totals());
+        entries.truncate(n);
+        entries
+    }
+
+    /// The rank of a user, `None` if they have no xp recorded
+    pub fn rank_of(&self, user: &UserId) -> Option<usize> {
+        rank(self.store.totals())
+            .into_iter()
+            .find(|entry| &entry.user == user)
+            .map(|entry| entry.rank)
+    }
+
+    /// Render the `n` highest ranked users as an embed, using `name` to display each user
+    pub fn embed(&self, n: usize, name: impl Fn(&UserId) -> String) -> Embed {
+        let entries = self.top_n(n);
+
+        let description = if entries.is_empty() {
+            "No one has earned any xp yet".to_owned()
+        } else {
+            entries
+                .iter()
+                .map(|entry| {
+                    let prefix = MEDALS
+                        .get(entry.rank - 1)
+                        .map_or_else(|| format!("#{}", entry.rank), |&medal| medal.to_owned());
+                    format!("{prefix} {}: {} xp", name(&entry.user), entry.xp)
+                })
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        Embed::new()
+            .title("Leaderboard")
+            .description(description)
+            .color(Color::GUILDED_GOLD)
+    }
+}
@@ -1,9 +1,43 @@
 //! Ties together vived sub modules
+//!
+//! Each sub crate is available under its own module, and [`prelude`] exports the things most bots need.
 
-pub use vived_models::*;
+#[deprecated(note = "use the item from `vived::prelude`, `vived::models`, `vived::api` or `vived::ws` instead")]
+mod compat;
+pub mod prelude;
+pub mod state;
+
+#[cfg(feature = "leaderboard")]
+pub mod leaderboard;
+
+#[cfg(feature = "scheduler")]
+pub mod scheduler;
+
+#[cfg(feature = "watch")]
+pub mod watch;
+
+#[cfg(feature = "export")]
+pub mod export;
+
+#[cfg(feature = "outbox")]
+pub mod outbox;
+
+#[cfg(any(feature = "scheduler", feature = "outbox"))]
+mod stored_message;
+
+#[cfg(feature = "forwarder")]
+pub mod forwarder;
+
+#[cfg(feature = "welcome")]
+pub mod welcome;
+
+pub use vived_models as models;
 
 #[cfg(feature = "api")]
-pub use vived_api::*;
+pub use vived_api as api;
 
 #[cfg(feature = "websocket")]
-pub use vived_websocket::*;
\ No newline at end of file
+pub use vived_websocket as ws;
+
+#[allow(deprecated)]
+pub use compat::*;
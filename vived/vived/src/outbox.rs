@@ -0,0 +1,451 @@
+//! Messages that must be delivered, even if the bot crashes or restarts before they are sent
+//!
+//! [`Outbox::enqueue`] saves a message in an [`OutboxStore`] before anything is sent,
+//! and a background task sends the stored messages one by one, retrying with exponential backoff.
+//! A message is only removed from the store once guilded accepted it, or rejected it in a way retrying can't fix.
+//! Use [`JsonFileOutboxStore`] to keep messages on disk, [`Outbox::resume`] sends whatever is left after a restart.
+//!
+//! A crash right after guilded accepted a message, but before it was removed from the store, would send it twice.
+//! To prevent that every message gets an idempotency key, which is appended to the content as invisible zero width characters.
+//! Before retrying a message that might already have been sent we look for its key in the recent history of the channel.
+//! Messages without content, like embed only messages, can't carry the key, so those might still be sent twice.
+//! The key takes up [`KEY_MARKER_LENGTH`] characters of the content limit.
+//!
+//! ```no_run
+//! use std::sync::Arc;
+//! use vived::api::{endpoints::MessageCreate, ApiClient};
+//! use vived::outbox::{JsonFileOutboxStore, Outbox};
+//!
+//! # #[tokio::main]
+//! # async fn main() -> std::io::Result<()> {
+//! let client = ApiClient::new("token").unwrap();
+//! let store = Arc::new(JsonFileOutboxStore::new("outbox.json"));
+//!
+//! // sends anything left over from before a restart
+//! let outbox = Outbox::resume(store, client)?;
+//! outbox.enqueue(MessageCreate::new_with_content("channel", "Payment failed for server 42"))?;
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::HashMap;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{DateTime, Utc};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use vived_api::search::SearchOptions;
+use vived_api::{endpoints::MessageCreate, ApiClient, ApiError, Endpoint};
+
+/// Version of the on disk format, stored with every entry and in [`JsonFileOutboxStore`] files
+pub const OUTBOX_SCHEMA_VERSION: u32 = 1;
+
+/// Delay before the first retry
+const INITIAL_RETRY_DELAY: Duration = Duration::from_secs(1);
+/// Longest delay between retries
+const MAX_RETRY_DELAY: Duration = Duration::from_secs(300);
+/// How many recent messages are searched for the key of a message that might have been sent already
+const DUPLICATE_SEARCH_DEPTH: usize = 100;
+/// Zero width characters used to write the key, one for each base 4 digit
+const KEY_DIGITS: [char; 4] = ['\u{200B}', '\u{200C}', '\u{200D}', '\u{2060}'];
+/// How many characters the key adds to the content of a message
+pub const KEY_MARKER_LENGTH: usize = 32;
+
+/// A message waiting to be sent, as kept in an [`OutboxStore`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OutboxEntry {
+    /// Format version of the entry, see [`OUTBOX_SCHEMA_VERSION`]
+    pub version: u32,
+    /// Idempotency key, unique for every entry
+    pub key: u64,
+    /// The message to send, with the key already appended to its content
+    #[serde(with = "crate::stored_message")]
+    pub message: MessageCreate,
+    /// When the message was enqueued
+    pub enqueued_at: DateTime<Utc>,
+    /// How many times sending was started, if this is not 0 the message might have been sent already
+    pub attempts: u32,
+}
+
+impl OutboxEntry {
+    /// Create an entry for `message`, appending a new key to its content
+    fn new(message: MessageCreate) -> Self {
+        let key = new_key();
+        let message = match message.content_text().map(|content| format!("{content}{}", key_marker(key))) {
+            Some(content) => message.content(content),
+            None => message,
+        };
+
+        Self {
+            version: OUTBOX_SCHEMA_VERSION,
+            key,
+            message,
+            enqueued_at: Utc::now(),
+            attempts: 0,
+        }
+    }
+}
+
+/// Storage for outbox entries, implement this to keep them somewhere else than a json file
+///
+/// Every method should only return once the change is durable,
+/// the outbox relies on an entry being saved before the message is sent.
+pub trait OutboxStore: Send + Sync {
+    /// Save a new entry, or update an existing one with the same key
+    ///
+    /// # Errors
+    /// If the entry could not be saved
+    fn save(&self, entry: &OutboxEntry) -> io::Result<()>;
+
+    /// Remove the entry with the given key
+    ///
+    /// # Errors
+    /// If the entry could not be removed
+    fn remove(&self, key: u64) -> io::Result<()>;
+
+    /// Load every stored entry, oldest first
+    ///
+    /// # Errors
+    /// If the entries could not be read
+    fn load(&self) -> io::Result<Vec<OutboxEntry>>;
+}
+
+/// In memory [`OutboxStore`], entries are lost when the bot restarts
+#[derive(Debug, Default)]
+pub struct MemoryOutboxStore {
+    /// Stored entries
+    entries: RwLock<HashMap<u64, OutboxEntry>>,
+}
+
+impl OutboxStore for MemoryOutboxStore {
+    fn save(&self, entry: &OutboxEntry) -> io::Result<()> {
+        self.entries
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(entry.key, entry.clone());
+        Ok(())
+    }
+
+    fn remove(&self, key: u64) -> io::Result<()> {
+        self.entries
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&key);
+        Ok(())
+    }
+
+    fn load(&self) -> io::Result<Vec<OutboxEntry>> {
+        let mut entries: Vec<OutboxEntry> = self
+            .entries
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .cloned()
+            .collect();
+        entries.sort_by_key(|entry| entry.enqueued_at);
+        Ok(entries)
+    }
+}
+
+/// Contents of a [`JsonFileOutboxStore`] file
+#[derive(Debug, Serialize, Deserialize)]
+struct OutboxFile {
+    /// Format version of the file, see [`OUTBOX_SCHEMA_VERSION`]
+    version: u32,
+    /// Stored entries, oldest first
+    entries: Vec<OutboxEntry>,
+}
+
+/// [`OutboxStore`] that keeps every entry in a single json file
+///
+/// The whole file is rewritten on every change, first to a temporary file that then replaces the old one,
+/// so a crash never leaves a half written file behind.
+/// This is fine for the handful of messages an outbox usually holds.
+///
+/// ```
+/// use vived::api::endpoints::MessageCreate;
+/// use vived::outbox::{JsonFileOutboxStore, OutboxEntry, OutboxStore, OUTBOX_SCHEMA_VERSION};
+///
+/// let path = std::env::temp_dir().join(format!("vived-outbox-{}.json", std::process::id()));
+/// let store = JsonFileOutboxStore::new(&path);
+///
+/// let entry = OutboxEntry {
+///     version: OUTBOX_SCHEMA_VERSION,
+///     key: 1,
+///     message: MessageCreate::new_with_content("channel", "hello"),
+///     enqueued_at: chrono::Utc::now(),
+///     attempts: 0,
+/// };
+/// store.save(&entry).unwrap();
+///
+/// // a new store, like after a restart, sees the entry
+/// let restarted = JsonFileOutboxStore::new(&path);
+/// let entries = restarted.load().unwrap();
+/// assert_eq!(entries.len(), 1);
+/// assert_eq!(entries[0].message.content_text(), Some("hello"));
+///
+/// restarted.remove(1).unwrap();
+/// assert!(store.load().unwrap().is_empty());
+/// # std::fs::remove_file(&path).unwrap();
+/// ```
+#[derive(Debug)]
+pub struct JsonFileOutboxStore {
+    /// File the entries are kept in
+    path: PathBuf,
+    /// Makes sure only one change is written at a time
+    lock: Mutex<()>,
+}
+
+impl JsonFileOutboxStore {
+    /// Keep entries in the file at `path`, it is created on the first save
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            path: path.into(),
+            lock: Mutex::new(()),
+        }
+    }
+
+    /// Read the entries in the file, none if it doesn't exist yet
+    fn read(&self) -> io::Result<Vec<OutboxEntry>> {
+        let raw = match std::fs::read_to_string(&self.path) {
+            Ok(raw) => raw,
+            Err(error) if error.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(error) => return Err(error),
+        };
+
+        let file: OutboxFile = serde_json::from_str(&raw)?;
+        if file.version > OUTBOX_SCHEMA_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "outbox file has version {}, but at most {OUTBOX_SCHEMA_VERSION} is supported",
+                    file.version
+                ),
+            ));
+        }
+        Ok(file.entries)
+    }
+
+    /// Replace the file with `entries`
+    fn write(&self, entries: Vec<OutboxEntry>) -> io::Result<()> {
+        let file = OutboxFile {
+            version: OUTBOX_SCHEMA_VERSION,
+            entries,
+        };
+        let temporary = self.path.with_extension("tmp");
+        let mut written = std::fs::File::create(&temporary)?;
+        written.write_all(&serde_json::to_vec_pretty(&file)?)?;
+        // the contents have to be on disk before the rename, or a crash could leave an empty file in its place
+        written.sync_all()?;
+        std::fs::rename(temporary, &self.path)?;
+
+        // and the rename itself is only durable once the directory is synced
+        let directory = match self.path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent,
+            _ => Path::new("."),
+        };
+        std::fs::File::open(directory)?.sync_all()
+    }
+}
+
+impl OutboxStore for JsonFileOutboxStore {
+    fn save(&self, entry: &OutboxEntry) -> io::Result<()> {
+        let _lock = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut entries = self.read()?;
+        match entries.iter_mut().find(|stored| stored.key == entry.key) {
+            Some(stored) => *stored = entry.clone(),
+            None => entries.push(entry.clone()),
+        }
+        self.write(entries)
+    }
+
+    fn remove(&self, key: u64) -> io::Result<()> {
+        let _lock = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut entries = self.read()?;
+        entries.retain(|stored| stored.key != key);
+        self.write(entries)
+    }
+
+    fn load(&self) -> io::Result<Vec<OutboxEntry>> {
+        let _lock = self.lock.lock().unwrap_or_else(PoisonError::into_inner);
+        self.read()
+    }
+}
+
+/// Sends stored messages in the background, see the [module docs](self)
+///
+/// The background task stops once the outbox is dropped and every queued message was sent.
+pub struct Outbox {
+    /// Store every entry is saved in before it is sent
+    store: Arc<dyn OutboxStore>,
+    /// Hands new entries to the background task
+    queue: mpsc::UnboundedSender<OutboxEntry>,
+}
+
+impl std::fmt::Debug for Outbox {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Outbox")
+            .field("running", &!self.queue.is_closed())
+            .finish_non_exhaustive()
+    }
+}
+
+impl Outbox {
+    /// Start sending the entries left in `store`, and any new ones
+    ///
+    /// # Errors
+    /// If the entries in the store could not be loaded
+    ///
+    /// # Panics
+    /// If called outside of a tokio runtime
+    pub fn resume(store: Arc<dyn OutboxStore>, client: ApiClient) -> io::Result<Self> {
+        let leftover = store.load()?;
+        if !leftover.is_empty() {
+            log::info!("resuming {} messages left in the outbox", leftover.len());
+        }
+
+        let (queue, receiver) = mpsc::unbounded_channel();
+        for entry in leftover {
+            // the receiver is right here, so this can't fail
+            let _ = queue.send(entry);
+        }
+        tokio::spawn(worker(Arc::clone(&store), client, receiver));
+
+        Ok(Self { store, queue })
+    }
+
+    /// Save `message` and queue it to be sent, returning its idempotency key
+    ///
+    /// The message is saved before this returns, so it is sent even if the bot crashes right after.
+    ///
+    /// # Errors
+    /// If the message could not be saved, it is not sent in that case.
+    /// A message that is invalid, for example because its content plus the [`KEY_MARKER_LENGTH`] characters of the key
+    /// is too long, is not saved and fails with [`io::ErrorKind::InvalidInput`].
+    pub fn enqueue(&self, message: MessageCreate) -> io::Result<u64> {
+        let entry = OutboxEntry::new(message);
+        let key = entry.key;
+        // checked with the key added, so guilded won't reject it for being too long later
+        entry
+            .message
+            .validate()
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidInput, error.to_string()))?;
+
+        self.store.save(&entry)?;
+        if self.queue.send(entry).is_err() {
+            // only happens if the task panicked, the entry is still saved for the next resume
+            log::error!("outbox task is gone, message {key:016x} will be sent after a restart");
+        }
+        Ok(key)
+    }
+}
+
+/// Send every entry from `entries` in order
+async fn worker(
+    store: Arc<dyn OutboxStore>,
+    client: ApiClient,
+    mut entries: mpsc::UnboundedReceiver<OutboxEntry>,
+) {
+    while let Some(entry) = entries.recv().await {
+        deliver(store.as_ref(), &client, entry).await;
+    }
+}
+
+/// Send an entry, retrying until guilded accepts it or rejects it for good
+async fn deliver(store: &dyn OutboxStore, client: &ApiClient, mut entry: OutboxEntry) {
+    let mut delay = INITIAL_RETRY_DELAY;
+
+    loop {
+        if entry.attempts > 0 && already_sent(client, &entry).await {
+            log::info!("outbox message {:016x} was already sent", entry.key);
+            break;
+        }
+
+        // saved before sending, so after a crash we know to check if it was sent
+        entry.attempts += 1;
+        if let Err(error) = store.save(&entry) {
+            log::error!("failed to save outbox message {:016x}: {error}", entry.key);
+        }
+
+        match client.make_request(entry.message.clone()).await {
+            Ok(_) => break,
+            Err(error @ (ApiError::InvalidArgument(_) | ApiError::Guilded(_))) if !error.is_retryable() => {
+                // sending it again would get the same answer, and keep the messages after it waiting forever
+                log::error!("outbox message {:016x} was rejected, dropping it: {error}", entry.key);
+                break;
+            }
+            Err(error) => {
+                log::warn!("failed to send outbox message {:016x}, retrying in {delay:?}: {error}", entry.key);
+                tokio::time::sleep(delay).await;
+                delay = (delay * 2).min(MAX_RETRY_DELAY);
+            }
+        }
+    }
+
+    if let Err(error) = store.remove(entry.key) {
+        log::error!("failed to remove outbox message {:016x}: {error}", entry.key);
+    }
+}
+
+/// Look for the key of `entry` in the recent history of its channel
+async fn already_sent(client: &ApiClient, entry: &OutboxEntry) -> bool {
+    let marker = key_marker(entry.key);
+    if !entry
+        .message
+        .content_text()
+        .is_some_and(|content| content.ends_with(&marker))
+    {
+        return false;
+    }
+
+    let options = SearchOptions::default()
+        .max_messages(DUPLICATE_SEARCH_DEPTH)
+        .oldest(entry.enqueued_at);
+    let mut history = Box::pin(client.message_history(entry.message.channel_id().clone(), options));
+
+    while let Some(message) = history.next().await {
+        match message {
+            Ok(message) if message.content_or_empty().ends_with(&marker) => return true,
+            Ok(_) => {}
+            Err(error) => {
+                // sending twice is better than not at all
+                log::warn!("failed to check if outbox message {:016x} was sent: {error}", entry.key);
+                return false;
+            }
+        }
+    }
+    false
+}
+
+/// Make a key that is unique across restarts
+fn new_key() -> u64 {
+    /// Keeps keys made in the same nanosecond apart
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let nanos = now
+        .as_secs()
+        .wrapping_mul(1_000_000_000)
+        .wrapping_add(u64::from(now.subsec_nanos()));
+    nanos ^ COUNTER.fetch_add(1, Ordering::Relaxed).rotate_right(16)
+}
+
+/// Write `key` as invisible zero width characters
+fn key_marker(key: u64) -> String {
+    (0..KEY_MARKER_LENGTH)
+        .rev()
+        .map(|digit| match (key >> (digit * 2)) & 0b11 {
+            0 => KEY_DIGITS[0],
+            1 => KEY_DIGITS[1],
+            2 => KEY_DIGITS[2],
+            _ => KEY_DIGITS[3],
+        })
+        .collect()
+}
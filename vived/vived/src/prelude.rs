@@ -0,0 +1,22 @@
+//! The types and functions most bots need
+//!
+//! ```
+//! use vived::prelude::*;
+//! ```
+
+pub use crate::state::State;
+
+pub use vived_models::{
+    Channel, ChannelId, Color, Embed, EmbedAuthor, EmbedField, EmbedFooter, Message, MessageId,
+    Server, ServerId, ServerMember, User, UserId,
+};
+
+#[cfg(feature = "api")]
+pub use vived_api::{endpoints::*, ApiClient, ApiClientConfig, ApiError, Endpoint};
+
+#[cfg(feature = "websocket")]
+pub use vived_websocket::{
+    connect_to_websocket,
+    events::{EventKind, GuildedEvent},
+    wait::{wait_for_event, wait_for_message},
+};
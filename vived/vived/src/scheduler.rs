@@ -0,0 +1,428 @@
+//! Send messages at a later time
+//!
+//! A [`Scheduler`] runs a single background task that keeps every job in a heap ordered by deadline,
+//! and sends the messages through a shared [`ApiClient`], so scheduled messages go through the same ratelimiter as everything else.
+//! Jobs are mirrored in a [`ScheduleStore`] so they can survive restarts.
+//!
+//! Repeating jobs are rescheduled from their previous deadline rather than from when they were sent,
+//! so they don't slowly drift because of ratelimits or a slow api.
+//! If a deadline was missed completely (for example because the bot was offline) it is skipped instead of sent late many times over.
+//!
+//! ```no_run
+//! use chrono::{Duration, Utc};
+//! use vived::api::{endpoints::MessageCreate, ApiClient};
+//! use vived::scheduler::{Repeat, Scheduler};
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let client = ApiClient::new("token").unwrap();
+//! let scheduler = Scheduler::new(client);
+//!
+//! let mut reminder = scheduler.schedule(
+//!     Utc::now() + Duration::minutes(10),
+//!     MessageCreate::new_with_content("channel", "The meeting starts now!"),
+//! );
+//!
+//! let _daily = scheduler.schedule_repeating(
+//!     Repeat::Daily(chrono::NaiveTime::from_hms(9, 0, 0)),
+//!     || MessageCreate::new_with_content("channel", format!("Good morning, it is {}", Utc::now().date())),
+//! );
+//!
+//! match reminder.next_result().await {
+//!     Some(Ok(message)) => println!("sent {}", message.id),
+//!     Some(Err(error)) => println!("failed to send reminder: {error}"),
+//!     None => println!("reminder was canceled"),
+//! }
+//! # }
+//! ```
+
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, PoisonError, RwLock};
+
+use chrono::{DateTime, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use vived_api::{endpoints::MessageCreate, ApiClient, ApiError};
+use vived_models::Message;
+
+/// Id of a scheduled job
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct ScheduleId(pub u64);
+
+/// How a job repeats
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Repeat {
+    /// Every `interval` after the first deadline
+    Interval(std::time::Duration),
+    /// Every day at the given time (in utc)
+    Daily(NaiveTime),
+}
+
+impl Repeat {
+    /// The first deadline after `now`, counting from the `previous` deadline
+    ///
+    /// `None` if that deadline is too far in the future to represent, the job stops repeating then.
+    #[must_use]
+    pub fn next_after(&self, previous: DateTime<Utc>, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match *self {
+            Self::Interval(interval) => {
+                // an interval of zero would never get past now
+                let interval = chrono::Duration::from_std(interval)
+                    .ok()?
+                    .max(chrono::Duration::seconds(1));
+                let next = previous.checked_add_signed(interval)?;
+                if next > now {
+                    return Some(next);
+                }
+
+                let interval = interval.num_milliseconds();
+                let missed = (now - next).num_milliseconds() / interval;
+                let skipped = interval.checked_mul(missed + 1)?;
+                next.checked_add_signed(chrono::Duration::milliseconds(skipped))
+            }
+            Self::Daily(time) => {
+                let today = DateTime::<Utc>::from_utc(now.naive_utc().date().and_time(time), Utc);
+                if today > now {
+                    Some(today)
+                } else {
+                    today.checked_add_signed(chrono::Duration::days(1))
+                }
+            }
+        }
+    }
+
+    /// The first deadline after `now`, `None` if it is too far in the future to represent
+    #[must_use]
+    pub fn first_after(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.next_after(now, now)
+    }
+}
+
+/// A job as kept in a [`ScheduleStore`]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StoredJob {
+    /// Id of the job
+    pub id: ScheduleId,
+    /// When the job should fire next
+    pub at: DateTime<Utc>,
+    /// The message to send when it fires
+    #[serde(with = "crate::stored_message")]
+    pub message: MessageCreate,
+    /// How the job repeats, `None` for one off jobs
+    pub repeat: Option<Repeat>,
+}
+
+/// Storage for scheduled jobs, implement this to keep jobs across restarts
+///
+/// Repeating jobs created with a factory are stored with the message made for their next run,
+/// so after a restart they keep sending that message until they are scheduled again.
+pub trait ScheduleStore: Send + Sync {
+    /// Save a new job, or update an existing one
+    fn save(&self, job: &StoredJob);
+
+    /// Remove a job that is done or was canceled
+    fn remove(&self, id: ScheduleId);
+
+    /// Load every stored job
+    fn load(&self) -> Vec<StoredJob>;
+}
+
+/// In memory [`ScheduleStore`], jobs are lost when the bot restarts
+#[derive(Debug, Default)]
+pub struct MemoryScheduleStore {
+    /// Stored jobs
+    jobs: RwLock<HashMap<ScheduleId, StoredJob>>,
+}
+
+impl ScheduleStore for MemoryScheduleStore {
+    fn save(&self, job: &StoredJob) {
+        self.jobs
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(job.id, job.clone());
+    }
+
+    fn remove(&self, id: ScheduleId) {
+        self.jobs
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&id);
+    }
+
+    fn load(&self) -> Vec<StoredJob> {
+        self.jobs
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .values()
+            .cloned()
+            .collect()
+    }
+}
+
+/// Makes the message for each run of a repeating job
+type Factory = Box<dyn Fn() -> MessageCreate + Send + Sync>;
+
+/// A job inside the scheduler task
+struct Job {
+    /// The stored part of the job
+    stored: StoredJob,
+    /// Makes the message for the next run
+    factory: Option<Factory>,
+    /// Where results are reported, `None` for jobs restored from the store
+    results: Option<mpsc::UnboundedSender<Result<Message, ApiError>>>,
+}
+
+/// Messages sent to the scheduler task
+enum Command {
+    /// Add a new job
+    Add(Job),
+    /// Cancel a job
+    Cancel(ScheduleId),
+}
+
+/// Handle to a scheduled job
+///
+/// Dropping the handle does not cancel the job, it only stops the results from being reported.
+#[derive(Debug)]
+pub struct ScheduleHandle {
+    /// Id of the job
+    id: ScheduleId,
+    /// Used to cancel the job
+    commands: mpsc::UnboundedSender<Command>,
+    /// Results of each run
+    results: mpsc::UnboundedReceiver<Result<Message, ApiError>>,
+}
+
+impl ScheduleHandle {
+    /// Id of the job
+    #[must_use]
+    pub fn id(&self) -> ScheduleId {
+        self.id
+    }
+
+    /// Cancel the job, runs that are already sending still finish
+    pub fn cancel(&self) {
+        // if the task is gone there is nothing left to cancel
+        let _ = self.commands.send(Command::Cancel(self.id));
+    }
+
+    /// Wait for the result of the next run, `None` once the job is done or canceled
+    pub async fn next_result(&mut self) -> Option<Result<Message, ApiError>> {
+        self.results.recv().await
+    }
+}
+
+/// Sends messages at scheduled times, see the [module docs](self)
+///
+/// Cloning gives another handle to the same scheduler.
+/// Once every clone and [`ScheduleHandle`] is dropped the jobs can't be canceled anymore, but they still run.
+/// The background task stops after the last one-off job was sent, repeating jobs keep it running for good.
+#[derive(Debug, Clone)]
+pub struct Scheduler {
+    /// Commands to the background task
+    commands: mpsc::UnboundedSender<Command>,
+    /// Next id to hand out
+    next_id: Arc<AtomicU64>,
+}
+
+impl Scheduler {
+    /// Create a scheduler that keeps its jobs in memory
+    ///
+    /// # Panics
+    /// If called outside of a tokio runtime
+    #[must_use]
+    pub fn new(client: ApiClient) -> Self {
+        Self::with_store(client, MemoryScheduleStore::default())
+    }
+
+    /// Create a scheduler using the given store, and resume every job already in it
+    ///
+    /// # Panics
+    /// If called outside of a tokio runtime
+    #[must_use]
+    pub fn with_store(client: ApiClient, store: impl ScheduleStore + 'static) -> Self {
+        let jobs = store.load();
+        let next_id = jobs.iter().map(|job| job.id.0 + 1).max().unwrap_or(0);
+
+        let (commands, receiver) = mpsc::unbounded_channel();
+        for stored in jobs {
+            // can't fail, the receiver is right here
+            let _ = commands.send(Command::Add(Job {
+                stored,
+                factory: None,
+                results: None,
+            }));
+        }
+
+        tokio::spawn(run(client, store, receiver));
+
+        Self {
+            commands,
+            next_id: Arc::new(AtomicU64::new(next_id)),
+        }
+    }
+
+    /// Send `message` at `at`, a deadline in the past is sent right away
+    pub fn schedule(&self, at: DateTime<Utc>, message: MessageCreate) -> ScheduleHandle {
+        self.add(at, message, None, None)
+    }
+
+    /// Send a message made by `factory` every time `repeat` comes around
+    ///
+    /// The factory is called when the job is scheduled for its next run, not when it fires.
+    /// If the first run is too far in the future to represent the job is never added,
+    /// and the handle reports no results.
+    pub fn schedule_repeating<F>(&self, repeat: Repeat, factory: F) -> ScheduleHandle
+    where
+        F: Fn() -> MessageCreate + Send + Sync + 'static,
+    {
+        let Some(at) = repeat.first_after(Utc::now()) else {
+            log::error!("{repeat:?} never comes around, not scheduling it");
+            let (_, results) = mpsc::unbounded_channel();
+            return ScheduleHandle {
+                id: ScheduleId(self.next_id.fetch_add(1, Ordering::Relaxed)),
+                commands: self.commands.clone(),
+                results,
+            };
+        };
+
+        let message = factory();
+        self.add(at, message, Some(repeat), Some(Box::new(factory)))
+    }
+
+    /// Add a job to the background task
+    fn add(
+        &self,
+        at: DateTime<Utc>,
+        message: MessageCreate,
+        repeat: Option<Repeat>,
+        factory: Option<Factory>,
+    ) -> ScheduleHandle {
+        let id = ScheduleId(self.next_id.fetch_add(1, Ordering::Relaxed));
+        let (results, receiver) = mpsc::unbounded_channel();
+
+        let job = Job {
+            stored: StoredJob {
+                id,
+                at,
+                message,
+                repeat,
+            },
+            factory,
+            results: Some(results),
+        };
+        if self.commands.send(Command::Add(job)).is_err() {
+            log::error!("scheduler task is gone, job {} will never run", id.0);
+        }
+
+        ScheduleHandle {
+            id,
+            commands: self.commands.clone(),
+            results: receiver,
+        }
+    }
+}
+
+/// How long until `at`, zero if it is in the past
+fn until(at: DateTime<Utc>) -> std::time::Duration {
+    (at - Utc::now()).to_std().unwrap_or_default()
+}
+
+/// The background task of a scheduler
+async fn run(
+    client: ApiClient,
+    store: impl ScheduleStore,
+    mut commands: mpsc::UnboundedReceiver<Command>,
+) {
+    let mut deadlines: BinaryHeap<Reverse<(DateTime<Utc>, ScheduleId)>> = BinaryHeap::new();
+    let mut jobs: HashMap<ScheduleId, Job> = HashMap::new();
+    // false once every scheduler and handle is dropped, the jobs left are still run
+    let mut open = true;
+
+    loop {
+        if !open && jobs.is_empty() {
+            break;
+        }
+        let next = deadlines.peek().map(|&Reverse((at, _))| at);
+        let sleep = async {
+            match next {
+                Some(at) => tokio::time::sleep(until(at)).await,
+                None => std::future::pending().await,
+            }
+        };
+
+        tokio::select! {
+            command = commands.recv(), if open => match command {
+                Some(Command::Add(job)) => {
+                    store.save(&job.stored);
+                    deadlines.push(Reverse((job.stored.at, job.stored.id)));
+                    jobs.insert(job.stored.id, job);
+                }
+                Some(Command::Cancel(id)) => {
+                    // the deadline stays in the heap, and is skipped when it comes up
+                    if jobs.remove(&id).is_some() {
+                        store.remove(id);
+                    }
+                }
+                None => open = false,
+            },
+            () = sleep => {
+                let Some(Reverse((at, id))) = deadlines.pop() else { continue };
+                let Some(job) = jobs.get_mut(&id) else { continue };
+                if job.stored.at != at {
+                    continue;
+                }
+
+                let repeat = job.stored.repeat;
+                let next_at = repeat.and_then(|repeat| repeat.next_after(at, Utc::now()));
+                if repeat.is_some() && next_at.is_none() {
+                    log::warn!("the next run of job {} is too far in the future, this is its last run", id.0);
+                }
+
+                let Some(next_at) = next_at else {
+                    store.remove(id);
+                    if let Some(job) = jobs.remove(&id) {
+                        fire(&client, job.stored.message, job.results);
+                    }
+                    continue;
+                };
+
+                let next = job
+                    .factory
+                    .as_ref()
+                    .map_or_else(|| job.stored.message.clone(), |factory| factory());
+                let message = std::mem::replace(&mut job.stored.message, next);
+                job.stored.at = next_at;
+                store.save(&job.stored);
+                deadlines.push(Reverse((job.stored.at, id)));
+
+                fire(&client, message, job.results.clone());
+            }
+        }
+    }
+}
+
+/// Send a message without blocking the scheduler, reporting the result if anyone listens
+fn fire(
+    client: &ApiClient,
+    message: MessageCreate,
+    results: Option<mpsc::UnboundedSender<Result<Message, ApiError>>>,
+) {
+    let client = client.clone();
+    tokio::spawn(async move {
+        let result = client.make_request(message).await;
+        match results {
+            Some(results) => {
+                // the handle might have been dropped, that is fine
+                let _ = results.send(result);
+            }
+            None => {
+                if let Err(error) = result {
+                    log::error!("failed to send scheduled message: {error}");
+                }
+            }
+        }
+    });
+}
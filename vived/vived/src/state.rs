@@ -0,0 +1,192 @@
+//! Typed state storage for bots
+//!
+//! Create a [`State`] once and give a clone of it to each of your event handlers,
+//! all clones share the same storage.
+
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::{Arc, PoisonError, RwLock};
+
+use vived_models::ServerId;
+
+/// A type erased value in the state
+type AnyValue = Arc<dyn Any + Send + Sync>;
+
+/// Lets [`State`] clear per server state without knowing its type
+trait ServerScoped: Send + Sync {
+    /// Remove the state for the server
+    fn remove_server(&self, server: &ServerId);
+}
+
+/// Typed storage, holding at most one value of each type
+///
+/// Values are handed out as `Arc<T>`, so use interior mutability (like a `Mutex`) for anything that changes.
+#[derive(Clone, Default)]
+pub struct State {
+    /// The values, keyed by their type
+    values: Arc<RwLock<HashMap<TypeId, AnyValue>>>,
+    /// All registered per server states, so they can be cleared
+    servers: Arc<RwLock<Vec<Arc<dyn ServerScoped>>>>,
+}
+
+impl State {
+    /// Create empty state
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store a value, returning the old value of the same type if there was one
+    pub fn insert<T: Any + Send + Sync>(&self, value: T) -> Option<Arc<T>> {
+        self.values
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(TypeId::of::<T>(), Arc::new(value))
+            .and_then(|old| old.downcast::<T>().ok())
+    }
+
+    /// Get the value of the given type
+    #[must_use]
+    pub fn get<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.values
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&TypeId::of::<T>())
+            .map(Arc::clone)
+            .and_then(|value| value.downcast::<T>().ok())
+    }
+
+    /// Remove the value of the given type
+    pub fn remove<T: Any + Send + Sync>(&self) -> Option<Arc<T>> {
+        self.values
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&TypeId::of::<T>())
+            .and_then(|value| value.downcast::<T>().ok())
+    }
+
+    /// Register per server state of type `T`, created lazily by `factory` the first time a server asks for it
+    ///
+    /// Registering the same type again replaces the old factory and drops all existing values.
+    pub fn register_server_state<T, F>(&self, factory: F)
+    where
+        T: Send + Sync + 'static,
+        F: Fn(&ServerId) -> T + Send + Sync + 'static,
+    {
+        let states = Arc::new(ServerState::new(factory));
+
+        let old = self
+            .values
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(TypeId::of::<ServerState<T>>(), Arc::clone(&states) as AnyValue);
+
+        let mut servers = self.servers.write().unwrap_or_else(PoisonError::into_inner);
+        if let Some(old) = old {
+            servers.retain(|scoped| {
+                !std::ptr::eq(
+                    Arc::as_ptr(scoped).cast::<()>(),
+                    Arc::as_ptr(&old).cast::<()>(),
+                )
+            });
+        }
+        servers.push(states);
+    }
+
+    /// Get the state of type `T` for a server, creating it if needed
+    ///
+    /// Returns `None` if no factory was registered for `T` using [`State::register_server_state`]
+    #[must_use]
+    pub fn server_state<T: Send + Sync + 'static>(&self, server: &ServerId) -> Option<Arc<T>> {
+        self.get::<ServerState<T>>().map(|states| states.get(server))
+    }
+
+    /// Drop all per server state for a server, for example when the bot is removed from it
+    pub fn remove_server(&self, server: &ServerId) {
+        for scoped in self
+            .servers
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+        {
+            scoped.remove_server(server);
+        }
+    }
+}
+
+impl std::fmt::Debug for State {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("State")
+            .field(
+                "values",
+                &self.values.read().unwrap_or_else(PoisonError::into_inner).len(),
+            )
+            .field(
+                "server_states",
+                &self.servers.read().unwrap_or_else(PoisonError::into_inner).len(),
+            )
+            .finish()
+    }
+}
+
+/// State of type `T` kept separately for each server
+pub struct ServerState<T> {
+    /// Creates the state for servers that don't have one yet
+    factory: Box<dyn Fn(&ServerId) -> T + Send + Sync>,
+    /// The state of each server
+    values: RwLock<HashMap<ServerId, Arc<T>>>,
+}
+
+impl<T> ServerState<T> {
+    /// Create a new per server state using the given factory
+    pub fn new(factory: impl Fn(&ServerId) -> T + Send + Sync + 'static) -> Self {
+        Self {
+            factory: Box::new(factory),
+            values: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Get the state of a server, creating it if needed
+    pub fn get(&self, server: &ServerId) -> Arc<T> {
+        if let Some(value) = self
+            .values
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(server)
+        {
+            return Arc::clone(value);
+        }
+
+        let mut values = self.values.write().unwrap_or_else(PoisonError::into_inner);
+        Arc::clone(
+            values
+                .entry(server.clone())
+                .or_insert_with(|| Arc::new((self.factory)(server))),
+        )
+    }
+
+    /// Remove the state of a server
+    pub fn remove(&self, server: &ServerId) -> Option<Arc<T>> {
+        self.values
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(server)
+    }
+}
+
+impl<T: Send + Sync> ServerScoped for ServerState<T> {
+    fn remove_server(&self, server: &ServerId) {
+        self.remove(server);
+    }
+}
+
+impl<T> std::fmt::Debug for ServerState<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServerState")
+            .field(
+                "servers",
+                &self.values.read().unwrap_or_else(PoisonError::into_inner).len(),
+            )
+            .finish_non_exhaustive()
+    }
+}
@@ -0,0 +1,46 @@
+//! How a [`MessageCreate`] is stored by the scheduler and the outbox, use it with `#[serde(with = "...")]`
+//!
+//! The endpoint itself is not serializable, this writes its channel and json arguments.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use vived_api::endpoints::{MessageCreate, MessageCreateArguments};
+use vived_models::ChannelId;
+
+/// A stored message, borrowed for serializing
+#[derive(Serialize)]
+struct StoredRef<'a> {
+    /// Channel to send in
+    channel: &'a ChannelId,
+    /// Json arguments
+    arguments: &'a MessageCreateArguments,
+}
+
+/// A stored message
+#[derive(Deserialize)]
+struct Stored {
+    /// Channel to send in
+    channel: ChannelId,
+    /// Json arguments
+    arguments: MessageCreateArguments,
+}
+
+/// Serialize a message as its channel and arguments
+///
+/// # Errors
+/// If the serializer fails
+pub fn serialize<S: Serializer>(message: &MessageCreate, serializer: S) -> Result<S::Ok, S::Error> {
+    StoredRef {
+        channel: message.channel_id(),
+        arguments: message.arguments(),
+    }
+    .serialize(serializer)
+}
+
+/// Deserialize a message stored by [`serialize`]
+///
+/// # Errors
+/// If the value is not a stored message
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<MessageCreate, D::Error> {
+    let Stored { channel, arguments } = Stored::deserialize(deserializer)?;
+    Ok(MessageCreate::from_arguments(channel, arguments))
+}
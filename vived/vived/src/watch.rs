@@ -0,0 +1,228 @@
+//! Follow the activity of a single channel
+//!
+//! [`watch_channel`] turns the server wide websocket events into an ordered stream for one channel,
+//! optionally starting with some recent history so handlers have context.
+//! This makes it easy to run one task per channel.
+//!
+//! Guilded sends no reaction events over the websocket yet, so [`ChannelActivity`] only covers messages.
+//!
+//! History and live events are merged without duplicates:
+//! a message that shows up both in the history and as a live event is only yielded once.
+//! ```
+//! use futures_util::StreamExt;
+//! use vived::models::Message;
+//! use vived::watch::{watch_channel_with_history, ChannelActivity};
+//! use vived::ws::events::GuildedEvent;
+//!
+//! fn message(id: &str, channel: &str, minute: u8) -> Message {
+//!     serde_json::from_str(&format!(r#"{{
+//!         "id": "{id}", "type": "default", "serverId": "s", "channelId": "{channel}",
+//!         "content": "{id}", "createdAt": "2022-10-01T12:{minute:02}:00.000Z", "createdBy": "u"
+//!     }}"#)).unwrap()
+//! }
+//!
+//! fn created(message: Message) -> GuildedEvent {
+//!     GuildedEvent::ChatMessageCreated { server_id: "s".into(), message: message.into() }
+//! }
+//!
+//! # #[tokio::main]
+//! # async fn main() {
+//! let (sender, receiver) = tokio::sync::broadcast::channel(16);
+//!
+//! // history comes newest first from the api
+//! let history = vec![message("b", "c", 2), message("a", "c", 1)];
+//! let stream = watch_channel_with_history("c", &receiver, history);
+//!
+//! sender.send(created(message("b", "c", 2))).unwrap(); // already in the history
+//! sender.send(created(message("x", "other", 3))).unwrap(); // another channel
+//! sender.send(created(message("c", "c", 3))).unwrap();
+//! drop(sender);
+//!
+//! let seen: Vec<(String, bool)> = stream
+//!     .map(|activity| match activity {
+//!         ChannelActivity::MessageCreated { message, historical } => (message.id.0, historical),
+//!         _ => panic!("unexpected activity"),
+//!     })
+//!     .collect()
+//!     .await;
+//!
+//! assert_eq!(
+//!     seen,
+//!     [("a".to_owned(), true), ("b".to_owned(), true), ("c".to_owned(), false)]
+//! );
+//! # }
+//! ```
+
+use std::collections::{HashSet, VecDeque};
+use std::sync::Arc;
+
+use futures_util::Stream;
+use tokio::sync::broadcast;
+use vived_api::{endpoints::ChannelGetMessages, ApiClient};
+use vived_models::{ChannelId, Message, MessageId};
+use vived_websocket::events::{GuildedEvent, MessageDeleteData};
+
+/// Something that happened in a watched channel
+#[non_exhaustive]
+#[derive(Debug, Clone)]
+pub enum ChannelActivity {
+    /// A message was sent
+    MessageCreated {
+        /// The message
+        message: Message,
+        /// `true` if this message comes from the history fetched when the watch started
+        historical: bool,
+    },
+    /// A message was edited
+    MessageUpdated(Message),
+    /// A message was deleted
+    MessageDeleted(MessageDeleteData),
+}
+
+/// State of a channel watch between items
+struct Watch {
+    /// Channel being watched
+    channel: ChannelId,
+    /// Live events
+    events: broadcast::Receiver<GuildedEvent>,
+    /// History waiting to be yielded
+    history: VecDeque<Message>,
+    /// Ids of the history messages, so they aren't yielded again if they also arrive live
+    seen: HashSet<MessageId>,
+}
+
+/// Take the message out of an event, only cloning it if another receiver still holds on to it
+fn into_owned(message: Arc<Message>) -> Message {
+    Arc::try_unwrap(message).unwrap_or_else(|message| Message::clone(&message))
+}
+
+impl Watch {
+    /// Turn an event into activity, if it is for this channel and not a duplicate
+    fn activity(&mut self, event: GuildedEvent) -> Option<ChannelActivity> {
+        if event.channel_id() != Some(&self.channel) {
+            return None;
+        }
+
+        match event {
+            GuildedEvent::ChatMessageCreated { message, .. } => {
+                // each history message can only be duplicated once
+                if self.seen.remove(&message.id) {
+                    return None;
+                }
+                Some(ChannelActivity::MessageCreated {
+                    message: into_owned(message),
+                    historical: false,
+                })
+            }
+            GuildedEvent::ChatMessageUpdated { message, .. } => {
+                Some(ChannelActivity::MessageUpdated(into_owned(message)))
+            }
+            GuildedEvent::ChatMessageDeleted { message, .. } => {
+                Some(ChannelActivity::MessageDeleted(message))
+            }
+            GuildedEvent::RoleCreated { .. }
+            | GuildedEvent::RoleUpdated { .. }
+            | GuildedEvent::RoleDeleted { .. }
+            | GuildedEvent::SchedulingAvailabilityCreated { .. }
+            | GuildedEvent::SchedulingAvailabilityUpdated { .. }
+            | GuildedEvent::SchedulingAvailabilityDeleted { .. } => None,
+        }
+    }
+
+    /// Get the next activity, `None` once the websocket is closed
+    async fn next(&mut self) -> Option<ChannelActivity> {
+        if let Some(message) = self.history.pop_front() {
+            return Some(ChannelActivity::MessageCreated {
+                message,
+                historical: true,
+            });
+        }
+
+        loop {
+            match self.events.recv().await {
+                Ok(event) => {
+                    if let Some(activity) = self.activity(event) {
+                        return Some(activity);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!(
+                        "watch of channel {} lagged behind, skipped {skipped} events",
+                        self.channel
+                    );
+                }
+                Err(broadcast::error::RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// Watch a channel, starting with already fetched `history`
+///
+/// The history can be in any order, it is yielded oldest first.
+/// We subscribe to `events` when this is called, so nothing sent after it is missed.
+pub fn watch_channel_with_history(
+    channel: impl Into<ChannelId>,
+    events: &broadcast::Receiver<GuildedEvent>,
+    history: Vec<Message>,
+) -> impl Stream<Item = ChannelActivity> {
+    merge(channel.into(), events.resubscribe(), history)
+}
+
+/// Build the stream from an already subscribed receiver
+fn merge(
+    channel: ChannelId,
+    events: broadcast::Receiver<GuildedEvent>,
+    mut history: Vec<Message>,
+) -> impl Stream<Item = ChannelActivity> {
+    history.retain(|message| message.channel_id == channel);
+    history.sort_by(|a, b| {
+        a.created_at
+            .cmp(&b.created_at)
+            .then_with(|| a.id.0.cmp(&b.id.0))
+    });
+
+    let watch = Watch {
+        channel,
+        events,
+        seen: history.iter().map(|message| message.id.clone()).collect(),
+        history: history.into(),
+    };
+
+    futures_util::stream::unfold(watch, |mut watch| async move {
+        let activity = watch.next().await?;
+        Some((activity, watch))
+    })
+}
+
+/// Watch a channel, starting with the `backfill` most recent messages
+///
+/// If getting the history fails it is logged and the stream starts with the live events.
+/// See [`watch_channel_with_history`] for how history and live events are merged.
+pub async fn watch_channel(
+    client: &ApiClient,
+    channel: impl Into<ChannelId>,
+    events: &broadcast::Receiver<GuildedEvent>,
+    backfill: u8,
+) -> impl Stream<Item = ChannelActivity> {
+    let channel = channel.into();
+    // subscribe before getting the history, so messages sent while it loads aren't lost
+    let events = events.resubscribe();
+
+    let history = if backfill == 0 {
+        Vec::new()
+    } else {
+        match client
+            .make_request(ChannelGetMessages::new(channel.clone()).limit(backfill))
+            .await
+        {
+            Ok(page) => page.into(),
+            Err(error) => {
+                log::warn!("failed to get history of channel {channel}: {error}");
+                Vec::new()
+            }
+        }
+    };
+
+    merge(channel, events, history)
+}
@@ -0,0 +1,290 @@
+//! Greet members when they join a server
+//!
+//! [`handle_member_joined`] finds the channel to greet in, fills in the greeting and sends it.
+//! Templates can use these placeholders:
+//! - `{user}`: a mention of the member
+//! - `{name}`: the name the member shows up as in the server
+//! - `{server}`: the name of the server
+//! - `{member_count}`: how many members the server has, `?` if guilded didn't say
+//!
+//! Names are markdown escaped, so a member called `*bold*` can't format the greeting.
+//! ```
+//! use vived::welcome::render;
+//! use vived::models::{Server, ServerMember};
+//!
+//! let server: Server = serde_json::from_str(r#"{
+//!     "id": "s", "ownerId": "o", "name": "Rust_Lovers", "url": "rust",
+//!     "createdAt": "2022-10-01T12:00:00Z", "memberCount": 42
+//! }"#).unwrap();
+//! let member: ServerMember = serde_json::from_str(r#"{
+//!     "user": {"id": "u", "name": "*bold*", "createdAt": "2022-10-01T12:00:00Z"},
+//!     "roleIds": [], "joinedAt": "2022-10-02T12:00:00Z"
+//! }"#).unwrap();
+//!
+//! let fixtures = [
+//!     ("Welcome {user} to {server}!", "Welcome <@u> to Rust\\_Lovers!"),
+//!     ("{name} is member #{member_count}", "\\*bold\\* is member #42"),
+//!     ("{unknown} {user", "{unknown} {user"),
+//!     ("no placeholders", "no placeholders"),
+//! ];
+//! for (template, expected) in fixtures {
+//!     assert_eq!(render(template, &server, &member), expected);
+//! }
+//! ```
+
+use vived_api::endpoints::{GetServer, MessageCreate};
+use vived_api::{ApiClient, ApiError, GuildedErrorCode};
+use vived_models::{markdown, ChannelId, Color, Embed, Message, Server, ServerId, ServerMember};
+
+/// Where to send the greeting
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WelcomeChannel {
+    /// The default channel of the server
+    Default,
+    /// A specific channel
+    Specific(ChannelId),
+}
+
+/// An embed to send with the greeting, its title and description are templates too
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WelcomeEmbed {
+    /// Title template
+    pub title: String,
+    /// Description template
+    pub description: String,
+    /// Color of the embed
+    pub color: Option<Color>,
+}
+
+/// How to greet new members
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WelcomeConfig {
+    /// Where to send the greeting
+    pub channel: WelcomeChannel,
+    /// Content template
+    pub template: String,
+    /// Embed to send along with the content
+    pub embed: Option<WelcomeEmbed>,
+}
+
+impl WelcomeConfig {
+    /// Greet in the default channel of the server using `template`
+    #[must_use]
+    pub fn new(template: impl Into<String>) -> Self {
+        Self {
+            channel: WelcomeChannel::Default,
+            template: template.into(),
+            embed: None,
+        }
+    }
+
+    /// Greet in a specific channel instead of the default channel
+    #[must_use]
+    pub fn channel(mut self, channel: impl Into<ChannelId>) -> Self {
+        self.channel = WelcomeChannel::Specific(channel.into());
+        self
+    }
+
+    /// Send an embed along with the content
+    #[must_use]
+    pub fn embed(mut self, embed: WelcomeEmbed) -> Self {
+        self.embed = Some(embed);
+        self
+    }
+}
+
+/// Why a member couldn't be greeted
+#[derive(Debug)]
+pub enum WelcomeError {
+    /// [`WelcomeChannel::Default`] was used, but the server has no default channel
+    NoDefaultChannel(ServerId),
+    /// The bot isn't allowed to send the greeting
+    MissingPermissions {
+        /// The channel the greeting was sent to
+        channel: ChannelId,
+        /// The permissions guilded says are missing, can be empty if guilded didn't list them
+        permissions: Vec<String>,
+    },
+    /// Getting the server or sending the greeting failed
+    Api(ApiError),
+}
+
+impl From<ApiError> for WelcomeError {
+    fn from(v: ApiError) -> Self {
+        Self::Api(v)
+    }
+}
+
+impl std::fmt::Display for WelcomeError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::NoDefaultChannel(ref server) => {
+                write!(f, "Server {server} has no default channel to greet members in")
+            }
+            Self::MissingPermissions {
+                ref channel,
+                ref permissions,
+            } => write!(
+                f,
+                "Missing permissions to greet members in {channel}: {}",
+                permissions.join(", ")
+            ),
+            Self::Api(ref e) => write!(f, "Failed to greet member: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for WelcomeError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            Self::Api(ref e) => Some(e),
+            Self::NoDefaultChannel(_) | Self::MissingPermissions { .. } => None,
+        }
+    }
+}
+
+/// Fill in the placeholders of a template, see the [module docs](self)
+///
+/// Replacements are not scanned again, so a name containing `{user}` stays as written.
+#[must_use]
+pub fn render(template: &str, server: &Server, member: &ServerMember) -> String {
+    let placeholders = [
+        ("{user}", format!("<@{}>", member.user.id)),
+        ("{name}", markdown::escape(member.display_name())),
+        ("{server}", markdown::escape(&server.name)),
+        (
+            "{member_count}",
+            server
+                .member_count()
+                .map_or_else(|| "?".to_owned(), |count| count.to_string()),
+        ),
+    ];
+
+    let mut rendered = String::with_capacity(template.len());
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        rest = &rest[start..];
+
+        if let Some(&(placeholder, ref value)) = placeholders
+            .iter()
+            .find(|&&(placeholder, _)| rest.starts_with(placeholder))
+        {
+            rendered.push_str(value);
+            rest = &rest[placeholder.len()..];
+        } else {
+            rendered.push('{');
+            rest = &rest[1..];
+        }
+    }
+    rendered.push_str(rest);
+    rendered
+}
+
+/// Find the channel to greet in
+///
+/// # Errors
+/// [`WelcomeError::NoDefaultChannel`] if the default channel should be used but the server has none
+/// ```
+/// use vived::welcome::{resolve_channel, WelcomeChannel, WelcomeError};
+/// use vived::models::{ChannelId, Server};
+///
+/// let server: Server = serde_json::from_str(r#"{
+///     "id": "s", "ownerId": "o", "name": "Rust", "url": "rust",
+///     "createdAt": "2022-10-01T12:00:00Z", "defaultChannelId": null
+/// }"#).unwrap();
+///
+/// assert!(matches!(resolve_channel(&WelcomeChannel::Default, &server), Err(WelcomeError::NoDefaultChannel(_))));
+/// assert_eq!(
+///     resolve_channel(&WelcomeChannel::Specific(ChannelId::from("c")), &server).unwrap(),
+///     ChannelId::from("c")
+/// );
+/// ```
+pub fn resolve_channel(channel: &WelcomeChannel, server: &Server) -> Result<ChannelId, WelcomeError> {
+    match *channel {
+        WelcomeChannel::Specific(ref channel) => Ok(channel.clone()),
+        WelcomeChannel::Default => server
+            .default_channel_id
+            .clone()
+            .ok_or_else(|| WelcomeError::NoDefaultChannel(server.id.clone())),
+    }
+}
+
+/// The greeting for `member`, without sending it
+///
+/// # Errors
+/// Same as [`resolve_channel`]
+pub fn greeting(
+    server: &Server,
+    member: &ServerMember,
+    config: &WelcomeConfig,
+) -> Result<MessageCreate, WelcomeError> {
+    let channel = resolve_channel(&config.channel, server)?;
+    let mut message = MessageCreate::new(channel);
+
+    if !config.template.is_empty() {
+        message = message.content(render(&config.template, server, member));
+    }
+    if let Some(ref embed) = config.embed {
+        let mut rendered = Embed::new()
+            .title(render(&embed.title, server, member))
+            .description(render(&embed.description, server, member));
+        if let Some(color) = embed.color {
+            rendered = rendered.color(color);
+        }
+        message = message.embed(rendered);
+    }
+
+    Ok(message)
+}
+
+/// Greet a member that joined `server`
+///
+/// `cached` is used instead of requesting the server if it is the right server,
+/// pass `None` if you don't keep servers around.
+///
+/// # Errors
+/// - [`WelcomeError::NoDefaultChannel`] if the default channel should be used but the server has none
+/// - [`WelcomeError::MissingPermissions`] if guilded refused the greeting because of missing permissions
+/// - [`WelcomeError::Api`] if getting the server or sending the greeting failed otherwise
+pub async fn handle_member_joined(
+    client: &ApiClient,
+    cached: Option<&Server>,
+    server: &ServerId,
+    member: &ServerMember,
+    config: &WelcomeConfig,
+) -> Result<Message, WelcomeError> {
+    let fetched;
+    let server = match cached {
+        Some(cached) if &cached.id == server => cached,
+        _ => {
+            fetched = client.make_request(GetServer::new(server.clone())).await?;
+            &fetched
+        }
+    };
+
+    let message = greeting(server, member, config)?;
+    let channel = message.channel_id().clone();
+
+    client.make_request(message).await.map_err(|error| match error {
+        ApiError::Guilded(ref guilded) if guilded.code == GuildedErrorCode::Forbidden => {
+            WelcomeError::MissingPermissions {
+                channel,
+                permissions: guilded
+                    .meta
+                    .as_ref()
+                    .and_then(|meta| meta.get("missingPermissions"))
+                    .and_then(serde_json::Value::as_array)
+                    .map(|permissions| {
+                        permissions
+                            .iter()
+                            .filter_map(serde_json::Value::as_str)
+                            .map(str::to_owned)
+                            .collect()
+                    })
+                    .unwrap_or_default(),
+            }
+        }
+        error => WelcomeError::Api(error),
+    })
+}
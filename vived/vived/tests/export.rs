@@ -0,0 +1,221 @@
+//! Exporting a channel, against a local server that pages by timestamp like guilded does
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde_json::{json, Value};
+use vived::api::{ApiClient, ApiClientConfig};
+use vived::export::{export_channel, write_messages, ExportFormat, ExportOptions, CSV_HEADER};
+use vived::models::Message;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+/// `count` messages, newest first, where every 4 messages in a row share a timestamp
+fn history(count: usize) -> Vec<Value> {
+    let newest = Utc.ymd(2022, 10, 1).and_hms(12, 0, 0);
+    (0..count)
+        .map(|index| {
+            let minutes = i64::try_from(index / 4).unwrap();
+            json!({
+                "id": format!("message-{index}"), "type": "default", "serverId": "s", "channelId": "c",
+                "createdAt": (newest - chrono::Duration::minutes(minutes)).to_rfc3339(),
+                "createdBy": "Ann6LewA"
+            })
+        })
+        .collect()
+}
+
+fn created_at(message: &Value) -> DateTime<Utc> {
+    message["createdAt"].as_str().unwrap().parse().unwrap()
+}
+
+/// Answer a message list request out of `messages`, leaving out those sent at or after `before`
+fn respond(messages: &[Value], request: &Request) -> ResponseTemplate {
+    let mut before: Option<DateTime<Utc>> = None;
+    let mut limit = 50;
+    for (key, value) in request.url.query_pairs() {
+        match &*key {
+            "before" => before = Some(value.parse().unwrap()),
+            "limit" => limit = value.parse().unwrap(),
+            _ => {}
+        }
+    }
+
+    let page: Vec<&Value> = messages
+        .iter()
+        .filter(|message| before.is_none_or(|before| created_at(message) < before))
+        .take(limit)
+        .collect();
+    ResponseTemplate::new(200).set_body_json(json!({ "messages": page }))
+}
+
+/// A client for a local server holding `messages`
+async fn serve(messages: Vec<Value>) -> (MockServer, ApiClient) {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/channels/c/messages"))
+        .respond_with(move |request: &Request| respond(&messages, request))
+        .mount(&server)
+        .await;
+    let config = ApiClientConfig::default().api_origin(server.uri().parse().unwrap());
+    let client = ApiClient::new_with_config("token", config).unwrap();
+    (server, client)
+}
+
+/// Export channel `c`, appending to `written`
+async fn export_to(written: &mut Vec<u8>, client: &ApiClient, options: ExportOptions) {
+    export_channel(client, "c", written, options, |_| {})
+        .await
+        .unwrap();
+}
+
+async fn export(client: &ApiClient, options: ExportOptions) -> Vec<Message> {
+    let mut written = Vec::new();
+    export_to(&mut written, client, options).await;
+    String::from_utf8(written)
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).unwrap())
+        .collect()
+}
+
+/// The ids of the expected messages, newest first
+fn ids(count: usize) -> Vec<String> {
+    (0..count).map(|index| format!("message-{index}")).collect()
+}
+
+#[tokio::test]
+async fn a_resumed_export_writes_every_message_once() {
+    let (_server, client) = serve(history(10)).await;
+
+    // stops halfway through the second group of messages sharing a timestamp
+    let options = ExportOptions::new(ExportFormat::JsonLines).max_messages(6);
+    let mut exported = export(&client, options).await;
+    let oldest = exported.last().unwrap().created_at;
+    let written = exported
+        .iter()
+        .filter(|message| message.created_at == oldest)
+        .map(|message| message.id.clone())
+        .collect::<Vec<_>>();
+    assert_eq!(written.len(), 2);
+
+    let options = ExportOptions::new(ExportFormat::JsonLines).resume_after(oldest, written);
+    exported.extend(export(&client, options).await);
+
+    let exported: Vec<String> = exported.into_iter().map(|message| message.id.0).collect();
+    assert_eq!(exported, ids(10));
+}
+
+#[tokio::test]
+async fn a_resumed_csv_export_appends_without_a_second_header() {
+    let (_server, client) = serve(history(10)).await;
+
+    let mut file = Vec::new();
+    let options = ExportOptions::new(ExportFormat::Csv).max_messages(6);
+    export_to(&mut file, &client, options).await;
+
+    // what a resume would read back from the interrupted file
+    let interrupted = String::from_utf8(file.clone()).unwrap();
+    let rows: Vec<Vec<&str>> = interrupted
+        .lines()
+        .skip(1)
+        .map(|line| line.split(',').collect())
+        .collect();
+    let oldest: DateTime<Utc> = rows.last().unwrap()[1].parse().unwrap();
+    let written: Vec<&str> = rows
+        .iter()
+        .filter(|row| row[1].parse::<DateTime<Utc>>().unwrap() == oldest)
+        .map(|row| row[0])
+        .collect();
+
+    let options = ExportOptions::new(ExportFormat::Csv).resume_after(oldest, written);
+    export_to(&mut file, &client, options).await;
+
+    let file = String::from_utf8(file).unwrap();
+    let mut lines = file.lines();
+    assert_eq!(lines.next(), Some(CSV_HEADER));
+    let exported: Vec<&str> = lines.map(|line| line.split(',').next().unwrap()).collect();
+    assert_eq!(exported, ids(10));
+}
+
+#[tokio::test]
+async fn the_csv_header_can_be_turned_off_and_back_on() {
+    let (_server, client) = serve(history(2)).await;
+    let rows = |file: &[u8]| -> Vec<String> {
+        String::from_utf8(file.to_vec())
+            .unwrap()
+            .lines()
+            .map(|line| line.split(',').next().unwrap().to_owned())
+            .collect()
+    };
+
+    let mut file = Vec::new();
+    let options = ExportOptions::new(ExportFormat::Csv).csv_header(false);
+    export_to(&mut file, &client, options).await;
+    assert_eq!(rows(&file), ids(2));
+
+    let mut file = Vec::new();
+    let newest = Utc.ymd(2022, 10, 1).and_hms(12, 0, 0);
+    let options = ExportOptions::new(ExportFormat::Csv)
+        .resume_after(newest, ["message-0"])
+        .csv_header(true);
+    export_to(&mut file, &client, options).await;
+    assert_eq!(rows(&file), ["id", "message-1"]);
+}
+
+#[tokio::test]
+async fn csv_fields_are_quoted_only_when_needed() {
+    let contents = [
+        "plain",
+        "a, b",
+        "say \"hi\"",
+        "first\nsecond",
+        "first\r\nsecond",
+        "",
+    ];
+    let messages = contents.iter().enumerate().map(|(index, content)| {
+        let mut message = history(1).remove(0);
+        message["id"] = format!("message-{index}").into();
+        message["content"] = (*content).into();
+        Ok(serde_json::from_value::<Message>(message).unwrap())
+    });
+
+    let mut file = Vec::new();
+    let count = write_messages(
+        &mut file,
+        futures_util::stream::iter(messages),
+        ExportFormat::Csv,
+        |_| {},
+    )
+    .await
+    .unwrap();
+    assert_eq!(count, contents.len());
+
+    let file = String::from_utf8(file).unwrap();
+    let rows = file.strip_prefix(&format!("{CSV_HEADER}\n")).unwrap();
+    let prefix = "2022-10-01T12:00:00+00:00,Ann6LewA,false";
+    let suffix = "0,,";
+    let expected: String = [
+        "plain",
+        "\"a, b\"",
+        "\"say \"\"hi\"\"\"",
+        "\"first\nsecond\"",
+        "\"first\r\nsecond\"",
+        "",
+    ]
+    .iter()
+    .enumerate()
+    .map(|(index, content)| format!("message-{index},{prefix},{content},{suffix}\n"))
+    .collect();
+    assert_eq!(rows, expected);
+}
+
+#[tokio::test]
+async fn json_lines_keep_the_whole_message() {
+    let mut message = history(1).remove(0);
+    message["content"] = "a, \"quoted\"\nmessage".into();
+    message["embeds"] = json!([{"title": "Title"}]);
+    let (_server, client) = serve(vec![message.clone()]).await;
+
+    let exported = export(&client, ExportOptions::new(ExportFormat::JsonLines)).await;
+    let expected: Message = serde_json::from_value(message).unwrap();
+    assert_eq!(exported, [expected]);
+}
@@ -0,0 +1,270 @@
+//! Batching, signing and buffering of the forwarder, and what it does with the answers of a local receiver
+
+use std::time::{Duration, Instant};
+
+use hmac::{Hmac, Mac};
+use serde_json::Value;
+use sha2::Sha256;
+use tokio::sync::broadcast;
+use vived::forwarder::{
+    forward_events, Forwarder, ForwarderConfig, ForwarderStats, FORWARD_SCHEMA_VERSION,
+    SIGNATURE_HEADER,
+};
+use vived::ws::events::GuildedEvent;
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+/// Longest a test waits for the forwarder
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A role deleted event for the role with id `id`
+fn event(id: u64) -> GuildedEvent {
+    serde_json::from_value(serde_json::json!({
+        "op": 0, "t": "RoleDeleted", "s": "SGVsbG8gV29ybGQj",
+        "d": {"serverId": "wlVr3Ggl", "role": {
+            "id": id, "serverId": "wlVr3Ggl", "createdAt": "2022-10-26T00:00:00.000Z",
+            "name": "Senior Moderators", "position": 4
+        }}
+    }))
+    .unwrap()
+}
+
+/// The role ids of the events in every batch `server` received, in order
+async fn batches(server: &MockServer) -> Vec<Vec<u64>> {
+    server
+        .received_requests()
+        .await
+        .unwrap()
+        .iter()
+        .map(|request| {
+            let body: Value = serde_json::from_slice(&request.body).unwrap();
+            assert_eq!(body["version"], FORWARD_SCHEMA_VERSION);
+            body["events"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|event| event["d"]["role"]["id"].as_u64().unwrap())
+                .collect()
+        })
+        .collect()
+}
+
+/// A receiver that accepts every batch
+async fn accepting() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .mount(&server)
+        .await;
+    server
+}
+
+/// Forward one event to `server`
+fn forward_one(server: &MockServer) -> Forwarder {
+    let (events, receiver) = broadcast::channel(16);
+    let config = ForwarderConfig::new(server.uri()).batch_interval(Duration::from_millis(10));
+    let forwarder = forward_events(receiver, config);
+    events.send(event(0)).unwrap();
+    forwarder
+}
+
+/// Wait until the stats of `forwarder` satisfy `done`
+async fn wait_for(forwarder: &Forwarder, done: impl Fn(ForwarderStats) -> bool) -> ForwarderStats {
+    let start = Instant::now();
+    loop {
+        let stats = forwarder.stats();
+        if done(stats) {
+            return stats;
+        }
+        assert!(start.elapsed() < TIMEOUT, "timed out at {stats:?}");
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+}
+
+#[tokio::test]
+async fn server_errors_are_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .with_priority(2)
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let forwarder = forward_one(&server);
+    let stats = wait_for(&forwarder, |stats| stats.forwarded == 1).await;
+    assert_eq!(stats.failed_requests, 1);
+    assert_eq!(stats.rejected, 0);
+}
+
+#[tokio::test]
+async fn rejected_batches_are_dropped() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(400))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let forwarder = forward_one(&server);
+    let stats = wait_for(&forwarder, |stats| stats.rejected == 1).await;
+    assert_eq!(stats.forwarded, 0);
+    assert_eq!(stats.failed_requests, 0);
+
+    // nothing is waiting to be retried
+    tokio::time::sleep(Duration::from_millis(700)).await;
+    assert_eq!(forwarder.buffered(), 0);
+    assert_eq!(server.received_requests().await.unwrap().len(), 1);
+}
+
+#[tokio::test]
+async fn unreachable_sinks_are_retried() {
+    // take a free port and close it again, so connecting to it fails
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let address = listener.local_addr().unwrap();
+    drop(listener);
+
+    let (events, receiver) = broadcast::channel(16);
+    let config = ForwarderConfig::new(format!("http://{address}"));
+    let forwarder = forward_events(receiver, config);
+    events.send(event(0)).unwrap();
+
+    let stats = wait_for(&forwarder, |stats| stats.failed_requests >= 2).await;
+    assert_eq!(stats.rejected, 0);
+    assert_eq!(stats.forwarded, 0);
+}
+
+#[tokio::test]
+async fn full_batches_are_sent_without_waiting_for_the_interval() {
+    let server = accepting().await;
+    let (events, receiver) = broadcast::channel(16);
+    let config = ForwarderConfig::new(server.uri())
+        .batch_size(3)
+        .batch_interval(Duration::from_secs(3600));
+    let forwarder = forward_events(receiver, config);
+
+    for id in 0..3 {
+        events.send(event(id)).unwrap();
+    }
+    wait_for(&forwarder, |stats| stats.forwarded == 3).await;
+    assert_eq!(batches(&server).await, [[0, 1, 2]]);
+
+    // less than a batch waits for the interval
+    events.send(event(3)).unwrap();
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(forwarder.buffered(), 1);
+    assert_eq!(batches(&server).await.len(), 1);
+
+    events.send(event(4)).unwrap();
+    events.send(event(5)).unwrap();
+    wait_for(&forwarder, |stats| stats.forwarded == 6).await;
+    assert_eq!(batches(&server).await, [[0, 1, 2], [3, 4, 5]]);
+}
+
+#[tokio::test]
+async fn partial_batches_are_sent_after_the_interval() {
+    let server = accepting().await;
+    let (events, receiver) = broadcast::channel(16);
+    let interval = Duration::from_millis(400);
+    let started = Instant::now();
+    let config = ForwarderConfig::new(server.uri())
+        .batch_size(50)
+        .batch_interval(interval);
+    let forwarder = forward_events(receiver, config);
+
+    events.send(event(0)).unwrap();
+    events.send(event(1)).unwrap();
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    assert!(batches(&server).await.is_empty());
+
+    wait_for(&forwarder, |stats| stats.forwarded == 2).await;
+    assert!(
+        started.elapsed() >= interval,
+        "sent after {:?}",
+        started.elapsed()
+    );
+    assert_eq!(batches(&server).await, [[0, 1]]);
+}
+
+#[tokio::test]
+async fn bodies_are_signed_with_the_secret() {
+    let server = accepting().await;
+    let (events, receiver) = broadcast::channel(16);
+    let config = ForwarderConfig::new(server.uri())
+        .secret("shared secret")
+        .batch_interval(Duration::from_millis(10));
+    let forwarder = forward_events(receiver, config);
+    events.send(event(0)).unwrap();
+    wait_for(&forwarder, |stats| stats.forwarded == 1).await;
+
+    // checked the way a receiver would, over the raw body
+    let requests: Vec<Request> = server.received_requests().await.unwrap();
+    let signature = requests[0].headers.get(&SIGNATURE_HEADER.into()).unwrap()[0].as_str();
+    let digest = signature.strip_prefix("sha256=").unwrap();
+    let digest: Vec<u8> = (0..digest.len())
+        .step_by(2)
+        .map(|index| u8::from_str_radix(&digest[index..index + 2], 16).unwrap())
+        .collect();
+
+    let mut mac = <Hmac<Sha256> as Mac>::new_from_slice(b"shared secret").unwrap();
+    mac.update(&requests[0].body);
+    mac.verify_slice(&digest).unwrap();
+
+    let mut wrong = <Hmac<Sha256> as Mac>::new_from_slice(b"another secret").unwrap();
+    wrong.update(&requests[0].body);
+    assert!(wrong.verify_slice(&digest).is_err());
+}
+
+#[tokio::test]
+async fn unsigned_bodies_have_no_signature() {
+    let server = accepting().await;
+    let forwarder = forward_one(&server);
+    wait_for(&forwarder, |stats| stats.forwarded == 1).await;
+
+    let requests = server.received_requests().await.unwrap();
+    assert!(!requests[0].headers.contains_key(&SIGNATURE_HEADER.into()));
+}
+
+#[tokio::test]
+async fn a_full_buffer_drops_the_oldest_events() {
+    let server = MockServer::start().await;
+    // the first batch is retried after half a second, the events sent meanwhile pile up
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(503))
+        .up_to_n_times(1)
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(200))
+        .with_priority(2)
+        .mount(&server)
+        .await;
+
+    let (events, receiver) = broadcast::channel(64);
+    let config = ForwarderConfig::new(server.uri())
+        .batch_interval(Duration::from_millis(10))
+        .buffer_capacity(5);
+    let forwarder = forward_events(receiver, config);
+
+    events.send(event(0)).unwrap();
+    wait_for(&forwarder, |stats| stats.failed_requests == 1).await;
+    for id in 1..=20 {
+        events.send(event(id)).unwrap();
+    }
+    drop(events);
+
+    let stats = wait_for(&forwarder, |stats| stats.forwarded == 6).await;
+    assert_eq!(stats.dropped, 15);
+    assert_eq!(forwarder.buffered(), 0);
+
+    let sent: Vec<u64> = batches(&server).await.concat();
+    assert_eq!(sent, [0, 0, 16, 17, 18, 19, 20]);
+}
@@ -0,0 +1,88 @@
+//! Ranking ties and servers without any xp
+
+use vived::leaderboard::{Leaderboard, LeaderboardEntry, MemoryXpStore, XpStore};
+use vived_api::ApiClient;
+use vived_models::UserId;
+
+/// A leaderboard with the given totals, no requests are made
+fn leaderboard(totals: &[(&str, i64)]) -> Leaderboard {
+    let store = MemoryXpStore::default();
+    for &(user, total) in totals {
+        store.set_total(&UserId::from(user), total);
+    }
+    Leaderboard::with_store(ApiClient::new("token").unwrap(), "server", store)
+}
+
+fn entry(rank: usize, user: &str, xp: i64) -> LeaderboardEntry {
+    LeaderboardEntry {
+        rank,
+        user: UserId::from(user),
+        xp,
+    }
+}
+
+#[test]
+fn ties_share_a_rank_and_skip_the_next() {
+    let leaderboard = leaderboard(&[("d", 5), ("b", 20), ("c", 20), ("a", 30), ("e", 5)]);
+
+    assert_eq!(
+        leaderboard.top_n(10),
+        [
+            entry(1, "a", 30),
+            entry(2, "b", 20),
+            entry(2, "c", 20),
+            entry(4, "d", 5),
+            entry(4, "e", 5),
+        ]
+    );
+    assert_eq!(leaderboard.rank_of(&UserId::from("c")), Some(2));
+    assert_eq!(leaderboard.rank_of(&UserId::from("e")), Some(4));
+}
+
+#[test]
+fn top_n_cuts_through_a_tie_by_id() {
+    let leaderboard = leaderboard(&[("b", 10), ("a", 10), ("c", 10)]);
+
+    assert_eq!(leaderboard.top_n(2), [entry(1, "a", 10), entry(1, "b", 10)]);
+}
+
+#[test]
+fn negative_totals_rank_below_zero() {
+    let leaderboard = leaderboard(&[("a", -5), ("b", 0)]);
+
+    assert_eq!(leaderboard.top_n(2), [entry(1, "b", 0), entry(2, "a", -5)]);
+}
+
+#[test]
+fn everyone_tied_for_first_gets_a_gold_medal() {
+    let leaderboard = leaderboard(&[("a", 10), ("b", 10), ("c", 10), ("d", 1)]);
+
+    let embed = leaderboard.embed(10, |user| user.0.clone());
+    assert_eq!(
+        embed.description.as_deref(),
+        Some("🥇 a: 10 xp\n🥇 b: 10 xp\n🥇 c: 10 xp\n#4 d: 1 xp")
+    );
+}
+
+#[test]
+fn empty_server() {
+    let leaderboard = leaderboard(&[]);
+
+    assert_eq!(leaderboard.top_n(3), []);
+    assert_eq!(leaderboard.rank_of(&UserId::from("a")), None);
+
+    let embed = leaderboard.embed(3, |user| user.0.clone());
+    assert_eq!(embed.title.as_deref(), Some("Leaderboard"));
+    assert_eq!(embed.description.as_deref(), Some("No one has earned any xp yet"));
+}
+
+#[test]
+fn top_zero_is_empty() {
+    let leaderboard = leaderboard(&[("a", 1)]);
+
+    assert_eq!(leaderboard.top_n(0), []);
+    assert_eq!(
+        leaderboard.embed(0, |user| user.0.clone()).description.as_deref(),
+        Some("No one has earned any xp yet")
+    );
+}
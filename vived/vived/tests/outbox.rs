@@ -0,0 +1,192 @@
+//! Killing the outbox between sending and forgetting a message, then starting it again on the same file
+
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde_json::{json, Value};
+use tokio::runtime::Runtime;
+use vived::api::endpoints::MessageCreate;
+use vived::api::{ApiClient, ApiClientConfig};
+use vived::outbox::{JsonFileOutboxStore, Outbox, OutboxStore};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+/// Longest any step of a test may take
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Where messages of channel `c` are posted and listed
+const MESSAGES: &str = "/api/v1/channels/c/messages";
+
+/// Guilded as far as the outbox sees it: messages are kept once accepted, and show up in the history
+struct Guilded {
+    server: MockServer,
+    /// Runs the server, kept apart from the runtimes the outbox is killed with
+    _runtime: Runtime,
+    /// Accepted messages, oldest first
+    accepted: Arc<Mutex<Vec<Value>>>,
+    /// Every message post, accepted or not
+    posts: Arc<AtomicUsize>,
+}
+
+impl Guilded {
+    /// Fail the first `failures` posts, and answer accepted ones after `delay`
+    fn start(failures: usize, delay: Duration) -> Self {
+        let runtime = Runtime::new().unwrap();
+        let server = runtime.block_on(MockServer::start());
+        let accepted = Arc::new(Mutex::new(Vec::new()));
+        let posts = Arc::new(AtomicUsize::new(0));
+
+        let (stored, count) = (Arc::clone(&accepted), Arc::clone(&posts));
+        let post = move |request: &Request| {
+            if count.fetch_add(1, Ordering::SeqCst) < failures {
+                return ResponseTemplate::new(500)
+                    .set_body_json(json!({"code": "InternalServerError", "message": "try again"}));
+            }
+            let body: Value = serde_json::from_slice(&request.body).unwrap();
+            let message = json!({
+                "id": format!("message-{}", stored.lock().unwrap().len()), "type": "default",
+                "serverId": "s", "channelId": "c", "content": body["content"],
+                "createdAt": chrono::Utc::now().to_rfc3339(), "createdBy": "Ann6LewA"
+            });
+            stored.lock().unwrap().push(message.clone());
+            ResponseTemplate::new(200)
+                .set_body_json(json!({ "message": message }))
+                .set_delay(delay)
+        };
+
+        let history = Arc::clone(&accepted);
+        let get = move |_: &Request| {
+            let newest_first: Vec<Value> = history.lock().unwrap().iter().rev().cloned().collect();
+            ResponseTemplate::new(200).set_body_json(json!({ "messages": newest_first }))
+        };
+
+        runtime.block_on(async {
+            Mock::given(method("POST"))
+                .and(path(MESSAGES))
+                .respond_with(post)
+                .mount(&server)
+                .await;
+            Mock::given(method("GET"))
+                .and(path(MESSAGES))
+                .respond_with(get)
+                .mount(&server)
+                .await;
+        });
+
+        Self {
+            server,
+            _runtime: runtime,
+            accepted,
+            posts,
+        }
+    }
+
+    /// Start an outbox on `store` in a runtime of its own, so it can be killed by shutting that down
+    fn outbox(&self, store: &Arc<JsonFileOutboxStore>) -> (Runtime, Outbox) {
+        let config = ApiClientConfig::default().api_origin(self.server.uri().parse().unwrap());
+        let runtime = Runtime::new().unwrap();
+        let outbox = runtime.block_on(async {
+            let client = ApiClient::new_with_config("token", config).unwrap();
+            let store: Arc<dyn OutboxStore> = Arc::clone(store) as _;
+            Outbox::resume(store, client).unwrap()
+        });
+        (runtime, outbox)
+    }
+
+    fn contents(&self) -> Vec<String> {
+        self.accepted
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|message| message["content"].as_str().unwrap().to_owned())
+            .collect()
+    }
+}
+
+/// Wait until `done` returns `true`
+fn wait_for(mut done: impl FnMut() -> bool) {
+    let start = std::time::Instant::now();
+    while !done() {
+        assert!(start.elapsed() < TIMEOUT, "timed out");
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+/// A store file no other test uses
+fn store(name: &str) -> (PathBuf, Arc<JsonFileOutboxStore>) {
+    let path =
+        std::env::temp_dir().join(format!("vived-outbox-{name}-{}.json", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+    let store = Arc::new(JsonFileOutboxStore::new(&path));
+    (path, store)
+}
+
+#[test]
+fn a_message_accepted_before_the_kill_is_not_sent_again() {
+    // the answer never arrives before the kill
+    let guilded = Guilded::start(0, Duration::from_secs(60));
+    let (path, store) = store("accepted");
+
+    let (runtime, outbox) = guilded.outbox(&store);
+    outbox
+        .enqueue(MessageCreate::new_with_content("c", "payment failed"))
+        .unwrap();
+    wait_for(|| guilded.posts.load(Ordering::SeqCst) == 1);
+    runtime.shutdown_background();
+    assert_eq!(store.load().unwrap().len(), 1);
+
+    // a new store on the same file, like after a restart
+    let restarted = Arc::new(JsonFileOutboxStore::new(&path));
+    let (runtime, _outbox) = guilded.outbox(&restarted);
+    wait_for(|| restarted.load().unwrap().is_empty());
+    runtime.shutdown_background();
+
+    assert_eq!(guilded.posts.load(Ordering::SeqCst), 1);
+    let contents = guilded.contents();
+    assert_eq!(contents.len(), 1);
+    assert!(contents[0].starts_with("payment failed"));
+    std::fs::remove_file(path).unwrap();
+}
+
+#[test]
+fn a_message_killed_while_waiting_to_retry_is_sent_once() {
+    let guilded = Guilded::start(1, Duration::ZERO);
+    let (path, store) = store("retry");
+
+    let (runtime, outbox) = guilded.outbox(&store);
+    outbox
+        .enqueue(MessageCreate::new_with_content("c", "payment failed"))
+        .unwrap();
+    // the first post failed, so the outbox is waiting before it tries again
+    wait_for(|| guilded.posts.load(Ordering::SeqCst) == 1);
+    runtime.shutdown_background();
+    assert!(guilded.contents().is_empty());
+
+    let restarted = Arc::new(JsonFileOutboxStore::new(&path));
+    let (runtime, _outbox) = guilded.outbox(&restarted);
+    wait_for(|| restarted.load().unwrap().is_empty());
+    runtime.shutdown_background();
+
+    assert_eq!(guilded.posts.load(Ordering::SeqCst), 2);
+    assert_eq!(guilded.contents().len(), 1);
+    let _ = std::fs::remove_file(path);
+}
+
+#[test]
+fn messages_too_long_for_the_key_are_refused() {
+    let guilded = Guilded::start(0, Duration::ZERO);
+    let (path, store) = store("too-long");
+    let (runtime, outbox) = guilded.outbox(&store);
+
+    let content = "a".repeat(vived::models::limits::MESSAGE_CONTENT);
+    let error = outbox
+        .enqueue(MessageCreate::new_with_content("c", content))
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    assert!(store.load().unwrap().is_empty());
+
+    runtime.shutdown_background();
+    let _ = std::fs::remove_file(path);
+}
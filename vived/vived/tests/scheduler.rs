@@ -0,0 +1,158 @@
+//! When repeating jobs come around, and what is kept in a schedule store
+
+use std::time::Duration;
+
+use chrono::{DateTime, NaiveTime, TimeZone, Utc};
+use vived::api::endpoints::MessageCreate;
+use vived::api::{ApiClient, ApiClientConfig, RatelimitWeight};
+use vived::scheduler::{Repeat, ScheduleId, Scheduler, StoredJob};
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn start() -> DateTime<Utc> {
+    Utc.ymd(2022, 6, 1).and_hms(12, 0, 0)
+}
+
+fn seconds(seconds: i64) -> chrono::Duration {
+    chrono::Duration::seconds(seconds)
+}
+
+#[test]
+fn intervals_count_from_the_previous_deadline() {
+    let repeat = Repeat::Interval(Duration::from_secs(60));
+    assert_eq!(
+        repeat.next_after(start(), start() + seconds(10)),
+        Some(start() + seconds(60))
+    );
+}
+
+#[test]
+fn a_deadline_equal_to_now_is_already_passed() {
+    let repeat = Repeat::Interval(Duration::from_secs(60));
+    assert_eq!(
+        repeat.next_after(start(), start() + seconds(60)),
+        Some(start() + seconds(120))
+    );
+}
+
+#[test]
+fn missed_runs_are_skipped() {
+    let repeat = Repeat::Interval(Duration::from_secs(60));
+    assert_eq!(
+        repeat.next_after(start(), start() + seconds(185)),
+        Some(start() + seconds(240))
+    );
+}
+
+#[test]
+fn zero_intervals_repeat_every_second() {
+    let repeat = Repeat::Interval(Duration::ZERO);
+    assert_eq!(
+        repeat.next_after(start(), start()),
+        Some(start() + seconds(1))
+    );
+}
+
+#[test]
+fn daily_runs_later_today() {
+    let repeat = Repeat::Daily(NaiveTime::from_hms(18, 30, 0));
+    assert_eq!(
+        repeat.first_after(start()),
+        Some(Utc.ymd(2022, 6, 1).and_hms(18, 30, 0))
+    );
+}
+
+#[test]
+fn daily_runs_tomorrow_once_the_time_passed() {
+    let repeat = Repeat::Daily(NaiveTime::from_hms(9, 0, 0));
+    assert_eq!(
+        repeat.first_after(start()),
+        Some(Utc.ymd(2022, 6, 2).and_hms(9, 0, 0))
+    );
+
+    let repeat = Repeat::Daily(NaiveTime::from_hms(12, 0, 0));
+    assert_eq!(
+        repeat.first_after(start()),
+        Some(Utc.ymd(2022, 6, 2).and_hms(12, 0, 0))
+    );
+}
+
+#[test]
+fn deadlines_past_the_end_of_time_stop_the_job() {
+    let end = DateTime::<Utc>::MAX_UTC;
+
+    assert_eq!(Repeat::Interval(Duration::MAX).first_after(start()), None);
+    assert_eq!(
+        Repeat::Interval(Duration::from_secs(3600)).next_after(end - seconds(60), end),
+        None
+    );
+    assert_eq!(
+        Repeat::Interval(Duration::from_secs(60)).next_after(DateTime::<Utc>::MIN_UTC, end),
+        None
+    );
+    assert_eq!(
+        Repeat::Daily(NaiveTime::from_hms(0, 0, 0)).first_after(end),
+        None
+    );
+}
+
+#[tokio::test]
+async fn jobs_that_never_come_around_are_not_scheduled() {
+    let scheduler = Scheduler::new(ApiClient::new("token").unwrap());
+    let mut handle = scheduler.schedule_repeating(Repeat::Interval(Duration::MAX), || {
+        MessageCreate::new_with_content("channel", "never")
+    });
+
+    assert!(handle.next_result().await.is_none());
+}
+
+#[test]
+fn stored_jobs_keep_their_message() {
+    let job = StoredJob {
+        id: ScheduleId(3),
+        at: start(),
+        message: MessageCreate::new_with_content("channel", "hello").silent(true),
+        repeat: Some(Repeat::Daily(NaiveTime::from_hms(9, 0, 0))),
+    };
+
+    let json = serde_json::to_value(&job).unwrap();
+    assert_eq!(json["message"]["channel"], "channel");
+    assert_eq!(json["message"]["arguments"]["content"], "hello");
+
+    let loaded: StoredJob = serde_json::from_value(json).unwrap();
+    assert_eq!(loaded.message.channel_id().0, "channel");
+    assert_eq!(loaded.message.content_text(), Some("hello"));
+    assert_eq!(
+        serde_json::to_value(loaded.message.arguments()).unwrap()["isSilent"],
+        true
+    );
+}
+
+#[tokio::test]
+async fn jobs_still_run_after_the_scheduler_is_dropped() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(201))
+        .mount(&server)
+        .await;
+    let config = ApiClientConfig::default()
+        .api_origin(server.uri().parse().unwrap())
+        .permit_hold(RatelimitWeight::Normal, Duration::ZERO);
+    let scheduler = Scheduler::new(ApiClient::new_with_config("token", config).unwrap());
+
+    let handle = scheduler.schedule(
+        Utc::now() + chrono::Duration::milliseconds(300),
+        MessageCreate::new_with_content("channel", "still sent"),
+    );
+    drop(handle);
+    drop(scheduler);
+
+    let started = std::time::Instant::now();
+    while server.received_requests().await.unwrap().is_empty() {
+        assert!(
+            started.elapsed() < Duration::from_secs(5),
+            "the job never ran"
+        );
+        tokio::time::sleep(Duration::from_millis(20)).await;
+    }
+}
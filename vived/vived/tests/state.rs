@@ -0,0 +1,161 @@
+//! Sharing `State` between handlers running at the same time
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Barrier, Mutex};
+
+use vived::state::State;
+use vived_models::ServerId;
+
+#[derive(Debug, Default)]
+struct Counter(AtomicUsize);
+
+#[test]
+fn server_state_is_created_once_under_contention() {
+    let state = State::new();
+    let created = Arc::new(AtomicUsize::new(0));
+    let factory_created = Arc::clone(&created);
+    state.register_server_state(move |_: &ServerId| {
+        factory_created.fetch_add(1, Ordering::SeqCst);
+        Counter::default()
+    });
+
+    let barrier = Arc::new(Barrier::new(16));
+    let handles: Vec<_> = (0..16)
+        .map(|_| {
+            let state = state.clone();
+            let barrier = Arc::clone(&barrier);
+            std::thread::spawn(move || {
+                barrier.wait();
+                state.server_state::<Counter>(&ServerId::from("s")).unwrap()
+            })
+        })
+        .collect();
+    let values: Vec<Arc<Counter>> = handles
+        .into_iter()
+        .map(|handle| handle.join().unwrap())
+        .collect();
+
+    assert_eq!(created.load(Ordering::SeqCst), 1);
+    assert!(values.iter().all(|value| Arc::ptr_eq(value, &values[0])));
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn handlers_update_shared_state() {
+    let state = State::new();
+    state.insert(Mutex::new(Vec::<usize>::new()));
+    state.register_server_state(|_: &ServerId| Counter::default());
+
+    let handlers: Vec<_> = (0..8)
+        .map(|handler| {
+            let state = state.clone();
+            tokio::spawn(async move {
+                for event in 0..100 {
+                    let server = ServerId::from(if event % 2 == 0 { "even" } else { "odd" });
+                    state
+                        .server_state::<Counter>(&server)
+                        .unwrap()
+                        .0
+                        .fetch_add(1, Ordering::SeqCst);
+                    state
+                        .get::<Mutex<Vec<usize>>>()
+                        .unwrap()
+                        .lock()
+                        .unwrap()
+                        .push(handler);
+                    tokio::task::yield_now().await;
+                }
+            })
+        })
+        .collect();
+    for handler in handlers {
+        handler.await.unwrap();
+    }
+
+    let count = |server: &str| {
+        state
+            .server_state::<Counter>(&ServerId::from(server))
+            .unwrap()
+            .0
+            .load(Ordering::SeqCst)
+    };
+    assert_eq!(count("even"), 400);
+    assert_eq!(count("odd"), 400);
+    assert_eq!(
+        state
+            .get::<Mutex<Vec<usize>>>()
+            .unwrap()
+            .lock()
+            .unwrap()
+            .len(),
+        800
+    );
+}
+
+#[test]
+fn removing_a_server_while_it_is_used() {
+    let state = State::new();
+    state.register_server_state(|_: &ServerId| Counter::default());
+    let server = ServerId::from("s");
+
+    let barrier = Arc::new(Barrier::new(5));
+    let users: Vec<_> = (0..4)
+        .map(|_| {
+            let state = state.clone();
+            let server = server.clone();
+            let barrier = Arc::clone(&barrier);
+            std::thread::spawn(move || {
+                barrier.wait();
+                for _ in 0..1000 {
+                    state
+                        .server_state::<Counter>(&server)
+                        .unwrap()
+                        .0
+                        .fetch_add(1, Ordering::SeqCst);
+                }
+            })
+        })
+        .collect();
+    barrier.wait();
+    for _ in 0..100 {
+        state.remove_server(&server);
+    }
+    for user in users {
+        user.join().unwrap();
+    }
+
+    // whatever survived the removals is a single fresh value, not a mix
+    let first = state.server_state::<Counter>(&server).unwrap();
+    let again = state.server_state::<Counter>(&server).unwrap();
+    assert!(Arc::ptr_eq(&first, &again));
+
+    state.remove_server(&server);
+    let fresh = state.server_state::<Counter>(&server).unwrap();
+    assert_eq!(fresh.0.load(Ordering::SeqCst), 0);
+}
+
+#[test]
+fn values_of_different_types_do_not_collide() {
+    let state = State::new();
+    let writers: Vec<_> = (0..8_u32)
+        .map(|i| {
+            let state = state.clone();
+            std::thread::spawn(move || {
+                if i % 2 == 0 {
+                    state.insert(i);
+                } else {
+                    state.insert(i.to_string());
+                }
+            })
+        })
+        .collect();
+    for writer in writers {
+        writer.join().unwrap();
+    }
+
+    assert_eq!(*state.get::<u32>().unwrap() % 2, 0);
+    assert_eq!(
+        state.get::<String>().unwrap().parse::<u32>().unwrap() % 2,
+        1
+    );
+    assert!(state.get::<u64>().is_none());
+}
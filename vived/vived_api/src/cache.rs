@@ -0,0 +1,103 @@
+//! Caching wrapper around the api client
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use tokio::sync::RwLock;
+use vived_models::{RoleId, ServerId, ServerMember, UserId};
+
+use crate::endpoints::GetServerMember;
+use crate::{ApiClient, ApiError};
+
+/// An [`ApiClient`] that caches server members
+///
+/// Derefs to the inner [`ApiClient`], so every other request is made as normal.
+/// Call [`CachedClient::invalidate_member`] when you receive a member updated event,
+/// and [`CachedClient::invalidate_role`] when a role changes, so stale members aren't served until the ttl runs out.
+#[derive(Debug)]
+pub struct CachedClient {
+    /// The wrapped client
+    client: ApiClient,
+    /// How long a cached member is used for
+    ttl: Duration,
+    /// Cached members, along with when they were fetched
+    members: RwLock<HashMap<(ServerId, UserId), (ServerMember, Instant)>>,
+}
+
+impl CachedClient {
+    /// Wrap a client, caching members for `ttl`
+    #[must_use]
+    pub fn new(client: ApiClient, ttl: Duration) -> Self {
+        Self {
+            client,
+            ttl,
+            members: RwLock::new(HashMap::new()),
+        }
+    }
+
+    /// Get a server member, using the cache if the entry is younger than the ttl
+    ///
+    /// # Errors
+    /// Same as [`ApiClient::make_request`]
+    pub async fn get_member(
+        &self,
+        server: impl Into<ServerId>,
+        user: impl Into<UserId>,
+    ) -> Result<ServerMember, ApiError> {
+        let key = (server.into(), user.into());
+
+        if let Some(&(ref member, fetched_at)) = self.members.read().await.get(&key) {
+            if fetched_at.elapsed() < self.ttl {
+                return Ok(member.clone());
+            }
+        }
+
+        let member = self
+            .client
+            .make_request(GetServerMember::new(key.0.clone(), key.1.clone()))
+            .await?;
+        self.members
+            .write()
+            .await
+            .insert(key, (member.clone(), Instant::now()));
+
+        Ok(member)
+    }
+
+    /// Remove a member from the cache, so the next lookup fetches it again
+    pub async fn invalidate_member(&self, server: &ServerId, user: &UserId) {
+        self.members
+            .write()
+            .await
+            .remove(&(server.clone(), user.clone()));
+    }
+
+    /// Remove every cached member of a server that has the given role
+    ///
+    /// Call this on role updated and deleted events, so permissions aren't resolved from stale roles.
+    pub async fn invalidate_role(&self, server: &ServerId, role: RoleId) {
+        self.members
+            .write()
+            .await
+            .retain(|key, cached| &key.0 != server || !cached.0.role_ids.contains(&role));
+    }
+
+    /// Remove every cached member
+    pub async fn clear(&self) {
+        self.members.write().await.clear();
+    }
+
+    /// Unwrap the inner client, dropping the cache
+    #[must_use]
+    pub fn into_inner(self) -> ApiClient {
+        self.client
+    }
+}
+
+impl std::ops::Deref for CachedClient {
+    type Target = ApiClient;
+
+    fn deref(&self) -> &Self::Target {
+        &self.client
+    }
+}
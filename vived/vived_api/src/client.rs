@@ -1,9 +1,18 @@
 //! Ratelimiter and error handling client
 
+use futures_util::Stream;
 use serde::Deserialize;
-use std::sync::Arc;
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
 use std::{future::Future, time::Duration};
-use tokio::sync::{RwLock, Semaphore};
+use tokio::sync::Semaphore;
+use vived_models::{ChannelId, Message};
+
+use crate::search::SearchOptions;
+use crate::{GuildedErrorCode, ResponseMeta};
 
 use log::{debug, error, info, trace, warn};
 
@@ -12,16 +21,139 @@ use log::{debug, error, info, trace, warn};
 const CONCURRENT_REQUEST: usize = 30;
 /// How many seconds should the request permit be locked down after a request
 const LOCK_HOLD_DURATION: u64 = 30;
+/// How many seconds should the request permit be locked down after a light request
+const LIGHT_LOCK_HOLD_DURATION: u64 = 15;
+/// Default for how long a request can wait for a permit before we warn about it
+const STARVATION_THRESHOLD: u64 = 5;
+/// Default for how many bytes of a body are logged before it is cut off
+const MAX_LOGGED_BODY: usize = 4096;
+/// Default for how many messages `broadcast_message` sends at once
+const BROADCAST_CONCURRENCY: usize = 3;
+/// Default for how many times an idempotent request is retried after a transient network error
+const TRANSIENT_RETRIES: usize = 3;
+/// Backoff before the first transient retry, in milliseconds, doubled for each following retry
+const TRANSIENT_BACKOFF_MS: u64 = 200;
+/// Max random jitter added to the transient backoff, in milliseconds
+const TRANSIENT_JITTER_MS: u32 = 100;
+/// Backoff added for each retry in [`ApiClient::make_request_with_retry`], in milliseconds
+const RETRY_BACKOFF_MS: u64 = 500;
+
+/// How heavy an endpoint is on the ratelimit
+///
+/// This decides how long the request permit is held after the request is done
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[non_exhaustive]
+pub enum RatelimitWeight {
+    /// Cheap requests, like simple gets
+    Light,
+    /// Everything else, like sending messages
+    #[default]
+    Normal,
+}
+
+impl RatelimitWeight {
+    /// How long the request permit is held after a request of this weight by default,
+    /// see [`ApiClientConfig::permit_hold`]
+    #[must_use]
+    pub fn hold_duration(self) -> Duration {
+        match self {
+            Self::Light => Duration::from_secs(LIGHT_LOCK_HOLD_DURATION),
+            Self::Normal => Duration::from_secs(LOCK_HOLD_DURATION),
+        }
+    }
+}
+
+/// Counters the ratelimiter updates as it works
+#[derive(Debug, Default)]
+struct RatelimitCounters {
+    /// Requests currently waiting for a permit
+    waiting: AtomicUsize,
+    /// Requests that waited longer than the starvation threshold
+    starved: AtomicU64,
+    /// How long the last request waited for a permit, in milliseconds
+    last_wait_ms: AtomicU64,
+    /// The longest any request has waited for a permit, in milliseconds
+    longest_wait_ms: AtomicU64,
+}
+
+/// Keeps the waiting counter correct, even if the request is dropped while waiting
+struct WaitingGuard<'a>(&'a AtomicUsize);
+
+impl<'a> WaitingGuard<'a> {
+    /// Mark a request as waiting
+    fn new(waiting: &'a AtomicUsize) -> Self {
+        waiting.fetch_add(1, Ordering::Relaxed);
+        Self(waiting)
+    }
+}
+
+impl Drop for WaitingGuard<'_> {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+/// A snapshot of what the ratelimiter is doing
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct RatelimitStats {
+    /// Permits that can be taken right now
+    pub available_permits: usize,
+    /// Requests currently waiting for a permit
+    pub waiting_requests: usize,
+    /// How many requests have waited longer than [`ApiClientConfig::starvation_threshold`]
+    pub starved_requests: u64,
+    /// How long the last request waited for a permit
+    pub last_wait: Duration,
+    /// The longest any request has waited for a permit
+    pub longest_wait: Duration,
+}
 
 /// What action should the ratelimiter code take based on the result of the api call
 enum ApiResultAction<R> {
     /// Return the given value to the caller
-    /// (This might actually either be a Ok() or Err())
+    /// (This might actually either be a `Ok()` or `Err()`)
     Return(R),
     /// Activate ratelimit lock and  retry after the specified seconds
     RetryAfter(u64),
     /// Active ratelimit lock and retry with exponential backoff
     RetryWithBackoff,
+    /// A network error that might go away happened on an idempotent request,
+    /// retry after a short backoff, or return the error if out of retries
+    RetryTransient(ApiError),
+}
+
+/// The url of the last request made in [`ApiClient::handle_ratelimit`], for logging
+fn last_url(url: &Mutex<Option<String>>) -> String {
+    url.lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone()
+        .unwrap_or_else(|| "unknown url".to_owned())
+}
+
+/// Point a request at `origin` instead of guilded, keeping the path and query
+fn redirect(request: &mut reqwest::Request, origin: &reqwest::Url) {
+    let url = request.url_mut();
+    // both urls are http(s), which can always take a new scheme, host and port
+    let _ = url.set_scheme(origin.scheme());
+    let _ = url.set_host(origin.host_str());
+    let _ = url.set_port(origin.port());
+}
+
+/// Is this an error that might go away if the request is sent again,
+/// like a dropped connection or timeout, rather than a problem with the request itself
+fn is_transient(error: &reqwest::Error) -> bool {
+    error.is_connect() || error.is_timeout() || (error.is_request() && !error.is_builder())
+}
+
+/// How long to wait before a transient retry, exponential with a bit of jitter
+/// so many clients failing at once don't retry in lockstep
+fn transient_backoff(attempt: usize) -> Duration {
+    let jitter = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |now| now.subsec_nanos() % TRANSIENT_JITTER_MS);
+    Duration::from_millis(TRANSIENT_BACKOFF_MS << attempt.min(5))
+        + Duration::from_millis(jitter.into())
 }
 
 // Make conversion from ApiError to ApiResultAction easy
@@ -45,7 +177,7 @@ macro_rules! ret_error {
 #[derive(Deserialize, Debug)]
 pub struct GuildedError {
     /// Error code
-    pub code: String,
+    pub code: GuildedErrorCode,
     /// Message detailing the error
     pub message: String,
     /// this information is based on the specific error, and contains additional information
@@ -64,8 +196,30 @@ pub enum ApiError {
     /// And error occurred with parsing the returned json data
     /// This will also produce a `debug` log with the raw content data
     JsonError(serde_json::Error),
+    /// A successful response could not be parsed, usually because a model is behind the api
+    Decode {
+        /// Type name of the endpoint that was requested
+        endpoint: &'static str,
+        /// Where in the body parsing failed, like `invites[2].createdAt`, `.` for the top level
+        path: String,
+        /// The body around the failure, hidden if [`ApiClientConfig::log_bodies`] is off
+        snippet: String,
+        /// The whole body, `None` if [`ApiClientConfig::log_bodies`] is off
+        body: Option<String>,
+        /// The error from serde
+        source: serde_json::Error,
+    },
     /// A error occurred and guilded provided us with a nice explanation
     Guilded(GuildedError),
+    /// The ratelimit was hit more times than [`ApiClientConfig::max_ratelimit_retries`] allows
+    RateLimited {
+        /// How long guilded asked us to wait before trying again
+        retry_after: Duration,
+    },
+    /// The request did not finish within the given deadline
+    Timeout(Duration),
+    /// An argument of the request is invalid, the request was never sent
+    InvalidArgument(String),
 }
 
 impl From<GuildedError> for ApiError {
@@ -104,17 +258,181 @@ impl From<&str> for ApiError {
     }
 }
 
+impl ApiError {
+    /// Create an [`ApiError::Other`] from anything that can be displayed
+    ///
+    /// The message is always stored as a `String`, this only saves calling `format!` or `to_string` yourself.
+    /// ```
+    /// use vived_api::ApiError;
+    ///
+    /// let limit = 5;
+    /// let error = ApiError::other(format_args!("at most {limit} allowed"));
+    /// assert_eq!(error.to_string(), "error: at most 5 allowed");
+    /// ```
+    pub fn other(message: impl std::fmt::Display) -> Self {
+        Self::Other(message.to_string())
+    }
+
+    /// Create an [`ApiError::InvalidArgument`] from anything that can be displayed
+    pub fn invalid_argument(message: impl std::fmt::Display) -> Self {
+        Self::InvalidArgument(message.to_string())
+    }
+
+    /// Create an [`ApiError::Decode`] for a `source` error from parsing `raw`
+    ///
+    /// ```
+    /// use vived_api::endpoints::GetServerInvites;
+    /// use vived_api::{ApiError, Endpoint};
+    ///
+    /// // the third invite is missing `createdAt`
+    /// let raw = r#"{"invites": [
+    ///     {"id": "a", "serverId": "s", "createdBy": "u", "createdAt": "2022-10-01T12:00:00Z"},
+    ///     {"id": "b", "serverId": "s", "createdBy": "u", "createdAt": "2022-10-01T12:00:00Z"},
+    ///     {"id": "c", "serverId": "s", "createdBy": "u", "useCount": 1}
+    /// ]}"#;
+    /// let source = GetServerInvites::new("s").parse_response(raw).unwrap_err();
+    /// let error = ApiError::decode(std::any::type_name::<GetServerInvites>(), raw, source);
+    ///
+    /// let ApiError::Decode { endpoint, path, snippet, .. } = &error else { panic!() };
+    /// assert!(endpoint.ends_with("GetServerInvites"));
+    /// assert_eq!(path, "invites[2]");
+    /// assert!(snippet.contains(r#""useCount": 1}"#));
+    /// assert!(error.to_string().contains("missing field `createdAt`"));
+    ///
+    /// // a value of the wrong type points at its field
+    /// let raw = r#"{"invites": [{"id": "a", "serverId": "s", "createdBy": "u", "createdAt": "2022-10-01T12:00:00Z", "useCount": "many"}]}"#;
+    /// let source = GetServerInvites::new("s").parse_response(raw).unwrap_err();
+    /// let ApiError::Decode { path, .. } = ApiError::decode("GetServerInvites", raw, source) else { panic!() };
+    /// assert_eq!(path, "invites[0].useCount");
+    /// ```
+    #[must_use]
+    pub fn decode(endpoint: &'static str, raw: &str, source: serde_json::Error) -> Self {
+        let offset = crate::decode::offset(raw, source.line(), source.column()).unwrap_or(0);
+        Self::Decode {
+            endpoint,
+            path: crate::decode::path(raw, offset),
+            snippet: crate::decode::snippet(raw, offset),
+            body: Some(raw.to_owned()),
+            source,
+        }
+    }
+
+    /// Might sending the same request again work
+    ///
+    /// This is `true` for network errors and for internal server errors from guilded.
+    /// ```
+    /// use vived_api::ApiError;
+    ///
+    /// let guilded = |code: &str| ApiError::Guilded(serde_json::from_str(&format!(r#"{{"code": "{code}", "message": ""}}"#)).unwrap());
+    /// assert!(guilded("InternalServerError").is_retryable());
+    /// assert!(!guilded("NotFoundError").is_retryable());
+    /// assert!(!ApiError::invalid_argument("bad id").is_retryable());
+    /// ```
+    #[must_use]
+    pub fn is_retryable(&self) -> bool {
+        match *self {
+            Self::Request(_) => true,
+            Self::Guilded(ref error) => error.code == GuildedErrorCode::InternalServer,
+            Self::Other(_)
+            | Self::JsonError(_)
+            | Self::Decode { .. }
+            | Self::RateLimited { .. }
+            | Self::Timeout(_)
+            | Self::InvalidArgument(_) => false,
+        }
+    }
+
+    /// The code guilded gave for this error, if it is a [`ApiError::Guilded`]
+    ///
+    /// ```
+    /// use vived_api::{ApiError, GuildedErrorCode};
+    ///
+    /// let error = ApiError::Guilded(serde_json::from_str(r#"{"code": "NotFoundError", "message": "Not found"}"#).unwrap());
+    /// assert_eq!(error.guilded_code(), Some(&GuildedErrorCode::NotFound));
+    /// ```
+    #[must_use]
+    pub fn guilded_code(&self) -> Option<&GuildedErrorCode> {
+        match *self {
+            Self::Guilded(ref error) => Some(&error.code),
+            Self::Other(_)
+            | Self::Request(_)
+            | Self::JsonError(_)
+            | Self::Decode { .. }
+            | Self::RateLimited { .. }
+            | Self::Timeout(_)
+            | Self::InvalidArgument(_) => None,
+        }
+    }
+}
+
 impl std::fmt::Display for ApiError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match *self {
             Self::Other(ref s) => write!(f, "error: {s}"),
             Self::Request(ref e) => write!(f, "Request error: {e}"),
             Self::JsonError(ref e) => write!(f, "Json error: {e}"),
+            Self::Decode {
+                endpoint,
+                ref path,
+                ref snippet,
+                ref source,
+                ..
+            } => write!(
+                f,
+                "Failed to decode the response of {endpoint} at {path}: {source}, near `{snippet}`"
+            ),
             Self::Guilded(ref e) => write!(f, "Guilded error: {}", e.message),
+            Self::RateLimited { retry_after } => write!(
+                f,
+                "Ratelimited, retry after {} seconds",
+                retry_after.as_secs()
+            ),
+            Self::Timeout(timeout) => write!(f, "Request timed out after {timeout:?}"),
+            Self::InvalidArgument(ref s) => write!(f, "Invalid argument: {s}"),
         }
     }
 }
 
+impl std::error::Error for ApiError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match *self {
+            Self::Request(ref e) => Some(e),
+            Self::JsonError(ref e) | Self::Decode { source: ref e, .. } => Some(e),
+            Self::Other(_)
+            | Self::Guilded(_)
+            | Self::RateLimited { .. }
+            | Self::Timeout(_)
+            | Self::InvalidArgument(_) => None,
+        }
+    }
+}
+
+/// A response body that could not be parsed, together with the body itself, see [`Endpoint::parse_response_with_context`]
+#[derive(Debug)]
+pub struct ApiErrorWithBody {
+    /// The error from serde
+    pub source: serde_json::Error,
+    /// The whole response body
+    pub body: String,
+}
+
+impl std::fmt::Display for ApiErrorWithBody {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "failed to parse response ({} bytes): {}",
+            self.body.len(),
+            self.source
+        )
+    }
+}
+
+impl std::error::Error for ApiErrorWithBody {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.source)
+    }
+}
+
 /// An endpoint details to the client how to perform an action
 /// # Note
 /// You shouldn't need to implement this your self, you can if there are new routes that we don't support yet
@@ -127,44 +445,272 @@ pub trait Endpoint<R> {
     /// # Errors
     /// errors if the raw string cant be parsed into the expected json structure.
     fn from_raw(raw: &str) -> Result<R, serde_json::Error>;
+
+    /// Convert the raw api response of this request, defaults to [`Endpoint::from_raw`]
+    ///
+    /// Override this if parsing depends on the arguments of the request.
+    ///
+    /// # Errors
+    /// errors if the raw string cant be parsed into the expected json structure.
+    fn parse_response(&self, raw: &str) -> Result<R, serde_json::Error> {
+        Self::from_raw(raw)
+    }
+
+    /// Same as [`Endpoint::parse_response`], but keeps the body around if parsing fails
+    ///
+    /// This is what [`crate::ApiClient`] uses, override [`Endpoint::parse_response`] instead of this.
+    /// ```
+    /// use vived_api::{endpoints::GetServer, Endpoint};
+    ///
+    /// let error = GetServer::new("s").parse_response_with_context(r#"{"server": {"id": 5}}"#).unwrap_err();
+    /// assert_eq!(error.body, r#"{"server": {"id": 5}}"#);
+    /// assert!(error.source.is_data());
+    /// ```
+    ///
+    /// # Errors
+    /// If the raw string cant be parsed into the expected json structure.
+    fn parse_response_with_context(&self, raw: &str) -> Result<R, ApiErrorWithBody> {
+        self.parse_response(raw).map_err(|source| ApiErrorWithBody {
+            source,
+            body: raw.to_owned(),
+        })
+    }
+
+    /// How heavy this endpoint is on the ratelimit
+    fn ratelimit_weight(&self) -> RatelimitWeight {
+        RatelimitWeight::Normal
+    }
+
+    /// Check the request before it is sent, so mistakes don't turn into an opaque error from guilded
+    ///
+    /// # Errors
+    /// If guilded would reject the request anyway
+    fn validate(&self) -> Result<(), ApiError> {
+        Ok(())
+    }
+
+    /// Can this request safely be sent again after a network error
+    ///
+    /// Defaults to going by the http method, so GET, PUT and DELETE are retried but POST isn't.
+    fn is_idempotent(&self, method: &reqwest::Method) -> bool {
+        method.is_idempotent()
+    }
+}
+
+/// Settings for an [`ApiClient`]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct ApiClientConfig {
+    /// How many times a ratelimited request is retried before giving up with [`ApiError::RateLimited`]
+    ///
+    /// `None` (the default) retries forever.
+    pub max_ratelimit_retries: Option<usize>,
+    /// How many times an idempotent request is retried after a transient network error,
+    /// see [`Endpoint::is_idempotent`]
+    pub max_transient_retries: usize,
+    /// Warn when a request has waited longer than this for a ratelimit permit
+    pub starvation_threshold: Duration,
+    /// How long the permit is held after a [`RatelimitWeight::Normal`] request
+    pub normal_permit_hold: Duration,
+    /// How long the permit is held after a [`RatelimitWeight::Light`] request
+    pub light_permit_hold: Duration,
+    /// Log request headers at trace level
+    pub log_headers: bool,
+    /// Log request and response bodies, turn this off if message content must never end up in the logs
+    ///
+    /// When off only the size and a hash of the body is logged, so identical bodies can still be matched up.
+    /// This also applies to the response bodies logged when a response doesn't match the expected schema.
+    pub log_bodies: bool,
+    /// Bodies longer than this many bytes are cut off in the logs
+    pub max_logged_body: usize,
+    /// How many messages [`crate::helpers::broadcast_message`] sends at once
+    pub broadcast_concurrency: usize,
+    /// Send requests to this origin instead of guilded, keeping the path and query
+    ///
+    /// Meant for tests against a local server, or a proxy in front of the api.
+    pub api_origin: Option<reqwest::Url>,
+}
+
+impl Default for ApiClientConfig {
+    fn default() -> Self {
+        Self {
+            max_ratelimit_retries: None,
+            max_transient_retries: TRANSIENT_RETRIES,
+            starvation_threshold: Duration::from_secs(STARVATION_THRESHOLD),
+            normal_permit_hold: RatelimitWeight::Normal.hold_duration(),
+            light_permit_hold: RatelimitWeight::Light.hold_duration(),
+            log_headers: true,
+            log_bodies: true,
+            max_logged_body: MAX_LOGGED_BODY,
+            broadcast_concurrency: BROADCAST_CONCURRENCY,
+            api_origin: None,
+        }
+    }
+}
+
+impl ApiClientConfig {
+    /// Set the max number of ratelimit retries
+    #[must_use]
+    pub fn max_ratelimit_retries(mut self, retries: Option<usize>) -> Self {
+        self.max_ratelimit_retries = retries;
+        self
+    }
+
+    /// Set the max number of transient error retries, 0 disables them
+    #[must_use]
+    pub fn max_transient_retries(mut self, retries: usize) -> Self {
+        self.max_transient_retries = retries;
+        self
+    }
+
+    /// Set the starvation warning threshold
+    #[must_use]
+    pub fn starvation_threshold(mut self, threshold: Duration) -> Self {
+        self.starvation_threshold = threshold;
+        self
+    }
+
+    /// Set how long the permit is held after a request of `weight`
+    ///
+    /// Shorter holds than the defaults can get the bot ratelimited by guilded.
+    #[must_use]
+    pub fn permit_hold(mut self, weight: RatelimitWeight, hold: Duration) -> Self {
+        match weight {
+            RatelimitWeight::Light => self.light_permit_hold = hold,
+            RatelimitWeight::Normal => self.normal_permit_hold = hold,
+        }
+        self
+    }
+
+    /// How long the permit is held after a request of `weight`
+    fn hold_duration(&self, weight: RatelimitWeight) -> Duration {
+        match weight {
+            RatelimitWeight::Light => self.light_permit_hold,
+            RatelimitWeight::Normal => self.normal_permit_hold,
+        }
+    }
+
+    /// Set if request headers are logged
+    #[must_use]
+    pub fn log_headers(mut self, log_headers: bool) -> Self {
+        self.log_headers = log_headers;
+        self
+    }
+
+    /// Set if bodies are logged
+    #[must_use]
+    pub fn log_bodies(mut self, log_bodies: bool) -> Self {
+        self.log_bodies = log_bodies;
+        self
+    }
+
+    /// Set how many bytes of a body are logged
+    #[must_use]
+    pub fn max_logged_body(mut self, max_logged_body: usize) -> Self {
+        self.max_logged_body = max_logged_body;
+        self
+    }
+
+    /// Set how many messages are broadcast at once, at least 1
+    #[must_use]
+    pub fn broadcast_concurrency(mut self, concurrency: usize) -> Self {
+        self.broadcast_concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Set the origin requests are sent to instead of guilded, like `http://127.0.0.1:8080`
+    #[must_use]
+    pub fn api_origin(mut self, origin: reqwest::Url) -> Self {
+        self.api_origin = Some(origin);
+        self
+    }
+
+    /// Create an [`ApiError::Decode`], hiding the snippet and body if bodies aren't logged
+    fn decode_error(
+        &self,
+        endpoint: &'static str,
+        raw: &str,
+        source: serde_json::Error,
+    ) -> ApiError {
+        let mut error = ApiError::decode(endpoint, raw, source);
+        if let ApiError::Decode {
+            ref mut snippet,
+            ref mut body,
+            ..
+        } = error
+        {
+            if !self.log_bodies {
+                *snippet = self.loggable_body(snippet);
+                *body = None;
+            }
+        }
+        error
+    }
+
+    /// Format a body for the logs, following the logging settings
+    fn loggable_body(&self, body: &str) -> String {
+        if !self.log_bodies {
+            let mut hasher = DefaultHasher::new();
+            body.hash(&mut hasher);
+            return format!(
+                "<{} bytes hidden, hash {:016x}>",
+                body.len(),
+                hasher.finish()
+            );
+        }
+
+        if body.len() <= self.max_logged_body {
+            return body.to_owned();
+        }
+
+        // don't cut a char in half
+        let mut end = self.max_logged_body;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        format!("{}... ({} bytes total)", &body[..end], body.len())
+    }
 }
 
 /// This client handles ratelimiter and errors.
 /// This means that you could just do a while true loop and spam its methods and it will make sure you don't get ratelimited.
 /// THO! sending 100 requests without triggering a ratelimit is gonna take around 90 seconds :P
 /// so like don't if you don't actually need
-#[derive(Debug)]
+///
+/// Cloning is cheap, and all clones share the same ratelimiter and connection pool.
+#[derive(Clone)]
 pub struct ApiClient {
-    /// The `reqwest` client to use
-    client: RwLock<reqwest::Client>,
+    /// The `reqwest` client to use, swapped out by [`ApiClient::set_token`]
+    client: Arc<RwLock<AuthorizedClient>>,
     /// This is used to keep the number of concurrent tasks within a specific amount
     sem: Arc<Semaphore>,
+    /// Client settings
+    config: ApiClientConfig,
+    /// Ratelimiter counters
+    counters: Arc<RatelimitCounters>,
 }
 
-impl ApiClient {
-    /// Create a new api client using the provided token
+/// A `reqwest` client with the token in its default headers
+#[derive(Debug)]
+struct AuthorizedClient {
+    /// The client
+    client: reqwest::Client,
+    /// When the token was set
+    token_updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl AuthorizedClient {
+    /// Build a client sending `token`
     ///
     /// # Errors
-    /// if provided token contains invalid chars
-    ///
-    /// or if there is an error constructing the reqwest client, which can happen
-    /// when there is no resolver or tls backend found on the system.
-    pub fn new(token: &str) -> Result<Self, ApiError> {
+    /// If the token contains invalid chars, or the client can't be built
+    fn new(token: &str) -> Result<Self, ApiError> {
         let user_agent = format!(
             "library: vived, version: {}, rustc version: {}",
             version::version!(),
             rustc_version_runtime::version()
         );
-
         info!("using User-Agent: {}", user_agent);
-        info!(
-            "RATELIMITER SETTINGS: max concurrent requests: {}",
-            CONCURRENT_REQUEST
-        );
-        info!(
-            "RATELIMITER SETTINGS: lock hold time: {} seconds",
-            LOCK_HOLD_DURATION
-        );
 
         let mut headers = reqwest::header::HeaderMap::new();
         headers.insert(
@@ -179,38 +725,212 @@ impl ApiClient {
             .default_headers(headers)
             .build()?;
 
+        Ok(Self {
+            client,
+            token_updated_at: chrono::Utc::now(),
+        })
+    }
+}
+
+impl std::fmt::Debug for ApiClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        // the reqwest client only contains the token and user agent anyway
+        f.debug_struct("ApiClient")
+            .field("config", &self.config)
+            .field("ratelimit", &self.ratelimit_stats())
+            .field("token_updated_at", &self.token_updated_at())
+            .finish_non_exhaustive()
+    }
+}
+
+impl ApiClient {
+    /// Create a new api client using the provided token
+    ///
+    /// # Errors
+    /// if provided token contains invalid chars
+    ///
+    /// or if there is an error constructing the reqwest client, which can happen
+    /// when there is no resolver or tls backend found on the system.
+    pub fn new(token: &str) -> Result<Self, ApiError> {
+        Self::new_with_config(token, ApiClientConfig::default())
+    }
+
+    /// Create a new api client using the provided token and settings
+    ///
+    /// # Errors
+    /// if provided token contains invalid chars
+    ///
+    /// or if there is an error constructing the reqwest client, which can happen
+    /// when there is no resolver or tls backend found on the system.
+    pub fn new_with_config(token: &str, config: ApiClientConfig) -> Result<Self, ApiError> {
+        let client = AuthorizedClient::new(token)?;
+        crate::instrument::describe();
+
+        info!(
+            "RATELIMITER SETTINGS: max concurrent requests: {}",
+            CONCURRENT_REQUEST
+        );
+        info!(
+            "RATELIMITER SETTINGS: lock hold time: {:?} ({:?} for light requests)",
+            config.normal_permit_hold, config.light_permit_hold
+        );
+        info!(
+            "RATELIMITER SETTINGS: max retries: {:?}",
+            config.max_ratelimit_retries
+        );
+
         Ok(Self {
             sem: Arc::new(Semaphore::new(CONCURRENT_REQUEST)),
-            client: RwLock::new(client),
+            client: Arc::new(RwLock::new(client)),
+            config,
+            counters: Arc::new(RatelimitCounters::default()),
         })
     }
 
+    /// Replace the token used for requests, keeping the ratelimiter state
+    ///
+    /// Requests that are already being sent finish with the old token, every request sent after this uses the new one.
+    /// That includes retries of requests started before, and clones of this client.
+    /// ```
+    /// use vived_api::ApiClient;
+    ///
+    /// let client = ApiClient::new("old token").unwrap();
+    /// let created = client.token_updated_at();
+    ///
+    /// client.set_token("new token").unwrap();
+    /// assert!(client.token_updated_at() >= created);
+    ///
+    /// // an invalid token leaves the old one in place
+    /// let rotated = client.token_updated_at();
+    /// assert!(client.set_token("bad\ntoken").is_err());
+    /// assert_eq!(client.token_updated_at(), rotated);
+    /// ```
+    ///
+    /// # Errors
+    /// Same as [`ApiClient::new`]
+    pub fn set_token(&self, new_token: &str) -> Result<(), ApiError> {
+        let client = AuthorizedClient::new(new_token)?;
+        *self.client.write().unwrap_or_else(PoisonError::into_inner) = client;
+        info!("api token updated");
+        Ok(())
+    }
+
+    /// When the token was last set, either by creating the client or by [`ApiClient::set_token`]
+    #[must_use]
+    pub fn token_updated_at(&self) -> chrono::DateTime<chrono::Utc> {
+        self.client
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .token_updated_at
+    }
+
+    /// The current `reqwest` client, cloning it is cheap
+    fn http_client(&self) -> reqwest::Client {
+        self.client
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .client
+            .clone()
+    }
+
+    /// The settings of this client
+    pub(crate) fn config(&self) -> &ApiClientConfig {
+        &self.config
+    }
+
+    /// Get a snapshot of the ratelimiter state
+    #[must_use]
+    pub fn ratelimit_stats(&self) -> RatelimitStats {
+        RatelimitStats {
+            available_permits: self.sem.available_permits(),
+            waiting_requests: self.counters.waiting.load(Ordering::Relaxed),
+            starved_requests: self.counters.starved.load(Ordering::Relaxed),
+            last_wait: Duration::from_millis(self.counters.last_wait_ms.load(Ordering::Relaxed)),
+            longest_wait: Duration::from_millis(
+                self.counters.longest_wait_ms.load(Ordering::Relaxed),
+            ),
+        }
+    }
+
+    /// Record how long a request waited for its permit, and warn if it was starved
+    fn record_permit_wait(&self, waited: Duration) {
+        let waited_ms = u64::try_from(waited.as_millis()).unwrap_or(u64::MAX);
+        self.counters
+            .last_wait_ms
+            .store(waited_ms, Ordering::Relaxed);
+        self.counters
+            .longest_wait_ms
+            .fetch_max(waited_ms, Ordering::Relaxed);
+
+        if waited > self.config.starvation_threshold {
+            self.counters.starved.fetch_add(1, Ordering::Relaxed);
+            warn!(
+                "Request waited {:?} for a ratelimit permit, {} requests are still waiting",
+                waited,
+                self.counters.waiting.load(Ordering::Relaxed)
+            );
+        }
+    }
+
+    /// Check if we have retried a ratelimited request as many times as we are allowed to
+    fn ratelimit_retries_exhausted(&self, retries: usize) -> bool {
+        let exhausted = self
+            .config
+            .max_ratelimit_retries
+            .is_some_and(|max| retries >= max);
+
+        if exhausted {
+            warn!("Ratelimit hit, giving up after {} retries", retries);
+        }
+
+        exhausted
+    }
+
     /// Handle ratelimits and retry logic
     /// operates on `ApiResultAction`
     // The expects in this function actually panic on a closed Semaphore, which would be an invalid state for two reason:
     // 1. The semaphore is only closed when the client is dropped, which means that the client is no longer valid
     // 2. without the semaphore the client would be useless, as it would not be able to make any requests
     #[allow(clippy::expect_used)]
-    async fn handle_ratelimit<C, F, R>(&self, closure: C) -> R
+    async fn handle_ratelimit<C, F, T>(
+        &self,
+        weight: RatelimitWeight,
+        url: &Mutex<Option<String>>,
+        closure: C,
+    ) -> Result<T, ApiError>
     where
         C: Fn() -> F,
-        F: Future<Output = ApiResultAction<R>>,
+        F: Future<Output = ApiResultAction<Result<T, ApiError>>>,
     {
+        let wait_start = Instant::now();
+        let waiting = WaitingGuard::new(&self.counters.waiting);
         let permit = Arc::clone(&self.sem)
             .acquire_owned()
             .await
             .expect("Ratelimiter semaphore has been closed unexpectedly");
+        drop(waiting);
+        self.record_permit_wait(wait_start.elapsed());
+        crate::instrument::available_permits(self.sem.available_permits());
 
         let mut backoff_amount: u64 = 20;
 
         let mut lockdown_permits = None;
+        let mut retries: usize = 0;
+        let mut transient_retries: usize = 0;
 
         let result = loop {
             match closure().await {
                 ApiResultAction::Return(value) => break value,
                 ApiResultAction::RetryAfter(wait_amount) => {
+                    if self.ratelimit_retries_exhausted(retries) {
+                        break Err(ApiError::RateLimited {
+                            retry_after: Duration::from_secs(wait_amount),
+                        });
+                    }
+
                     warn!(
-                        "Ratelimit hit, blocking all requests for {} seconds",
+                        "Ratelimit hit on {}, blocking all requests for {} seconds",
+                        last_url(url),
                         wait_amount
                     );
 
@@ -223,11 +943,19 @@ impl ApiClient {
                             .expect("Ratelimiter semaphore has been closed unexpectedly"),
                     );
 
+                    crate::instrument::ratelimited(Duration::from_secs(wait_amount));
                     tokio::time::sleep(Duration::from_secs(wait_amount)).await;
                 }
                 ApiResultAction::RetryWithBackoff => {
+                    if self.ratelimit_retries_exhausted(retries) {
+                        break Err(ApiError::RateLimited {
+                            retry_after: Duration::from_secs(backoff_amount),
+                        });
+                    }
+
                     warn!(
-                        "Ratelimit hit, blocking all requests for {} seconds (BACKOFF MODE)",
+                        "Ratelimit hit on {}, blocking all requests for {} seconds (BACKOFF MODE)",
+                        last_url(url),
                         backoff_amount
                     );
 
@@ -240,10 +968,29 @@ impl ApiClient {
                             .expect("Ratelimiter semaphore has been closed unexpectedly"),
                     );
 
+                    crate::instrument::ratelimited(Duration::from_secs(backoff_amount));
                     tokio::time::sleep(Duration::from_secs(backoff_amount)).await;
                     backoff_amount *= 2;
                 }
+                ApiResultAction::RetryTransient(error) => {
+                    if transient_retries >= self.config.max_transient_retries {
+                        break Err(error);
+                    }
+
+                    let delay = transient_backoff(transient_retries);
+                    warn!(
+                        "Transient error on {}, retrying in {delay:?}: {error}",
+                        last_url(url)
+                    );
+                    tokio::time::sleep(delay).await;
+
+                    // these don't count towards the ratelimit retries
+                    transient_retries += 1;
+                    continue;
+                }
             }
+
+            retries += 1;
         };
 
         if let Some(permits) = lockdown_permits {
@@ -251,11 +998,16 @@ impl ApiClient {
         }
 
         // Make permit last longer than the call so we don't get requests too quickly
+        let hold_duration = self.config.hold_duration(weight);
+        #[cfg(feature = "metrics")]
+        let sem = Arc::clone(&self.sem);
         tokio::spawn(async move {
-            trace!("holding permit for {LOCK_HOLD_DURATION} seconds");
-            tokio::time::sleep(Duration::from_secs(LOCK_HOLD_DURATION)).await;
+            trace!("holding permit for {hold_duration:?}");
+            tokio::time::sleep(hold_duration).await;
             drop(permit);
             trace!("dropped permit");
+            #[cfg(feature = "metrics")]
+            crate::instrument::available_permits(sem.available_permits());
         });
 
         result
@@ -264,46 +1016,145 @@ impl ApiClient {
     /// Make a request to the guilded api using the provided endpoint builder
     ///
     /// # Errors
-    /// If there is a connection error or an error parsing the return json data
+    /// If [`Endpoint::validate`] fails, there is a connection error or an error parsing the return json data
     ///
     /// # Panics
     /// If a ratelimit is hit and the "Retry-After" header is malformed
-    pub async fn make_request<'a, E, R>(&self, builder: E) -> Result<R, ApiError>
+    pub async fn make_request<E, R>(&self, builder: E) -> Result<R, ApiError>
     where
         E: Endpoint<R>,
     {
-        self.handle_ratelimit(|| async {
-            let client = self.client.read().await;
+        self.make_request_with_meta(builder)
+            .await
+            .map(|(value, _)| value)
+    }
 
-            let request = ret_error!(builder.build(&client).build());
+    /// Same as [`ApiClient::make_request`], but also returns the status, timing and headers of the response
+    ///
+    /// # Errors
+    /// Same as [`ApiClient::make_request`]
+    ///
+    /// # Panics
+    /// Same as [`ApiClient::make_request`]
+    pub async fn make_request_with_meta<E, R>(
+        &self,
+        builder: E,
+    ) -> Result<(R, ResponseMeta), ApiError>
+    where
+        E: Endpoint<R>,
+    {
+        self.send(&builder).await
+    }
+
+    /// Make a request, and send it again up to `max_retries` times if it fails with a [retryable](ApiError::is_retryable) error
+    ///
+    /// The wait before a retry grows linearly, 0.5 seconds more for each retry.
+    /// Ratelimits are already retried by every request, this is for errors that are normally returned right away.
+    ///
+    /// # Note
+    /// A network error can happen after guilded got the request,
+    /// so something like a [`crate::endpoints::MessageCreate`] might happen twice.
+    ///
+    /// # Errors
+    /// Same as [`ApiClient::make_request`], with the error of the last attempt
+    pub async fn make_request_with_retry<E, R>(
+        &self,
+        builder: E,
+        max_retries: usize,
+    ) -> Result<R, ApiError>
+    where
+        E: Endpoint<R>,
+    {
+        let mut retries: usize = 0;
+        loop {
+            match self.send(&builder).await {
+                Err(error) if error.is_retryable() && retries < max_retries => {
+                    retries += 1;
+                    let delay = Duration::from_millis(RETRY_BACKOFF_MS)
+                        .saturating_mul(retries.try_into().unwrap_or(u32::MAX));
+                    warn!(
+                        "request failed, retrying in {delay:?} ({retries}/{max_retries}): {error}"
+                    );
+                    tokio::time::sleep(delay).await;
+                }
+                result => return result.map(|(value, _)| value),
+            }
+        }
+    }
+
+    /// Send a request, everything that makes a request goes through here
+    async fn send<E, R>(&self, builder: &E) -> Result<(R, ResponseMeta), ApiError>
+    where
+        E: Endpoint<R>,
+    {
+        builder.validate()?;
+
+        // the url is only known once the request is built, inside the closure
+        let url = Mutex::new(None);
+        self.handle_ratelimit(builder.ratelimit_weight(), &url, || async {
+            // cloned so rotating the token doesn't have to wait for this request
+            let client = self.http_client();
+
+            let mut request = ret_error!(builder.build(&client).build());
+            if let Some(ref origin) = self.config.api_origin {
+                redirect(&mut request, origin);
+            }
+            *url.lock().unwrap_or_else(PoisonError::into_inner) = Some(request.url().to_string());
 
             debug!("making request");
             trace!("URL: {}", request.url());
             trace!("METHOD: {}", request.method());
-            trace!("HEADERS: {:#?}", request.headers());
+            if self.config.log_headers {
+                trace!("HEADERS: {:#?}", request.headers());
+            }
 
-            if let Some(body) = request.body().and_then(reqwest::Body::as_bytes) {
-                trace!("BODY: {}", String::from_utf8_lossy(body));
-            } else {
-                trace!("NO VALID BODY");
+            // formatting the body isn't free, so skip it if it wont be logged anyway
+            if log::log_enabled!(log::Level::Trace) {
+                if let Some(body) = request.body().and_then(reqwest::Body::as_bytes) {
+                    trace!(
+                        "BODY: {}",
+                        self.config.loggable_body(&String::from_utf8_lossy(body))
+                    );
+                } else {
+                    trace!("NO VALID BODY");
+                }
             }
 
+            let idempotent = builder.is_idempotent(request.method());
+            let start = Instant::now();
             let res = client.execute(request).await;
+            crate::instrument::request::<E>(
+                res.as_ref().ok().map(reqwest::Response::status),
+                start.elapsed(),
+            );
 
             let res = match res {
                 Ok(value) => value,
+                Err(error) if idempotent && is_transient(&error) => {
+                    return ApiResultAction::RetryTransient(ApiError::Request(error))
+                }
                 Err(error) => return ApiResultAction::Return(Err(ApiError::Request(error))),
             };
 
             let status = res.status();
 
             if status.is_success() {
+                let headers = res.headers().clone();
                 let content = ret_error!(res.text().await);
+                let meta = ResponseMeta::from_parts(status, &headers, start.elapsed());
 
-                E::from_raw(&content)
+                // TODO: let the ratelimiter slow down before guilded starts returning 429s
+                if meta.ratelimit_remaining() == Some(0) {
+                    debug!("guilded says the ratelimit is used up");
+                }
+
+                builder
+                    .parse_response_with_context(&content)
+                    .map(|value| (value, meta))
                     .map_err(|err| {
-                        error!("RESPONSE BODY: {}", content);
-                        err.into()
+                        error!("RESPONSE BODY: {}", self.config.loggable_body(&err.body));
+                        self.config
+                            .decode_error(std::any::type_name::<E>(), &err.body, err.source)
                     })
                     .into()
             } else if status == reqwest::StatusCode::TOO_MANY_REQUESTS {
@@ -324,7 +1175,7 @@ impl ApiClient {
                 ApiResultAction::Return(Err(match serde_json::from_str::<GuildedError>(&content) {
                     Ok(error) => ApiError::Guilded(error),
                     Err(error) => {
-                        error!("RESPONSE BODY: {}", content);
+                        error!("RESPONSE BODY: {}", self.config.loggable_body(&content));
                         ApiError::JsonError(error)
                     }
                 }))
@@ -332,4 +1183,102 @@ impl ApiClient {
         })
         .await
     }
+
+    /// Make a request to a route this crate doesn't have an endpoint for yet
+    ///
+    /// `path` is relative to the api base url, like `servers/{id}/members`.
+    /// This goes through the same ratelimiting, retries and error handling as [`ApiClient::make_request`],
+    /// so error responses from guilded become [`ApiError::Guilded`].
+    /// Returns the status and the json body, [`serde_json::Value::Null`] if the body was empty.
+    ///
+    /// # Errors
+    /// Same as [`ApiClient::make_request`], or [`ApiError::InvalidArgument`] if the path has `.` or `..` segments
+    pub async fn execute_raw(
+        &self,
+        method: reqwest::Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+        query: &[(&str, &str)],
+    ) -> Result<(reqwest::StatusCode, serde_json::Value), ApiError> {
+        self.make_request_with_meta(crate::endpoints::RawRequest::new(method, path, body, query))
+            .await
+            .map(|(value, meta)| (meta.status, value))
+    }
+
+    /// Make a request to the guilded api, giving up if it hasn't finished within `timeout`
+    ///
+    /// The deadline covers everything, including waiting for a ratelimit permit and any retries.
+    /// If the deadline is hit the request is abandoned and [`ApiError::Timeout`] is returned.
+    ///
+    /// # Errors
+    /// Same as [`ApiClient::make_request`], or [`ApiError::Timeout`] if the deadline is hit
+    pub async fn make_request_with_timeout<E, R>(
+        &self,
+        builder: E,
+        timeout: Duration,
+    ) -> Result<R, ApiError>
+    where
+        E: Endpoint<R>,
+    {
+        tokio::time::timeout(timeout, self.make_request(builder))
+            .await
+            .map_err(|_| ApiError::Timeout(timeout))?
+    }
+
+    /// Stream the history of a channel, newest first
+    ///
+    /// Pages are requested as the stream is polled, see [`crate::search`].
+    pub fn message_history(
+        &self,
+        channel: impl Into<ChannelId>,
+        options: SearchOptions,
+    ) -> impl Stream<Item = Result<Message, ApiError>> {
+        crate::search::message_history(self.clone(), channel.into(), options)
+    }
+
+    /// Stream the messages in the history of a channel that match `predicate`, newest first
+    ///
+    /// Messages are yielded as they are found, so callers can stop early.
+    /// `options` bounds how many messages are looked at, not how many are returned.
+    /// See [`crate::search`] for some ready made predicates.
+    pub fn search_messages<P>(
+        &self,
+        channel: impl Into<ChannelId>,
+        options: SearchOptions,
+        predicate: P,
+    ) -> impl Stream<Item = Result<Message, ApiError>>
+    where
+        P: FnMut(&Message) -> bool,
+    {
+        crate::search::search_messages(self.clone(), channel.into(), options, predicate)
+    }
+
+    /// Delete recent messages in a channel, like a purge command would
+    ///
+    /// Deletes go out a few at a time and through the ratelimiter,
+    /// and a message that can't be deleted is reported instead of stopping the purge.
+    /// See [`crate::purge`] for the options, including a dry run.
+    ///
+    /// # Errors
+    /// If getting the history of the channel fails, nothing is deleted in that case
+    pub async fn purge_messages(
+        &self,
+        channel: impl Into<ChannelId>,
+        options: crate::purge::PurgeOptions,
+    ) -> Result<crate::purge::PurgeReport, ApiError> {
+        crate::purge::purge_messages(self, channel.into(), options).await
+    }
+
+    /// Rank the members of a role by xp
+    ///
+    /// This makes one request per member with the role, see [`GetRoleLeaderboard`](crate::endpoints::GetRoleLeaderboard) for why.
+    ///
+    /// # Errors
+    /// If listing the members or getting the xp of one of them fails
+    pub async fn role_leaderboard(
+        &self,
+        leaderboard: crate::endpoints::GetRoleLeaderboard,
+    ) -> Result<Vec<crate::endpoints::RoleLeaderboardEntry>, ApiError> {
+        leaderboard.fetch(self).await
+    }
 }
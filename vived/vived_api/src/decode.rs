@@ -0,0 +1,129 @@
+//! Locate where in a response body json parsing failed
+//!
+//! serde only reports a line and column, these turn that into a path like `invites[2].createdAt`
+//! and a short piece of the body around it.
+
+use std::fmt::Write;
+
+/// How many bytes of the body are kept on each side of the failure
+const SNIPPET_RADIUS: usize = 40;
+
+/// Byte offset of a 1 based line and column as reported by [`serde_json::Error`]
+///
+/// `None` if the error has no position, like errors from [`serde_json::from_value`].
+pub(crate) fn offset(raw: &str, line: usize, column: usize) -> Option<usize> {
+    if line == 0 {
+        return None;
+    }
+
+    let line_start = if line == 1 {
+        0
+    } else {
+        raw.match_indices('\n').nth(line - 2)?.0 + 1
+    };
+    Some((line_start + column.saturating_sub(1)).min(raw.len()))
+}
+
+/// A container the scan is inside of
+enum Frame {
+    /// An object, with the last key seen in it
+    Object(Option<String>),
+    /// An array, with the index of the current element
+    Array(usize),
+}
+
+/// Path to the value being parsed at `offset`, `.` for the top level
+pub(crate) fn path(raw: &str, offset: usize) -> String {
+    let mut stack = Vec::new();
+    let mut expecting_key = false;
+    let mut chars = raw.char_indices().take_while(|&(index, _)| index < offset);
+
+    while let Some((_, c)) = chars.next() {
+        match c {
+            '{' => {
+                stack.push(Frame::Object(None));
+                expecting_key = true;
+            }
+            '[' => {
+                stack.push(Frame::Array(0));
+                expecting_key = false;
+            }
+            '}' | ']' => {
+                stack.pop();
+                expecting_key = false;
+            }
+            ',' => match stack.last_mut() {
+                Some(&mut Frame::Array(ref mut index)) => *index += 1,
+                Some(&mut Frame::Object(_)) => expecting_key = true,
+                None => {}
+            },
+            ':' => expecting_key = false,
+            '"' => {
+                let mut text = String::new();
+                let mut escaped = false;
+                for (_, character) in chars.by_ref() {
+                    match character {
+                        _ if escaped => escaped = false,
+                        '\\' => escaped = true,
+                        '"' => break,
+                        _ => text.push(character),
+                    }
+                }
+                if expecting_key {
+                    if let Some(&mut Frame::Object(ref mut key)) = stack.last_mut() {
+                        *key = Some(text);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    // errors reported at the end of an object, like a missing field, are about the object itself
+    if raw.get(offset..).is_some_and(|rest| rest.starts_with('}')) {
+        if let Some(&mut Frame::Object(ref mut key)) = stack.last_mut() {
+            *key = None;
+        }
+    }
+
+    let mut path = String::new();
+    for frame in &stack {
+        match *frame {
+            Frame::Object(Some(ref key)) => {
+                if !path.is_empty() {
+                    path.push('.');
+                }
+                path.push_str(key);
+            }
+            Frame::Object(None) => {}
+            // writing to a string can't fail
+            Frame::Array(index) => {
+                let _ = write!(path, "[{index}]");
+            }
+        }
+    }
+
+    if path.is_empty() {
+        path.push('.');
+    }
+    path
+}
+
+/// The part of the body around `offset`, with `...` where it was cut
+pub(crate) fn snippet(raw: &str, offset: usize) -> String {
+    let mut start = offset.saturating_sub(SNIPPET_RADIUS);
+    while !raw.is_char_boundary(start) {
+        start -= 1;
+    }
+    let mut end = offset.saturating_add(SNIPPET_RADIUS).min(raw.len());
+    while !raw.is_char_boundary(end) {
+        end += 1;
+    }
+
+    format!(
+        "{}{}{}",
+        if start > 0 { "..." } else { "" },
+        &raw[start..end],
+        if end < raw.len() { "..." } else { "" }
+    )
+}
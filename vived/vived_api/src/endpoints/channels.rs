@@ -1,10 +1,12 @@
 //! Endpoints for interacting with channels
 
-use super::BASE_URL;
+use super::{check_segments, segment, BASE_URL};
+use crate::{Maybe, Page};
 
 use serde::{Deserialize, Serialize};
 
 /// Get a channel from an id
+#[derive(Debug)]
 pub struct GetChannel(vived_models::ChannelId);
 
 impl GetChannel {
@@ -17,10 +19,14 @@ impl GetChannel {
 impl crate::Endpoint<vived_models::Channel> for GetChannel {
     fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
         client.get(
-            format!("{BASE_URL}/channels/{}", self.0)
+            format!("{BASE_URL}/channels/{}", segment(&self.0))
         )
     }
 
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.0.0])
+    }
+
     fn from_raw(raw: &str) -> Result<vived_models::Channel, serde_json::Error> {
         #[derive(Deserialize)]
         /// Response from the server
@@ -30,4 +36,219 @@ impl crate::Endpoint<vived_models::Channel> for GetChannel {
         }
         serde_json::from_str::<ChannelGetResponse>(raw).map(|r| r.channel)
     }
-}
\ No newline at end of file
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
+}
+
+/// Get all channels in a server
+///
+/// If guilded splits them over several pages, pass the [`Page::cursor`] of a page to [`GetServerChannels::cursor`] to get the next one.
+/// ```
+/// use vived_api::{endpoints::GetServerChannels, Endpoint};
+///
+/// let client = reqwest::Client::new();
+/// let query = |endpoint: GetServerChannels| endpoint.build(&client).build().unwrap().url().query().map(str::to_owned);
+///
+/// assert_eq!(query(GetServerChannels::new("s")), None);
+/// assert_eq!(query(GetServerChannels::new("s").cursor("abc")).as_deref(), Some("cursor=abc"));
+/// ```
+#[derive(Debug)]
+#[must_use]
+pub struct GetServerChannels {
+    /// Server to get the channels of
+    server: vived_models::ServerId,
+    /// Cursor of the page to get, the first page if `None`
+    cursor: Option<String>,
+}
+
+impl GetServerChannels {
+    /// Create a new `GetServerChannels` instructions
+    pub fn new(server: impl Into<vived_models::ServerId>) -> Self {
+        Self {
+            server: server.into(),
+            cursor: None,
+        }
+    }
+
+    /// Get the page after the one `cursor` came from
+    pub fn cursor(mut self, cursor: impl Into<String>) -> Self {
+        self.cursor = Some(cursor.into());
+        self
+    }
+}
+
+impl crate::Endpoint<Page<vived_models::Channel>> for GetServerChannels {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        let request = client.get(
+            format!("{BASE_URL}/servers/{}/channels", segment(&self.server))
+        );
+        match self.cursor {
+            Some(ref cursor) => request.query(&[("cursor", cursor)]),
+            None => request,
+        }
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.server.0])
+    }
+
+    fn from_raw(raw: &str) -> Result<Page<vived_models::Channel>, serde_json::Error> {
+        Page::from_raw(raw, "channels")
+    }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
+}
+
+/// List who is in a voice channel
+///
+/// Guilded has no endpoint for this, so this always fails with [`crate::ApiError::Other`] without sending anything.
+/// It exists so bots that expect it find out right away, instead of getting a confusing error from guilded.
+/// ```
+/// use vived_api::{endpoints::GetVoiceParticipants, ApiError, Endpoint};
+///
+/// let error = GetVoiceParticipants::new("channel").validate().unwrap_err();
+/// assert!(matches!(error, ApiError::Other(ref message) if message == "not supported by API"));
+/// ```
+#[derive(Debug)]
+#[must_use]
+pub struct GetVoiceParticipants(vived_models::ChannelId);
+
+impl GetVoiceParticipants {
+    /// Create a new `GetVoiceParticipants` instruction for the given channel
+    pub fn new(channel: impl Into<vived_models::ChannelId>) -> Self {
+        Self(channel.into())
+    }
+}
+
+impl crate::Endpoint<Vec<vived_models::UserId>> for GetVoiceParticipants {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client.get(format!(
+            "{BASE_URL}/channels/{}/voice-participants",
+            segment(&self.0)
+        ))
+    }
+
+    /// # Errors
+    /// Always, guilded has no endpoint for this
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        Err(crate::ApiError::other("not supported by API"))
+    }
+
+    fn from_raw(_: &str) -> Result<Vec<vived_models::UserId>, serde_json::Error> {
+        Ok(Vec::new())
+    }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
+}
+
+/// Json arguments for `ChannelUpdate`
+#[derive(Serialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+struct ChannelUpdateArguments {
+    /// New name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    name: Option<String>,
+    /// New topic
+    #[serde(skip_serializing_if = "Maybe::is_leave")]
+    topic: Maybe<String>,
+    /// New visibility
+    #[serde(skip_serializing_if = "Option::is_none")]
+    is_public: Option<bool>,
+}
+
+/// Update the name, topic or visibility of a channel
+///
+/// Fields that aren't set are left alone.
+/// The name can't be cleared, since every channel needs one.
+#[derive(Debug)]
+#[must_use]
+pub struct ChannelUpdate {
+    /// Channel to update
+    channel: vived_models::ChannelId,
+    /// Json arguments
+    arguments: ChannelUpdateArguments,
+}
+
+impl ChannelUpdate {
+    /// Create a new `ChannelUpdate` instruction for the given channel
+    pub fn new(channel: impl Into<vived_models::ChannelId>) -> Self {
+        Self {
+            channel: channel.into(),
+            arguments: ChannelUpdateArguments::default(),
+        }
+    }
+
+    /// Create a new `ChannelUpdate` instruction filled in with the current values of `channel`
+    ///
+    /// A channel without a topic leaves the topic alone, use [`ChannelUpdate::clear_topic`] to remove it.
+    pub fn from_channel(channel: &vived_models::Channel) -> Self {
+        Self {
+            channel: channel.id.clone(),
+            arguments: ChannelUpdateArguments {
+                name: Some(channel.name.clone()),
+                topic: channel.topic.clone().map_or(Maybe::Leave, Maybe::Set),
+                is_public: Some(channel.is_public),
+            },
+        }
+    }
+
+    /// Set the name
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.arguments.name = Some(name.into());
+        self
+    }
+
+    /// Set the topic
+    pub fn topic(mut self, topic: impl Into<String>) -> Self {
+        self.arguments.topic = Maybe::Set(topic.into());
+        self
+    }
+
+    /// Remove the topic
+    pub fn clear_topic(mut self) -> Self {
+        self.arguments.topic = Maybe::Clear;
+        self
+    }
+
+    /// Set if the channel is public
+    pub fn public(mut self, public: bool) -> Self {
+        self.arguments.is_public = Some(public);
+        self
+    }
+
+    /// Leave the visibility alone, undoing an earlier [`ChannelUpdate::public`] or [`ChannelUpdate::from_channel`]
+    pub fn reset_public(mut self) -> Self {
+        self.arguments.is_public = None;
+        self
+    }
+}
+
+impl crate::Endpoint<vived_models::Channel> for ChannelUpdate {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client
+            .patch(format!("{BASE_URL}/channels/{}", segment(&self.channel)))
+            .json(&self.arguments)
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.channel.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(raw: &str) -> Result<vived_models::Channel, serde_json::Error> {
+        /// Response from the channel update endpoint
+        #[derive(Deserialize, Debug)]
+        struct ChannelUpdateResponse {
+            /// The updated channel
+            channel: vived_models::Channel,
+        }
+        serde_json::from_str::<ChannelUpdateResponse>(raw).map(|resp| resp.channel)
+    }
+}
@@ -0,0 +1,46 @@
+//! Endpoints for custom emotes
+
+use serde::Deserialize;
+use vived_models::{Emote, ServerId};
+
+use crate::Endpoint;
+
+use super::{check_segments, segment, BASE_URL};
+
+/// Get every custom emote of a server
+#[derive(Debug)]
+#[must_use]
+pub struct GetServerEmotes(ServerId);
+
+impl GetServerEmotes {
+    /// Create a new `GetServerEmotes` instruction for the given server
+    pub fn new(server: impl Into<ServerId>) -> Self {
+        Self(server.into())
+    }
+}
+
+impl Endpoint<Vec<Emote>> for GetServerEmotes {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client.get(format!("{BASE_URL}/servers/{}/emotes", segment(&self.0)))
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.0.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(raw: &str) -> Result<Vec<Emote>, serde_json::Error> {
+        #[derive(Deserialize)]
+        /// Response containing the emotes
+        struct EmotesResponse {
+            /// The emotes
+            emotes: Vec<Emote>,
+        }
+        serde_json::from_str::<EmotesResponse>(raw).map(|resp| resp.emotes)
+    }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
+}
@@ -0,0 +1,171 @@
+//! Endpoints for server invites
+
+use serde::{Deserialize, Serialize};
+use vived_models::{ChannelId, Invite, ServerId};
+
+use crate::Endpoint;
+
+use super::{check_segments, segment, BASE_URL};
+
+/// Response containing a single invite
+#[derive(Deserialize, Debug)]
+struct InviteResponse {
+    /// The invite
+    invite: Invite,
+}
+
+/// Json arguments for `CreateServerInvite`
+#[derive(Serialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CreateServerInviteArguments {
+    /// Channel people land in after joining
+    #[serde(skip_serializing_if = "Option::is_none")]
+    channel_id: Option<ChannelId>,
+    /// Seconds until the invite expires
+    #[serde(skip_serializing_if = "Option::is_none")]
+    expires_in: Option<u64>,
+}
+
+/// Create an invite link to a server
+/// ```
+/// use std::time::Duration;
+/// use vived_api::endpoints::CreateServerInvite;
+/// use vived_api::Endpoint;
+///
+/// let request = CreateServerInvite::new("server")
+///     .channel("channel")
+///     .expires_in(Duration::from_secs(3600))
+///     .build(&reqwest::Client::new())
+///     .build()
+///     .unwrap();
+///
+/// assert_eq!(request.url().path(), "/api/v1/servers/server/invites");
+/// let body: serde_json::Value = serde_json::from_slice(request.body().unwrap().as_bytes().unwrap()).unwrap();
+/// assert_eq!(body, serde_json::json!({"channelId": "channel", "expiresIn": 3600}));
+/// ```
+#[derive(Debug)]
+#[must_use]
+pub struct CreateServerInvite {
+    /// Server to create the invite for
+    server: ServerId,
+    /// Json arguments
+    arguments: CreateServerInviteArguments,
+}
+
+impl CreateServerInvite {
+    /// Create a new `CreateServerInvite` instruction for the given server, the invite never expires by default
+    pub fn new(server: impl Into<ServerId>) -> Self {
+        Self {
+            server: server.into(),
+            arguments: CreateServerInviteArguments::default(),
+        }
+    }
+
+    /// Send people that join with the invite to this channel
+    pub fn channel(mut self, channel: impl Into<ChannelId>) -> Self {
+        self.arguments.channel_id = Some(channel.into());
+        self
+    }
+
+    /// Make the invite expire after `expires_in`, rounded down to whole seconds
+    pub fn expires_in(mut self, expires_in: std::time::Duration) -> Self {
+        self.arguments.expires_in = Some(expires_in.as_secs());
+        self
+    }
+}
+
+impl Endpoint<Invite> for CreateServerInvite {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client
+            .post(format!("{BASE_URL}/servers/{}/invites", segment(&self.server)))
+            .json(&self.arguments)
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.server.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(raw: &str) -> Result<Invite, serde_json::Error> {
+        serde_json::from_str::<InviteResponse>(raw).map(|resp| resp.invite)
+    }
+}
+
+/// Get every invite of a server
+#[derive(Debug)]
+#[must_use]
+pub struct GetServerInvites(ServerId);
+
+impl GetServerInvites {
+    /// Create a new `GetServerInvites` instruction for the given server
+    pub fn new(server: impl Into<ServerId>) -> Self {
+        Self(server.into())
+    }
+}
+
+impl Endpoint<Vec<Invite>> for GetServerInvites {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client.get(format!("{BASE_URL}/servers/{}/invites", segment(&self.0)))
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.0.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(raw: &str) -> Result<Vec<Invite>, serde_json::Error> {
+        #[derive(Deserialize)]
+        /// Response containing the invites
+        struct InvitesResponse {
+            /// The invites
+            invites: Vec<Invite>,
+        }
+        serde_json::from_str::<InvitesResponse>(raw).map(|resp| resp.invites)
+    }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
+}
+
+/// Delete an invite, the link stops working right away
+#[derive(Debug)]
+#[must_use]
+pub struct DeleteServerInvite {
+    /// Server the invite is for
+    server: ServerId,
+    /// Invite to delete
+    invite: String,
+}
+
+impl DeleteServerInvite {
+    /// Create a new `DeleteServerInvite` instruction for the given server and invite
+    pub fn new(server: impl Into<ServerId>, invite_id: &str) -> Self {
+        Self {
+            server: server.into(),
+            invite: invite_id.to_owned(),
+        }
+    }
+}
+
+impl Endpoint<()> for DeleteServerInvite {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client.delete(format!(
+            "{BASE_URL}/servers/{server}/invites/{invite}",
+            server = segment(&self.server),
+            invite = segment(&self.invite)
+        ))
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.server.0, &self.invite])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(_: &str) -> Result<(), serde_json::Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,114 @@
+//! Endpoints for media channels
+
+use serde::Serialize;
+use vived_models::{ChannelId, Media, MediaId};
+
+use crate::{Endpoint, Page};
+
+use super::{check_segments, segment, BASE_URL};
+
+/// Query arguments for `GetChannelMedia`
+#[derive(Serialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GetChannelMediaArguments {
+    /// before
+    #[serde(skip_serializing_if = "Option::is_none")]
+    before: Option<chrono::DateTime<chrono::Utc>>,
+    /// limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u8>,
+}
+
+/// Get the media posted in a media channel
+#[derive(Debug)]
+#[must_use]
+pub struct GetChannelMedia {
+    /// Channel to get media from
+    channel: ChannelId,
+    /// Query arguments
+    arguments: GetChannelMediaArguments,
+}
+
+impl GetChannelMedia {
+    /// Create a new `GetChannelMedia` instruction for the given channel
+    pub fn new(channel: impl Into<ChannelId>) -> Self {
+        Self {
+            channel: channel.into(),
+            arguments: GetChannelMediaArguments::default(),
+        }
+    }
+
+    /// Set the before argument
+    pub fn before(mut self, before: chrono::DateTime<chrono::Utc>) -> Self {
+        self.arguments.before = Some(before);
+        self
+    }
+
+    /// Set the limit argument
+    pub fn limit(mut self, limit: u8) -> Self {
+        self.arguments.limit = Some(limit);
+        self
+    }
+}
+
+impl Endpoint<Page<Media>> for GetChannelMedia {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client
+            .get(format!("{BASE_URL}/channels/{id}/media", id = segment(&self.channel)))
+            .query(&self.arguments)
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.channel.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(raw: &str) -> Result<Page<Media>, serde_json::Error> {
+        Page::from_raw(raw, "media")
+    }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
+}
+
+/// Delete media from a media channel
+#[derive(Debug)]
+#[must_use]
+pub struct DeleteMedia {
+    /// Channel the media is in
+    channel: ChannelId,
+    /// Media to delete
+    media: MediaId,
+}
+
+impl DeleteMedia {
+    /// Create a new `DeleteMedia` instruction for the given channel and media
+    pub fn new(channel: impl Into<ChannelId>, media: impl Into<MediaId>) -> Self {
+        Self {
+            channel: channel.into(),
+            media: media.into(),
+        }
+    }
+}
+
+impl Endpoint<()> for DeleteMedia {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client.delete(format!(
+            "{BASE_URL}/channels/{channel}/media/{media}",
+            channel = segment(&self.channel),
+            media = self.media
+        ))
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.channel.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(_: &str) -> Result<(), serde_json::Error> {
+        Ok(())
+    }
+}
@@ -0,0 +1,386 @@
+//! Endpoints for interacting with server members
+//! <https://www.guilded.gg/docs/api/members/ServerMember>
+
+use serde::{Deserialize, Serialize};
+use vived_models::limits::{self, NICKNAME, XP_AWARD};
+use vived_models::{Permission, ServerId, ServerMember, ServerMemberSummary, UserId};
+
+use crate::{ApiError, Endpoint};
+
+use super::{check_segments, segment, BASE_URL};
+
+/// Get a member of a server
+#[derive(Debug)]
+#[must_use]
+pub struct GetServerMember {
+    /// Server the member is in
+    server: ServerId,
+    /// Member to get
+    user: UserId,
+}
+
+impl GetServerMember {
+    /// Create a new `GetServerMember` instruction
+    pub fn new(server: impl Into<ServerId>, user: impl Into<UserId>) -> Self {
+        Self {
+            server: server.into(),
+            user: user.into(),
+        }
+    }
+}
+
+impl Endpoint<ServerMember> for GetServerMember {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client.get(format!(
+            "{BASE_URL}/servers/{server}/members/{user}",
+            server = segment(&self.server),
+            user = segment(&self.user)
+        ))
+    }
+
+    fn validate(&self) -> Result<(), ApiError> {
+        check_segments(&[&self.server.0, &self.user.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(raw: &str) -> Result<ServerMember, serde_json::Error> {
+        /// Response from the get member endpoint
+        #[derive(Deserialize, Debug)]
+        struct GetServerMemberResponse {
+            /// The member
+            member: ServerMember,
+        }
+        serde_json::from_str::<GetServerMemberResponse>(raw).map(|resp| resp.member)
+    }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
+}
+
+/// Get every member of a server
+///
+/// Members come without their nickname and join date, use [`GetServerMember`] for those.
+/// ```
+/// use vived_api::{endpoints::GetServerMembers, Endpoint};
+/// use vived_models::RoleId;
+///
+/// let raw = r#"{"members": [
+///     {"user": {"id": "a", "type": "bot", "name": "Bot"}, "roleIds": [1, 2]},
+///     {"user": {"id": "b", "name": "Someone", "avatar": null}, "roleIds": []}
+/// ]}"#;
+/// let members = GetServerMembers::new("s").parse_response(raw).unwrap();
+/// assert_eq!(members.len(), 2);
+/// assert!(members[0].has_role(RoleId(2)));
+/// assert!(!members[1].has_role(RoleId(2)));
+///
+/// let request = GetServerMembers::new("s").build(&reqwest::Client::new()).build().unwrap();
+/// assert_eq!(request.url().path(), "/api/v1/servers/s/members");
+/// ```
+#[derive(Debug)]
+#[must_use]
+pub struct GetServerMembers(ServerId);
+
+impl GetServerMembers {
+    /// Create a new `GetServerMembers` instruction for the given server
+    pub fn new(server: impl Into<ServerId>) -> Self {
+        Self(server.into())
+    }
+}
+
+impl Endpoint<Vec<ServerMemberSummary>> for GetServerMembers {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client.get(format!("{BASE_URL}/servers/{}/members", segment(&self.0)))
+    }
+
+    fn validate(&self) -> Result<(), ApiError> {
+        check_segments(&[&self.0.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(raw: &str) -> Result<Vec<ServerMemberSummary>, serde_json::Error> {
+        /// Response from the list members endpoint
+        #[derive(Deserialize, Debug)]
+        struct GetServerMembersResponse {
+            /// The members
+            members: Vec<ServerMemberSummary>,
+        }
+        serde_json::from_str::<GetServerMembersResponse>(raw).map(|resp| resp.members)
+    }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
+}
+
+/// Get the permissions a member has in a server, with all their roles taken into account
+///
+/// Collect the result into a [`vived_models::PermissionSet`] to check for permissions.
+/// ```
+/// use vived_api::{endpoints::MemberPermissionsGet, Endpoint};
+/// use vived_models::{Permission, PermissionSet};
+///
+/// let raw = r#"{"serverMemberPermissions": {"userId": "u", "permissions": ["CanReadChats", "CanUpdateServer"]}}"#;
+/// let permissions: PermissionSet = MemberPermissionsGet::new("s", "u").parse_response(raw).unwrap().into();
+/// assert!(permissions.contains(&Permission::UpdateServer));
+///
+/// let request = MemberPermissionsGet::new("s", "u").build(&reqwest::Client::new()).build().unwrap();
+/// assert_eq!(request.url().path(), "/api/v1/servers/s/members/u/permissions");
+/// ```
+#[derive(Debug)]
+#[must_use]
+pub struct MemberPermissionsGet {
+    /// Server the member is in
+    server: ServerId,
+    /// Member to get the permissions of
+    user: UserId,
+}
+
+impl MemberPermissionsGet {
+    /// Create a new `MemberPermissionsGet` instruction
+    pub fn new(server: impl Into<ServerId>, user: impl Into<UserId>) -> Self {
+        Self {
+            server: server.into(),
+            user: user.into(),
+        }
+    }
+}
+
+impl Endpoint<Vec<Permission>> for MemberPermissionsGet {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client.get(format!(
+            "{BASE_URL}/servers/{server}/members/{user}/permissions",
+            server = segment(&self.server),
+            user = segment(&self.user)
+        ))
+    }
+
+    fn validate(&self) -> Result<(), ApiError> {
+        check_segments(&[&self.server.0, &self.user.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(raw: &str) -> Result<Vec<Permission>, serde_json::Error> {
+        /// The permissions of the member
+        #[derive(Deserialize, Debug)]
+        struct MemberPermissions {
+            /// The permissions
+            permissions: Vec<Permission>,
+        }
+
+        /// Response from the member permissions endpoint
+        #[derive(Deserialize, Debug)]
+        #[serde(rename_all = "camelCase")]
+        struct MemberPermissionsGetResponse {
+            /// The permissions of the member
+            server_member_permissions: MemberPermissions,
+        }
+        serde_json::from_str::<MemberPermissionsGetResponse>(raw)
+            .map(|resp| resp.server_member_permissions.permissions)
+    }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
+}
+
+/// Json arguments for `MemberNicknameSet`
+#[derive(Serialize, Debug)]
+struct MemberNicknameSetArguments {
+    /// The new nickname
+    nickname: String,
+}
+
+/// Set the nickname of a server member
+#[derive(Debug)]
+#[must_use]
+pub struct MemberNicknameSet {
+    /// Server the member is in
+    server: ServerId,
+    /// Member to rename
+    user: UserId,
+    /// Json arguments
+    arguments: MemberNicknameSetArguments,
+}
+
+impl MemberNicknameSet {
+    /// Create a new `MemberNicknameSet` instruction
+    ///
+    /// # Errors
+    /// If the nickname is empty or longer than 32 characters
+    pub fn new(
+        server: impl Into<ServerId>,
+        user: impl Into<UserId>,
+        nickname: impl Into<String>,
+    ) -> Result<Self, ApiError> {
+        let nickname = nickname.into();
+
+        if !limits::fits_nickname(&nickname) {
+            return Err(ApiError::invalid_argument(format_args!(
+                "nickname must be between 1 and {NICKNAME} characters, but was {}",
+                limits::char_count(&nickname)
+            )));
+        }
+
+        Ok(Self {
+            server: server.into(),
+            user: user.into(),
+            arguments: MemberNicknameSetArguments { nickname },
+        })
+    }
+}
+
+impl Endpoint<String> for MemberNicknameSet {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client
+            .put(format!(
+                "{BASE_URL}/servers/{server}/members/{user}/nickname",
+                server = segment(&self.server),
+                user = segment(&self.user)
+            ))
+            .json(&self.arguments)
+    }
+
+    fn validate(&self) -> Result<(), ApiError> {
+        check_segments(&[&self.server.0, &self.user.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(raw: &str) -> Result<String, serde_json::Error> {
+        /// Response from the nickname set endpoint
+        #[derive(Deserialize, Debug)]
+        struct MemberNicknameSetResponse {
+            /// The new nickname
+            nickname: String,
+        }
+        serde_json::from_str::<MemberNicknameSetResponse>(raw).map(|resp| resp.nickname)
+    }
+}
+
+/// Remove the nickname of a server member
+#[derive(Debug)]
+#[must_use]
+pub struct MemberNicknameDelete {
+    /// Server the member is in
+    server: ServerId,
+    /// Member to remove the nickname from
+    user: UserId,
+}
+
+impl MemberNicknameDelete {
+    /// Create a new `MemberNicknameDelete` instruction
+    pub fn new(server: impl Into<ServerId>, user: impl Into<UserId>) -> Self {
+        Self {
+            server: server.into(),
+            user: user.into(),
+        }
+    }
+}
+
+impl Endpoint<()> for MemberNicknameDelete {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client.delete(format!(
+            "{BASE_URL}/servers/{server}/members/{user}/nickname",
+            server = segment(&self.server),
+            user = segment(&self.user)
+        ))
+    }
+
+    fn validate(&self) -> Result<(), ApiError> {
+        check_segments(&[&self.server.0, &self.user.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(_: &str) -> Result<(), serde_json::Error> {
+        Ok(())
+    }
+}
+
+/// Json arguments for `MemberXpAward`
+#[derive(Serialize, Debug)]
+struct MemberXpAwardArguments {
+    /// Xp to award, negative to take xp away
+    amount: i32,
+}
+
+/// Award xp to a server member, returning their new total
+///
+/// Guilded has no endpoint to read xp, award 0 to read a total without changing it:
+/// ```
+/// use vived_api::{endpoints::MemberXpAward, Endpoint};
+///
+/// let read = MemberXpAward::new("s", "u", 0).unwrap();
+/// let request = read.build(&reqwest::Client::new()).build().unwrap();
+/// assert_eq!(request.url().path(), "/api/v1/servers/s/members/u/xp");
+/// let body: serde_json::Value = serde_json::from_slice(request.body().unwrap().as_bytes().unwrap()).unwrap();
+/// assert_eq!(body, serde_json::json!({"amount": 0}));
+///
+/// assert_eq!(read.parse_response(r#"{"total": 120}"#).unwrap(), 120);
+/// ```
+#[derive(Debug)]
+#[must_use]
+pub struct MemberXpAward {
+    /// Server the member is in
+    server: ServerId,
+    /// Member to award xp to
+    user: UserId,
+    /// Json arguments
+    arguments: MemberXpAwardArguments,
+}
+
+impl MemberXpAward {
+    /// Create a new `MemberXpAward` instruction, a negative amount takes xp away
+    ///
+    /// # Errors
+    /// If the amount is not between -1000 and 1000
+    pub fn new(
+        server: impl Into<ServerId>,
+        user: impl Into<UserId>,
+        amount: i32,
+    ) -> Result<Self, ApiError> {
+        if !(-XP_AWARD..=XP_AWARD).contains(&amount) {
+            return Err(ApiError::invalid_argument(format_args!(
+                "xp amount must be between -{XP_AWARD} and {XP_AWARD}, but was {amount}"
+            )));
+        }
+
+        Ok(Self {
+            server: server.into(),
+            user: user.into(),
+            arguments: MemberXpAwardArguments { amount },
+        })
+    }
+}
+
+impl Endpoint<i64> for MemberXpAward {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client
+            .post(format!(
+                "{BASE_URL}/servers/{server}/members/{user}/xp",
+                server = segment(&self.server),
+                user = segment(&self.user)
+            ))
+            .json(&self.arguments)
+    }
+
+    fn validate(&self) -> Result<(), ApiError> {
+        check_segments(&[&self.server.0, &self.user.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(raw: &str) -> Result<i64, serde_json::Error> {
+        /// Response from the xp award endpoint
+        #[derive(Deserialize, Debug)]
+        struct MemberXpAwardResponse {
+            /// The new xp total of the member
+            total: i64,
+        }
+        serde_json::from_str::<MemberXpAwardResponse>(raw).map(|resp| resp.total)
+    }
+}
@@ -1,17 +1,18 @@
 //! <https://www.guilded.gg/docs/api/chat/ChatMessage>
 
 use serde::{Deserialize, Serialize};
-use vived_models::{ChannelId, MessageId, Embed, Message};
+use vived_models::limits::{
+    self, DEFAULT_MESSAGES_PER_REQUEST, MESSAGES_PER_REQUEST, MESSAGE_CONTENT, REPLY_MESSAGE_IDS,
+};
+use vived_models::{ChannelId, Color, MessageId, Embed, Message};
 
-use crate::Endpoint;
+use crate::{ApiClient, ApiError, Endpoint, Page};
 
-use super::BASE_URL;
-
-// TODO: implement embed, private, silent, and reply_message_ids
+use super::{check_segments, segment, BASE_URL};
 
 
 /// Arguments passed as json to the guilded api
-#[derive(Serialize, Default)]
+#[derive(Serialize, Deserialize, Default, Debug, Clone)]
 pub struct MessageCreateArguments {
     /// Content to send
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -29,10 +30,90 @@ pub struct MessageCreateArguments {
     silent: Option<bool>,
     /// Message ids to reply to
     #[serde(skip_serializing_if = "Option::is_none")]
+    // the alias reads messages stored before the field was renamed
+    #[serde(rename = "replyMessageIds", alias = "reply_message_ids")]
     reply_message_ids: Option<Vec<vived_models::MessageId>>,
 }
 
+/// A reusable message, for responses a bot sends a lot like errors, see [`MessageCreate::from_template`]
+///
+/// ```
+/// use vived_api::endpoints::MessageTemplate;
+/// use vived_models::{Color, Embed};
+///
+/// let error = MessageTemplate::error("No permission", "You need to be a moderator to do that");
+/// let embed = error.to_embed().unwrap();
+/// assert_eq!(embed.color, Some(Color::RED));
+/// assert_eq!(embed.title.as_deref(), Some("No permission"));
+///
+/// assert_eq!(MessageTemplate::success("Done", "").to_embed().unwrap().color, Some(Color::GREEN));
+/// assert_eq!(MessageTemplate::info("Help", "").to_embed().unwrap().color, Some(Color::BLUE));
+///
+/// let help = MessageTemplate::text("Use !help for a list of commands");
+/// assert!(help.to_embed().is_none());
+/// assert_eq!(help.to_content(), Some("Use !help for a list of commands"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[must_use]
+pub struct MessageTemplate {
+    /// Content of the message
+    content: Option<String>,
+    /// Embed of the message
+    embed: Option<Embed>,
+}
+
+impl MessageTemplate {
+    /// A message with only the given content
+    pub fn text(content: impl Into<String>) -> Self {
+        Self {
+            content: Some(content.into()),
+            embed: None,
+        }
+    }
+
+    /// A message with only the given embed
+    pub fn embed(embed: Embed) -> Self {
+        Self {
+            content: None,
+            embed: Some(embed),
+        }
+    }
+
+    /// An embed with a title, description and color
+    fn colored(title: &str, description: &str, color: Color) -> Self {
+        Self::embed(Embed::new().title(title).description(description).color(color))
+    }
+
+    /// A red embed for when something went wrong
+    pub fn error(title: &str, description: &str) -> Self {
+        Self::colored(title, description, Color::RED)
+    }
+
+    /// A green embed for when something worked
+    pub fn success(title: &str, description: &str) -> Self {
+        Self::colored(title, description, Color::GREEN)
+    }
+
+    /// A blue embed for general information, like help text
+    pub fn info(title: &str, description: &str) -> Self {
+        Self::colored(title, description, Color::BLUE)
+    }
+
+    /// The content this template sends, if any
+    #[must_use]
+    pub fn to_content(&self) -> Option<&str> {
+        self.content.as_deref()
+    }
+
+    /// The embed this template sends, if any
+    #[must_use]
+    pub fn to_embed(&self) -> Option<&Embed> {
+        self.embed.as_ref()
+    }
+}
+
 /// Send a message
+#[derive(Debug, Clone)]
 #[must_use]
 pub struct MessageCreate {
     /// Channel to send in
@@ -42,8 +123,22 @@ pub struct MessageCreate {
 }
 
 impl MessageCreate {
-    // we make two constructors so we can enforce that at least embed or content is given
-    // you can add both by adding the missing one with the corresponding method
+    /// Create a new empty message create instruction for the given channel
+    ///
+    /// Set the content or an embed before sending it, a message without either is rejected when the request is made.
+    /// ```
+    /// use vived_api::{endpoints::MessageCreate, Endpoint};
+    ///
+    /// assert!(MessageCreate::new("channel").validate().is_err());
+    /// assert!(MessageCreate::new("channel").content("hi").validate().is_ok());
+    /// assert!(MessageCreate::new("channel").content("漢".repeat(4001)).validate().is_err());
+    /// ```
+    pub fn new(channel: impl Into<ChannelId>) -> Self {
+        Self {
+            channel: channel.into(),
+            arguments: MessageCreateArguments::default(),
+        }
+    }
 
     /// Create a new message create instruction for the given channel.
     /// With the given content
@@ -69,29 +164,106 @@ impl MessageCreate {
         }
     }
 
+    /// Create a new message create instruction for the given channel from a template
+    ///
+    /// ```
+    /// use vived_api::endpoints::{MessageCreate, MessageTemplate};
+    /// use vived_api::Endpoint;
+    ///
+    /// let message = MessageCreate::from_template("channel", MessageTemplate::error("Oops", "Unknown command"));
+    /// assert!(message.validate().is_ok());
+    /// assert_eq!(message.content_text(), None);
+    /// ```
+    pub fn from_template(channel: impl Into<ChannelId>, template: MessageTemplate) -> Self {
+        Self {
+            channel: channel.into(),
+            arguments: MessageCreateArguments {
+                content: template.content,
+                embeds: template.embed.map(|embed| vec![embed]),
+                ..Default::default()
+            },
+        }
+    }
+
+    /// Create a reply to `message`, in the same channel, with the given content
+    pub fn as_reply_to(message: &Message, content: impl Into<String>) -> Self {
+        Self::new_with_content(message.channel_id.clone(), content).reply(message.id.clone())
+    }
+
     /// Create a new message create instruction based on a message object
+    ///
+    /// If the message somehow replies to itself that reply is dropped.
     pub fn new_from_message(channel: Option<impl Into<ChannelId>>, message: Message) -> Self {
         let channel = channel.map_or(message.channel_id, Into::into);
-        Self {
+        let mut create = Self {
             channel,
             arguments: MessageCreateArguments {
                 content: message.content,
                 embeds: Some(message.embeds),
                 private: Some(message.is_private),
                 silent: Some(message.is_silent),
-                reply_message_ids: message.reply_message_ids,
+                reply_message_ids: None,
             },
+        };
+
+        for reply in message.reply_message_ids.into_iter().flatten() {
+            if reply == message.id {
+                log::warn!("message {reply} replies to itself, dropping the reply");
+            } else {
+                create.push_reply(reply);
+            }
+        }
+        create
+    }
+
+    /// Create a message from its json arguments, see [`MessageCreate::arguments`]
+    pub fn from_arguments(channel: impl Into<ChannelId>, arguments: MessageCreateArguments) -> Self {
+        Self {
+            channel: channel.into(),
+            arguments,
+        }
+    }
+
+    /// Copy this message, but send it to another channel
+    pub fn clone_for_channel(&self, channel: impl Into<ChannelId>) -> Self {
+        Self {
+            channel: channel.into(),
+            arguments: self.arguments.clone(),
         }
     }
 
     // implement builder pattern for the MessageCreateArguments
 
+    /// The channel the message is sent in
+    #[must_use]
+    pub fn channel_id(&self) -> &ChannelId {
+        &self.channel
+    }
+
+    /// The json arguments sent to guilded, for storing the message to send it later
+    #[must_use]
+    pub fn arguments(&self) -> &MessageCreateArguments {
+        &self.arguments
+    }
+
+    /// The content of the message, if it was set
+    #[must_use]
+    pub fn content_text(&self) -> Option<&str> {
+        self.arguments.content.as_deref()
+    }
+
     /// Set the content of the message
     pub fn content(mut self, content: impl Into<String>) -> Self {
         self.arguments.content = Some(content.into());
         self
     }
 
+    /// Set the content of the message
+    #[deprecated(note = "use `MessageCreate::content` instead")]
+    pub fn with_content(self, content: impl Into<String>) -> Self {
+        self.content(content)
+    }
+
     /// Embed to send
     pub fn embed(mut self, embed: Embed) -> Self {
         self.arguments.embeds = Some(vec![embed]);
@@ -110,21 +282,43 @@ impl MessageCreate {
         self
     }
 
-    /// Reply Message Ids
+    /// Reply Message Ids, replacing any replies already set
+    ///
+    /// Duplicates are removed, and only the first 5 replies are kept since that is all guilded allows.
+    /// ```
+    /// use vived_api::endpoints::MessageCreate;
+    ///
+    /// let message = MessageCreate::new_with_content("channel", "hi")
+    ///     .replies(vec!["a", "b", "a", "c", "d", "e", "f"]);
+    /// let json = serde_json::to_value(message.arguments()).unwrap();
+    /// assert_eq!(json["replyMessageIds"], serde_json::json!(["a", "b", "c", "d", "e"]));
+    /// ```
     pub fn replies(mut self, replies: Vec<impl Into<vived_models::MessageId>>) -> Self {
-        self.arguments.reply_message_ids = Some(replies.into_iter().map(Into::into).collect());
+        self.arguments.reply_message_ids = None;
+        for reply in replies {
+            self.push_reply(reply.into());
+        }
         self
     }
 
-    /// Add single reply
+    /// Add single reply, see [`MessageCreate::replies`] for the limits
     pub fn reply(mut self, reply: impl Into<vived_models::MessageId>) -> Self {
-        self.arguments
-            .reply_message_ids
-            .get_or_insert_with(Vec::new)
-            .push(reply.into());
-
+        self.push_reply(reply.into());
         self
     }
+
+    /// Add a reply, skipping duplicates and replies past the limit
+    fn push_reply(&mut self, reply: MessageId) {
+        let replies = self.arguments.reply_message_ids.get_or_insert_with(Vec::new);
+        if replies.contains(&reply) {
+            return;
+        }
+        if replies.len() >= REPLY_MESSAGE_IDS {
+            log::warn!("a message can reply to at most {REPLY_MESSAGE_IDS} messages, ignoring reply to {reply}");
+            return;
+        }
+        replies.push(reply);
+    }
 }
 
 impl Endpoint<Message> for MessageCreate {
@@ -132,11 +326,36 @@ impl Endpoint<Message> for MessageCreate {
         client
             .post(format!(
                 "{BASE_URL}/channels/{id}/messages",
-                id = self.channel
+                id = segment(&self.channel)
             ))
             .json(&self.arguments)
     }
 
+    /// # Errors
+    /// - if the channel id is invalid
+    /// - if there is neither content nor an embed
+    /// - if the content or an embed is too long
+    fn validate(&self) -> Result<(), ApiError> {
+        check_segments(&[&self.channel.0])?;
+
+        let content = self.arguments.content.as_deref().unwrap_or_default();
+        let embeds = self.arguments.embeds.as_deref().unwrap_or_default();
+
+        if content.is_empty() && embeds.is_empty() {
+            return Err(ApiError::invalid_argument("a message needs content or an embed"));
+        }
+        if !limits::fits_message_content(content) {
+            return Err(ApiError::invalid_argument(format_args!(
+                "message content can be at most {MESSAGE_CONTENT} characters, but was {}",
+                limits::char_count(content)
+            )));
+        }
+        if !embeds.iter().all(limits::fits_embed) {
+            return Err(ApiError::invalid_argument("embed is over one of the embed limits"));
+        }
+        Ok(())
+    }
+
     /// # Errors
     /// - if the json is invalid or doesn't match the schema
     fn from_raw(raw: &str) -> Result<Message, serde_json::Error> {
@@ -152,7 +371,7 @@ impl Endpoint<Message> for MessageCreate {
 
 
 /// Json arguments for `ChannelGetMessages`
-#[derive(Serialize)]
+#[derive(Serialize, Debug)]
 #[serde(rename_all = "camelCase")]
 struct ChannelGetMessagesArguments {
     /// before
@@ -172,30 +391,76 @@ impl Default for ChannelGetMessagesArguments {
         Self {
             before: None,
             after: None,
-            limit: 50,
+            limit: DEFAULT_MESSAGES_PER_REQUEST,
             include_private: false,
         }
     }
 }
 
 /// Get a list of recent messages in a channel
+#[derive(Debug)]
 #[must_use]
 pub struct ChannelGetMessages {
     /// Channel to get messages from
     channel: ChannelId,
     /// Arguments
     arguments: ChannelGetMessagesArguments,
+    /// Message to leave out of the result, see [`ChannelGetMessages::from_message`]
+    exclude: Option<MessageId>,
 }
 
 impl ChannelGetMessages {
     /// Create a new `ChannelGetMessages` instruction for the given channel
+    ///
+    /// By default this gets the 50 most recent messages, with no date bounds and without private messages.
     pub fn new(channel: impl Into<ChannelId>) -> Self {
         Self {
             channel: channel.into(),
             arguments: ChannelGetMessagesArguments::default(),
+            exclude: None,
         }
     }
 
+    /// Get the messages sent after `after_message`, not including `after_message` itself
+    ///
+    /// Other messages sent at the exact same time as `after_message` are still included.
+    /// ```
+    /// use vived_api::{endpoints::ChannelGetMessages, Endpoint};
+    /// use vived_models::Message;
+    ///
+    /// let message = r#"{
+    ///     "id": "a", "type": "default", "serverId": "s", "channelId": "c",
+    ///     "createdAt": "2022-10-01T12:00:00.000Z", "createdBy": "u"
+    /// }"#;
+    /// let after: Message = serde_json::from_str(message).unwrap();
+    ///
+    /// let page = ChannelGetMessages::from_message("c", &after)
+    ///     .parse_response(&format!(r#"{{"messages": [{message}, {}]}}"#, message.replace(r#""a""#, r#""b""#)))
+    ///     .unwrap();
+    /// assert_eq!(page.len(), 1);
+    /// assert_eq!(page[0].id.0, "b");
+    /// ```
+    pub fn from_message(channel: impl Into<ChannelId>, after_message: &Message) -> Self {
+        let mut endpoint = Self::new(channel).after(after_message.created_at);
+        endpoint.exclude = Some(after_message.id.clone());
+        endpoint
+    }
+
+    /// Same as [`ChannelGetMessages::new`], for when you want to make it clear the defaults are used
+    pub fn with_defaults(channel: impl Into<ChannelId>) -> Self {
+        Self::new(channel)
+    }
+
+    /// Get the 50 most recent messages, which is also the default
+    pub fn with_limit_50(channel: impl Into<ChannelId>) -> Self {
+        Self::new(channel).limit(DEFAULT_MESSAGES_PER_REQUEST)
+    }
+
+    /// Get the 100 most recent messages, which is the most guilded allows in one request
+    pub fn with_limit_100(channel: impl Into<ChannelId>) -> Self {
+        Self::new(channel).limit(MESSAGES_PER_REQUEST)
+    }
+
     /// Set the before argument
     pub fn before(mut self, before: chrono::DateTime<chrono::Utc>) -> Self {
         self.arguments.before = Some(before);
@@ -208,19 +473,59 @@ impl ChannelGetMessages {
         self
     }
 
+    /// Only get messages sent between `start` and `end`
+    ///
+    /// # Panics
+    /// If `start` is not before `end`
+    pub fn between(
+        self,
+        start: chrono::DateTime<chrono::Utc>,
+        end: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        assert!(
+            start < end,
+            "start of the range ({start}) must be before the end ({end})"
+        );
+        self.after(start).before(end)
+    }
+
+    /// Only get messages sent after `start`, alias for [`ChannelGetMessages::after`]
+    pub fn since(self, start: chrono::DateTime<chrono::Utc>) -> Self {
+        self.after(start)
+    }
+
+    /// Only get messages sent before `end`, alias for [`ChannelGetMessages::before`]
+    pub fn until(self, end: chrono::DateTime<chrono::Utc>) -> Self {
+        self.before(end)
+    }
+
     /// Set the limit argument
     pub fn limit(mut self, limit: u8) -> Self {
         // limit is capped at 100
         // produce warning if limit is higher than 100
-        if limit > 100 {
-            log::warn!("limit is capped at 100, but {} was given", limit);
+        if limit > MESSAGES_PER_REQUEST {
+            log::warn!("limit is capped at {MESSAGES_PER_REQUEST}, but {limit} was given");
         }
-        self.arguments.limit = limit.min(100); 
+        self.arguments.limit = limit.min(MESSAGES_PER_REQUEST);
 
         self
     }
 
     /// Set the include private argument
+    ///
+    /// It is always sent, as `includePrivate=true` or `includePrivate=false`.
+    /// ```
+    /// use vived_api::{endpoints::ChannelGetMessages, Endpoint};
+    ///
+    /// let client = reqwest::Client::new();
+    /// let query = |endpoint: ChannelGetMessages| endpoint.build(&client).build().unwrap().url().query().map(str::to_owned);
+    ///
+    /// assert_eq!(query(ChannelGetMessages::new("c")).as_deref(), Some("limit=50&includePrivate=false"));
+    /// assert_eq!(
+    ///     query(ChannelGetMessages::new("c").include_private(true)).as_deref(),
+    ///     Some("limit=50&includePrivate=true")
+    /// );
+    /// ```
     pub fn include_private(mut self, include_private: bool) -> Self {
         self.arguments.include_private = include_private;
         self
@@ -228,31 +533,46 @@ impl ChannelGetMessages {
 }
 
 
-impl Endpoint<Vec<Message>> for ChannelGetMessages {
+impl Endpoint<Page<Message>> for ChannelGetMessages {
     fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
         client
             .get(format!(
                 "{BASE_URL}/channels/{id}/messages",
-                id = self.channel
+                id = segment(&self.channel)
             ))
             .query(&self.arguments)
     }
 
+    fn validate(&self) -> Result<(), ApiError> {
+        check_segments(&[&self.channel.0])
+    }
+
     /// # Errors
     /// - if the json is invalid or doesn't match the schema
-    fn from_raw(raw: &str) -> Result<Vec<Message>, serde_json::Error> {
-        /// Response from the channel get messages endpoint
-        #[derive(Deserialize, Debug)]
-        struct ChannelGetMessagesResponse {
-            /// Messages
-            messages: Vec<Message>,
+    fn from_raw(raw: &str) -> Result<Page<Message>, serde_json::Error> {
+        Page::from_raw(raw, "messages")
+    }
+
+    /// Leaves out the message given to [`ChannelGetMessages::from_message`]
+    ///
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn parse_response(&self, raw: &str) -> Result<Page<Message>, serde_json::Error> {
+        let mut page = Self::from_raw(raw)?;
+        if let Some(ref exclude) = self.exclude {
+            page.items.retain(|message: &Message| &message.id != exclude);
         }
-        serde_json::from_str::<ChannelGetMessagesResponse>(raw).map(|resp| resp.messages)
+        Ok(page)
+    }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
     }
 }
 
 
 /// Get specific message in a channel
+#[derive(Debug)]
 #[must_use]
 pub struct ChannelGetMessage {
     /// Channel to get message from
@@ -275,11 +595,15 @@ impl Endpoint<Message> for ChannelGetMessage {
     fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
         client.get(format!(
             "{BASE_URL}/channels/{channel}/messages/{message}",
-            channel = self.channel,
-            message = self.message
+            channel = segment(&self.channel),
+            message = segment(&self.message)
         ))
     }
 
+    fn validate(&self) -> Result<(), ApiError> {
+        check_segments(&[&self.channel.0, &self.message.0])
+    }
+
     /// # Errors
     /// - if the json is invalid or doesn't match the schema
     fn from_raw(raw: &str) -> Result<Message, serde_json::Error> {
@@ -291,10 +615,14 @@ impl Endpoint<Message> for ChannelGetMessage {
         }
         serde_json::from_str::<ChannelGetMessageResponse>(raw).map(|resp| resp.message)
     }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
 }
 
 /// Edit message json arguments
-#[derive(Serialize, Default)]
+#[derive(Serialize, Default, Debug)]
 #[serde(rename_all = "camelCase")]
 struct MessageEditArguments {
     /// Message content
@@ -307,6 +635,7 @@ struct MessageEditArguments {
 
 
 /// Edit a message
+#[derive(Debug)]
 #[must_use]
 pub struct MessageEdit {
     /// Channel to edit message in
@@ -345,12 +674,16 @@ impl Endpoint<Message> for MessageEdit {
         client
             .put(format!(
                 "{BASE_URL}/channels/{channel}/messages/{message}",
-                channel = self.channel,
-                message = self.message
+                channel = segment(&self.channel),
+                message = segment(&self.message)
             ))
             .json(&self.arguments)
     }
 
+    fn validate(&self) -> Result<(), ApiError> {
+        check_segments(&[&self.channel.0, &self.message.0])
+    }
+
     /// # Errors
     /// - if the json is invalid or doesn't match the schema
     fn from_raw(raw: &str) -> Result<Message, serde_json::Error> {
@@ -388,14 +721,69 @@ impl Endpoint<()> for MessageDelete {
     fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
         client.delete(format!(
             "{BASE_URL}/channels/{channel}/messages/{message}",
-            channel = self.channel,
-            message = self.message
+            channel = segment(&self.channel),
+            message = segment(&self.message)
         ))
     }
 
+    fn validate(&self) -> Result<(), ApiError> {
+        check_segments(&[&self.channel.0, &self.message.0])
+    }
+
     /// # Errors
     /// - if the json is invalid or doesn't match the schema
     fn from_raw(_: &str) -> Result<(), serde_json::Error> {
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+/// Default number of deletes sent at once by `BulkDeleteMessages`
+const BULK_DELETE_BATCH_SIZE: usize = 10;
+
+/// Delete many messages in a channel
+///
+/// Guilded does not have a bulk delete endpoint, so this sends a [`MessageDelete`] for each message.
+/// The deletes are sent in batches, and all requests still go through the client ratelimiter.
+#[derive(Debug)]
+#[must_use]
+pub struct BulkDeleteMessages {
+    /// Channel to delete messages in
+    channel: ChannelId,
+    /// Messages to delete
+    messages: Vec<MessageId>,
+    /// How many deletes to send at once
+    batch_size: usize,
+}
+
+impl BulkDeleteMessages {
+    /// Create a new `BulkDeleteMessages` instruction for the given channel and messages
+    pub fn new(channel: impl Into<ChannelId>, messages: Vec<MessageId>) -> Self {
+        Self {
+            channel: channel.into(),
+            messages,
+            batch_size: BULK_DELETE_BATCH_SIZE,
+        }
+    }
+
+    /// Set how many deletes are sent at once, defaults to 10
+    pub fn batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    /// Delete the messages
+    ///
+    /// Returns the result of each delete, in the same order as the messages were given.
+    pub async fn execute(&self, client: &ApiClient) -> Vec<Result<(), ApiError>> {
+        let mut results = Vec::with_capacity(self.messages.len());
+
+        for batch in self.messages.chunks(self.batch_size) {
+            let deletes = batch.iter().map(|message| {
+                client.make_request(MessageDelete::new(self.channel.clone(), message.clone()))
+            });
+            results.extend(futures_util::future::join_all(deletes).await);
+        }
+
+        results
+    }
+}
@@ -1,12 +1,86 @@
 //! Implement different guilded api endpoints
+//!
+//! Ids are percent-encoded when they are put in the url, so an id can't change which route is requested.
+//! Ids that are empty or only dots can't be made safe that way, those are rejected before the request is sent.
+//! ```
+//! use vived_api::endpoints::{GetChannel, MessageDelete};
+//! use vived_api::{ApiError, Endpoint};
+//!
+//! let client = reqwest::Client::new();
+//!
+//! let request = GetChannel::new("../servers/xyz").build(&client).build().unwrap();
+//! assert_eq!(request.url().path(), "/api/v1/channels/..%2Fservers%2Fxyz");
+//!
+//! let request = MessageDelete::new("channel", "message?all=true").build(&client).build().unwrap();
+//! assert_eq!(request.url().path(), "/api/v1/channels/channel/messages/message%3Fall%3Dtrue");
+//! assert_eq!(request.url().query(), None);
+//!
+//! assert!(matches!(GetChannel::new("..").validate(), Err(ApiError::InvalidArgument(_))));
+//! assert!(matches!(GetChannel::new("").validate(), Err(ApiError::InvalidArgument(_))));
+//! ```
+
+use std::fmt::Write;
+
+use crate::ApiError;
 
 /// Base url of guilded api
 const BASE_URL: &str = "https://www.guilded.gg/api/v1";
 
+/// Percent-encode an id so it always ends up as exactly one path segment
+fn segment(id: &impl std::fmt::Display) -> String {
+    let id = id.to_string();
+    let mut encoded = String::with_capacity(id.len());
+
+    for c in id.chars() {
+        if c.is_ascii_alphanumeric() || matches!(c, '-' | '.' | '_' | '~' | '@' | ':') {
+            encoded.push(c);
+        } else {
+            let mut bytes = [0; 4];
+            for byte in c.encode_utf8(&mut bytes).bytes() {
+                // writing to a string can't fail
+                let _ = write!(encoded, "%{byte:02X}");
+            }
+        }
+    }
+
+    encoded
+}
+
+/// Check the ids that go in the url path
+///
+/// # Errors
+/// If an id is empty or only dots, the url parser would drop it or go up a level
+fn check_segments(ids: &[&str]) -> Result<(), ApiError> {
+    match ids.iter().find(|id| id.chars().all(|c| c == '.')) {
+        Some(id) => Err(ApiError::invalid_argument(format_args!(
+            "{id:?} is not a valid id"
+        ))),
+        None => Ok(()),
+    }
+}
+
 mod messages;
 mod server;
 mod channels;
+mod members;
+mod xp;
+mod scheduling;
+mod media;
+mod users;
+mod invites;
+mod subscriptions;
+mod emotes;
+mod raw;
 
 pub use messages::*;
 pub use server::*;
-pub use channels::*;
\ No newline at end of file
+pub use channels::*;
+pub use members::*;
+pub use xp::*;
+pub use scheduling::*;
+pub use media::*;
+pub use users::*;
+pub use invites::*;
+pub use subscriptions::*;
+pub use emotes::*;
+pub use raw::*;
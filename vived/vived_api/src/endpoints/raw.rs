@@ -0,0 +1,111 @@
+//! A request to any route, for things the other endpoints don't cover yet
+
+use crate::Endpoint;
+
+use super::{check_segments, BASE_URL};
+
+/// A request to a route given by its path, see [`crate::ApiClient::execute_raw`]
+///
+/// The path is relative to the api base url, the leading `/` is optional.
+/// Successful responses are returned as json, an empty body becomes [`serde_json::Value::Null`].
+/// ```
+/// use vived_api::endpoints::RawRequest;
+/// use vived_api::reqwest::Method;
+/// use vived_api::{ApiError, Endpoint};
+///
+/// let client = reqwest::Client::new();
+///
+/// let get = RawRequest::new(Method::GET, "/servers/abc/members", None, &[("limit", "10"), ("q", "a b")]);
+/// let request = get.build(&client).build().unwrap();
+/// assert_eq!(request.url().as_str(), "https://www.guilded.gg/api/v1/servers/abc/members?limit=10&q=a+b");
+/// assert!(request.body().is_none());
+///
+/// let body = serde_json::json!({"content": "hi"});
+/// let post = RawRequest::new(Method::POST, "channels/abc/messages", Some(body.clone()), &[]);
+/// let request = post.build(&client).build().unwrap();
+/// assert_eq!(request.method(), Method::POST);
+/// assert_eq!(request.url().path(), "/api/v1/channels/abc/messages");
+/// let sent: serde_json::Value = serde_json::from_slice(request.body().unwrap().as_bytes().unwrap()).unwrap();
+/// assert_eq!(sent, body);
+///
+/// assert_eq!(post.parse_response("").unwrap(), serde_json::Value::Null);
+/// assert_eq!(post.parse_response(r#"{"a": 1}"#).unwrap()["a"], 1);
+///
+/// let escape = RawRequest::new(Method::GET, "servers/../../v2", None, &[]);
+/// assert!(matches!(escape.validate(), Err(ApiError::InvalidArgument(_))));
+/// ```
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct RawRequest {
+    /// The http method
+    method: reqwest::Method,
+    /// Path relative to the base url, without the leading `/`
+    path: String,
+    /// Json body to send
+    body: Option<serde_json::Value>,
+    /// Query parameters
+    query: Vec<(String, String)>,
+}
+
+impl RawRequest {
+    /// Create a new `RawRequest` for `path`
+    pub fn new(
+        method: reqwest::Method,
+        path: &str,
+        body: Option<serde_json::Value>,
+        query: &[(&str, &str)],
+    ) -> Self {
+        Self {
+            method,
+            path: path.trim_start_matches('/').to_owned(),
+            body,
+            query: query
+                .iter()
+                .map(|&(key, value)| (key.to_owned(), value.to_owned()))
+                .collect(),
+        }
+    }
+}
+
+impl Endpoint<serde_json::Value> for RawRequest {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        let mut request = client.request(self.method.clone(), format!("{BASE_URL}/{}", self.path));
+        if !self.query.is_empty() {
+            request = request.query(&self.query);
+        }
+        if let Some(ref body) = self.body {
+            request = request.json(body);
+        }
+        request
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        if self.path.contains(['?', '#']) {
+            return Err(crate::ApiError::invalid_argument(
+                "pass query parameters separately instead of in the path",
+            ));
+        }
+
+        // empty segments are fine, only `.` and `..` would move the request off the given route
+        let segments: Vec<&str> = self.path.split('/').filter(|segment| !segment.is_empty()).collect();
+        check_segments(&segments)
+    }
+
+    /// # Errors
+    /// - if the body isn't json
+    fn from_raw(raw: &str) -> Result<serde_json::Value, serde_json::Error> {
+        if raw.trim().is_empty() {
+            Ok(serde_json::Value::Null)
+        } else {
+            serde_json::from_str(raw)
+        }
+    }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        if self.method == reqwest::Method::GET {
+            crate::RatelimitWeight::Light
+        } else {
+            crate::RatelimitWeight::Normal
+        }
+    }
+}
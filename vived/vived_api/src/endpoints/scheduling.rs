@@ -0,0 +1,275 @@
+//! Endpoints for availabilities in scheduling channels
+
+use serde::{Deserialize, Serialize};
+use vived_models::{ChannelId, SchedulingAvailability, SchedulingAvailabilityId, UserId};
+
+use crate::{Endpoint, Page};
+
+use super::{check_segments, segment, BASE_URL};
+
+/// Response containing a single availability
+#[derive(Deserialize, Debug)]
+struct AvailabilityResponse {
+    /// The availability
+    availability: SchedulingAvailability,
+}
+
+/// Json arguments for `CreateSchedulingAvailability`
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct CreateSchedulingAvailabilityArguments {
+    /// Start of the availability
+    start_date: chrono::DateTime<chrono::Utc>,
+    /// End of the availability
+    end_date: chrono::DateTime<chrono::Utc>,
+}
+
+/// Post an availability in a scheduling channel
+#[derive(Debug)]
+#[must_use]
+pub struct CreateSchedulingAvailability {
+    /// Channel to post in
+    channel: ChannelId,
+    /// Json arguments
+    arguments: CreateSchedulingAvailabilityArguments,
+}
+
+impl CreateSchedulingAvailability {
+    /// Create a new `CreateSchedulingAvailability` instruction for the given channel and time range
+    pub fn new(
+        channel: impl Into<ChannelId>,
+        start_date: chrono::DateTime<chrono::Utc>,
+        end_date: chrono::DateTime<chrono::Utc>,
+    ) -> Self {
+        Self {
+            channel: channel.into(),
+            arguments: CreateSchedulingAvailabilityArguments {
+                start_date,
+                end_date,
+            },
+        }
+    }
+}
+
+impl Endpoint<SchedulingAvailability> for CreateSchedulingAvailability {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client
+            .post(format!(
+                "{BASE_URL}/channels/{id}/availabilities",
+                id = segment(&self.channel)
+            ))
+            .json(&self.arguments)
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.channel.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(raw: &str) -> Result<SchedulingAvailability, serde_json::Error> {
+        serde_json::from_str::<AvailabilityResponse>(raw).map(|resp| resp.availability)
+    }
+}
+
+/// Query arguments for `GetSchedulingAvailabilities`
+#[derive(Serialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+struct GetSchedulingAvailabilitiesArguments {
+    /// before
+    #[serde(skip_serializing_if = "Option::is_none")]
+    before: Option<chrono::DateTime<chrono::Utc>>,
+    /// after
+    #[serde(skip_serializing_if = "Option::is_none")]
+    after: Option<chrono::DateTime<chrono::Utc>>,
+    /// limit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    limit: Option<u8>,
+    /// Only get availabilities of this user
+    #[serde(skip_serializing_if = "Option::is_none")]
+    user_id: Option<UserId>,
+}
+
+/// Get the availabilities in a scheduling channel
+#[derive(Debug)]
+#[must_use]
+pub struct GetSchedulingAvailabilities {
+    /// Channel to get availabilities from
+    channel: ChannelId,
+    /// Query arguments
+    arguments: GetSchedulingAvailabilitiesArguments,
+}
+
+impl GetSchedulingAvailabilities {
+    /// Create a new `GetSchedulingAvailabilities` instruction for the given channel
+    pub fn new(channel: impl Into<ChannelId>) -> Self {
+        Self {
+            channel: channel.into(),
+            arguments: GetSchedulingAvailabilitiesArguments::default(),
+        }
+    }
+
+    /// Set the before argument
+    pub fn before(mut self, before: chrono::DateTime<chrono::Utc>) -> Self {
+        self.arguments.before = Some(before);
+        self
+    }
+
+    /// Set the after argument
+    pub fn after(mut self, after: chrono::DateTime<chrono::Utc>) -> Self {
+        self.arguments.after = Some(after);
+        self
+    }
+
+    /// Set the limit argument
+    pub fn limit(mut self, limit: u8) -> Self {
+        self.arguments.limit = Some(limit);
+        self
+    }
+
+    /// Only get availabilities of the given user
+    pub fn user(mut self, user: impl Into<UserId>) -> Self {
+        self.arguments.user_id = Some(user.into());
+        self
+    }
+}
+
+impl Endpoint<Page<SchedulingAvailability>> for GetSchedulingAvailabilities {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client
+            .get(format!(
+                "{BASE_URL}/channels/{id}/availabilities",
+                id = segment(&self.channel)
+            ))
+            .query(&self.arguments)
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.channel.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(raw: &str) -> Result<Page<SchedulingAvailability>, serde_json::Error> {
+        Page::from_raw(raw, "availabilities")
+    }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
+}
+
+/// Json arguments for `UpdateSchedulingAvailability`
+#[derive(Serialize, Default, Debug)]
+#[serde(rename_all = "camelCase")]
+struct UpdateSchedulingAvailabilityArguments {
+    /// New start of the availability
+    #[serde(skip_serializing_if = "Option::is_none")]
+    start_date: Option<chrono::DateTime<chrono::Utc>>,
+    /// New end of the availability
+    #[serde(skip_serializing_if = "Option::is_none")]
+    end_date: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// Change the time range of an availability
+#[derive(Debug)]
+#[must_use]
+pub struct UpdateSchedulingAvailability {
+    /// Channel the availability is in
+    channel: ChannelId,
+    /// Availability to update
+    availability: SchedulingAvailabilityId,
+    /// Json arguments
+    arguments: UpdateSchedulingAvailabilityArguments,
+}
+
+impl UpdateSchedulingAvailability {
+    /// Create a new `UpdateSchedulingAvailability` instruction for the given channel and availability
+    pub fn new(
+        channel: impl Into<ChannelId>,
+        availability: impl Into<SchedulingAvailabilityId>,
+    ) -> Self {
+        Self {
+            channel: channel.into(),
+            availability: availability.into(),
+            arguments: UpdateSchedulingAvailabilityArguments::default(),
+        }
+    }
+
+    /// Set the start date
+    pub fn start_date(mut self, start_date: chrono::DateTime<chrono::Utc>) -> Self {
+        self.arguments.start_date = Some(start_date);
+        self
+    }
+
+    /// Set the end date
+    pub fn end_date(mut self, end_date: chrono::DateTime<chrono::Utc>) -> Self {
+        self.arguments.end_date = Some(end_date);
+        self
+    }
+}
+
+impl Endpoint<SchedulingAvailability> for UpdateSchedulingAvailability {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client
+            .patch(format!(
+                "{BASE_URL}/channels/{channel}/availabilities/{availability}",
+                channel = segment(&self.channel),
+                availability = self.availability
+            ))
+            .json(&self.arguments)
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.channel.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(raw: &str) -> Result<SchedulingAvailability, serde_json::Error> {
+        serde_json::from_str::<AvailabilityResponse>(raw).map(|resp| resp.availability)
+    }
+}
+
+/// Delete an availability
+#[derive(Debug)]
+#[must_use]
+pub struct DeleteSchedulingAvailability {
+    /// Channel the availability is in
+    channel: ChannelId,
+    /// Availability to delete
+    availability: SchedulingAvailabilityId,
+}
+
+impl DeleteSchedulingAvailability {
+    /// Create a new `DeleteSchedulingAvailability` instruction for the given channel and availability
+    pub fn new(
+        channel: impl Into<ChannelId>,
+        availability: impl Into<SchedulingAvailabilityId>,
+    ) -> Self {
+        Self {
+            channel: channel.into(),
+            availability: availability.into(),
+        }
+    }
+}
+
+impl Endpoint<()> for DeleteSchedulingAvailability {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client.delete(format!(
+            "{BASE_URL}/channels/{channel}/availabilities/{availability}",
+            channel = segment(&self.channel),
+            availability = self.availability
+        ))
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.channel.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(_: &str) -> Result<(), serde_json::Error> {
+        Ok(())
+    }
+}
@@ -2,9 +2,11 @@
 
 use serde::Deserialize;
 
-use super::BASE_URL;
+use super::{check_segments, segment, BASE_URL};
+use crate::Page;
 
 /// Get a server by id
+#[derive(Debug)]
 pub struct GetServer(vived_models::ServerId);
 
 impl GetServer {
@@ -17,10 +19,14 @@ impl GetServer {
 impl crate::Endpoint<vived_models::Server> for GetServer {
     fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
         client.get(
-            format!("{BASE_URL}/servers/{}", self.0)
+            format!("{BASE_URL}/servers/{}", segment(&self.0))
         )
     }
 
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.0.0])
+    }
+
     fn from_raw(raw: &str) -> Result<vived_models::Server, serde_json::Error> {
         #[derive(Deserialize)]
         /// Response from the server
@@ -30,4 +36,70 @@ impl crate::Endpoint<vived_models::Server> for GetServer {
         }
         serde_json::from_str::<ServerGetResponse>(raw).map(|r| r.server)
     }
-}
\ No newline at end of file
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
+}
+
+/// Get all groups in a server
+#[derive(Debug)]
+pub struct GetServerGroups(vived_models::ServerId);
+
+impl GetServerGroups {
+    /// Create a new `GetServerGroups` instructions
+    pub fn new(server: impl Into<vived_models::ServerId>) -> Self {
+        Self(server.into())
+    }
+}
+
+impl crate::Endpoint<Page<vived_models::ServerGroup>> for GetServerGroups {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client.get(
+            format!("{BASE_URL}/servers/{}/groups", segment(&self.0))
+        )
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.0.0])
+    }
+
+    fn from_raw(raw: &str) -> Result<Page<vived_models::ServerGroup>, serde_json::Error> {
+        Page::from_raw(raw, "groups")
+    }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
+}
+
+/// Get all channel categories in a server
+#[derive(Debug)]
+pub struct GetServerCategories(vived_models::ServerId);
+
+impl GetServerCategories {
+    /// Create a new `GetServerCategories` instructions
+    pub fn new(server: impl Into<vived_models::ServerId>) -> Self {
+        Self(server.into())
+    }
+}
+
+impl crate::Endpoint<Page<vived_models::ServerCategory>> for GetServerCategories {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client.get(
+            format!("{BASE_URL}/servers/{}/categories", segment(&self.0))
+        )
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.0.0])
+    }
+
+    fn from_raw(raw: &str) -> Result<Page<vived_models::ServerCategory>, serde_json::Error> {
+        Page::from_raw(raw, "categories")
+    }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
+}
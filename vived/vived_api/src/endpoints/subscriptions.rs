@@ -0,0 +1,98 @@
+//! Endpoints for server subscriptions
+
+use serde::Deserialize;
+use vived_models::{ServerId, ServerSubscription, SubscriptionTier};
+
+use crate::Endpoint;
+
+use super::{check_segments, segment, BASE_URL};
+
+/// Get every active subscription in a server
+#[derive(Debug)]
+#[must_use]
+pub struct GetServerSubscriptions(ServerId);
+
+impl GetServerSubscriptions {
+    /// Create a new `GetServerSubscriptions` instruction for the given server
+    pub fn new(server: impl Into<ServerId>) -> Self {
+        Self(server.into())
+    }
+}
+
+impl Endpoint<Vec<ServerSubscription>> for GetServerSubscriptions {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client.get(format!("{BASE_URL}/servers/{}/subscriptions", segment(&self.0)))
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.0.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(raw: &str) -> Result<Vec<ServerSubscription>, serde_json::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        /// Response containing the subscriptions
+        struct SubscriptionsResponse {
+            /// The subscriptions
+            server_subscriptions: Vec<ServerSubscription>,
+        }
+        serde_json::from_str::<SubscriptionsResponse>(raw).map(|resp| resp.server_subscriptions)
+    }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
+}
+
+/// Get the tiers people can subscribe to in a server
+/// ```
+/// use vived_api::endpoints::GetSubscriptionTiers;
+/// use vived_api::Endpoint;
+///
+/// let tiers = GetSubscriptionTiers::new("s")
+///     .parse_response(r#"{"serverSubscriptionTiers": [{"type": "Gold", "serverId": "s", "cost": 999, "createdAt": "2022-10-01T12:00:00Z"}]}"#)
+///     .unwrap();
+/// assert_eq!(tiers[0].cost, 999);
+/// ```
+#[derive(Debug)]
+#[must_use]
+pub struct GetSubscriptionTiers(ServerId);
+
+impl GetSubscriptionTiers {
+    /// Create a new `GetSubscriptionTiers` instruction for the given server
+    pub fn new(server: impl Into<ServerId>) -> Self {
+        Self(server.into())
+    }
+}
+
+impl Endpoint<Vec<SubscriptionTier>> for GetSubscriptionTiers {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client.get(format!(
+            "{BASE_URL}/servers/{}/subscriptions/tiers",
+            segment(&self.0)
+        ))
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.0.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(raw: &str) -> Result<Vec<SubscriptionTier>, serde_json::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename_all = "camelCase")]
+        /// Response containing the tiers
+        struct TiersResponse {
+            /// The tiers
+            server_subscription_tiers: Vec<SubscriptionTier>,
+        }
+        serde_json::from_str::<TiersResponse>(raw).map(|resp| resp.server_subscription_tiers)
+    }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
+}
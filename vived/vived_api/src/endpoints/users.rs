@@ -0,0 +1,52 @@
+//! Endpoints for users
+//! <https://www.guilded.gg/docs/api/users/User>
+
+use serde::Deserialize;
+use vived_models::{User, UserId};
+
+use crate::Endpoint;
+
+use super::{check_segments, segment, BASE_URL};
+
+/// Get a user
+#[derive(Debug)]
+#[must_use]
+pub struct GetUser(UserId);
+
+impl GetUser {
+    /// Create a new `GetUser` instruction for the given user
+    pub fn new(user: impl Into<UserId>) -> Self {
+        Self(user.into())
+    }
+
+    /// Get the user of the bot itself, useful for ignoring its own messages
+    pub fn me() -> Self {
+        Self::new("@me")
+    }
+}
+
+impl Endpoint<User> for GetUser {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client.get(format!("{BASE_URL}/users/{}", segment(&self.0)))
+    }
+
+    fn validate(&self) -> Result<(), crate::ApiError> {
+        check_segments(&[&self.0.0])
+    }
+
+    /// # Errors
+    /// - if the json is invalid or doesn't match the schema
+    fn from_raw(raw: &str) -> Result<User, serde_json::Error> {
+        /// Response from the get user endpoint
+        #[derive(Deserialize, Debug)]
+        struct GetUserResponse {
+            /// The user
+            user: User,
+        }
+        serde_json::from_str::<GetUserResponse>(raw).map(|resp| resp.user)
+    }
+
+    fn ratelimit_weight(&self) -> crate::RatelimitWeight {
+        crate::RatelimitWeight::Light
+    }
+}
@@ -0,0 +1,171 @@
+//! Endpoints for server xp
+//! <https://www.guilded.gg/docs/api/server-xp/ServerXpForUserCreate>
+//!
+//! Guilded has no endpoint to read xp, or to rank members by it.
+//! A total is read by awarding 0 xp with [`MemberXpAward`], which returns the total without changing it.
+//! [`GetRoleLeaderboard`] is done client side on top of that, see [`crate::ApiClient::role_leaderboard`].
+
+use futures_util::StreamExt;
+use vived_models::{RoleId, ServerId, ServerMemberSummary};
+
+use crate::{ApiClient, ApiError, GuildedErrorCode};
+
+use super::{GetServerMembers, MemberXpAward};
+
+/// How many members a role leaderboard shows by default
+const ROLE_LEADERBOARD_LIMIT: u8 = 10;
+/// How many xp totals a role leaderboard requests at once
+const XP_CONCURRENCY: usize = 3;
+
+/// Rank xp totals, highest first, as `(rank, item, xp)`
+///
+/// Items with the same xp share a rank and are ordered by `id`, the next rank is skipped.
+/// ```
+/// use vived_api::endpoints::rank_by_xp;
+///
+/// let totals = vec![("c", 5), ("b", 10), ("a", 10), ("d", 1)];
+/// let ranked = rank_by_xp(totals, |name| *name);
+/// assert_eq!(ranked, [(1, "a", 10), (1, "b", 10), (3, "c", 5), (4, "d", 1)]);
+/// ```
+pub fn rank_by_xp<T>(mut totals: Vec<(T, i64)>, id: impl Fn(&T) -> &str) -> Vec<(usize, T, i64)> {
+    totals.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| id(&a.0).cmp(id(&b.0))));
+
+    let mut ranked: Vec<(usize, T, i64)> = Vec::with_capacity(totals.len());
+    for (index, (item, xp)) in totals.into_iter().enumerate() {
+        let rank = match ranked.last() {
+            Some(&(previous_rank, _, previous_xp)) if previous_xp == xp => previous_rank,
+            _ => index + 1,
+        };
+        ranked.push((rank, item, xp));
+    }
+    ranked
+}
+
+/// A ranked member on a role leaderboard
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RoleLeaderboardEntry {
+    /// Rank of the member, starting at 1
+    pub rank: usize,
+    /// The member
+    pub member: ServerMemberSummary,
+    /// Xp total of the member
+    pub xp: i64,
+}
+
+impl RoleLeaderboardEntry {
+    /// Rank members by xp, highest first, keeping the top `limit`
+    ///
+    /// Members with the same xp share a rank and are ordered by id, the next rank is skipped.
+    /// ```
+    /// use vived_api::endpoints::RoleLeaderboardEntry;
+    /// use vived_models::ServerMemberSummary;
+    ///
+    /// let member = |id: &str| -> ServerMemberSummary {
+    ///     serde_json::from_value(serde_json::json!({"user": {"id": id, "name": id}, "roleIds": [1]})).unwrap()
+    /// };
+    /// let members = vec![(member("c"), 5), (member("b"), 10), (member("a"), 10), (member("d"), 1)];
+    ///
+    /// let ranked = RoleLeaderboardEntry::rank(members.clone(), 3);
+    /// let ranked: Vec<(usize, &str, i64)> = ranked.iter().map(|entry| (entry.rank, entry.member.user.id.0.as_str(), entry.xp)).collect();
+    /// assert_eq!(ranked, [(1, "a", 10), (1, "b", 10), (3, "c", 5)]);
+    ///
+    /// assert!(RoleLeaderboardEntry::rank(members, 0).is_empty());
+    /// ```
+    #[must_use]
+    pub fn rank(members: Vec<(ServerMemberSummary, i64)>, limit: usize) -> Vec<Self> {
+        rank_by_xp(members, |member| &member.user.id.0)
+            .into_iter()
+            .take(limit)
+            .map(|(rank, member, xp)| Self { rank, member, xp })
+            .collect()
+    }
+}
+
+/// Rank the members of a role by xp, like "top 10 Warriors"
+///
+/// Guilded has no endpoint for this, so it is done client side:
+/// every member of the server is listed, the ones with the role are kept,
+/// and the xp of each of them is read with a 0 xp [`MemberXpAward`] before ranking.
+///
+/// That is one request per member with the role, whatever the limit is,
+/// so for big roles this takes a while and uses up a good part of the ratelimit.
+/// Cache the result instead of running it on every command.
+/// Send it with [`crate::ApiClient::role_leaderboard`].
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct GetRoleLeaderboard {
+    /// Server the role is in
+    server: ServerId,
+    /// Role to rank the members of
+    role: RoleId,
+    /// How many members to show
+    limit: u8,
+}
+
+impl GetRoleLeaderboard {
+    /// Create a new `GetRoleLeaderboard` instruction, showing the top 10 by default
+    pub fn new(server: impl Into<ServerId>, role: impl Into<RoleId>) -> Self {
+        Self {
+            server: server.into(),
+            role: role.into(),
+            limit: ROLE_LEADERBOARD_LIMIT,
+        }
+    }
+
+    /// Show at most `limit` members
+    pub fn limit(mut self, limit: u8) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// List the members of the role, get their xp and rank them
+    ///
+    /// Members that left the server in the meantime are left out.
+    pub(crate) async fn fetch(
+        &self,
+        client: &ApiClient,
+    ) -> Result<Vec<RoleLeaderboardEntry>, ApiError> {
+        if self.limit == 0 {
+            return Ok(Vec::new());
+        }
+
+        let members = client
+            .make_request(GetServerMembers::new(self.server.clone()))
+            .await?;
+
+        let with_role = members
+            .into_iter()
+            .filter(|member| member.has_role(self.role));
+        let results: Vec<(ServerMemberSummary, Result<i64, ApiError>)> =
+            futures_util::stream::iter(with_role)
+                .map(|member| async move {
+                    let request =
+                        MemberXpAward::new(self.server.clone(), member.user.id.clone(), 0);
+                    let xp = match request {
+                        Ok(request) => client.make_request(request).await,
+                        Err(error) => Err(error),
+                    };
+                    (member, xp)
+                })
+                .buffered(XP_CONCURRENCY)
+                .collect()
+                .await;
+
+        let mut ranked = Vec::with_capacity(results.len());
+        for (member, xp) in results {
+            match xp {
+                Ok(xp) => ranked.push((member, xp)),
+                Err(ApiError::Guilded(ref error)) if error.code == GuildedErrorCode::NotFound => {
+                    log::debug!(
+                        "{} left {} before their xp was read",
+                        member.user.id,
+                        self.server
+                    );
+                }
+                Err(error) => return Err(error),
+            }
+        }
+
+        Ok(RoleLeaderboardEntry::rank(ranked, self.limit.into()))
+    }
+}
@@ -0,0 +1,91 @@
+//! Known error codes returned by guilded
+
+use serde::Deserialize;
+
+/// The `code` of a [`crate::GuildedError`]
+///
+/// Codes are matched exactly as guilded sends them, case included.
+/// Codes vived doesn't know about yet end up in [`GuildedErrorCode::Other`],
+/// and [`GuildedErrorCode::as_str`] always gives back the raw code.
+/// ```
+/// use vived_api::{GuildedError, GuildedErrorCode};
+///
+/// let fixtures = [
+///     (r#"{"code": "BadRequestError", "message": "Invalid body"}"#, GuildedErrorCode::BadRequest),
+///     (r#"{"code": "UnauthorizedError", "message": "Invalid token"}"#, GuildedErrorCode::Unauthorized),
+///     (r#"{"code": "ForbiddenError", "message": "Missing permission", "meta": {"missingPermissions": ["CanReadChats"]}}"#, GuildedErrorCode::Forbidden),
+///     (r#"{"code": "NotFoundError", "message": "Not found"}"#, GuildedErrorCode::NotFound),
+///     (r#"{"code": "TooManyRequestsError", "message": "Slow down"}"#, GuildedErrorCode::TooManyRequests),
+///     (r#"{"code": "ChannelNotFound", "message": "Channel not found"}"#, GuildedErrorCode::ChannelNotFound),
+///     (r#"{"code": "forbiddenerror", "message": "wrong case"}"#, GuildedErrorCode::Other("forbiddenerror".to_owned())),
+/// ];
+///
+/// for (raw, code) in fixtures {
+///     let error: GuildedError = serde_json::from_str(raw).unwrap();
+///     assert_eq!(error.code, code);
+///     assert!(raw.contains(error.code.as_str()));
+/// }
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Deserialize)]
+#[serde(from = "String")]
+pub enum GuildedErrorCode {
+    /// The request was malformed, `BadRequestError`
+    BadRequest,
+    /// The token is missing or invalid, `UnauthorizedError`
+    Unauthorized,
+    /// The bot is missing permissions, `ForbiddenError`
+    Forbidden,
+    /// The resource doesn't exist, `NotFoundError`
+    NotFound,
+    /// The resource conflicts with an existing one, `ConflictError`
+    Conflict,
+    /// The ratelimit was hit, `TooManyRequestsError`
+    TooManyRequests,
+    /// Something went wrong on guilded's side, `InternalServerError`
+    InternalServer,
+    /// The channel doesn't exist, `ChannelNotFound`
+    ChannelNotFound,
+    /// A code vived doesn't know about
+    Other(String),
+}
+
+impl GuildedErrorCode {
+    /// The code as guilded sent it
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Self::BadRequest => "BadRequestError",
+            Self::Unauthorized => "UnauthorizedError",
+            Self::Forbidden => "ForbiddenError",
+            Self::NotFound => "NotFoundError",
+            Self::Conflict => "ConflictError",
+            Self::TooManyRequests => "TooManyRequestsError",
+            Self::InternalServer => "InternalServerError",
+            Self::ChannelNotFound => "ChannelNotFound",
+            Self::Other(ref code) => code,
+        }
+    }
+}
+
+impl From<String> for GuildedErrorCode {
+    fn from(code: String) -> Self {
+        match code.as_str() {
+            "BadRequestError" => Self::BadRequest,
+            "UnauthorizedError" => Self::Unauthorized,
+            "ForbiddenError" => Self::Forbidden,
+            "NotFoundError" => Self::NotFound,
+            "ConflictError" => Self::Conflict,
+            "TooManyRequestsError" => Self::TooManyRequests,
+            "InternalServerError" => Self::InternalServer,
+            "ChannelNotFound" => Self::ChannelNotFound,
+            _ => Self::Other(code),
+        }
+    }
+}
+
+impl std::fmt::Display for GuildedErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
@@ -0,0 +1,161 @@
+//! Helpers built on top of multiple endpoints
+
+use std::collections::HashSet;
+use std::future::Future;
+
+use futures_util::StreamExt;
+use vived_models::{Channel, ChannelId, GroupId, Message, ServerId, ServerMember, ServerTree};
+
+use crate::endpoints::{
+    GetServerCategories, GetServerChannels, GetServerGroups, MemberNicknameDelete,
+    MemberNicknameSet, MessageCreate,
+};
+use crate::{ApiClient, ApiError};
+
+/// Get the full group -> category -> channel structure of a server
+///
+/// The groups, categories and channels are requested at the same time.
+///
+/// # Errors
+/// If any of the requests fail
+pub async fn get_server_tree(
+    client: &ApiClient,
+    server: impl Into<ServerId>,
+) -> Result<ServerTree, ApiError> {
+    let server = server.into();
+
+    let (groups, categories, channels) = tokio::try_join!(
+        client.make_request(GetServerGroups::new(server.clone())),
+        client.make_request(GetServerCategories::new(server.clone())),
+        client.make_request(GetServerChannels::new(server)),
+    )?;
+
+    Ok(ServerTree::new(
+        groups.into(),
+        categories.into(),
+        channels.into(),
+    ))
+}
+
+/// Get the voice and stream channels of a server, optionally only the ones in `group`
+///
+/// See [`vived_models::ChannelType::is_voice`].
+/// Guilded doesn't expose who is in a voice channel, so only the channels themselves are returned.
+/// Every page of channels is requested, until guilded stops sending a cursor.
+///
+/// # Errors
+/// If any of the requests fail
+pub async fn get_voice_channels(
+    client: &ApiClient,
+    server: impl Into<ServerId>,
+    group: Option<&GroupId>,
+) -> Result<Vec<Channel>, ApiError> {
+    let server = server.into();
+    let mut request = GetServerChannels::new(server.clone());
+    let mut seen = HashSet::new();
+    let mut voice = Vec::new();
+
+    loop {
+        let page = client.make_request(request).await?;
+        let cursor = page.cursor().map(str::to_owned);
+        voice.extend(page.into_iter().filter(|channel| {
+            channel.channel_type.is_voice()
+                && group.is_none_or(|group| channel.group_id.as_ref() == Some(group))
+        }));
+
+        match cursor {
+            // a cursor we already followed would never end
+            Some(cursor) if seen.insert(cursor.clone()) => {
+                request = GetServerChannels::new(server.clone()).cursor(cursor);
+            }
+            _ => return Ok(voice),
+        }
+    }
+}
+
+/// Send the same message to multiple channels
+///
+/// The channel set on `message` is ignored.
+/// At most [`ApiClientConfig::broadcast_concurrency`](crate::ApiClientConfig::broadcast_concurrency) messages are sent at once,
+/// and a failure in one channel doesn't stop the others.
+/// The results are in the same order as `channels`.
+pub async fn broadcast_message(
+    client: &ApiClient,
+    channels: &[ChannelId],
+    message: &MessageCreate,
+) -> Vec<(ChannelId, Result<Message, ApiError>)> {
+    futures_util::stream::iter(channels)
+        .map(|channel| async move {
+            let result = client
+                .make_request(message.clone_for_channel(channel.clone()))
+                .await;
+            (channel.clone(), result)
+        })
+        .buffered(client.config().broadcast_concurrency.max(1))
+        .collect()
+        .await
+}
+
+/// Api calls on a [`ServerMember`]
+///
+/// The member model doesn't know which server it is from, so that has to be passed in.
+/// ```no_run
+/// use vived_api::helpers::ServerMemberExt;
+/// use vived_models::ServerMember;
+///
+/// # async fn run(client: vived_api::ApiClient, mut member: ServerMember) -> Result<(), vived_api::ApiError> {
+/// member.set_nickname(&client, "wlVr3Ggl", "Verified Name").await?;
+/// assert_eq!(member.display_name(), "Verified Name");
+/// # Ok(())
+/// # }
+/// ```
+pub trait ServerMemberExt {
+    /// Set the nickname of this member in `server`, updating [`ServerMember::nickname`] once it is set
+    ///
+    /// # Errors
+    /// If the nickname is empty or too long, see [`MemberNicknameSet::new`], or if the request fails
+    fn set_nickname(
+        &mut self,
+        client: &ApiClient,
+        server: impl Into<ServerId> + Send,
+        nickname: impl Into<String> + Send,
+    ) -> impl Future<Output = Result<(), ApiError>> + Send;
+
+    /// Remove the nickname of this member in `server`, clearing [`ServerMember::nickname`] once it is removed
+    ///
+    /// # Errors
+    /// If the request fails
+    fn clear_nickname(
+        &mut self,
+        client: &ApiClient,
+        server: impl Into<ServerId> + Send,
+    ) -> impl Future<Output = Result<(), ApiError>> + Send;
+}
+
+impl ServerMemberExt for ServerMember {
+    fn set_nickname(
+        &mut self,
+        client: &ApiClient,
+        server: impl Into<ServerId> + Send,
+        nickname: impl Into<String> + Send,
+    ) -> impl Future<Output = Result<(), ApiError>> + Send {
+        let request = MemberNicknameSet::new(server, self.user.id.clone(), nickname);
+        async move {
+            self.nickname = Some(client.make_request(request?).await?);
+            Ok(())
+        }
+    }
+
+    fn clear_nickname(
+        &mut self,
+        client: &ApiClient,
+        server: impl Into<ServerId> + Send,
+    ) -> impl Future<Output = Result<(), ApiError>> + Send {
+        let request = MemberNicknameDelete::new(server, self.user.id.clone());
+        async move {
+            client.make_request(request).await?;
+            self.nickname = None;
+            Ok(())
+        }
+    }
+}
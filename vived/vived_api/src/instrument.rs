@@ -0,0 +1,105 @@
+//! Metrics recorded with the [`metrics`](https://docs.rs/metrics) crate, behind the `metrics` feature
+//!
+//! Install any recorder, like `metrics-exporter-prometheus`, and these series show up without further setup.
+//! The names are part of the public api and only change in a breaking release.
+//! Without the feature nothing is recorded, and the calls compile to nothing.
+
+/// Counter of finished requests, labeled with `endpoint` and `status`
+///
+/// `endpoint` is the name of the endpoint type, like `MessageCreate`.
+/// `status` is the status class, `2xx` to `5xx`, or `error` if no response was received.
+pub const REQUESTS_TOTAL: &str = "vived_api_requests_total";
+
+/// Histogram of how long requests took in seconds, labeled with `endpoint`
+///
+/// Measured from sending the request until the response headers arrived, waiting for the ratelimiter is not included.
+pub const REQUEST_DURATION_SECONDS: &str = "vived_api_request_duration_seconds";
+
+/// Counter of responses that said we were ratelimited
+pub const RATELIMITED_TOTAL: &str = "vived_api_ratelimited_total";
+
+/// Counter of seconds spent blocking every request after being ratelimited
+pub const RATELIMIT_SLEEP_SECONDS_TOTAL: &str = "vived_api_ratelimit_sleep_seconds_total";
+
+/// Gauge of how many more requests can be sent right now
+pub const AVAILABLE_PERMITS: &str = "vived_api_available_permits";
+
+/// Name of an endpoint type without its module path
+#[cfg(feature = "metrics")]
+fn endpoint_name<E>() -> &'static str {
+    let name = std::any::type_name::<E>();
+    name.rsplit("::").next().unwrap_or(name)
+}
+
+/// Describe every metric to the recorder, only done once
+#[cfg(feature = "metrics")]
+pub(crate) fn describe() {
+    static DESCRIBED: std::sync::Once = std::sync::Once::new();
+    DESCRIBED.call_once(|| {
+        ::metrics::describe_counter!(REQUESTS_TOTAL, "Finished requests to the guilded api");
+        ::metrics::describe_histogram!(
+            REQUEST_DURATION_SECONDS,
+            ::metrics::Unit::Seconds,
+            "How long requests to the guilded api took"
+        );
+        ::metrics::describe_counter!(RATELIMITED_TOTAL, "Responses that said we were ratelimited");
+        ::metrics::describe_counter!(
+            RATELIMIT_SLEEP_SECONDS_TOTAL,
+            ::metrics::Unit::Seconds,
+            "Time spent blocking requests after being ratelimited"
+        );
+        ::metrics::describe_gauge!(AVAILABLE_PERMITS, "Requests that can be sent right now");
+    });
+}
+
+/// Describe every metric to the recorder, only done once
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn describe() {}
+
+/// Record a finished request to endpoint `E`, `status` is `None` if no response was received
+#[cfg(feature = "metrics")]
+pub(crate) fn request<E>(status: Option<reqwest::StatusCode>, duration: std::time::Duration) {
+    let endpoint = endpoint_name::<E>();
+    let status = match status.map(|status| status.as_u16()) {
+        Some(200..=299) => "2xx",
+        Some(300..=399) => "3xx",
+        Some(400..=499) => "4xx",
+        Some(500..=599) => "5xx",
+        Some(_) => "other",
+        None => "error",
+    };
+    ::metrics::increment_counter!(REQUESTS_TOTAL, "endpoint" => endpoint, "status" => status);
+    ::metrics::histogram!(REQUEST_DURATION_SECONDS, duration.as_secs_f64(), "endpoint" => endpoint);
+}
+
+/// Record a finished request to endpoint `E`, `status` is `None` if no response was received
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn request<E>(_status: Option<reqwest::StatusCode>, _duration: std::time::Duration) {}
+
+/// Record being ratelimited, and blocking requests for `sleep`
+#[cfg(feature = "metrics")]
+pub(crate) fn ratelimited(sleep: std::time::Duration) {
+    ::metrics::increment_counter!(RATELIMITED_TOTAL);
+    ::metrics::counter!(RATELIMIT_SLEEP_SECONDS_TOTAL, sleep.as_secs());
+}
+
+/// Record being ratelimited, and blocking requests for `sleep`
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn ratelimited(_sleep: std::time::Duration) {}
+
+/// Record how many permits the ratelimiter has left
+#[cfg(feature = "metrics")]
+pub(crate) fn available_permits(permits: usize) {
+    // gauges are floats, far more permits than fit exactly are never configured
+    #[allow(clippy::cast_precision_loss)]
+    let permits = permits as f64;
+    ::metrics::gauge!(AVAILABLE_PERMITS, permits);
+}
+
+/// Record how many permits the ratelimiter has left
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn available_permits(_permits: usize) {}
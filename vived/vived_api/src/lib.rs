@@ -36,7 +36,32 @@
 
 //! Interact with the guiled api!
 
+#[cfg(feature = "cache")]
+mod cache;
 mod client;
+mod decode;
 pub mod endpoints;
+mod error_code;
+pub mod instrument;
+pub mod helpers;
+mod maybe;
+mod meta;
+mod page;
+pub mod purge;
+pub mod search;
 
-pub use client::{ApiError, ApiClient, Endpoint, GuildedError};
+pub use client::{
+    ApiClient, ApiClientConfig, ApiError, ApiErrorWithBody, Endpoint, GuildedError, RatelimitStats,
+    RatelimitWeight,
+};
+pub use error_code::GuildedErrorCode;
+pub use helpers::{broadcast_message, get_server_tree, get_voice_channels, ServerMemberExt};
+pub use maybe::Maybe;
+pub use meta::ResponseMeta;
+pub use page::Page;
+
+/// Re-exported since it is part of the [`Endpoint`] trait
+pub use reqwest;
+
+#[cfg(feature = "cache")]
+pub use cache::CachedClient;
@@ -0,0 +1,61 @@
+//! Three state fields for update requests
+
+use serde::{Serialize, Serializer};
+
+/// A field in an update request that can be left alone, set, or cleared
+///
+/// `Option` can't tell "don't touch this" apart from "set this to null",
+/// which guilded treats differently.
+/// Use it with `#[serde(skip_serializing_if = "Maybe::is_leave")]`.
+/// ```
+/// use serde::Serialize;
+/// use vived_api::Maybe;
+///
+/// #[derive(Serialize)]
+/// struct Update {
+///     #[serde(skip_serializing_if = "Maybe::is_leave")]
+///     topic: Maybe<String>,
+/// }
+///
+/// let leave = Update { topic: Maybe::Leave };
+/// let set = Update { topic: Maybe::Set("hello".to_owned()) };
+/// let clear = Update { topic: Maybe::Clear };
+///
+/// assert_eq!(serde_json::to_string(&leave).unwrap(), "{}");
+/// assert_eq!(serde_json::to_string(&set).unwrap(), r#"{"topic":"hello"}"#);
+/// assert_eq!(serde_json::to_string(&clear).unwrap(), r#"{"topic":null}"#);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Maybe<T> {
+    /// Leave the current value alone, the field is not sent
+    #[default]
+    Leave,
+    /// Set a new value
+    Set(T),
+    /// Clear the value, the field is sent as `null`
+    Clear,
+}
+
+impl<T> Maybe<T> {
+    /// Is this [`Maybe::Leave`]
+    #[must_use]
+    pub fn is_leave(&self) -> bool {
+        matches!(self, &Self::Leave)
+    }
+}
+
+impl<T> From<Option<T>> for Maybe<T> {
+    /// `Some` sets the value and `None` clears it
+    fn from(value: Option<T>) -> Self {
+        value.map_or(Self::Clear, Self::Set)
+    }
+}
+
+impl<T: Serialize> Serialize for Maybe<T> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match *self {
+            Self::Set(ref value) => value.serialize(serializer),
+            Self::Leave | Self::Clear => serializer.serialize_none(),
+        }
+    }
+}
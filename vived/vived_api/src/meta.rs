@@ -0,0 +1,89 @@
+//! Information about a response besides its body
+
+use std::time::Duration;
+
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+
+/// Headers that might hold a request id
+const REQUEST_ID_HEADERS: [&str; 3] = ["x-request-id", "request-id", "guilded-request-id"];
+
+/// Is this header worth keeping, header names are always lowercase here
+fn is_kept(name: &str) -> bool {
+    name == "retry-after" || name.starts_with("x-ratelimit-") || REQUEST_ID_HEADERS.contains(&name)
+}
+
+/// Status, timing and the interesting headers of a response, see [`crate::ApiClient::make_request_with_meta`]
+///
+/// Only the `retry-after`, `x-ratelimit-*` and request id headers are kept.
+/// Header names are matched case-insensitively.
+/// ```
+/// use std::time::Duration;
+/// use vived_api::ResponseMeta;
+/// use vived_api::reqwest::{header::{HeaderMap, HeaderName, HeaderValue}, StatusCode};
+///
+/// let mut headers = HeaderMap::new();
+/// for (name, value) in [("X-RateLimit-Remaining", "3"), ("X-Request-Id", "abc"), ("Content-Type", "json")] {
+///     headers.insert(HeaderName::from_bytes(name.as_bytes()).unwrap(), HeaderValue::from_static(value));
+/// }
+///
+/// let meta = ResponseMeta::from_parts(StatusCode::OK, &headers, Duration::from_millis(20));
+/// assert_eq!(meta.header("x-ratelimit-remaining"), Some("3"));
+/// assert_eq!(meta.header("X-RATELIMIT-REMAINING"), Some("3"));
+/// assert_eq!(meta.ratelimit_remaining(), Some(3));
+/// assert_eq!(meta.request_id(), Some("abc"));
+/// assert_eq!(meta.header("content-type"), None);
+/// ```
+#[derive(Debug, Clone)]
+pub struct ResponseMeta {
+    /// Status code of the response
+    pub status: StatusCode,
+    /// The kept headers
+    pub headers: HeaderMap,
+    /// How long it took from sending the request to having read the whole response
+    pub elapsed: Duration,
+}
+
+impl ResponseMeta {
+    /// Keep the interesting parts of a response
+    #[must_use]
+    pub fn from_parts(status: StatusCode, headers: &HeaderMap, elapsed: Duration) -> Self {
+        let headers = headers
+            .iter()
+            .filter(|&(name, _)| is_kept(name.as_str()))
+            .map(|(name, value)| (name.clone(), value.clone()))
+            .collect();
+
+        Self {
+            status,
+            headers,
+            elapsed,
+        }
+    }
+
+    /// Get a kept header as a string, `None` if it is missing or not valid ascii
+    #[must_use]
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .get(name.to_ascii_lowercase())
+            .and_then(|value| value.to_str().ok())
+    }
+
+    /// How many seconds guilded asked us to wait before trying again
+    #[must_use]
+    pub fn retry_after(&self) -> Option<u64> {
+        self.header("retry-after")?.parse().ok()
+    }
+
+    /// How many requests are left before the ratelimit is hit, if guilded said
+    #[must_use]
+    pub fn ratelimit_remaining(&self) -> Option<u64> {
+        self.header("x-ratelimit-remaining")?.parse().ok()
+    }
+
+    /// The id guilded gave this request, useful when reporting problems to them
+    #[must_use]
+    pub fn request_id(&self) -> Option<&str> {
+        REQUEST_ID_HEADERS.iter().find_map(|name| self.header(name))
+    }
+}
@@ -0,0 +1,129 @@
+//! Pages returned by list endpoints
+
+use std::marker::PhantomData;
+
+use serde::de::{self, DeserializeOwned, Deserializer, MapAccess, Visitor};
+use serde_json::{Map, Value};
+
+/// A page of items returned by a list endpoint
+///
+/// Derefs to a slice of the items, so it can mostly be used like a `Vec`.
+/// Anything else guilded sends along with the items is kept in `raw_meta`,
+/// so pagination info can be used before vived knows about it.
+/// ```
+/// use vived_api::{endpoints::GetChannelMedia, Endpoint};
+///
+/// let page = GetChannelMedia::new("c").parse_response(r#"{"media": [], "hasMore": true, "cursor": "abc"}"#).unwrap();
+/// assert!(page.is_empty());
+/// assert_eq!(page.has_more, Some(true));
+/// assert_eq!(page.cursor(), Some("abc"));
+/// assert_eq!(page.raw_meta.unwrap()["cursor"], "abc");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    /// The items on this page
+    pub items: Vec<T>,
+    /// Every other field in the response, `None` if there were none
+    pub raw_meta: Option<Value>,
+    /// Are there more items after this page, `None` if guilded didn't say
+    pub has_more: Option<bool>,
+}
+
+impl<T: DeserializeOwned> Page<T> {
+    /// Parse a list response, where the items are stored under `key`
+    ///
+    /// The items are parsed straight from `raw`, so errors in them keep their position in the body.
+    pub(crate) fn from_raw(raw: &str, key: &'static str) -> Result<Self, serde_json::Error> {
+        let mut deserializer = serde_json::Deserializer::from_str(raw);
+        let (items, fields) = (&mut deserializer).deserialize_map(PageVisitor {
+            key,
+            items: PhantomData,
+        })?;
+        deserializer.end()?;
+
+        let items = items.ok_or_else(|| <serde_json::Error as de::Error>::missing_field(key))?;
+        let has_more = fields.get("hasMore").and_then(Value::as_bool);
+        let raw_meta = (!fields.is_empty()).then_some(Value::Object(fields));
+
+        Ok(Self {
+            items,
+            raw_meta,
+            has_more,
+        })
+    }
+}
+
+impl<T> Page<T> {
+    /// The cursor guilded sent for the next page, `None` if it sent none or there are no more pages
+    #[must_use]
+    pub fn cursor(&self) -> Option<&str> {
+        if self.has_more == Some(false) {
+            return None;
+        }
+        self.raw_meta.as_ref()?.get("cursor")?.as_str()
+    }
+}
+
+/// Splits a list response into the items and every other field
+struct PageVisitor<T> {
+    /// Field the items are stored under
+    key: &'static str,
+    /// Type of the items
+    items: PhantomData<T>,
+}
+
+impl<'de, T: DeserializeOwned> Visitor<'de> for PageVisitor<T> {
+    type Value = (Option<Vec<T>>, Map<String, Value>);
+
+    fn expecting(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(formatter, "an object with a `{}` list", self.key)
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut items = None;
+        let mut fields = Map::new();
+
+        while let Some(name) = map.next_key::<String>()? {
+            if name == self.key {
+                items = Some(map.next_value()?);
+            } else {
+                let value = map.next_value()?;
+                fields.insert(name, value);
+            }
+        }
+
+        Ok((items, fields))
+    }
+}
+
+impl<T> std::ops::Deref for Page<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &Self::Target {
+        &self.items
+    }
+}
+
+impl<T> IntoIterator for Page<T> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.into_iter()
+    }
+}
+
+impl<'a, T> IntoIterator for &'a Page<T> {
+    type Item = &'a T;
+    type IntoIter = std::slice::Iter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.items.iter()
+    }
+}
+
+impl<T> From<Page<T>> for Vec<T> {
+    fn from(page: Page<T>) -> Self {
+        page.items
+    }
+}
@@ -0,0 +1,295 @@
+//! Delete many recent messages of a channel at once, see [`crate::ApiClient::purge_messages`]
+//!
+//! A purge pages through the history of a channel newest first, picks the messages to delete with [`select`],
+//! and deletes them a few at a time, so the ratelimit isn't used up in one go.
+//! A message that can't be deleted (for example because it is too old, or the bot isn't allowed to)
+//! ends up in [`PurgeReport::failed`] instead of stopping the purge.
+//!
+//! ```no_run
+//! use vived_api::purge::PurgeOptions;
+//! use vived_api::search::from_user;
+//! use vived_api::ApiClient;
+//!
+//! # async fn run(client: ApiClient) -> Result<(), vived_api::ApiError> {
+//! let options = PurgeOptions::new(200).filter(from_user("spammer"));
+//!
+//! // check what would be deleted first
+//! let preview = client.purge_messages("channel", options.clone().dry_run(true)).await?;
+//! println!("would delete {} messages", preview.deleted.len());
+//!
+//! let report = client.purge_messages("channel", options).await?;
+//! println!("deleted {}, {} failed", report.deleted.len(), report.failed.len());
+//! # Ok(())
+//! # }
+//! ```
+
+use std::sync::Arc;
+
+use futures_util::StreamExt;
+use vived_models::{ChannelId, Message, MessageId};
+
+use crate::endpoints::MessageDelete;
+use crate::search::SearchOptions;
+use crate::{ApiClient, ApiError};
+
+/// Most messages a single purge deletes, whatever [`PurgeOptions::new`] was given
+pub const PURGE_LIMIT: usize = 1000;
+/// Default for how many messages a purge looks at, compared to how many it deletes
+const SCAN_FACTOR: usize = 5;
+/// Default for how many deletes are sent at once
+const PURGE_CONCURRENCY: usize = 3;
+
+/// Decides which messages are deleted
+type Filter = Arc<dyn Fn(&Message) -> bool + Send + Sync>;
+
+/// Which messages a purge deletes and how
+#[derive(Clone)]
+#[must_use]
+pub struct PurgeOptions {
+    /// Delete at most this many messages, capped at [`PURGE_LIMIT`]
+    max_messages: usize,
+    /// Look at most at this many messages, matching or not
+    max_scanned: usize,
+    /// Leave messages older than this alone
+    max_age: Option<chrono::Duration>,
+    /// Only delete messages this returns `true` for
+    filter: Option<Filter>,
+    /// Only report what would be deleted
+    dry_run: bool,
+    /// How many deletes are sent at once
+    concurrency: usize,
+    /// Include private messages
+    include_private: bool,
+}
+
+impl std::fmt::Debug for PurgeOptions {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("PurgeOptions")
+            .field("max_messages", &self.max_messages)
+            .field("max_scanned", &self.max_scanned)
+            .field("max_age", &self.max_age)
+            .field("filter", &self.filter.is_some())
+            .field("dry_run", &self.dry_run)
+            .field("concurrency", &self.concurrency)
+            .field("include_private", &self.include_private)
+            .finish()
+    }
+}
+
+impl PurgeOptions {
+    /// Delete up to `max_messages` of the most recent messages
+    ///
+    /// By default up to five times as many messages are looked at, which only matters with a [filter](PurgeOptions::filter).
+    pub fn new(max_messages: usize) -> Self {
+        if max_messages > PURGE_LIMIT {
+            log::warn!("a purge deletes at most {PURGE_LIMIT} messages, but {max_messages} were asked for");
+        }
+        let max_messages = max_messages.min(PURGE_LIMIT);
+
+        Self {
+            max_messages,
+            max_scanned: max_messages.saturating_mul(SCAN_FACTOR),
+            max_age: None,
+            filter: None,
+            dry_run: false,
+            concurrency: PURGE_CONCURRENCY,
+            include_private: false,
+        }
+    }
+
+    /// Look at most at `max_scanned` messages, matching or not
+    pub fn max_scanned(mut self, max_scanned: usize) -> Self {
+        self.max_scanned = max_scanned;
+        self
+    }
+
+    /// Leave messages older than `max_age` alone
+    pub fn max_age(mut self, max_age: chrono::Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
+    /// Only delete messages `filter` returns `true` for, see [`crate::search`] for some ready made ones
+    pub fn filter(mut self, filter: impl Fn(&Message) -> bool + Send + Sync + 'static) -> Self {
+        self.filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// Only report what would be deleted, without deleting anything
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
+
+    /// Set how many deletes are sent at once, defaults to 3
+    pub fn concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        self
+    }
+
+    /// Set if private messages are included
+    pub fn include_private(mut self, include_private: bool) -> Self {
+        self.include_private = include_private;
+        self
+    }
+
+    /// Should `message` be deleted, going by its age at `now` and the filter
+    fn selects(&self, message: &Message, now: chrono::DateTime<chrono::Utc>) -> bool {
+        let too_old = self
+            .max_age
+            .is_some_and(|max_age| now - message.created_at > max_age);
+        !too_old && self.filter.as_ref().is_none_or(|filter| filter(message))
+    }
+}
+
+/// What a purge did, or would do in a dry run
+#[derive(Debug, Default)]
+#[non_exhaustive]
+pub struct PurgeReport {
+    /// Messages that were deleted, or would be in a dry run, newest first
+    pub deleted: Vec<MessageId>,
+    /// Messages that were looked at but left alone, because of the filter or their age
+    pub skipped: usize,
+    /// Messages that couldn't be deleted, with why
+    pub failed: Vec<(MessageId, ApiError)>,
+    /// Was this a dry run
+    pub dry_run: bool,
+}
+
+/// Pick the messages to delete out of `messages`, which should be newest first, returning them and how many were skipped
+///
+/// This is the selection both a real purge and a dry run use.
+/// Messages are only counted as skipped up to the point enough messages were picked.
+/// ```
+/// use vived_api::purge::{select, PurgeOptions};
+/// use vived_api::search::from_user;
+/// use vived_models::Message;
+///
+/// let now = chrono::DateTime::parse_from_rfc3339("2022-10-01T12:00:00Z").unwrap().with_timezone(&chrono::Utc);
+/// let message = |id: &str, author: &str, minutes_ago: i64| -> Message {
+///     serde_json::from_value(serde_json::json!({
+///         "id": id, "type": "default", "serverId": "s", "channelId": "c", "createdBy": author,
+///         "createdAt": (now - chrono::Duration::minutes(minutes_ago)).to_rfc3339(),
+///     })).unwrap()
+/// };
+/// let history = vec![
+///     message("a", "spammer", 1),
+///     message("b", "friend", 2),
+///     message("c", "spammer", 3),
+///     message("d", "spammer", 60),
+/// ];
+/// let ids = |selected: Vec<Message>| selected.into_iter().map(|message| message.id.0).collect::<Vec<_>>();
+///
+/// let (selected, skipped) = select(history.clone(), &PurgeOptions::new(10), now);
+/// assert_eq!(ids(selected), ["a", "b", "c", "d"]);
+/// assert_eq!(skipped, 0);
+///
+/// let (selected, skipped) = select(history.clone(), &PurgeOptions::new(2), now);
+/// assert_eq!(ids(selected), ["a", "b"]);
+/// assert_eq!(skipped, 0);
+///
+/// let spam = PurgeOptions::new(10).filter(from_user("spammer"));
+/// let (selected, skipped) = select(history.clone(), &spam, now);
+/// assert_eq!(ids(selected), ["a", "c", "d"]);
+/// assert_eq!(skipped, 1);
+///
+/// let recent_spam = spam.max_age(chrono::Duration::minutes(10));
+/// let (selected, skipped) = select(history, &recent_spam, now);
+/// assert_eq!(ids(selected), ["a", "c"]);
+/// assert_eq!(skipped, 2);
+/// ```
+#[must_use]
+pub fn select(
+    messages: impl IntoIterator<Item = Message>,
+    options: &PurgeOptions,
+    now: chrono::DateTime<chrono::Utc>,
+) -> (Vec<Message>, usize) {
+    let mut selected = Vec::new();
+    let mut skipped = 0;
+
+    for message in messages {
+        if selected.len() >= options.max_messages {
+            break;
+        }
+        if options.selects(&message, now) {
+            selected.push(message);
+        } else {
+            skipped += 1;
+        }
+    }
+
+    (selected, skipped)
+}
+
+/// Purge `channel`, see [`crate::ApiClient::purge_messages`]
+pub(crate) async fn purge_messages(
+    client: &ApiClient,
+    channel: ChannelId,
+    options: PurgeOptions,
+) -> Result<PurgeReport, ApiError> {
+    let now = chrono::Utc::now();
+    let mut search = SearchOptions::default()
+        .max_messages(options.max_scanned)
+        .include_private(options.include_private);
+    if let Some(max_age) = options.max_age {
+        // history is newest first, so nothing past this point could be picked
+        search = search.oldest(now - max_age);
+    }
+
+    let mut history = Box::pin(client.message_history(channel.clone(), search));
+    let mut messages = Vec::new();
+    let mut picked = 0;
+    while picked < options.max_messages {
+        let message = match history.next().await {
+            Some(message) => message?,
+            None => break,
+        };
+        if options.selects(&message, now) {
+            picked += 1;
+        }
+        messages.push(message);
+    }
+    drop(history);
+
+    let (selected, skipped) = select(messages, &options, now);
+    let ids = selected.into_iter().map(|message| message.id);
+
+    if options.dry_run {
+        return Ok(PurgeReport {
+            deleted: ids.collect(),
+            skipped,
+            failed: Vec::new(),
+            dry_run: true,
+        });
+    }
+
+    let results: Vec<(MessageId, Result<(), ApiError>)> = futures_util::stream::iter(ids)
+        .map(|message| {
+            let channel = channel.clone();
+            async move {
+                let result = client
+                    .make_request(MessageDelete::new(channel, message.clone()))
+                    .await;
+                (message, result)
+            }
+        })
+        .buffered(options.concurrency)
+        .collect()
+        .await;
+
+    let mut report = PurgeReport {
+        skipped,
+        ..PurgeReport::default()
+    };
+    for (message, result) in results {
+        match result {
+            Ok(()) => report.deleted.push(message),
+            Err(error) => {
+                log::warn!("failed to delete message {message} in {channel} while purging: {error}");
+                report.failed.push((message, error));
+            }
+        }
+    }
+
+    Ok(report)
+}
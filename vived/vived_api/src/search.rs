@@ -0,0 +1,313 @@
+//! Page through message history, and search it
+//!
+//! Guilded has no search endpoint, so [`crate::ApiClient::search_messages`] pages through the history of a channel
+//! and checks every message locally.
+//! The streams are lazy: a page is only requested once every message of the previous one was used,
+//! so dropping the stream (or just not polling it anymore) stops any further requests.
+//!
+//! Several messages can be sent at the same millisecond, so a page doesn't stop right before the oldest message of the
+//! previous one. It starts at that message's timestamp, and leaves out the messages already yielded.
+//!
+//! ```no_run
+//! use futures_util::StreamExt;
+//! use vived_api::search::{contains_text, SearchOptions};
+//! use vived_api::ApiClient;
+//!
+//! # async fn run(client: ApiClient) -> Result<(), vived_api::ApiError> {
+//! let options = SearchOptions::default().max_messages(2000);
+//! let mut results = Box::pin(client.search_messages("channel", options, contains_text("https://example.com")));
+//!
+//! // only the first match is needed, so no more pages are requested after it is found
+//! let found = results.next().await.transpose()?;
+//!
+//! if let Some(message) = found {
+//!     println!("found it: {}", message.id);
+//! }
+//! # Ok(())
+//! # }
+//! ```
+
+use std::collections::{HashSet, VecDeque};
+
+use futures_util::{Stream, StreamExt};
+use vived_models::limits::MESSAGES_PER_REQUEST;
+use vived_models::{ChannelId, Message, MessageId, UserId};
+
+use crate::endpoints::ChannelGetMessages;
+use crate::{ApiClient, ApiError};
+
+/// Default for how many messages a search looks at
+const MAX_SEARCHED_MESSAGES: usize = 1000;
+
+/// Bounds how far back a search or history scan goes
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct SearchOptions {
+    /// Look at most at this many messages, matching or not
+    pub max_messages: usize,
+    /// Stop at messages older than this
+    pub oldest: Option<chrono::DateTime<chrono::Utc>>,
+    /// Start at messages sent before this, instead of at the newest message
+    pub before: Option<chrono::DateTime<chrono::Utc>>,
+    /// Messages sent exactly at `before` that were already seen, set with [`SearchOptions::resume_after`]
+    ///
+    /// When this is set the other messages sent at `before` are included.
+    pub seen_at_before: Option<Vec<MessageId>>,
+    /// Include private messages
+    pub include_private: bool,
+}
+
+impl Default for SearchOptions {
+    fn default() -> Self {
+        Self {
+            max_messages: MAX_SEARCHED_MESSAGES,
+            oldest: None,
+            before: None,
+            seen_at_before: None,
+            include_private: false,
+        }
+    }
+}
+
+impl SearchOptions {
+    /// Set how many messages to look at, at most
+    #[must_use]
+    pub fn max_messages(mut self, max_messages: usize) -> Self {
+        self.max_messages = max_messages;
+        self
+    }
+
+    /// Stop at messages older than `oldest`
+    #[must_use]
+    pub fn oldest(mut self, oldest: chrono::DateTime<chrono::Utc>) -> Self {
+        self.oldest = Some(oldest);
+        self
+    }
+
+    /// Start at messages sent before `before`, instead of at the newest message
+    #[must_use]
+    pub fn before(mut self, before: chrono::DateTime<chrono::Utc>) -> Self {
+        self.before = Some(before);
+        self.seen_at_before = None;
+        self
+    }
+
+    /// Continue a scan that stopped at `oldest`, leaving out `seen`, the messages sent exactly at `oldest` it already got
+    ///
+    /// Unlike [`SearchOptions::before`] this keeps the other messages sent at the same time.
+    /// ```
+    /// use vived_api::search::SearchOptions;
+    ///
+    /// let oldest = chrono::DateTime::parse_from_rfc3339("2022-10-01T12:00:00Z").unwrap().with_timezone(&chrono::Utc);
+    /// let options = SearchOptions::default().resume_after(oldest, ["a", "b"]);
+    /// assert_eq!(options.before, Some(oldest));
+    /// assert_eq!(options.seen_at_before.unwrap().len(), 2);
+    /// ```
+    #[must_use]
+    pub fn resume_after<I>(mut self, oldest: chrono::DateTime<chrono::Utc>, seen: I) -> Self
+    where
+        I: IntoIterator,
+        I::Item: Into<MessageId>,
+    {
+        self.before = Some(oldest);
+        self.seen_at_before = Some(seen.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Set if private messages are included
+    #[must_use]
+    pub fn include_private(mut self, include_private: bool) -> Self {
+        self.include_private = include_private;
+        self
+    }
+}
+
+/// State of a history scan between items
+struct History {
+    /// Client used for the requests
+    client: ApiClient,
+    /// Channel being scanned
+    channel: ChannelId,
+    /// Bounds of the scan
+    options: SearchOptions,
+    /// Messages of the current page not yielded yet
+    buffer: VecDeque<Message>,
+    /// Start of the next page
+    before: Option<chrono::DateTime<chrono::Utc>>,
+    /// Messages sent exactly at `before` that were already yielded, `None` if `before` itself is left out
+    seen_at_before: Option<HashSet<MessageId>>,
+    /// Messages yielded so far
+    seen: usize,
+    /// There are no more pages, or a request failed
+    done: bool,
+}
+
+impl History {
+    /// Get the next message, requesting a new page if needed
+    async fn next(&mut self) -> Option<Result<Message, ApiError>> {
+        if self.seen >= self.options.max_messages {
+            return None;
+        }
+
+        // a page can be made up of messages that were already yielded
+        while self.buffer.is_empty() && !self.done {
+            if let Err(error) = self.fetch().await {
+                self.done = true;
+                return Some(Err(error));
+            }
+        }
+
+        let message = self.buffer.pop_front()?;
+        if self
+            .options
+            .oldest
+            .is_some_and(|oldest| message.created_at < oldest)
+        {
+            self.buffer.clear();
+            self.done = true;
+            return None;
+        }
+
+        self.seen += 1;
+        Some(Ok(message))
+    }
+
+    /// Request the next page, never asking for more messages than the scan has left
+    async fn fetch(&mut self) -> Result<(), ApiError> {
+        if self
+            .seen_at_before
+            .as_ref()
+            .is_some_and(|seen| seen.len() >= usize::from(MESSAGES_PER_REQUEST))
+        {
+            log::warn!(
+                "more than {MESSAGES_PER_REQUEST} messages in {} were sent at {:?}, some of them are skipped",
+                self.channel,
+                self.before
+            );
+            self.seen_at_before = None;
+        }
+
+        // the messages at `before` that were already yielded come back, so make room for them
+        let repeated = self.seen_at_before.as_ref().map_or(0, HashSet::len);
+        let wanted = (self.options.max_messages - self.seen).saturating_add(repeated);
+        let limit = u8::try_from(wanted)
+            .unwrap_or(MESSAGES_PER_REQUEST)
+            .min(MESSAGES_PER_REQUEST);
+
+        let mut request = ChannelGetMessages::new(self.channel.clone())
+            .limit(limit)
+            .include_private(self.options.include_private);
+        if let Some(before) = self.before {
+            // `before` leaves out messages sent at it, guilded timestamps are in milliseconds
+            let including = self
+                .seen_at_before
+                .as_ref()
+                .and_then(|_| before.checked_add_signed(chrono::Duration::milliseconds(1)));
+            request = request.before(including.unwrap_or(before));
+        }
+
+        let page = self.client.make_request(request).await?;
+        self.done = page.len() < usize::from(limit) || page.has_more == Some(false);
+
+        // messages come newest first, so the last one is where the next page starts
+        let Some(oldest) = page.last().map(|message| message.created_at) else {
+            return Ok(());
+        };
+        let at_oldest = page
+            .iter()
+            .filter(|message| message.created_at == oldest)
+            .map(|message| message.id.clone())
+            .collect::<Vec<_>>();
+
+        let new = page
+            .into_iter()
+            .filter(|message| self.is_new(message))
+            .collect::<Vec<_>>();
+        self.buffer.extend(new);
+
+        match self.seen_at_before {
+            Some(ref mut seen) if self.before == Some(oldest) => seen.extend(at_oldest),
+            _ => self.seen_at_before = Some(at_oldest.into_iter().collect()),
+        }
+        self.before = Some(oldest);
+        Ok(())
+    }
+
+    /// Is `message` in the part of the history the next page starts at, and not yielded yet
+    fn is_new(&self, message: &Message) -> bool {
+        match (self.before, self.seen_at_before.as_ref()) {
+            (Some(before), Some(seen)) => {
+                message.created_at < before
+                    || (message.created_at == before && !seen.contains(&message.id))
+            }
+            (Some(before), None) => message.created_at < before,
+            (None, _) => true,
+        }
+    }
+}
+
+/// Stream the history of `channel`, newest first, see [`crate::ApiClient::message_history`]
+pub(crate) fn message_history(
+    client: ApiClient,
+    channel: ChannelId,
+    options: SearchOptions,
+) -> impl Stream<Item = Result<Message, ApiError>> {
+    let history = History {
+        client,
+        channel,
+        buffer: VecDeque::new(),
+        before: options.before,
+        seen_at_before: options
+            .seen_at_before
+            .as_ref()
+            .map(|seen| seen.iter().cloned().collect()),
+        options,
+        seen: 0,
+        done: false,
+    };
+
+    futures_util::stream::unfold(history, |mut history| async move {
+        let message = history.next().await?;
+        Some((message, history))
+    })
+}
+
+/// Stream the messages in the history of `channel` that match `predicate`, see [`crate::ApiClient::search_messages`]
+pub(crate) fn search_messages<P>(
+    client: ApiClient,
+    channel: ChannelId,
+    options: SearchOptions,
+    mut predicate: P,
+) -> impl Stream<Item = Result<Message, ApiError>>
+where
+    P: FnMut(&Message) -> bool,
+{
+    message_history(client, channel, options).filter(move |result| {
+        let keep = result.as_ref().map_or(true, &mut predicate);
+        futures_util::future::ready(keep)
+    })
+}
+
+/// Matches messages whose content contains `text`, ignoring case
+pub fn contains_text(text: impl Into<String>) -> impl Fn(&Message) -> bool {
+    let text = text.into().to_lowercase();
+    move |message| message.content_or_empty().to_lowercase().contains(&text)
+}
+
+/// Matches messages sent by `user`
+pub fn from_user(user: impl Into<UserId>) -> impl Fn(&Message) -> bool {
+    let user = user.into();
+    move |message| message.created_by.creator().as_user() == Some(&user)
+}
+
+/// Matches messages with at least one embed
+#[must_use]
+pub fn has_embeds(message: &Message) -> bool {
+    !message.embeds.is_empty()
+}
+
+/// Matches messages that mention `user`
+pub fn mentions_user(user: impl Into<UserId>) -> impl Fn(&Message) -> bool {
+    let user = user.into();
+    move |message| message.mentions.users.contains(&user)
+}
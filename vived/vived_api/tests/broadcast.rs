@@ -0,0 +1,114 @@
+//! Broadcasting a message to several channels, against a local server
+
+use std::time::{Duration, Instant};
+
+use vived_api::endpoints::MessageCreate;
+use vived_api::{broadcast_message, ApiClient, ApiClientConfig, ApiError, RatelimitWeight};
+use vived_models::ChannelId;
+use wiremock::matchers::{method, path_regex};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+/// Answers a message create with the message posted in the channel from the url
+fn created(request: &Request) -> ResponseTemplate {
+    let channel = request
+        .url
+        .path_segments()
+        .unwrap()
+        .nth(3)
+        .unwrap()
+        .to_owned();
+    ResponseTemplate::new(201).set_body_json(serde_json::json!({
+        "message": {
+            "id": "00000000-0000-0000-0000-000000000000",
+            "type": "default",
+            "channelId": channel,
+            "content": "hello",
+            "createdAt": "2022-10-26T00:00:00.000Z",
+            "createdBy": "Ann6LewA"
+        }
+    }))
+}
+
+fn client(server: &MockServer, concurrency: usize) -> ApiClient {
+    let config = ApiClientConfig::default()
+        .api_origin(server.uri().parse().unwrap())
+        .permit_hold(RatelimitWeight::Normal, Duration::ZERO)
+        .broadcast_concurrency(concurrency);
+    ApiClient::new_with_config("token", config).unwrap()
+}
+
+fn channels(count: usize) -> Vec<ChannelId> {
+    (0..count)
+        .map(|index| ChannelId::from(format!("channel-{index}")))
+        .collect()
+}
+
+#[tokio::test]
+async fn one_forbidden_channel_does_not_stop_the_others() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path_regex("^/api/v1/channels/channel-1/messages$"))
+        .respond_with(ResponseTemplate::new(403).set_body_json(serde_json::json!({
+            "code": "ForbiddenError",
+            "message": "missing permission"
+        })))
+        .with_priority(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path_regex("^/api/v1/channels/[^/]+/messages$"))
+        .respond_with(created)
+        .mount(&server)
+        .await;
+
+    let channels = channels(3);
+    let message = MessageCreate::new_with_content("ignored", "hello");
+    let results = broadcast_message(&client(&server, 3), &channels, &message).await;
+
+    let order: Vec<&ChannelId> = results.iter().map(|result| &result.0).collect();
+    assert_eq!(order, channels.iter().collect::<Vec<_>>());
+
+    assert_eq!(results[0].1.as_ref().unwrap().channel_id, channels[0]);
+    assert!(
+        matches!(results[1].1, Err(ApiError::Guilded(_))),
+        "{:?}",
+        results[1].1
+    );
+    assert_eq!(results[2].1.as_ref().unwrap().channel_id, channels[2]);
+    assert_eq!(server.received_requests().await.unwrap().len(), 3);
+}
+
+#[tokio::test]
+async fn concurrency_is_bounded() {
+    const DELAY: Duration = Duration::from_millis(200);
+
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(move |request: &Request| created(request).set_delay(DELAY))
+        .mount(&server)
+        .await;
+
+    let message = MessageCreate::new_with_content("ignored", "hello");
+    let start = Instant::now();
+    let results = broadcast_message(&client(&server, 2), &channels(6), &message).await;
+    let elapsed = start.elapsed();
+
+    assert!(results.iter().all(|result| result.1.is_ok()));
+    // 6 messages 2 at a time is 3 rounds, all at once would be a single one
+    assert!(elapsed >= DELAY * 3, "{elapsed:?}");
+}
+
+#[tokio::test]
+async fn no_channels_sends_nothing() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(created)
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let message = MessageCreate::new_with_content("ignored", "hello");
+    assert!(broadcast_message(&client(&server, 3), &[], &message)
+        .await
+        .is_empty());
+}
@@ -0,0 +1,66 @@
+//! Role invalidation in the member cache, against a local server
+
+use std::time::Duration;
+
+use vived_api::{ApiClient, ApiClientConfig, CachedClient};
+use vived_models::RoleId;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Serve a member of `server` with the given roles, expecting it to be fetched `fetches` times
+async fn mount_member(mock: &MockServer, server: &str, user: &str, roles: &[u32], fetches: u64) {
+    Mock::given(method("GET"))
+        .and(path(format!("/api/v1/servers/{server}/members/{user}")))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "member": {
+                "user": {"id": user, "type": "user", "name": user, "createdAt": "2022-01-01T00:00:00.000Z"},
+                "roleIds": roles,
+                "joinedAt": "2022-10-26T00:00:00.000Z"
+            }
+        })))
+        .expect(fetches)
+        .mount(mock)
+        .await;
+}
+
+fn client(mock: &MockServer) -> CachedClient {
+    let config = ApiClientConfig::default()
+        .api_origin(mock.uri().parse().unwrap())
+        .permit_hold(vived_api::RatelimitWeight::Light, Duration::ZERO);
+    let client = ApiClient::new_with_config("token", config).unwrap();
+    CachedClient::new(client, Duration::from_secs(3600))
+}
+
+#[tokio::test]
+async fn invalidating_a_role_refetches_only_its_members() {
+    let mock = MockServer::start().await;
+    mount_member(&mock, "server", "moderator", &[1, 2], 2).await;
+    mount_member(&mock, "server", "member", &[2], 1).await;
+    mount_member(&mock, "other", "moderator", &[1], 1).await;
+
+    let cache = client(&mock);
+    for _ in 0..2 {
+        cache.get_member("server", "moderator").await.unwrap();
+        cache.get_member("server", "member").await.unwrap();
+        cache.get_member("other", "moderator").await.unwrap();
+    }
+
+    cache.invalidate_role(&"server".into(), RoleId(1)).await;
+
+    cache.get_member("server", "moderator").await.unwrap();
+    cache.get_member("server", "member").await.unwrap();
+    cache.get_member("other", "moderator").await.unwrap();
+
+    // the expected fetch counts are checked when the server is dropped
+}
+
+#[tokio::test]
+async fn invalidating_an_unused_role_keeps_everyone() {
+    let mock = MockServer::start().await;
+    mount_member(&mock, "server", "member", &[2], 1).await;
+
+    let cache = client(&mock);
+    cache.get_member("server", "member").await.unwrap();
+    cache.invalidate_role(&"server".into(), RoleId(3)).await;
+    cache.get_member("server", "member").await.unwrap();
+}
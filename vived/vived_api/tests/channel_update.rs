@@ -0,0 +1,71 @@
+//! The json body `ChannelUpdate` sends for each combination of changes
+
+use serde_json::{json, Value};
+use vived_api::endpoints::ChannelUpdate;
+use vived_api::Endpoint;
+use vived_models::Channel;
+
+fn body(update: &ChannelUpdate) -> Value {
+    let request = update.build(&reqwest::Client::new()).build().unwrap();
+    serde_json::from_slice(request.body().unwrap().as_bytes().unwrap()).unwrap()
+}
+
+fn channel(topic: Option<&str>, is_public: bool) -> Channel {
+    serde_json::from_value(json!({
+        "id": "00000000-0000-0000-0000-000000000001",
+        "type": "chat",
+        "name": "general",
+        "topic": topic,
+        "createdAt": "2022-10-26T00:00:00.000Z",
+        "createdBy": "Ann6LewA",
+        "serverId": "wlVr3Ggl",
+        "isPublic": is_public
+    }))
+    .unwrap()
+}
+
+#[test]
+fn serialization_matrix() {
+    let new = || ChannelUpdate::new("channel");
+    let cases = [
+        ("nothing", new(), json!({})),
+        ("name", new().name("rules"), json!({"name": "rules"})),
+        ("topic", new().topic("be nice"), json!({"topic": "be nice"})),
+        ("clear topic", new().clear_topic(), json!({"topic": null})),
+        ("public", new().public(true), json!({"isPublic": true})),
+        ("private", new().public(false), json!({"isPublic": false})),
+        ("reset public", new().reset_public(), json!({})),
+        ("public then reset", new().public(true).reset_public(), json!({})),
+        ("topic then clear", new().topic("be nice").clear_topic(), json!({"topic": null})),
+        ("clear then topic", new().clear_topic().topic("be nice"), json!({"topic": "be nice"})),
+        (
+            "everything",
+            new().name("rules").topic("be nice").public(false),
+            json!({"name": "rules", "topic": "be nice", "isPublic": false}),
+        ),
+    ];
+
+    for (name, update, expected) in cases {
+        assert_eq!(body(&update), expected, "{name}");
+    }
+}
+
+#[test]
+fn from_channel_without_a_topic_leaves_it_alone() {
+    let update = ChannelUpdate::from_channel(&channel(None, false));
+    assert_eq!(body(&update), json!({"name": "general", "isPublic": false}));
+}
+
+#[test]
+fn from_channel_keeps_the_current_values() {
+    let update = ChannelUpdate::from_channel(&channel(Some("welcome"), true));
+    assert_eq!(
+        body(&update),
+        json!({"name": "general", "topic": "welcome", "isPublic": true})
+    );
+
+    let update = ChannelUpdate::from_channel(&channel(Some("welcome"), true))
+        .clear_topic()
+        .reset_public();
+    assert_eq!(body(&update), json!({"name": "general", "topic": null}));
+}
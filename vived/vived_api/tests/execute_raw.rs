@@ -0,0 +1,86 @@
+//! Requests to routes without an endpoint, against a local server
+
+use serde_json::json;
+use vived_api::reqwest::{Method, StatusCode};
+use vived_api::{ApiClient, ApiClientConfig, ApiError, GuildedErrorCode};
+use wiremock::matchers::{body_json, header, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn client(server: &MockServer) -> ApiClient {
+    let config = ApiClientConfig::default().api_origin(server.uri().parse().unwrap());
+    ApiClient::new_with_config("token", config).unwrap()
+}
+
+#[tokio::test]
+async fn get_with_query_params() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/servers/wlVr3Ggl/bans"))
+        .and(query_param("limit", "5"))
+        .and(header("Authorization", "Bearer token"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"serverMemberBans": []})))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (status, body) = client(&server)
+        .execute_raw(
+            Method::GET,
+            "servers/wlVr3Ggl/bans",
+            None,
+            &[("limit", "5")],
+        )
+        .await
+        .unwrap();
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, json!({"serverMemberBans": []}));
+}
+
+#[tokio::test]
+async fn post_with_a_body() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/v1/channels/c/messages"))
+        .and(body_json(json!({"content": "hello"})))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let (status, body) = client(&server)
+        .execute_raw(
+            Method::POST,
+            "channels/c/messages",
+            Some(json!({"content": "hello"})),
+            &[],
+        )
+        .await
+        .unwrap();
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    assert!(body.is_null());
+}
+
+#[tokio::test]
+async fn not_found_is_a_guilded_error() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({
+            "code": "NotFoundError",
+            "message": "Channel not found"
+        })))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let error = client(&server)
+        .execute_raw(Method::GET, "channels/missing", None, &[])
+        .await
+        .unwrap_err();
+    match error {
+        ApiError::Guilded(error) => {
+            assert_eq!(error.code, GuildedErrorCode::NotFound);
+            assert_eq!(error.message, "Channel not found");
+        }
+        error => panic!("expected a guilded error, got {error:?}"),
+    }
+}
@@ -0,0 +1,122 @@
+//! Paging through the history of a channel, against a local server that pages by timestamp like guilded does
+
+use chrono::{DateTime, TimeZone, Utc};
+use futures_util::StreamExt;
+use serde_json::{json, Value};
+use vived_api::search::SearchOptions;
+use vived_api::{ApiClient, ApiClientConfig, ApiError};
+use vived_models::Message;
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+fn client(server: &MockServer) -> ApiClient {
+    let config = ApiClientConfig::default().api_origin(server.uri().parse().unwrap());
+    ApiClient::new_with_config("token", config).unwrap()
+}
+
+/// `count` messages, newest first, where every `group` messages in a row share a timestamp
+fn history(count: usize, group: usize) -> Vec<(String, DateTime<Utc>)> {
+    let newest = Utc.ymd(2022, 10, 1).and_hms(12, 0, 0);
+    (0..count)
+        .map(|index| {
+            let minutes = i64::try_from(index / group).unwrap();
+            (
+                format!("message-{index}"),
+                newest - chrono::Duration::minutes(minutes),
+            )
+        })
+        .collect()
+}
+
+/// Answer a message list request out of `messages`, leaving out those sent at or after `before`
+fn respond(messages: &[(String, DateTime<Utc>)], request: &Request) -> ResponseTemplate {
+    let mut before = None;
+    let mut limit = 50;
+    for (key, value) in request.url.query_pairs() {
+        match &*key {
+            "before" => before = Some(DateTime::parse_from_rfc3339(&value).unwrap()),
+            "limit" => limit = value.parse().unwrap(),
+            _ => {}
+        }
+    }
+
+    let page: Vec<Value> = messages
+        .iter()
+        .filter(|&&(_, created_at)| before.is_none_or(|before| created_at < before))
+        .take(limit)
+        .map(|&(ref id, created_at)| {
+            json!({
+                "id": id, "type": "default", "serverId": "s", "channelId": "c",
+                "content": id, "createdAt": created_at.to_rfc3339(), "createdBy": "Ann6LewA"
+            })
+        })
+        .collect();
+    ResponseTemplate::new(200).set_body_json(json!({ "messages": page }))
+}
+
+/// Serve `messages` as the history of channel `c`
+async fn history_server(messages: Vec<(String, DateTime<Utc>)>) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/channels/c/messages"))
+        .respond_with(move |request: &Request| respond(&messages, request))
+        .mount(&server)
+        .await;
+    server
+}
+
+async fn ids(client: &ApiClient, options: SearchOptions) -> Vec<String> {
+    client
+        .message_history("c", options)
+        .map(|message: Result<Message, ApiError>| message.unwrap().id.0)
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn messages_sharing_a_timestamp_are_all_yielded_once() {
+    // 100 messages fit a page, so the first one ends in the middle of a group
+    let messages = history(250, 7);
+    let expected: Vec<String> = messages.iter().map(|(id, _)| id.clone()).collect();
+    let server = history_server(messages).await;
+
+    let options = SearchOptions::default().max_messages(1000);
+    assert_eq!(ids(&client(&server), options).await, expected);
+}
+
+#[tokio::test]
+async fn resuming_leaves_out_only_what_was_seen() {
+    let messages = history(10, 4);
+    let expected: Vec<String> = messages.iter().map(|(id, _)| id.clone()).collect();
+    let oldest_seen = messages[5].1;
+    let server = history_server(messages).await;
+
+    let options = SearchOptions::default().resume_after(oldest_seen, ["message-4", "message-5"]);
+    assert_eq!(ids(&client(&server), options).await, expected[6..]);
+}
+
+#[tokio::test]
+async fn more_messages_at_one_time_than_fit_a_page_still_end() {
+    let server = history_server(history(150, 150)).await;
+
+    let options = SearchOptions::default().max_messages(1000);
+    assert_eq!(ids(&client(&server), options).await.len(), 100);
+}
+
+#[tokio::test]
+async fn dropping_the_stream_stops_requesting_pages() {
+    let server = MockServer::start().await;
+    let messages = history(250, 1);
+    Mock::given(method("GET"))
+        .and(path("/api/v1/channels/c/messages"))
+        .respond_with(move |request: &Request| respond(&messages, request))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let client = client(&server);
+    let mut stream = Box::pin(client.message_history("c", SearchOptions::default()));
+    let first = stream.next().await.unwrap().unwrap();
+    assert_eq!(first.id.0, "message-0");
+    drop(stream);
+}
@@ -0,0 +1,172 @@
+//! What ends up in the logs, and in decode errors, for the body logging settings
+
+use std::sync::{Mutex, Once};
+
+use vived_api::endpoints::{GetChannel, MessageCreate};
+use vived_api::{ApiClient, ApiClientConfig, ApiError};
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Every message logged by any test in this file
+static RECORDS: Mutex<Vec<String>> = Mutex::new(Vec::new());
+
+/// Keeps every log message in [`RECORDS`]
+struct Recorder;
+
+impl log::Log for Recorder {
+    fn enabled(&self, _: &log::Metadata<'_>) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record<'_>) {
+        RECORDS.lock().unwrap().push(record.args().to_string());
+    }
+
+    fn flush(&self) {}
+}
+
+/// Start recording, tests run in parallel so each one looks for its own marker
+fn record_logs() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&Recorder).unwrap();
+        log::set_max_level(log::LevelFilter::Trace);
+    });
+}
+
+/// Logged messages containing `marker`
+fn logged(marker: &str) -> Vec<String> {
+    RECORDS
+        .lock()
+        .unwrap()
+        .iter()
+        .filter(|record| record.contains(marker))
+        .cloned()
+        .collect()
+}
+
+fn client(server: &MockServer, config: ApiClientConfig) -> ApiClient {
+    let config = config.api_origin(server.uri().parse().unwrap());
+    ApiClient::new_with_config("token", config).unwrap()
+}
+
+/// A server that answers everything with a channel that is missing most of its fields
+async fn broken_server(marker: &str) -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+            "channel": {"id": "abc", "topic": marker}
+        })))
+        .mount(&server)
+        .await;
+    server
+}
+
+#[tokio::test]
+async fn long_bodies_are_cut_off() {
+    record_logs();
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let marker = "cut-off-marker";
+    let content = format!("{marker}{}", "x".repeat(3000));
+    let config = ApiClientConfig::default().max_logged_body(64);
+    let _ = client(&server, config)
+        .make_request(MessageCreate::new_with_content("channel", content))
+        .await;
+
+    let bodies = logged(marker);
+    assert!(!bodies.is_empty());
+    for body in bodies {
+        assert!(body.len() < 200, "logged {} bytes", body.len());
+        assert!(body.contains("bytes total)"));
+    }
+}
+
+#[tokio::test]
+async fn hidden_bodies_never_reach_the_logs() {
+    record_logs();
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .respond_with(ResponseTemplate::new(500))
+        .mount(&server)
+        .await;
+
+    let marker = "private-request-marker";
+    let config = ApiClientConfig::default().log_bodies(false);
+    let _ = client(&server, config)
+        .make_request(MessageCreate::new_with_content("channel", marker))
+        .await;
+
+    assert_eq!(logged(marker), Vec::<String>::new());
+    assert!(!logged("bytes hidden, hash").is_empty());
+}
+
+#[tokio::test]
+async fn hidden_response_bodies_are_hashed_in_decode_errors() {
+    record_logs();
+    let marker = "private-response-marker";
+    let server = broken_server(marker).await;
+
+    let config = ApiClientConfig::default().log_bodies(false);
+    let error = client(&server, config)
+        .make_request(GetChannel::new("abc"))
+        .await
+        .unwrap_err();
+
+    let ApiError::Decode {
+        ref snippet,
+        ref body,
+        ..
+    } = error
+    else {
+        panic!("expected a decode error, got {error:?}")
+    };
+    assert!(snippet.contains("bytes hidden, hash"), "{snippet}");
+    assert!(body.is_none());
+    assert!(!error.to_string().contains(marker));
+    assert_eq!(logged(marker), Vec::<String>::new());
+}
+
+#[tokio::test]
+async fn the_same_body_gets_the_same_hash() {
+    let marker = "hashed-twice-marker";
+    let server = broken_server(marker).await;
+    let client = client(&server, ApiClientConfig::default().log_bodies(false));
+
+    let mut snippets = Vec::new();
+    for _ in 0..2 {
+        match client.make_request(GetChannel::new("abc")).await {
+            Err(ApiError::Decode { snippet, .. }) => snippets.push(snippet),
+            other => panic!("expected a decode error, got {other:?}"),
+        }
+    }
+    assert_eq!(snippets[0], snippets[1]);
+}
+
+#[tokio::test]
+async fn shown_response_bodies_stay_in_decode_errors() {
+    record_logs();
+    let marker = "public-response-marker";
+    let server = broken_server(marker).await;
+
+    let error = client(&server, ApiClientConfig::default())
+        .make_request(GetChannel::new("abc"))
+        .await
+        .unwrap_err();
+
+    let ApiError::Decode {
+        ref snippet,
+        ref body,
+        ..
+    } = error
+    else {
+        panic!("expected a decode error, got {error:?}")
+    };
+    assert!(snippet.contains(marker), "{snippet}");
+    assert!(body.as_deref().is_some_and(|body| body.contains(marker)));
+    assert!(logged(marker).iter().any(|record| record.starts_with("RESPONSE BODY")));
+}
@@ -0,0 +1,78 @@
+//! Member conveniences against a local server
+
+use vived_api::{ApiClient, ApiClientConfig, ApiError, ServerMemberExt};
+use vived_models::ServerMember;
+use wiremock::matchers::{body_json, method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn client(server: &MockServer) -> ApiClient {
+    let config = ApiClientConfig::default().api_origin(server.uri().parse().unwrap());
+    ApiClient::new_with_config("token", config).unwrap()
+}
+
+fn member() -> ServerMember {
+    serde_json::from_value(serde_json::json!({
+        "user": {"id": "Ann6LewA", "type": "user", "name": "Ann", "createdAt": "2022-01-01T00:00:00.000Z"},
+        "roleIds": [],
+        "joinedAt": "2022-10-26T00:00:00.000Z"
+    }))
+    .unwrap()
+}
+
+#[tokio::test]
+async fn set_nickname_updates_the_member() {
+    let server = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .and(path("/api/v1/servers/wlVr3Ggl/members/Ann6LewA/nickname"))
+        .and(body_json(serde_json::json!({"nickname": "Verified Ann"})))
+        .respond_with(
+            ResponseTemplate::new(200)
+                .set_body_json(serde_json::json!({"nickname": "Verified Ann"})),
+        )
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut member = member();
+    member
+        .set_nickname(&client(&server), "wlVr3Ggl", "Verified Ann")
+        .await
+        .unwrap();
+    assert_eq!(member.display_name(), "Verified Ann");
+}
+
+#[tokio::test]
+async fn invalid_nickname_is_not_sent() {
+    let server = MockServer::start().await;
+    Mock::given(method("PUT"))
+        .respond_with(ResponseTemplate::new(200))
+        .expect(0)
+        .mount(&server)
+        .await;
+
+    let mut member = member();
+    let result = member
+        .set_nickname(&client(&server), "wlVr3Ggl", "x".repeat(33))
+        .await;
+    assert!(matches!(result, Err(ApiError::InvalidArgument(_))));
+    assert_eq!(member.nickname, None);
+}
+
+#[tokio::test]
+async fn clear_nickname_removes_it() {
+    let server = MockServer::start().await;
+    Mock::given(method("DELETE"))
+        .and(path("/api/v1/servers/wlVr3Ggl/members/Ann6LewA/nickname"))
+        .respond_with(ResponseTemplate::new(204))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let mut member = member();
+    member.nickname = Some("Old".to_owned());
+    member
+        .clear_nickname(&client(&server), "wlVr3Ggl")
+        .await
+        .unwrap();
+    assert_eq!(member.display_name(), "Ann");
+}
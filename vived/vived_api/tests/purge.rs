@@ -0,0 +1,148 @@
+//! Purging a channel against a local server, with some deletes refused
+
+use std::collections::HashSet;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use chrono::{DateTime, TimeZone, Utc};
+use serde_json::{json, Value};
+use vived_api::purge::PurgeOptions;
+use vived_api::{ApiClient, ApiClientConfig, GuildedErrorCode, RatelimitWeight};
+use wiremock::matchers::{method, path, path_regex};
+use wiremock::{Mock, MockServer, Request, ResponseTemplate};
+
+/// Messages that can't be deleted
+const PROTECTED: [&str; 2] = ["message-3", "message-101"];
+
+/// `count` messages, newest first, where every 7 messages in a row share a timestamp
+fn history(count: usize) -> Vec<(String, DateTime<Utc>)> {
+    let newest = Utc.ymd(2022, 10, 1).and_hms(12, 0, 0);
+    (0..count)
+        .map(|index| {
+            let minutes = i64::try_from(index / 7).unwrap();
+            (
+                format!("message-{index}"),
+                newest - chrono::Duration::minutes(minutes),
+            )
+        })
+        .collect()
+}
+
+/// Answer a message list request out of `messages`, leaving out those sent at or after `before`
+fn list(messages: &[(String, DateTime<Utc>)], request: &Request) -> ResponseTemplate {
+    let mut before: Option<DateTime<Utc>> = None;
+    let mut limit = 50;
+    for (key, value) in request.url.query_pairs() {
+        match &*key {
+            "before" => before = Some(value.parse().unwrap()),
+            "limit" => limit = value.parse().unwrap(),
+            _ => {}
+        }
+    }
+
+    let page: Vec<Value> = messages
+        .iter()
+        .filter(|&&(_, created_at)| before.is_none_or(|before| created_at < before))
+        .take(limit)
+        .map(|&(ref id, created_at)| {
+            json!({
+                "id": id, "type": "default", "serverId": "s", "channelId": "c",
+                "createdAt": created_at.to_rfc3339(), "createdBy": "Ann6LewA"
+            })
+        })
+        .collect();
+    ResponseTemplate::new(200).set_body_json(json!({ "messages": page }))
+}
+
+/// Serve the history of channel `c`, refusing to delete [`PROTECTED`], returning every delete request made
+async fn purge_server(
+    messages: Vec<(String, DateTime<Utc>)>,
+) -> (MockServer, Arc<Mutex<Vec<String>>>) {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/channels/c/messages"))
+        .respond_with(move |request: &Request| list(&messages, request))
+        .mount(&server)
+        .await;
+
+    let deletes = Arc::new(Mutex::new(Vec::new()));
+    let recorded = Arc::clone(&deletes);
+    Mock::given(method("DELETE"))
+        .and(path_regex("^/api/v1/channels/c/messages/[^/]+$"))
+        .respond_with(move |request: &Request| {
+            let id = request
+                .url
+                .path_segments()
+                .unwrap()
+                .next_back()
+                .unwrap()
+                .to_owned();
+            let protected = PROTECTED.contains(&id.as_str());
+            recorded.lock().unwrap().push(id);
+            if protected {
+                ResponseTemplate::new(403).set_body_json(json!({
+                    "code": "ForbiddenError",
+                    "message": "You do not have permission to delete this message"
+                }))
+            } else {
+                ResponseTemplate::new(204)
+            }
+        })
+        .mount(&server)
+        .await;
+
+    (server, deletes)
+}
+
+fn client(server: &MockServer) -> ApiClient {
+    // a local server has no ratelimit, without holds the purge takes a fraction of a second instead of minutes
+    let config = ApiClientConfig::default()
+        .api_origin(server.uri().parse().unwrap())
+        .permit_hold(RatelimitWeight::Light, Duration::ZERO)
+        .permit_hold(RatelimitWeight::Normal, Duration::ZERO);
+    ApiClient::new_with_config("token", config).unwrap()
+}
+
+#[tokio::test]
+async fn refused_deletes_are_reported_without_stopping_the_purge() {
+    let (server, deletes) = purge_server(history(150)).await;
+
+    let report = client(&server)
+        .purge_messages("c", PurgeOptions::new(150))
+        .await
+        .unwrap();
+
+    // every message was tried exactly once, including those sharing a timestamp across a page boundary
+    let deletes = deletes.lock().unwrap();
+    assert_eq!(deletes.len(), 150);
+    assert_eq!(deletes.iter().collect::<HashSet<_>>().len(), 150);
+
+    assert_eq!(report.deleted.len(), 148);
+    let failed: Vec<&str> = report
+        .failed
+        .iter()
+        .map(|failed| failed.0 .0.as_str())
+        .collect();
+    assert_eq!(failed, PROTECTED);
+    for (_, error) in &report.failed {
+        assert!(
+            error.guilded_code() == Some(&GuildedErrorCode::Forbidden),
+            "{error:?}"
+        );
+    }
+}
+
+#[tokio::test]
+async fn a_dry_run_deletes_nothing() {
+    let (server, deletes) = purge_server(history(150)).await;
+
+    let report = client(&server)
+        .purge_messages("c", PurgeOptions::new(120).dry_run(true))
+        .await
+        .unwrap();
+
+    assert!(report.dry_run);
+    assert_eq!(report.deleted.len(), 120);
+    assert!(report.failed.is_empty());
+    assert!(deletes.lock().unwrap().is_empty());
+}
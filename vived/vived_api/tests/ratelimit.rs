@@ -0,0 +1,112 @@
+//! Timing of the ratelimiter, with the permit holds shortened so the test doesn't take a minute
+
+use std::time::{Duration, Instant};
+
+use vived_api::{ApiClient, ApiClientConfig, Endpoint, RatelimitWeight};
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const NORMAL_HOLD: Duration = Duration::from_millis(600);
+const LIGHT_HOLD: Duration = Duration::from_millis(300);
+
+/// A GET with a chosen weight
+struct Weighted(RatelimitWeight);
+
+impl Endpoint<serde_json::Value> for Weighted {
+    fn build(&self, client: &reqwest::Client) -> reqwest::RequestBuilder {
+        client.get("https://www.guilded.gg/api/v1/ping")
+    }
+
+    fn from_raw(raw: &str) -> Result<serde_json::Value, serde_json::Error> {
+        serde_json::from_str(raw)
+    }
+
+    fn ratelimit_weight(&self) -> RatelimitWeight {
+        self.0
+    }
+}
+
+async fn server() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({})))
+        .mount(&server)
+        .await;
+    server
+}
+
+fn client(server: &MockServer, config: ApiClientConfig) -> ApiClient {
+    let config = config
+        .api_origin(server.uri().parse().unwrap())
+        .permit_hold(RatelimitWeight::Normal, NORMAL_HOLD)
+        .permit_hold(RatelimitWeight::Light, LIGHT_HOLD);
+    ApiClient::new_with_config("token", config).unwrap()
+}
+
+async fn time_requests(client: &ApiClient, weight: RatelimitWeight, count: usize) -> Duration {
+    let start = Instant::now();
+    let requests = (0..count).map(|_| client.make_request(Weighted(weight)));
+    for result in futures_util::future::join_all(requests).await {
+        result.unwrap();
+    }
+    start.elapsed()
+}
+
+#[tokio::test]
+async fn light_gets_finish_faster() {
+    let server = server().await;
+
+    let normal = client(&server, ApiClientConfig::default());
+    let normal = time_requests(&normal, RatelimitWeight::Normal, 60).await;
+    let light = client(&server, ApiClientConfig::default());
+    let light = time_requests(&light, RatelimitWeight::Light, 60).await;
+
+    // the second 30 requests wait for the permits of the first 30
+    assert!(normal >= NORMAL_HOLD, "{normal:?}");
+    assert!(light >= LIGHT_HOLD, "{light:?}");
+    assert!(light < normal, "light {light:?}, normal {normal:?}");
+}
+
+#[tokio::test]
+async fn starved_requests_are_counted() {
+    let server = server().await;
+    let config = ApiClientConfig::default().starvation_threshold(LIGHT_HOLD / 2);
+    let client = client(&server, config);
+
+    time_requests(&client, RatelimitWeight::Light, 31).await;
+
+    let stats = client.ratelimit_stats();
+    assert_eq!(stats.starved_requests, 1, "{stats:?}");
+    assert!(stats.longest_wait >= LIGHT_HOLD / 2, "{stats:?}");
+    assert_eq!(stats.waiting_requests, 0);
+}
+
+#[tokio::test]
+async fn clones_share_the_permits() {
+    let server = server().await;
+    let original = client(&server, ApiClientConfig::default());
+    let clone = original.clone();
+
+    // all 30 permits are still held right after the responses came in
+    time_requests(&original, RatelimitWeight::Normal, 30).await;
+    assert_eq!(clone.ratelimit_stats().available_permits, 0);
+
+    // so the clone has to wait for them
+    let waited = time_requests(&clone, RatelimitWeight::Normal, 1).await;
+    assert!(waited >= NORMAL_HOLD / 2, "{waited:?}");
+}
+
+#[tokio::test]
+async fn clones_are_bounded_together() {
+    let server = server().await;
+    let original = client(&server, ApiClientConfig::default());
+    let clone = original.clone();
+
+    let (first, second) = tokio::join!(
+        time_requests(&original, RatelimitWeight::Normal, 30),
+        time_requests(&clone, RatelimitWeight::Normal, 30),
+    );
+
+    // with separate semaphores both halves would get 30 permits and finish right away
+    assert!(first.max(second) >= NORMAL_HOLD, "{first:?} {second:?}");
+}
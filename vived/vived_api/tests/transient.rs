@@ -0,0 +1,123 @@
+//! Which errors are retried, against a local server that drops connections
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use vived_api::endpoints::{GetChannel, MessageCreate};
+use vived_api::{ApiClient, ApiClientConfig, ApiError};
+use wiremock::matchers::method;
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+const CHANNEL: &str = r#"{"channel": {
+    "id": "00000000-0000-0000-0000-000000000001",
+    "type": "chat",
+    "name": "general",
+    "createdAt": "2022-10-26T00:00:00.000Z",
+    "createdBy": "Ann6LewA",
+    "serverId": "wlVr3Ggl"
+}}"#;
+
+/// A server that closes the first `drops` connections without answering,
+/// and answers every later request with `CHANNEL`
+///
+/// Returns its origin and how many connections it accepted.
+async fn flaky_server(drops: usize) -> (reqwest::Url, Arc<AtomicUsize>) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let origin = format!("http://{}", listener.local_addr().unwrap()).parse().unwrap();
+    let accepted = Arc::new(AtomicUsize::new(0));
+
+    let counter = Arc::clone(&accepted);
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            if counter.fetch_add(1, Ordering::SeqCst) < drops {
+                drop(stream);
+                continue;
+            }
+
+            tokio::spawn(async move {
+                // every request here fits in one read
+                let mut buffer = vec![0; 64 * 1024];
+                let _ = stream.read(&mut buffer).await;
+                let response = format!(
+                    "HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: {}\r\nconnection: close\r\n\r\n{CHANNEL}",
+                    CHANNEL.len()
+                );
+                let _ = stream.write_all(response.as_bytes()).await;
+            });
+        }
+    });
+
+    (origin, accepted)
+}
+
+fn client(origin: reqwest::Url) -> ApiClient {
+    let config = ApiClientConfig::default()
+        .api_origin(origin)
+        .max_transient_retries(2);
+    ApiClient::new_with_config("token", config).unwrap()
+}
+
+#[tokio::test]
+async fn dropped_get_is_retried() {
+    let (origin, accepted) = flaky_server(1).await;
+
+    let channel = client(origin).make_request(GetChannel::new("abc")).await.unwrap();
+    assert_eq!(channel.name, "general");
+    assert_eq!(accepted.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn dropped_post_fails_fast() {
+    let (origin, accepted) = flaky_server(1).await;
+
+    let result = client(origin)
+        .make_request(MessageCreate::new_with_content("abc", "hello"))
+        .await;
+    assert!(matches!(result, Err(ApiError::Request(_))), "{result:?}");
+    assert_eq!(accepted.load(Ordering::SeqCst), 1);
+}
+
+#[tokio::test]
+async fn retries_give_up() {
+    let (origin, accepted) = flaky_server(usize::MAX).await;
+
+    let result = client(origin).make_request(GetChannel::new("abc")).await;
+    assert!(matches!(result, Err(ApiError::Request(_))), "{result:?}");
+    // the first try and 2 retries
+    assert_eq!(accepted.load(Ordering::SeqCst), 3);
+}
+
+#[tokio::test]
+async fn refused_connection_is_retried_then_returned() {
+    // bind and drop a listener, so nothing is listening on the port
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let origin = format!("http://{}", listener.local_addr().unwrap()).parse().unwrap();
+    drop(listener);
+
+    let start = std::time::Instant::now();
+    let result = client(origin).make_request(GetChannel::new("abc")).await;
+    // the two retries back off for 200ms and 400ms
+    assert!(start.elapsed() >= std::time::Duration::from_millis(600), "{:?}", start.elapsed());
+    let Err(ApiError::Request(ref error)) = result else {
+        panic!("expected a request error, got {result:?}")
+    };
+    assert!(error.is_connect(), "{error:?}");
+}
+
+#[tokio::test]
+async fn decode_errors_are_not_retried() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .respond_with(ResponseTemplate::new(200).set_body_string("{\"channel\": 1}"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let result = client(server.uri().parse().unwrap())
+        .make_request(GetChannel::new("abc"))
+        .await;
+    assert!(matches!(result, Err(ApiError::Decode { .. })), "{result:?}");
+}
@@ -0,0 +1,107 @@
+//! Listing the voice channels of a server spread over several pages, against a local server
+
+use serde_json::{json, Value};
+use vived_api::{get_voice_channels, ApiClient, ApiClientConfig};
+use vived_models::GroupId;
+use wiremock::matchers::{method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+fn client(server: &MockServer) -> ApiClient {
+    let config = ApiClientConfig::default().api_origin(server.uri().parse().unwrap());
+    ApiClient::new_with_config("token", config).unwrap()
+}
+
+fn channel(id: u8, kind: &str, group: &str) -> Value {
+    json!({
+        "id": format!("00000000-0000-0000-0000-{id:012}"),
+        "type": kind,
+        "name": format!("channel {id}"),
+        "createdAt": "2022-10-26T00:00:00.000Z",
+        "createdBy": "Ann6LewA",
+        "serverId": "wlVr3Ggl",
+        "groupId": group
+    })
+}
+
+/// Serve `pages` in order, each one pointing at the next with its index as cursor
+async fn paged_server(pages: Vec<Vec<Value>>) -> MockServer {
+    let server = MockServer::start().await;
+    let count = pages.len();
+    for (index, channels) in pages.into_iter().enumerate() {
+        let has_more = index + 1 < count;
+        let mut body = json!({"channels": channels, "hasMore": has_more});
+        if has_more {
+            body["cursor"] = json!(format!("page-{}", index + 1));
+        }
+
+        let mock = Mock::given(method("GET")).and(path("/api/v1/servers/wlVr3Ggl/channels"));
+        let mock = if index == 0 {
+            mock
+        } else {
+            mock.and(query_param("cursor", format!("page-{index}")))
+        };
+        mock.respond_with(ResponseTemplate::new(200).set_body_json(body))
+            .expect(1)
+            .with_priority(if index == 0 { 2 } else { 1 })
+            .mount(&server)
+            .await;
+    }
+    server
+}
+
+fn names(channels: &[vived_models::Channel]) -> Vec<&str> {
+    channels
+        .iter()
+        .map(|channel| channel.name.as_str())
+        .collect()
+}
+
+#[tokio::test]
+async fn every_page_is_requested() {
+    let server = paged_server(vec![
+        vec![channel(1, "voice", "a"), channel(2, "chat", "a")],
+        vec![channel(3, "stream", "b")],
+        vec![channel(4, "voice", "a")],
+    ])
+    .await;
+
+    let channels = get_voice_channels(&client(&server), "wlVr3Ggl", None)
+        .await
+        .unwrap();
+    assert_eq!(names(&channels), ["channel 1", "channel 3", "channel 4"]);
+}
+
+#[tokio::test]
+async fn the_group_filter_applies_to_every_page() {
+    let server = paged_server(vec![
+        vec![channel(1, "voice", "a"), channel(2, "voice", "b")],
+        vec![channel(3, "voice", "a"), channel(4, "voice", "b")],
+    ])
+    .await;
+
+    let group = GroupId::from("b");
+    let channels = get_voice_channels(&client(&server), "wlVr3Ggl", Some(&group))
+        .await
+        .unwrap();
+    assert_eq!(names(&channels), ["channel 2", "channel 4"]);
+}
+
+#[tokio::test]
+async fn a_repeated_cursor_ends_the_listing() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v1/servers/wlVr3Ggl/channels"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "channels": [channel(1, "voice", "a")],
+            "hasMore": true,
+            "cursor": "again"
+        })))
+        .expect(2)
+        .mount(&server)
+        .await;
+
+    let channels = get_voice_channels(&client(&server), "wlVr3Ggl", None)
+        .await
+        .unwrap();
+    assert_eq!(channels.len(), 2);
+}
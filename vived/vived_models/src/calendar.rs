@@ -0,0 +1,725 @@
+//! Events in calendar channels
+//! <https://www.guilded.gg/docs/api/calendarEvents/CalendarEvent>
+//!
+//! [`CalendarEvent::next_occurrence`] works out when a repeating event happens next,
+//! and [`CalendarEvent::to_ics`] exports an event so people can add it to their own calendar app.
+//! Both follow the recurrence rules of ics (RFC 5545), so they always agree with each other:
+//! - the start of the event is always the first occurrence, even if it isn't on one of the repeat days
+//! - weeks start on monday, which matters for repeating every few weeks
+//! - monthly and yearly repeats keep the day of the month, and skip months that don't have it,
+//!   so an event on the 31st repeats in the months with 31 days, and one on february 29th only in leap years
+//! - skipped months don't count towards [`RepeatInfo::ends_after_occurrences`]
+
+use chrono::{DateTime, Datelike, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Someone's answer to a calendar event
+///
+/// ```
+/// use vived_models::RsvpStatus;
+///
+/// for status in RsvpStatus::all() {
+///     assert_eq!(status.to_string().parse(), Ok(*status));
+/// }
+/// assert_eq!("Going".parse(), Ok(RsvpStatus::Going));
+/// assert_eq!("NOT RESPONDED".parse(), Ok(RsvpStatus::NotResponded));
+/// assert!("unknown".parse::<RsvpStatus>().is_err());
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum RsvpStatus {
+    /// Going to the event
+    Going,
+    /// Might go to the event
+    Maybe,
+    /// Not going to the event
+    Declined,
+    /// Invited, but hasn't answered yet
+    Invited,
+    /// Wants to go, but the event is full
+    Waitlisted,
+    /// Hasn't answered
+    #[serde(rename = "not responded")]
+    NotResponded,
+    /// A status vived doesn't know about
+    #[serde(other)]
+    Unknown,
+}
+
+impl RsvpStatus {
+    /// Every known status, useful for listing the options in an error message
+    #[must_use]
+    pub fn all() -> &'static [Self] {
+        &[
+            Self::Going,
+            Self::Maybe,
+            Self::Declined,
+            Self::Invited,
+            Self::Waitlisted,
+            Self::NotResponded,
+        ]
+    }
+
+    /// The status as guilded names it
+    #[must_use]
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Going => "going",
+            Self::Maybe => "maybe",
+            Self::Declined => "declined",
+            Self::Invited => "invited",
+            Self::Waitlisted => "waitlisted",
+            Self::NotResponded => "not responded",
+            Self::Unknown => "unknown",
+        }
+    }
+}
+
+impl std::fmt::Display for RsvpStatus {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Returned when parsing an [`RsvpStatus`] that doesn't exist
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RsvpStatusParseError(pub String);
+
+impl std::fmt::Display for RsvpStatusParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let options: Vec<&str> = RsvpStatus::all().iter().copied().map(RsvpStatus::as_str).collect();
+        write!(f, "unknown rsvp status {:?}, expected one of: {}", self.0, options.join(", "))
+    }
+}
+
+impl std::error::Error for RsvpStatusParseError {}
+
+impl std::str::FromStr for RsvpStatus {
+    type Err = RsvpStatusParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::all()
+            .iter()
+            .find(|status| status.as_str().eq_ignore_ascii_case(s.trim()))
+            .copied()
+            .ok_or_else(|| RsvpStatusParseError(s.to_owned()))
+    }
+}
+
+/// How an event repeats
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum RepeatType {
+    /// The event doesn't repeat
+    Once,
+    /// Every day
+    EveryDay,
+    /// Every week, on the days in [`RepeatInfo::on`] if given
+    EveryWeek,
+    /// Every month
+    EveryMonth,
+    /// As set in [`RepeatInfo::every`]
+    Custom,
+}
+
+/// The unit of a custom repeat
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum RepeatInterval {
+    /// Days
+    Day,
+    /// Weeks
+    Week,
+    /// Months
+    Month,
+    /// Years
+    Year,
+}
+
+/// Repeat every `count` `interval`s, like every 2 weeks
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+pub struct RepeatEvery {
+    /// How many intervals are between repeats, 0 is treated as 1
+    pub count: u32,
+    /// The unit
+    pub interval: RepeatInterval,
+}
+
+/// A day of the week to repeat on
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum RepeatDay {
+    /// Sunday
+    Sunday,
+    /// Monday
+    Monday,
+    /// Tuesday
+    Tuesday,
+    /// Wednesday
+    Wednesday,
+    /// Thursday
+    Thursday,
+    /// Friday
+    Friday,
+    /// Saturday
+    Saturday,
+}
+
+impl From<RepeatDay> for chrono::Weekday {
+    fn from(day: RepeatDay) -> Self {
+        match day {
+            RepeatDay::Sunday => Self::Sun,
+            RepeatDay::Monday => Self::Mon,
+            RepeatDay::Tuesday => Self::Tue,
+            RepeatDay::Wednesday => Self::Wed,
+            RepeatDay::Thursday => Self::Thu,
+            RepeatDay::Friday => Self::Fri,
+            RepeatDay::Saturday => Self::Sat,
+        }
+    }
+}
+
+/// How a calendar event repeats
+/// <https://www.guilded.gg/docs/api/calendarEvents/CalendarEventSeries>
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct RepeatInfo {
+    /// How the event repeats
+    #[serde(rename = "type")]
+    pub repeat_type: RepeatType,
+    /// The interval of a [`RepeatType::Custom`] repeat
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub every: Option<RepeatEvery>,
+    /// Stop after this many occurrences, the first one included
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ends_after_occurrences: Option<u32>,
+    /// Stop repeating after this date
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<DateTime<Utc>>,
+    /// Days of the week to repeat on, for weekly repeats
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub on: Vec<RepeatDay>,
+}
+
+impl RepeatInfo {
+    /// The repeat as an ics style rule, `None` if the event doesn't repeat
+    fn rule(&self) -> Option<Rule> {
+        let (frequency, interval) = match self.repeat_type {
+            RepeatType::Once => return None,
+            RepeatType::EveryDay => (Frequency::Daily, 1),
+            RepeatType::EveryWeek => (Frequency::Weekly, 1),
+            RepeatType::EveryMonth => (Frequency::Monthly, 1),
+            RepeatType::Custom => {
+                let Some(every) = self.every else {
+                    log::warn!(
+                        "custom repeat without an interval, treating the event as not repeating"
+                    );
+                    return None;
+                };
+                let frequency = match every.interval {
+                    RepeatInterval::Day => Frequency::Daily,
+                    RepeatInterval::Week => Frequency::Weekly,
+                    RepeatInterval::Month => Frequency::Monthly,
+                    RepeatInterval::Year => Frequency::Yearly,
+                };
+                (frequency, every.count.max(1))
+            }
+        };
+
+        let mut days: Vec<chrono::Weekday> = Vec::new();
+        if frequency == Frequency::Weekly {
+            days = self.on.iter().copied().map(chrono::Weekday::from).collect();
+            days.sort_by_key(chrono::Weekday::num_days_from_monday);
+            days.dedup();
+        }
+
+        Some(Rule {
+            frequency,
+            interval,
+            days,
+            count: self.ends_after_occurrences.map(|count| count.max(1)),
+            until: self.end_date,
+        })
+    }
+}
+
+/// The unit of a [`Rule`], named like ics `FREQ`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Frequency {
+    /// `DAILY`
+    Daily,
+    /// `WEEKLY`
+    Weekly,
+    /// `MONTHLY`
+    Monthly,
+    /// `YEARLY`
+    Yearly,
+}
+
+/// A repeat in the shape of an ics `RRULE`
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Rule {
+    /// The unit
+    frequency: Frequency,
+    /// Units between repeats, at least 1
+    interval: u32,
+    /// Days of weekly repeats starting from monday, empty for the day the event starts on
+    days: Vec<chrono::Weekday>,
+    /// Most occurrences
+    count: Option<u32>,
+    /// Last moment an occurrence can start
+    until: Option<DateTime<Utc>>,
+}
+
+impl Rule {
+    /// Number of the month, counted from year 0
+    fn month_index(date: DateTime<Utc>) -> i64 {
+        i64::from(date.year()) * 12 + i64::from(date.month0())
+    }
+
+    /// Monday of the week `date` is in
+    fn week_start(date: DateTime<Utc>) -> Option<chrono::NaiveDate> {
+        let offset = i64::from(date.weekday().num_days_from_monday());
+        date.naive_utc()
+            .date()
+            .checked_sub_signed(chrono::Duration::days(offset))
+    }
+
+    /// The first period that can have an occurrence after `after`
+    ///
+    /// Occurrences have to be counted from the start if the rule has a count.
+    fn first_period(&self, start: DateTime<Utc>, after: DateTime<Utc>) -> i64 {
+        if self.count.is_some() || after <= start {
+            return 0;
+        }
+
+        let units = match self.frequency {
+            Frequency::Daily => (after.naive_utc().date() - start.naive_utc().date()).num_days(),
+            Frequency::Weekly => match Self::week_start(start) {
+                Some(week) => (after.naive_utc().date() - week).num_days().div_euclid(7),
+                None => 0,
+            },
+            Frequency::Monthly => Self::month_index(after) - Self::month_index(start),
+            Frequency::Yearly => i64::from(after.year()) - i64::from(start.year()),
+        };
+        // start one period early, the period `after` is in can end before it
+        (units.div_euclid(i64::from(self.interval)) - 1).max(0)
+    }
+
+    /// Candidates for occurrences in a period, oldest first
+    ///
+    /// Returns `None` once the dates can't be represented anymore.
+    fn period(&self, start: DateTime<Utc>, period: i64) -> Option<Vec<DateTime<Utc>>> {
+        let offset = period.checked_mul(i64::from(self.interval))?;
+        let date = start.naive_utc().date();
+        let at =
+            |day: chrono::NaiveDate| DateTime::<Utc>::from_utc(day.and_time(start.time()), Utc);
+
+        let mut candidates = match self.frequency {
+            Frequency::Daily => {
+                vec![at(date.checked_add_signed(chrono::Duration::days(offset))?)]
+            }
+            Frequency::Weekly => {
+                let week =
+                    Self::week_start(start)?.checked_add_signed(chrono::Duration::weeks(offset))?;
+                let days = if self.days.is_empty() {
+                    vec![start.weekday()]
+                } else {
+                    self.days.clone()
+                };
+                let mut candidates = Vec::with_capacity(days.len());
+                for day in days {
+                    let weekday = chrono::Duration::days(i64::from(day.num_days_from_monday()));
+                    candidates.push(at(week.checked_add_signed(weekday)?));
+                }
+                candidates
+            }
+            Frequency::Monthly => {
+                let month = Self::month_index(start).checked_add(offset)?;
+                let year = i32::try_from(month.div_euclid(12)).ok()?;
+                let month = u32::try_from(month.rem_euclid(12)).ok()? + 1;
+                chrono::NaiveDate::from_ymd_opt(year, month, date.day())
+                    .map(at)
+                    .into_iter()
+                    .collect()
+            }
+            Frequency::Yearly => {
+                let year = i32::try_from(i64::from(date.year()).checked_add(offset)?).ok()?;
+                chrono::NaiveDate::from_ymd_opt(year, date.month(), date.day())
+                    .map(at)
+                    .into_iter()
+                    .collect()
+            }
+        };
+
+        if period == 0 {
+            candidates.retain(|&candidate| candidate > start);
+            candidates.insert(0, start);
+        }
+        Some(candidates)
+    }
+
+    /// The occurrences of an event starting at `start`, skipping ahead to close before `after` if possible
+    fn occurrences(&self, start: DateTime<Utc>, after: DateTime<Utc>) -> Occurrences<'_> {
+        Occurrences {
+            rule: self,
+            start,
+            period: self.first_period(start, after),
+            pending: Vec::new(),
+            counted: 0,
+            exhausted: false,
+        }
+    }
+
+    /// The rule as the value of an ics `RRULE`
+    fn to_ics(&self, start: DateTime<Utc>) -> String {
+        let frequency = match self.frequency {
+            Frequency::Daily => "DAILY",
+            Frequency::Weekly => "WEEKLY",
+            Frequency::Monthly => "MONTHLY",
+            Frequency::Yearly => "YEARLY",
+        };
+        let mut parts = vec![format!("FREQ={frequency}")];
+        if self.interval > 1 {
+            parts.push(format!("INTERVAL={}", self.interval));
+        }
+        if !self.days.is_empty() {
+            let days: Vec<&str> = self
+                .days
+                .iter()
+                .map(|day| match *day {
+                    chrono::Weekday::Mon => "MO",
+                    chrono::Weekday::Tue => "TU",
+                    chrono::Weekday::Wed => "WE",
+                    chrono::Weekday::Thu => "TH",
+                    chrono::Weekday::Fri => "FR",
+                    chrono::Weekday::Sat => "SA",
+                    chrono::Weekday::Sun => "SU",
+                })
+                .collect();
+            parts.push(format!("BYDAY={}", days.join(",")));
+        }
+        match (self.count, self.until) {
+            (Some(count), None) => parts.push(format!("COUNT={count}")),
+            (None, Some(until)) => parts.push(format!("UNTIL={}", ics_time(until))),
+            // ics doesn't allow both, so count the occurrences that happen before the end date
+            (Some(_), Some(_)) => {
+                let count = self.occurrences(start, start).count();
+                parts.push(format!("COUNT={count}"));
+            }
+            (None, None) => {}
+        }
+        parts.join(";")
+    }
+}
+
+/// Iterator over the occurrences of a repeating event
+#[derive(Debug)]
+struct Occurrences<'a> {
+    /// The repeat
+    rule: &'a Rule,
+    /// The start of the event
+    start: DateTime<Utc>,
+    /// The next period to look at
+    period: i64,
+    /// Candidates left in the current period, newest first
+    pending: Vec<DateTime<Utc>>,
+    /// Occurrences so far, only right when iterating from the first period
+    counted: u32,
+    /// No periods are left
+    exhausted: bool,
+}
+
+impl Iterator for Occurrences<'_> {
+    type Item = DateTime<Utc>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            if let Some(candidate) = self.pending.pop() {
+                if self.rule.count.is_some_and(|count| self.counted >= count)
+                    || self.rule.until.is_some_and(|until| candidate > until)
+                {
+                    self.exhausted = true;
+                    self.pending.clear();
+                    return None;
+                }
+                self.counted += 1;
+                return Some(candidate);
+            }
+            if self.exhausted {
+                return None;
+            }
+
+            match self.rule.period(self.start, self.period) {
+                Some(mut candidates) => {
+                    candidates.reverse();
+                    self.pending = candidates;
+                    self.period += 1;
+                }
+                None => self.exhausted = true,
+            }
+        }
+    }
+}
+
+/// A time in ics `DATE-TIME` form, in utc
+fn ics_time(time: DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape text for an ics `TEXT` value
+fn ics_escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            ';' => escaped.push_str("\\;"),
+            ',' => escaped.push_str("\\,"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => {}
+            other => escaped.push(other),
+        }
+    }
+    escaped
+}
+
+/// Fold an ics content line so no line is longer than 75 bytes, and end it
+fn ics_line(output: &mut String, line: &str) {
+    let mut length = 0;
+    for c in line.chars() {
+        if length + c.len_utf8() > 75 {
+            output.push_str("\r\n ");
+            length = 1;
+        }
+        output.push(c);
+        length += c.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+/// A complete ics calendar with the events, ready to be saved as an `.ics` file
+///
+/// See [`CalendarEvent::to_ics`] for a single event.
+#[must_use]
+pub fn ics_calendar<'a>(events: impl IntoIterator<Item = &'a CalendarEvent>) -> String {
+    let mut calendar = String::new();
+    ics_line(&mut calendar, "BEGIN:VCALENDAR");
+    ics_line(&mut calendar, "VERSION:2.0");
+    ics_line(&mut calendar, "PRODID:-//vived//calendar events//EN");
+    for event in events {
+        calendar.push_str(&event.to_ics());
+    }
+    ics_line(&mut calendar, "END:VCALENDAR");
+    calendar
+}
+
+/// An event in a calendar channel
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CalendarEvent {
+    /// The id of the event
+    pub id: crate::CalendarEventId,
+    /// The server the event is in
+    pub server_id: crate::ServerId,
+    /// The calendar channel the event is in
+    pub channel_id: crate::ChannelId,
+    /// The name of the event
+    pub name: String,
+    /// The description of the event
+    pub description: Option<String>,
+    /// Where the event takes place
+    pub location: Option<String>,
+    /// A link for the event
+    pub url: Option<String>,
+    /// The color of the event
+    pub color: Option<crate::Color>,
+    /// When the event starts
+    pub starts_at: chrono::DateTime<chrono::Utc>,
+    /// How long the event lasts, in minutes
+    pub duration: Option<u32>,
+    /// Only people mentioned or with the right roles can see the event
+    #[serde(default)]
+    pub is_private: bool,
+    /// People can't rsvp to the event
+    #[serde(default)]
+    pub rsvp_disabled: bool,
+    /// Created at timestamp
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Who created the event
+    pub created_by: crate::UserId,
+    /// How the event repeats
+    ///
+    /// Guilded only takes this when creating or updating an event and doesn't send it back,
+    /// so set it from what the event was created with to use [`CalendarEvent::next_occurrence`] on repeats.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repeat_info: Option<RepeatInfo>,
+}
+
+impl CalendarEvent {
+    /// Can people rsvp to the event
+    #[must_use]
+    pub fn is_open_rsvp(&self) -> bool {
+        !self.rsvp_disabled
+    }
+
+    /// When the event ends, an event without a duration ends when it starts
+    ///
+    /// `None` if the end is too far in the future to represent.
+    #[must_use]
+    pub fn end_time(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        let duration = chrono::Duration::minutes(i64::from(self.duration.unwrap_or(0)));
+        self.starts_at.checked_add_signed(duration)
+    }
+
+    /// When the event next starts after `after`, `None` if it doesn't happen again
+    ///
+    /// Events without [`repeat_info`](CalendarEvent::repeat_info) only happen at [`starts_at`](CalendarEvent::starts_at).
+    /// An occurrence starting exactly at `after` doesn't count, so passing the last occurrence gives the one after it.
+    /// See the [module docs](self) for how repeats are worked out.
+    /// ```
+    /// use vived_models::CalendarEvent;
+    ///
+    /// let time = |time: &str| chrono::DateTime::parse_from_rfc3339(time).unwrap().with_timezone(&chrono::Utc);
+    /// let event = |starts_at: &str, repeat_info: serde_json::Value| -> CalendarEvent {
+    ///     serde_json::from_value(serde_json::json!({
+    ///         "id": 1, "serverId": "s", "channelId": "c", "name": "Raid", "createdBy": "u",
+    ///         "createdAt": "2022-01-01T00:00:00Z", "startsAt": starts_at, "repeatInfo": repeat_info,
+    ///     })).unwrap()
+    /// };
+    ///
+    /// // 2022-10-03 is a monday
+    /// let fixtures = [
+    ///     // no repeat
+    ///     ("2022-10-03T18:00:00Z", serde_json::Value::Null, "2022-10-01T00:00:00Z", Some("2022-10-03T18:00:00Z")),
+    ///     ("2022-10-03T18:00:00Z", serde_json::json!({"type": "once"}), "2022-10-03T18:00:00Z", None),
+    ///     ("2022-10-03T18:00:00Z", serde_json::json!({"type": "custom"}), "2022-10-03T18:00:00Z", None),
+    ///     // daily
+    ///     ("2022-10-03T18:00:00Z", serde_json::json!({"type": "everyDay"}), "2022-10-05T12:00:00Z", Some("2022-10-05T18:00:00Z")),
+    ///     ("2022-10-03T18:00:00Z", serde_json::json!({"type": "everyDay"}), "2022-10-05T18:00:00Z", Some("2022-10-06T18:00:00Z")),
+    ///     ("2022-10-03T18:00:00Z", serde_json::json!({"type": "everyDay"}), "2030-01-01T19:00:00Z", Some("2030-01-02T18:00:00Z")),
+    ///     ("2022-10-03T18:00:00Z", serde_json::json!({"type": "custom", "every": {"count": 3, "interval": "day"}}), "2022-10-04T00:00:00Z", Some("2022-10-06T18:00:00Z")),
+    ///     ("2022-10-03T18:00:00Z", serde_json::json!({"type": "custom", "every": {"count": 3, "interval": "day"}, "endDate": "2022-10-09T00:00:00Z"}), "2022-10-06T18:00:00Z", None),
+    ///     ("2022-10-03T18:00:00Z", serde_json::json!({"type": "custom", "every": {"count": 1, "interval": "day"}, "endsAfterOccurrences": 10, "endDate": "2022-10-05T23:00:00Z"}), "2022-10-05T18:00:00Z", None),
+    ///     // weekly
+    ///     ("2022-10-03T18:00:00Z", serde_json::json!({"type": "everyWeek"}), "2022-10-04T00:00:00Z", Some("2022-10-10T18:00:00Z")),
+    ///     ("2022-10-03T18:00:00Z", serde_json::json!({"type": "custom", "every": {"count": 2, "interval": "week"}, "on": ["tuesday", "thursday"]}), "2022-10-03T18:00:00Z", Some("2022-10-04T18:00:00Z")),
+    ///     ("2022-10-03T18:00:00Z", serde_json::json!({"type": "custom", "every": {"count": 2, "interval": "week"}, "on": ["thursday", "tuesday"]}), "2022-10-06T18:00:00Z", Some("2022-10-18T18:00:00Z")),
+    ///     ("2022-10-03T18:00:00Z", serde_json::json!({"type": "custom", "every": {"count": 2, "interval": "week"}, "on": ["tuesday", "thursday"]}), "2023-06-02T00:00:00Z", Some("2023-06-13T18:00:00Z")),
+    ///     ("2022-10-03T18:00:00Z", serde_json::json!({"type": "custom", "every": {"count": 1, "interval": "week"}, "on": ["monday"], "endsAfterOccurrences": 3}), "2022-10-16T00:00:00Z", Some("2022-10-17T18:00:00Z")),
+    ///     ("2022-10-03T18:00:00Z", serde_json::json!({"type": "custom", "every": {"count": 1, "interval": "week"}, "on": ["monday"], "endsAfterOccurrences": 3}), "2022-10-17T18:00:00Z", None),
+    ///     // the start counts even if it isn't on one of the days
+    ///     ("2022-10-03T18:00:00Z", serde_json::json!({"type": "custom", "every": {"count": 1, "interval": "week"}, "on": ["wednesday"], "endsAfterOccurrences": 2}), "2022-10-03T18:00:00Z", Some("2022-10-05T18:00:00Z")),
+    ///     ("2022-10-03T18:00:00Z", serde_json::json!({"type": "custom", "every": {"count": 1, "interval": "week"}, "on": ["wednesday"], "endsAfterOccurrences": 2}), "2022-10-05T18:00:00Z", None),
+    ///     // weeks start on monday, so saturday and the sunday after it are in the same week
+    ///     ("2022-10-08T18:00:00Z", serde_json::json!({"type": "custom", "every": {"count": 2, "interval": "week"}, "on": ["saturday", "sunday"]}), "2022-10-08T18:00:00Z", Some("2022-10-09T18:00:00Z")),
+    ///     ("2022-10-08T18:00:00Z", serde_json::json!({"type": "custom", "every": {"count": 2, "interval": "week"}, "on": ["saturday", "sunday"]}), "2022-10-09T18:00:00Z", Some("2022-10-22T18:00:00Z")),
+    ///     // monthly, skipping months without the day
+    ///     ("2022-01-31T18:00:00Z", serde_json::json!({"type": "everyMonth"}), "2022-01-31T18:00:00Z", Some("2022-03-31T18:00:00Z")),
+    ///     ("2022-01-31T18:00:00Z", serde_json::json!({"type": "everyMonth"}), "2022-03-31T18:00:00Z", Some("2022-05-31T18:00:00Z")),
+    ///     ("2022-01-15T18:00:00Z", serde_json::json!({"type": "everyMonth"}), "2024-02-20T00:00:00Z", Some("2024-03-15T18:00:00Z")),
+    ///     ("2022-01-31T18:00:00Z", serde_json::json!({"type": "everyMonth", "endsAfterOccurrences": 3}), "2022-04-01T00:00:00Z", Some("2022-05-31T18:00:00Z")),
+    ///     ("2022-01-31T18:00:00Z", serde_json::json!({"type": "everyMonth", "endsAfterOccurrences": 3}), "2022-05-31T18:00:00Z", None),
+    ///     ("2022-08-31T18:00:00Z", serde_json::json!({"type": "custom", "every": {"count": 2, "interval": "month"}}), "2022-12-31T18:00:00Z", Some("2023-08-31T18:00:00Z")),
+    ///     // yearly, february 29th only in leap years
+    ///     ("2024-02-29T18:00:00Z", serde_json::json!({"type": "custom", "every": {"count": 1, "interval": "year"}}), "2024-03-01T00:00:00Z", Some("2028-02-29T18:00:00Z")),
+    ///     ("2022-06-01T18:00:00Z", serde_json::json!({"type": "custom", "every": {"count": 1, "interval": "year"}}), "2025-07-01T00:00:00Z", Some("2026-06-01T18:00:00Z")),
+    /// ];
+    /// for (starts_at, repeat_info, after, expected) in fixtures {
+    ///     let next = event(starts_at, repeat_info.clone()).next_occurrence(time(after));
+    ///     assert_eq!(next, expected.map(time), "{repeat_info} starting at {starts_at}, after {after}");
+    /// }
+    /// ```
+    #[must_use]
+    pub fn next_occurrence(&self, after: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        match self.repeat_info.as_ref().and_then(RepeatInfo::rule) {
+            Some(rule) => rule
+                .occurrences(self.starts_at, after)
+                .find(|&occurrence| occurrence > after),
+            None => (self.starts_at > after).then_some(self.starts_at),
+        }
+    }
+
+    /// The event as an ics `VEVENT`, with an `RRULE` if it repeats
+    ///
+    /// Wrap it in a calendar with [`ics_calendar`] to get a file calendar apps can import.
+    /// ```
+    /// use vived_models::{ics_calendar, CalendarEvent};
+    ///
+    /// let event: CalendarEvent = serde_json::from_value(serde_json::json!({
+    ///     "id": 1, "serverId": "s", "channelId": "c", "name": "Raid; bring potions, food",
+    ///     "description": "Meet at the gate\nDon't be late", "duration": 90, "isPrivate": true,
+    ///     "createdBy": "u", "createdAt": "2022-10-01T12:00:00Z", "startsAt": "2022-10-03T18:00:00Z",
+    ///     "repeatInfo": {"type": "custom", "every": {"count": 2, "interval": "week"}, "on": ["thursday", "tuesday"], "endsAfterOccurrences": 5},
+    /// })).unwrap();
+    ///
+    /// assert_eq!(event.to_ics(), [
+    ///     "BEGIN:VEVENT",
+    ///     "UID:c-1@guilded.gg",
+    ///     "DTSTAMP:20221001T120000Z",
+    ///     "DTSTART:20221003T180000Z",
+    ///     "DTEND:20221003T193000Z",
+    ///     "SUMMARY:Raid\\; bring potions\\, food",
+    ///     "DESCRIPTION:Meet at the gate\\nDon't be late",
+    ///     "CLASS:PRIVATE",
+    ///     "RRULE:FREQ=WEEKLY;INTERVAL=2;BYDAY=TU,TH;COUNT=5",
+    ///     "END:VEVENT",
+    ///     "",
+    /// ].join("\r\n"));
+    ///
+    /// let mut once = event.clone();
+    /// once.repeat_info = None;
+    /// once.description = Some("a".repeat(100));
+    /// let ics = once.to_ics();
+    /// assert!(!ics.contains("RRULE"));
+    /// assert!(ics.lines().all(|line| line.trim_end_matches('\r').len() <= 75));
+    /// assert!(ics.contains(&format!("DESCRIPTION:{}\r\n {}\r\n", "a".repeat(63), "a".repeat(37))));
+    ///
+    /// let calendar = ics_calendar([&event, &once]);
+    /// assert!(calendar.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+    /// assert!(calendar.ends_with("END:VEVENT\r\nEND:VCALENDAR\r\n"));
+    /// assert_eq!(calendar.matches("BEGIN:VEVENT").count(), 2);
+    /// ```
+    #[must_use]
+    pub fn to_ics(&self) -> String {
+        let mut lines = vec![
+            "BEGIN:VEVENT".to_owned(),
+            format!("UID:{}-{}@guilded.gg", self.channel_id, self.id),
+            format!("DTSTAMP:{}", ics_time(self.created_at)),
+            format!("DTSTART:{}", ics_time(self.starts_at)),
+        ];
+        if self.duration.is_some() {
+            if let Some(end) = self.end_time() {
+                lines.push(format!("DTEND:{}", ics_time(end)));
+            }
+        }
+        lines.push(format!("SUMMARY:{}", ics_escape(&self.name)));
+        if let Some(ref description) = self.description {
+            lines.push(format!("DESCRIPTION:{}", ics_escape(description)));
+        }
+        if let Some(ref location) = self.location {
+            lines.push(format!("LOCATION:{}", ics_escape(location)));
+        }
+        if let Some(ref url) = self.url {
+            lines.push(format!("URL:{url}"));
+        }
+        if self.is_private {
+            lines.push("CLASS:PRIVATE".to_owned());
+        }
+        if let Some(rule) = self.repeat_info.as_ref().and_then(RepeatInfo::rule) {
+            lines.push(format!("RRULE:{}", rule.to_ics(self.starts_at)));
+        }
+        lines.push("END:VEVENT".to_owned());
+
+        let mut event = String::new();
+        for line in &lines {
+            ics_line(&mut event, line);
+        }
+        event
+    }
+}
+
+impl From<CalendarEvent> for crate::CalendarEventId {
+    fn from(event: CalendarEvent) -> Self {
+        event.id
+    }
+}
@@ -1,11 +1,11 @@
 //! Guilded channels
 //! <https://www.guilded.gg/docs/api/channels/Mentions>
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// Channel type
 #[non_exhaustive]
-#[derive(Debug, Deserialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum ChannelType {
     /// Announcements
@@ -21,17 +21,78 @@ pub enum ChannelType {
     /// Docs channel
     Docs,
     /// Voice Channel
+    ///
+    /// The api has no voice specific fields (like a user limit or region) and no voice state,
+    /// so voice channels look just like any other channel.
+    /// There is also no way to list who is in a voice channel,
+    /// `GetVoiceParticipants` in `vived_api` only exists to fail with "not supported by API".
     Voice,
     /// List channel
     List,
     /// Schedule channel
     Scheduling,
-    /// Stream channel
+    /// Stream channel, same as [`ChannelType::Voice`] there is no stream specific data in the api
     Stream,
 }
 
+impl ChannelType {
+    /// Returns `true` for channels made up of written posts, chat, announcements and forums
+    #[must_use]
+    pub fn is_text_based(&self) -> bool {
+        matches!(self, &Self::Chat | &Self::Announcements | &Self::Forums)
+    }
+
+    /// Returns `true` for channels built around media rather than text, media, voice and stream
+    #[must_use]
+    pub fn is_media_based(&self) -> bool {
+        matches!(self, &Self::Media | &Self::Voice | &Self::Stream)
+    }
+
+    /// Returns `true` for channels people can talk in, voice and stream
+    #[must_use]
+    pub fn is_voice(&self) -> bool {
+        matches!(self, &Self::Voice | &Self::Stream)
+    }
+
+    /// Returns `true` for channels that can have threads, chat, voice and stream
+    ///
+    /// Threads are started from a chat message, forum topics and announcements have replies instead.
+    #[must_use]
+    pub fn is_threaded(&self) -> bool {
+        matches!(self, &Self::Chat | &Self::Voice | &Self::Stream)
+    }
+
+    /// Returns `true` for channels the message endpoints work in, chat, voice and stream
+    #[must_use]
+    pub fn supports_messages(&self) -> bool {
+        matches!(self, &Self::Chat | &Self::Voice | &Self::Stream)
+    }
+
+    /// How many messages one request returns when no limit is given
+    ///
+    /// Guilded uses the same limits in every channel with messages, channels without messages return 0.
+    #[must_use]
+    pub fn default_message_limit(&self) -> u8 {
+        if self.supports_messages() {
+            crate::limits::DEFAULT_MESSAGES_PER_REQUEST
+        } else {
+            0
+        }
+    }
+
+    /// The most messages one request can return, 0 for channels without messages
+    #[must_use]
+    pub fn max_message_limit(&self) -> u8 {
+        if self.supports_messages() {
+            crate::limits::MESSAGES_PER_REQUEST
+        } else {
+            0
+        }
+    }
+}
+
 /// Thread Archived Information 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct ThreadArchivedInfo {
     /// Archived at timestamp
@@ -41,7 +102,7 @@ pub struct ThreadArchivedInfo {
 }
 
 /// Channel information
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct Channel {
     /// The id of the channel
@@ -55,8 +116,12 @@ pub struct Channel {
     pub topic: Option<String>,
     /// Created at timestamp
     pub created_at: chrono::DateTime<chrono::Utc>,
-    /// Created by
+    /// Created by, for channels created by a webhook this is a static id
+    ///
+    /// Use [`Channel::creator`] to get the actual creator
     pub created_by: crate::UserId,
+    /// The webhook that created the channel, if it was created by one
+    pub created_by_webhook_id: Option<crate::WebhookId>,
     /// Updated at
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
     /// server id
@@ -64,13 +129,96 @@ pub struct Channel {
     /// parent id
     pub parent_id: Option<crate::ChannelId>,
     /// category id
-    pub category_id: Option<crate::ChannelId>,
+    pub category_id: Option<crate::CategoryId>,
     /// group id
     pub group_id: Option<crate::GroupId>,
     /// is public
     #[serde(default)]
     pub is_public: bool,
-    /// Archived information
-    #[serde(flatten)]
-    pub archived_info: Option<ThreadArchivedInfo>,
-}
\ No newline at end of file
+    /// When the channel was archived
+    ///
+    /// Kept separate from [`Channel::archived_by`] because guilded doesn't always send both,
+    /// use [`Channel::archived_info`] to get them together.
+    pub archived_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Who archived the channel
+    pub archived_by: Option<crate::UserId>,
+}
+
+impl Channel {
+    /// Who created this channel
+    ///
+    /// ```
+    /// use vived_models::Channel;
+    ///
+    /// let by_user: Channel = serde_json::from_str(r#"{
+    ///     "id": "c", "type": "chat", "name": "general", "serverId": "s",
+    ///     "createdAt": "2022-10-01T12:00:00.000Z", "createdBy": "u"
+    /// }"#).unwrap();
+    /// assert_eq!(by_user.creator().as_user().unwrap().0, "u");
+    ///
+    /// let by_webhook: Channel = serde_json::from_str(r#"{
+    ///     "id": "c", "type": "chat", "name": "general", "serverId": "s",
+    ///     "createdAt": "2022-10-01T12:00:00.000Z", "createdBy": "Ann6LewA", "createdByWebhookId": "w"
+    /// }"#).unwrap();
+    /// assert_eq!(by_webhook.creator().as_webhook().unwrap().0, "w");
+    /// ```
+    #[must_use]
+    pub fn creator(&self) -> crate::Creator {
+        crate::Creator::from_fields(&self.created_by, self.created_by_webhook_id.as_ref())
+    }
+
+    /// Is the channel archived
+    ///
+    /// A channel counts as archived as soon as it has an archive date, even if guilded left out who archived it.
+    /// ```
+    /// use vived_models::Channel;
+    ///
+    /// let channel = |extra: &str| -> Channel {
+    ///     serde_json::from_str(&format!(r#"{{
+    ///         "id": "c", "type": "chat", "name": "thread", "serverId": "s",
+    ///         "createdAt": "2022-10-01T12:00:00.000Z", "createdBy": "u"{extra}
+    ///     }}"#)).unwrap()
+    /// };
+    ///
+    /// let archived = channel(r#", "archivedAt": "2022-10-02T12:00:00.000Z", "archivedBy": "m""#);
+    /// assert!(archived.is_archived());
+    /// assert_eq!(archived.archived_info().unwrap().archived_by.0, "m");
+    ///
+    /// let open = channel("");
+    /// assert!(!open.is_archived());
+    /// assert!(open.archived_info().is_none());
+    ///
+    /// let no_archiver = channel(r#", "archivedAt": "2022-10-02T12:00:00.000Z""#);
+    /// assert!(no_archiver.is_archived());
+    /// assert!(no_archiver.archived_info().is_none());
+    ///
+    /// let no_date = channel(r#", "archivedBy": "m""#);
+    /// assert!(!no_date.is_archived());
+    /// assert_eq!(no_date.archived_by.unwrap().0, "m");
+    ///
+    /// // the channel inside a websocket event, with keys channels don't have
+    /// let event: serde_json::Value = serde_json::from_str(r#"{
+    ///     "serverId": "s",
+    ///     "channel": {
+    ///         "id": "c", "type": "chat", "name": "thread", "serverId": "s", "parentId": "p",
+    ///         "createdAt": "2022-10-01T12:00:00.000Z", "createdBy": "u",
+    ///         "archivedAt": "2022-10-02T12:00:00.000Z", "archivedBy": "m", "messageId": "x"
+    ///     }
+    /// }"#).unwrap();
+    /// let from_event: Channel = serde_json::from_value(event["channel"].clone()).unwrap();
+    /// assert!(from_event.is_archived());
+    /// ```
+    #[must_use]
+    pub fn is_archived(&self) -> bool {
+        self.archived_at.is_some()
+    }
+
+    /// When and by who the channel was archived, `None` unless guilded sent both
+    #[must_use]
+    pub fn archived_info(&self) -> Option<ThreadArchivedInfo> {
+        Some(ThreadArchivedInfo {
+            archived_at: self.archived_at?,
+            archived_by: self.archived_by.clone()?,
+        })
+    }
+}
@@ -4,7 +4,48 @@ use serde::{Deserialize, Serialize};
 
 // the guilded api uses colors using u32
 
+/// Basic css color names, as `0xRRGGBB`
+static NAMED_COLORS: phf::Map<&'static str, u32> = phf::phf_map! {
+    "black" => 0x00_00_00,
+    "silver" => 0xC0_C0_C0,
+    "gray" => 0x80_80_80,
+    "grey" => 0x80_80_80,
+    "white" => 0xFF_FF_FF,
+    "maroon" => 0x80_00_00,
+    "red" => 0xFF_00_00,
+    "purple" => 0x80_00_80,
+    "fuchsia" => 0xFF_00_FF,
+    "magenta" => 0xFF_00_FF,
+    "green" => 0x00_80_00,
+    "lime" => 0x00_FF_00,
+    "olive" => 0x80_80_00,
+    "yellow" => 0xFF_FF_00,
+    "navy" => 0x00_00_80,
+    "blue" => 0x00_00_FF,
+    "teal" => 0x00_80_80,
+    "aqua" => 0x00_FF_FF,
+    "cyan" => 0x00_FF_FF,
+    "orange" => 0xFF_A5_00,
+    "pink" => 0xFF_C0_CB,
+    "gold" => 0xFF_D7_00,
+    "brown" => 0xA5_2A_2A,
+    "indigo" => 0x4B_00_82,
+    "violet" => 0xEE_82_EE,
+};
+
 /// A color is a simple rgb tuple
+///
+/// Serializes as the decimal integer guilded uses for colors
+/// ```
+/// use vived_models::Color;
+///
+/// for value in [0, 1, 0xFF, 0x100, 0xFF00, 0x12_34_56, 0xAB_CD_EF, 0xFF_FF_FE, 0xFF_FF_FF] {
+///     assert_eq!(u32::from(Color::from(value)), value);
+/// }
+///
+/// assert_eq!(Color::try_from("#F5C400"), Ok(Color::GUILDED_GOLD));
+/// assert_eq!(Color::GUILDED_GOLD.to_string(), "#F5C400");
+/// ```
 #[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(from = "u32")]
 #[serde(into = "u32")]
@@ -45,17 +86,59 @@ impl From<Color> for u32 {
     }
 }
 
+impl TryFrom<&str> for Color {
+    type Error = String;
+
+    fn try_from(hex: &str) -> Result<Self, Self::Error> {
+        Self::from_hex(hex)
+    }
+}
+
+impl std::fmt::Display for Color {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "#{:02X}{:02X}{:02X}", self.0, self.1, self.2)
+    }
+}
+
 impl Color {
+    /// Pure red
+    pub const RED: Self = Self::from_rgb(255, 0, 0);
+    /// Pure green
+    pub const GREEN: Self = Self::from_rgb(0, 255, 0);
+    /// Pure blue
+    pub const BLUE: Self = Self::from_rgb(0, 0, 255);
+    /// The yellow used in the guilded logo
+    pub const GUILDED_GOLD: Self = Self::from_rgb(0xF5, 0xC4, 0x00);
+
+    /// Create a color from its red, green and blue components
+    #[must_use]
+    pub const fn from_rgb(r: u8, g: u8, b: u8) -> Self {
+        Self(r, g, b)
+    }
+
     /// Convert a hex string to a color
     /// Might or might not contain a leading `#`
     ///
+    /// ```
+    /// use vived_models::Color;
+    ///
+    /// assert_eq!(Color::from_hex("f5c400"), Ok(Color::GUILDED_GOLD));
+    /// assert_eq!(Color::from_hex("+F+F+F"), Err("Invalid hex color digit".to_owned()));
+    /// assert_eq!(Color::from_hex("#FFÉFF"), Err("Invalid hex color digit".to_owned()));
+    /// assert_eq!(Color::from_hex("#FFF"), Err("Invalid hex color length".to_owned()));
+    /// ```
+    ///
     /// # Errors
     /// If the string is not a valid hex color
     pub fn from_hex(hex: &str) -> Result<Self, String> {
         // Remove leading "#"
         let hex = hex.strip_prefix('#').unwrap_or(hex);
 
-        // check if length is valid
+        // `from_str_radix` would also accept a sign,
+        // and this makes sure we only slice on char boundaries below
+        if !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err("Invalid hex color digit".to_owned());
+        }
         if hex.len() != 6 {
             return Err("Invalid hex color length".to_owned());
         }
@@ -66,16 +149,97 @@ impl Color {
         let b = &hex[4..6];
 
         // Convert hex to u8
-        let r = r.parse().map_err(|_| "Invalid hex color")?;
-        let b = b.parse().map_err(|_| "Invalid hex color")?;
-        let g = g.parse().map_err(|_| "Invalid hex color")?;
+        let r = u8::from_str_radix(r, 16).map_err(|_| "Invalid hex color")?;
+        let g = u8::from_str_radix(g, 16).map_err(|_| "Invalid hex color")?;
+        let b = u8::from_str_radix(b, 16).map_err(|_| "Invalid hex color")?;
 
         Ok(Self(r, g, b))
     }
 
+    /// Look up a basic css color name, like `"red"` or `"gold"`, ignoring case
+    ///
+    /// Names use their css values, so `"green"` is `#008000` and the bright [`Color::GREEN`] is `"lime"`.
+    /// ```
+    /// use vived_models::Color;
+    ///
+    /// assert_eq!(Color::from_name("red"), Some(Color::RED));
+    /// assert_eq!(Color::from_name("Gold"), Some(Color::from(0xFF_D7_00)));
+    /// assert_eq!(Color::from_name(" NAVY "), Some(Color::from(0x00_00_80)));
+    /// assert_eq!(Color::from_name("lime"), Some(Color::GREEN));
+    /// assert_eq!(Color::from_name("green"), Some(Color::from(0x00_80_00)));
+    /// assert_eq!(Color::from_name("grey"), Color::from_name("gray"));
+    /// assert_eq!(Color::from_name("blurple"), None);
+    /// assert_eq!(Color::from_name("#FF0000"), None);
+    /// ```
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        NAMED_COLORS
+            .get(name.trim().to_ascii_lowercase().as_str())
+            .copied()
+            .map(Self::from)
+    }
+
     /// Convert this color to hex
     #[must_use]
     pub fn to_hex(&self) -> String {
-        format!("#{:02X}{:02X}{:02X}", self.0, self.1, self.2)
+        self.to_string()
+    }
+}
+
+/// Serialize a [`Color`] as a `"#RRGGBB"` string, for human readable config files
+///
+/// Deserializing accepts both hex strings and the integers guilded uses.
+/// ```
+/// use serde::{Deserialize, Serialize};
+/// use vived_models::Color;
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct EmbedConfig {
+///     #[serde(with = "vived_models::color_hex")]
+///     color: Color,
+/// }
+///
+/// let config: EmbedConfig = serde_json::from_str(r##"{"color": "#F5C400"}"##).unwrap();
+/// assert_eq!(config.color, Color::GUILDED_GOLD);
+///
+/// let config: EmbedConfig = serde_json::from_str(r#"{"color": 16106496}"#).unwrap();
+/// assert_eq!(config.color, Color::GUILDED_GOLD);
+///
+/// assert_eq!(serde_json::to_string(&config).unwrap(), r##"{"color":"#F5C400"}"##);
+/// ```
+pub mod color_hex {
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    use super::Color;
+
+    /// The formats a color can be deserialized from
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum RawColor {
+        /// Integer, like in api responses
+        Int(u32),
+        /// Hex string, like in config files
+        Hex(String),
+    }
+
+    /// Serialize a color as a hex string
+    ///
+    /// # Errors
+    /// If the serializer fails
+    // serde requires the value to be passed by reference
+    #[allow(clippy::trivially_copy_pass_by_ref)]
+    pub fn serialize<S: Serializer>(color: &Color, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&color.to_hex())
+    }
+
+    /// Deserialize a color from either a hex string or an integer
+    ///
+    /// # Errors
+    /// If the value is neither, or the hex string is invalid
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Color, D::Error> {
+        match RawColor::deserialize(deserializer)? {
+            RawColor::Int(value) => Ok(Color::from(value)),
+            RawColor::Hex(hex) => Color::from_hex(&hex).map_err(serde::de::Error::custom),
+        }
     }
 }
@@ -0,0 +1,129 @@
+//! Who created a resource
+//!
+//! Resources created by a webhook have `createdBy` set to a static id,
+//! with the actual creator in `createdByWebhookId`. [`Creator`] combines the two.
+
+use serde::{Deserialize, Serialize};
+
+/// The static user id guilded uses as `createdBy` for resources created by a webhook
+const WEBHOOK_USER_ID: &str = "Ann6LewA";
+
+/// Fields used by the api to represent who created a resource
+/// They use 2 redundant fields
+///
+/// There is a more convenient structure the [`Creator`] enum
+/// You can translate this into that using `.into()` which gives you a nicer interface
+///
+/// I did try to make this deserialize into that automatically,
+/// but because of limitations on serde flatten we cant
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct CreatorRawFields {
+    /// What user created this resource, for a webhook this is the static id `Ann6LewA`
+    created_by: crate::UserId,
+    /// Potential id of webhook that created the resource, if present ignore `created_by`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    created_by_webhook_id: Option<crate::WebhookId>,
+}
+
+/// Who created a resource?
+#[derive(Deserialize, Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(from = "CreatorRawFields", into = "CreatorRawFields")]
+pub enum Creator {
+    /// Created by a webhook
+    Webhook(crate::WebhookId),
+    /// Created by a normal user
+    User(crate::UserId),
+}
+
+impl CreatorRawFields {
+    /// Convert this to the `Creator` enum
+    #[must_use]
+    pub fn into_enum(self) -> Creator {
+        self.into()
+    }
+
+    /// Get the `Creator` without consuming the fields
+    #[must_use]
+    pub fn creator(&self) -> Creator {
+        Creator::from_fields(&self.created_by, self.created_by_webhook_id.as_ref())
+    }
+}
+
+impl From<CreatorRawFields> for Creator {
+    fn from(raw: CreatorRawFields) -> Self {
+        if let Some(webhook_id) = raw.created_by_webhook_id {
+            Self::Webhook(webhook_id)
+        } else {
+            Self::User(raw.created_by)
+        }
+    }
+}
+
+impl From<Creator> for CreatorRawFields {
+    fn from(creator: Creator) -> Self {
+        match creator {
+            Creator::Webhook(webhook_id) => Self {
+                created_by: WEBHOOK_USER_ID.into(),
+                created_by_webhook_id: Some(webhook_id),
+            },
+            Creator::User(user_id) => Self {
+                created_by: user_id,
+                created_by_webhook_id: None,
+            },
+        }
+    }
+}
+
+impl Creator {
+    /// Build a `Creator` from the two api fields, for models that store them separately
+    #[must_use]
+    pub fn from_fields(created_by: &crate::UserId, webhook_id: Option<&crate::WebhookId>) -> Self {
+        webhook_id.map_or_else(
+            || Self::User(created_by.clone()),
+            |webhook_id| Self::Webhook(webhook_id.clone()),
+        )
+    }
+
+    /// Return the user id if this was created by a user
+    #[must_use]
+    pub fn as_user(&self) -> Option<&crate::UserId> {
+        if let Self::User(ref v) = *self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Return the webhook id if this was created by a webhook
+    #[must_use]
+    pub fn as_webhook(&self) -> Option<&crate::WebhookId> {
+        if let Self::Webhook(ref v) = *self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+}
+
+/// Formats as `user:{id}` or `webhook:{id}`
+/// ```
+/// use vived_models::Creator;
+///
+/// assert_eq!(Creator::User("EdVMVKR4".into()).to_string(), "user:EdVMVKR4");
+/// ```
+impl std::fmt::Display for Creator {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::User(ref id) => write!(f, "user:{id}"),
+            Self::Webhook(ref id) => write!(f, "webhook:{id}"),
+        }
+    }
+}
+
+/// Formats the same as [`Creator`]
+impl std::fmt::Display for CreatorRawFields {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        std::fmt::Display::fmt(&self.creator(), f)
+    }
+}
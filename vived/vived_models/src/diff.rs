@@ -0,0 +1,139 @@
+//! What changed between two versions of a message
+//!
+//! Guilded only sends the new version of an edited message,
+//! keep the old version around (for example with a message cache) to find out what was edited.
+
+use crate::message::Mentions;
+use crate::{Embed, Message};
+
+/// A value before and after a change
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change<T> {
+    /// The old value
+    pub before: T,
+    /// The new value
+    pub after: T,
+}
+
+impl<T: PartialEq> Change<T> {
+    /// `Some` if `before` and `after` differ
+    fn between(before: T, after: T) -> Option<Self> {
+        (before != after).then_some(Self { before, after })
+    }
+}
+
+/// What changed between two versions of a message, see [`MessageDiff::between`]
+///
+/// Every field is `None` (or empty) if that part didn't change.
+///
+/// ```
+/// use vived_models::{Message, MessageDiff};
+///
+/// fn message(extra: &str) -> Message {
+///     serde_json::from_str(&format!(r#"{{
+///         "id": "m", "type": "default", "serverId": "s", "channelId": "c",
+///         "createdAt": "2022-10-01T12:00:00Z", "createdBy": "u"{extra}
+///     }}"#)).unwrap()
+/// }
+///
+/// // (old, new, content edit, trivial, changed embed indexes)
+/// let cases = [
+///     (r#", "content": "hi""#, r#", "content": "hi""#, false, true, vec![]),
+///     (r#", "content": "hi""#, r#", "content": "hi", "updatedAt": "2022-10-01T12:05:00Z""#, false, true, vec![]),
+///     (r#", "content": "hi""#, r#", "content": "hello""#, true, false, vec![]),
+///     (r#", "content": "hi""#, r#""#, true, false, vec![]),
+///     (r#", "isPinned": false"#, r#", "isPinned": true"#, false, false, vec![]),
+///     (r#", "embeds": [{"title": "a"}, {"title": "b"}]"#, r#", "embeds": [{"title": "a"}, {"title": "c"}]"#, false, false, vec![1]),
+///     (r#", "embeds": [{"title": "a"}]"#, r#", "embeds": [{"title": "b"}, {"title": "c"}]"#, false, false, vec![0]),
+///     (
+///         r#", "mentions": {"users": [{"id": "a"}, {"id": "b"}]}"#,
+///         r#", "mentions": {"users": [{"id": "b"}, {"id": "a"}]}"#,
+///         false, true, vec![],
+///     ),
+///     (r#", "mentions": {"everyone": false}"#, r#", "mentions": {"everyone": true}"#, false, false, vec![]),
+/// ];
+///
+/// for (old, new, content_edit, trivial, changed_embeds) in cases {
+///     let diff = MessageDiff::between(&message(old), &message(new));
+///     assert_eq!(diff.is_content_edit(), content_edit, "{old} -> {new}");
+///     assert_eq!(diff.is_trivial(), trivial, "{old} -> {new}");
+///     assert_eq!(diff.changed_embeds, changed_embeds, "{old} -> {new}");
+/// }
+///
+/// let diff = MessageDiff::between(&message(r#", "content": "hi""#), &message(r#", "content": "hello""#));
+/// let content = diff.content.unwrap();
+/// assert_eq!(content.before.as_deref(), Some("hi"));
+/// assert_eq!(content.after.as_deref(), Some("hello"));
+///
+/// let diff = MessageDiff::between(&message(r#", "embeds": [{}]"#), &message(r#", "embeds": [{}, {}]"#));
+/// assert_eq!(diff.embed_count.unwrap().after, 2);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct MessageDiff {
+    /// The content, if it changed
+    pub content: Option<Change<Option<String>>>,
+    /// The embeds, if any of them changed or some were added or removed
+    pub embeds: Option<Change<Vec<Embed>>>,
+    /// The number of embeds, if it changed
+    pub embed_count: Option<Change<usize>>,
+    /// Indexes of embeds that exist in both versions but differ
+    pub changed_embeds: Vec<usize>,
+    /// Whether the message is pinned, if it changed
+    pub pinned: Option<Change<bool>>,
+    /// The mentions, if different users, channels or roles are mentioned
+    ///
+    /// The order of the mentions is ignored.
+    pub mentions: Option<Change<Mentions>>,
+}
+
+impl MessageDiff {
+    /// Find what changed from `old` to `new`
+    ///
+    /// The messages are expected to be two versions of the same message, their ids are not checked.
+    /// Timestamps like `updated_at` are not part of the diff.
+    #[must_use]
+    pub fn between(old: &Message, new: &Message) -> Self {
+        let changed_embeds = old
+            .embeds
+            .iter()
+            .zip(&new.embeds)
+            .enumerate()
+            .filter(|&(_, (before, after))| before != after)
+            .map(|(index, _)| index)
+            .collect();
+
+        Self {
+            content: Change::between(old.content.clone(), new.content.clone()),
+            embeds: Change::between(old.embeds.clone(), new.embeds.clone()),
+            embed_count: Change::between(old.embeds.len(), new.embeds.len()),
+            changed_embeds,
+            pinned: Change::between(old.is_pinned, new.is_pinned),
+            mentions: (!old.mentions.same_targets(&new.mentions)).then(|| Change {
+                before: old.mentions.clone(),
+                after: new.mentions.clone(),
+            }),
+        }
+    }
+
+    /// Did the content change
+    #[must_use]
+    pub fn is_content_edit(&self) -> bool {
+        self.content.is_some()
+    }
+
+    /// Did the embeds change
+    #[must_use]
+    pub fn is_embed_edit(&self) -> bool {
+        self.embeds.is_some()
+    }
+
+    /// Did nothing people can see change, for example if only `updated_at` changed
+    #[must_use]
+    pub fn is_trivial(&self) -> bool {
+        self.content.is_none()
+            && self.embeds.is_none()
+            && self.pinned.is_none()
+            && self.mentions.is_none()
+    }
+}
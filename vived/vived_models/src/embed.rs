@@ -1,14 +1,27 @@
 //! Embeds are nice features that allow you to send much nicer formatted text
 
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Deserialize a `null` the same way as a missing value
+///
+/// Embeds sent by other bots and bridges sometimes use `null` instead of leaving the key out,
+/// which `#[serde(default)]` alone doesn't handle.
+fn null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
 
 /// Footer of an embed
-#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
 pub struct EmbedFooter {
     /// Icon of the footer
     #[serde(default)]
     pub icon_url: Option<String>,
     /// Text of the footer
+    #[serde(default, deserialize_with = "null_as_default")]
     pub text: String,
 }
 
@@ -39,33 +52,70 @@ impl From<&str> for EmbedFooter {
     }
 }
 
-/// Embed Thumbnail, this is just a url
-#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+/// Embed image or thumbnail, a url with optional dimensions
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
 pub struct EmbedImage {
     /// Url of the thumbnail
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(default)]
     pub url: Option<String>,
+    /// Width of the image in pixels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub width: Option<u32>,
+    /// Height of the image in pixels
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default)]
+    pub height: Option<u32>,
+}
+
+impl EmbedImage {
+    /// Create an image with known dimensions
+    ///
+    /// ```
+    /// use vived_models::EmbedImage;
+    ///
+    /// let image = EmbedImage::with_dimensions("https://example.com/cat.png", 640, 480);
+    /// assert_eq!(
+    ///     serde_json::to_string(&image).unwrap(),
+    ///     r#"{"url":"https://example.com/cat.png","width":640,"height":480}"#
+    /// );
+    ///
+    /// let url_only: EmbedImage = serde_json::from_str(r#"{"url": "https://example.com/cat.png"}"#).unwrap();
+    /// assert_eq!(url_only.width, None);
+    /// ```
+    #[must_use]
+    pub fn with_dimensions(url: impl Into<String>, width: u32, height: u32) -> Self {
+        Self {
+            url: Some(url.into()),
+            width: Some(width),
+            height: Some(height),
+        }
+    }
 }
 
 // lets make it convenient to construct an embed image and grab the string
 impl From<String> for EmbedImage {
     fn from(url: String) -> Self {
-        Self { url: Some(url) }
+        Self {
+            url: Some(url),
+            ..Self::default()
+        }
     }
 }
 
 impl From<&str> for EmbedImage {
     fn from(v: &str) -> Self {
-        Self { url: Some(v.to_owned()) }
+        Self::from(v.to_owned())
     }
 }
 
 
 /// Embed Author
-#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
 pub struct EmbedAuthor {
     /// Name of the author
+    #[serde(default, deserialize_with = "null_as_default")]
     pub name: String,
     /// Url of the author
     #[serde(default)]
@@ -112,14 +162,14 @@ impl From<&str> for EmbedAuthor {
 }
 
 /// Embed field
-#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
 pub struct EmbedField {
     /// Name of the field
     pub name: String,
     /// Value of the field
     pub value: String,
     /// Whether or not this field should be inline
-    #[serde(default)]
+    #[serde(default, deserialize_with = "null_as_default")]
     pub inline: bool,
 }
 
@@ -143,35 +193,77 @@ impl EmbedField {
     }
 }
 
+/// A `(name, value)` tuple is a field that isn't inline
+///
+/// ```
+/// use vived_models::EmbedField;
+///
+/// let field = EmbedField::from(("name", String::from("value")));
+/// assert_eq!((field.name.as_str(), field.value.as_str(), field.inline), ("name", "value", false));
+/// ```
+impl<N, V> From<(N, V)> for EmbedField
+where
+    N: Into<String>,
+    V: Into<String>,
+{
+    fn from((name, value): (N, V)) -> Self {
+        Self::new(name, value)
+    }
+}
+
+/// A `(name, value, inline)` tuple
+///
+/// ```
+/// use vived_models::EmbedField;
+///
+/// let field = EmbedField::from(("name", "value", true));
+/// assert_eq!((field.name.as_str(), field.value.as_str(), field.inline), ("name", "value", true));
+/// ```
+impl<N, V> From<(N, V, bool)> for EmbedField
+where
+    N: Into<String>,
+    V: Into<String>,
+{
+    fn from((name, value, inline): (N, V, bool)) -> Self {
+        Self::new(name, value).inline(inline)
+    }
+}
+
 /// A guilded embed
 ///
 /// # Example of all embed fields
 /// ```rust
-/// vived::Embed::new()
-///    .title("Hello world")
-///    .description("This is a test message")
-///    .color(0x00ff00)
-///    .url("https://www.guilded.gg")
-///    .timestamp(chrono::Utc::now())
-///    .footer(vived::EmbedFooter::from("This is a footer").icon_url(
-///        "https://img.guildedcdn.com/asset/DefaultUserAvatars/profile_1.png",
-///    ))
-///    .thumbnail("https://img.guildedcdn.com/asset/DefaultUserAvatars/profile_2.png")
-///    .image("https://img.guildedcdn.com/asset/DefaultUserAvatars/profile_3.png")
-///    .author(
-///        vived::EmbedAuthor::from("This is an author")
-///            .url("https://www.guilded.gg")
-///            .icon_url(
-///                "https://img.guildedcdn.com/asset/DefaultUserAvatars/profile_4.png",
-///            ),
-///    )
-///    // lets create two of each
-///    .field(vived::EmbedField::new("Field 1", "This is field 1"))
-///    .field(vived::EmbedField::new("Field 2", "This is field 2"))
-///    .field(vived::EmbedField::new("Field 3", "This is field 3").inline(true))
-///    .field(vived::EmbedField::new("Field 4", "This is field 4").inline(true)),
+/// use vived_models::{Embed, EmbedAuthor, EmbedField, EmbedFooter};
+///
+/// let embed = Embed::new()
+///     .title("Hello world")
+///     .description("This is a test message")
+///     .color(0x00ff00)
+///     .url("https://www.guilded.gg")
+///     .timestamp(chrono::Utc::now())
+///     .footer(EmbedFooter::from("This is a footer").icon_url(
+///         "https://img.guildedcdn.com/asset/DefaultUserAvatars/profile_1.png",
+///     ))
+///     .thumbnail("https://img.guildedcdn.com/asset/DefaultUserAvatars/profile_2.png")
+///     .image("https://img.guildedcdn.com/asset/DefaultUserAvatars/profile_3.png")
+///     .author(
+///         EmbedAuthor::from("This is an author")
+///             .url("https://www.guilded.gg")
+///             .icon_url("https://img.guildedcdn.com/asset/DefaultUserAvatars/profile_4.png"),
+///     )
+///     // fields can be given as an `EmbedField` or as a tuple
+///     .field(EmbedField::new("Field 1", "This is field 1"))
+///     .field(("Field 2", "This is field 2"))
+///     .field(("Field 3", "This is field 3", true))
+///     .fields([("Field 4", "This is field 4", true), ("Field 5", "This is field 5", false)]);
+///
+/// assert_eq!(embed.fields.len(), 5);
+/// assert!(embed.fields[2].inline);
+/// assert_eq!(embed.author.unwrap().name, "This is an author");
 /// ```
-#[derive(Deserialize, Serialize, Debug, Clone, Default)]
+///
+/// Two embeds are equal if every part is, fields are compared in order since that is the order they are shown in.
+#[derive(Deserialize, Serialize, Debug, Clone, Default, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 #[serde(default)]
 pub struct Embed {
@@ -195,20 +287,22 @@ pub struct Embed {
     pub timestamp: Option<chrono::DateTime<chrono::Utc>>,
 
     /// Thumbnail of the embed
-    #[serde(default)]
+    #[serde(default, deserialize_with = "null_as_default")]
     pub thumbnail: EmbedImage,
     /// Image of the embed
-    #[serde(default)]
+    #[serde(default, deserialize_with = "null_as_default")]
     pub image: EmbedImage,
 
     /// Embed Author
     #[serde(skip_serializing_if = "Option::is_none")]
     pub author: Option<EmbedAuthor>,
     /// Fields of the embed
-    #[serde(default)]
+    #[serde(default, deserialize_with = "null_as_default")]
     pub fields: Vec<EmbedField>,
 }
 
+/// [`Embed`] is its own builder, this alias is for code that expects a separate builder type
+pub type EmbedBuilder = Embed;
 
 // Implement builder pattern for embed
 impl Embed {
@@ -218,6 +312,25 @@ impl Embed {
         Self::default()
     }
 
+    /// Create a new embed, same as [`Embed::new`]
+    ///
+    /// ```
+    /// use vived_models::Embed;
+    ///
+    /// let embed: Embed = Embed::builder().title("Hello").build();
+    /// assert_eq!(embed.title.as_deref(), Some("Hello"));
+    /// ```
+    #[must_use]
+    pub fn builder() -> EmbedBuilder {
+        Self::new()
+    }
+
+    /// Finish building the embed, this does nothing since an embed is its own builder
+    #[must_use]
+    pub fn build(self) -> Self {
+        self
+    }
+
     /// Set the title of the embed
     #[must_use]
     pub fn title(mut self, title: impl Into<String>) -> Self {
@@ -276,15 +389,22 @@ impl Embed {
 
     /// Set the author of the embed
     #[must_use]
-    pub fn author(mut self, author: EmbedAuthor) -> Self {
-        self.author = Some(author);
+    pub fn author(mut self, author: impl Into<EmbedAuthor>) -> Self {
+        self.author = Some(author.into());
         self
     }
 
     /// Add a field to the embed
     #[must_use]
-    pub fn field(mut self, field: EmbedField) -> Self {
-        self.fields.push(field);
+    pub fn field(mut self, field: impl Into<EmbedField>) -> Self {
+        self.fields.push(field.into());
+        self
+    }
+
+    /// Add several fields to the embed
+    #[must_use]
+    pub fn fields(mut self, fields: impl IntoIterator<Item = impl Into<EmbedField>>) -> Self {
+        self.fields.extend(fields.into_iter().map(Into::into));
         self
     }
 
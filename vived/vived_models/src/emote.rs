@@ -0,0 +1,37 @@
+//! Custom server emotes
+//! <https://www.guilded.gg/docs/api/emotes/Emote>
+
+use serde::{Deserialize, Serialize};
+
+/// A custom emote of a server
+/// ```
+/// use vived_models::Emote;
+///
+/// let emote: Emote = serde_json::from_str(r#"{
+///     "id": 90000000, "name": "partyblob", "url": "https://img.guildedcdn.com/asset/Emojis/partyblob.webp",
+///     "serverId": "s", "createdBy": "u", "createdAt": "2022-10-01T12:00:00Z"
+/// }"#).unwrap();
+/// assert_eq!(emote.id.0, 90_000_000);
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Emote {
+    /// The id of the emote
+    pub id: crate::EmoteId,
+    /// The name of the emote, without colons
+    pub name: String,
+    /// Url of the emote image
+    pub url: String,
+    /// Who uploaded the emote
+    pub created_by: crate::UserId,
+    /// The server the emote belongs to
+    pub server_id: crate::ServerId,
+    /// Created at timestamp
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<Emote> for crate::EmoteId {
+    fn from(emote: Emote) -> Self {
+        emote.id
+    }
+}
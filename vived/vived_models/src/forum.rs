@@ -0,0 +1,129 @@
+//! Topics in forum channels
+//! <https://www.guilded.gg/docs/api/forums/ForumTopic>
+
+use serde::{Deserialize, Serialize};
+
+/// A topic in a forum channel
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ForumTopic {
+    /// The id of the topic
+    pub id: crate::ForumTopicId,
+    /// The server the topic is in
+    pub server_id: crate::ServerId,
+    /// The forum channel the topic is in
+    pub channel_id: crate::ChannelId,
+    /// The title of the topic
+    pub title: String,
+    /// The content of the topic
+    pub content: Option<String>,
+    /// Created at timestamp
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Created by, for topics created by a webhook this is a static id
+    ///
+    /// Use [`ForumTopic::creator`] to get the actual creator
+    pub created_by: crate::UserId,
+    /// The webhook that created the topic, if it was created by one
+    pub created_by_webhook_id: Option<crate::WebhookId>,
+    /// Updated at
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// When the topic was last bumped by a reply
+    pub bumped_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Is the topic pinned to the top of the forum
+    #[serde(default)]
+    pub is_pinned: bool,
+    /// Is the topic locked, so no one can reply
+    #[serde(default)]
+    pub is_locked: bool,
+}
+
+impl ForumTopic {
+    /// Who created this topic
+    #[must_use]
+    pub fn creator(&self) -> crate::Creator {
+        crate::Creator::from_fields(&self.created_by, self.created_by_webhook_id.as_ref())
+    }
+
+    /// Is the topic pinned
+    #[must_use]
+    pub fn is_pinned(&self) -> bool {
+        self.is_pinned
+    }
+
+    /// Is the topic locked
+    #[must_use]
+    pub fn is_locked(&self) -> bool {
+        self.is_locked
+    }
+
+    /// When the topic was last bumped by a reply, `None` if it never was
+    #[must_use]
+    pub fn bump_at(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        self.bumped_at
+    }
+
+    /// When the topic was last bumped, or created if it never was
+    ///
+    /// ```
+    /// use vived_models::ForumTopic;
+    ///
+    /// let topic = |extra: &str| -> ForumTopic {
+    ///     serde_json::from_str(&format!(r#"{{
+    ///         "id": 1, "serverId": "s", "channelId": "c", "title": "Hi",
+    ///         "createdAt": "2022-10-01T12:00:00Z", "createdBy": "u"{extra}
+    ///     }}"#)).unwrap()
+    /// };
+    ///
+    /// let fresh = topic("");
+    /// assert_eq!(fresh.last_activity_at(), fresh.created_at);
+    ///
+    /// let bumped = topic(r#", "bumpedAt": "2022-10-03T12:00:00Z""#);
+    /// assert_eq!(bumped.last_activity_at(), bumped.bump_at().unwrap());
+    /// assert!(bumped.age() > bumped.time_since_activity());
+    /// ```
+    #[must_use]
+    pub fn last_activity_at(&self) -> chrono::DateTime<chrono::Utc> {
+        self.bumped_at.unwrap_or(self.created_at)
+    }
+
+    /// How long ago this topic was created
+    #[must_use]
+    pub fn age(&self) -> chrono::Duration {
+        chrono::Utc::now() - self.created_at
+    }
+
+    /// How long ago this topic was last bumped, or created if it never was
+    #[must_use]
+    pub fn time_since_activity(&self) -> chrono::Duration {
+        chrono::Utc::now() - self.last_activity_at()
+    }
+
+    /// Link to the topic on guilded
+    ///
+    /// Neither the server url nor the group of the channel are part of the topic,
+    /// they can be found on [`crate::Server`] and [`crate::Channel`].
+    #[must_use]
+    pub fn url(&self, server_url: &str, group: &crate::GroupId) -> String {
+        format!(
+            "https://www.guilded.gg/{server_url}/groups/{group}/channels/{channel}/forums/{topic}",
+            channel = self.channel_id,
+            topic = self.id
+        )
+    }
+
+    /// Url of the topic in the guilded api
+    #[must_use]
+    pub fn api_url(&self) -> String {
+        format!(
+            "https://www.guilded.gg/api/v1/channels/{channel}/topics/{topic}",
+            channel = self.channel_id,
+            topic = self.id
+        )
+    }
+}
+
+impl From<ForumTopic> for crate::ForumTopicId {
+    fn from(topic: ForumTopic) -> Self {
+        topic.id
+    }
+}
@@ -0,0 +1,65 @@
+//! Server groups and channel categories
+//! <https://www.guilded.gg/docs/api/groups/Group>
+
+use serde::{Deserialize, Serialize};
+
+/// A group in a server
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerGroup {
+    /// The id of the group
+    pub id: crate::GroupId,
+    /// The server the group is in
+    pub server_id: crate::ServerId,
+    /// The name of the group
+    pub name: String,
+    /// The description of the group
+    pub description: Option<String>,
+    /// The avatar of the group
+    /// A media-uri string
+    pub avatar: Option<String>,
+    /// Is this the home group of the server
+    #[serde(default)]
+    pub is_home: bool,
+    /// Emote used as the icon of the group
+    pub emote_id: Option<crate::EmoteId>,
+    /// is public
+    #[serde(default)]
+    pub is_public: bool,
+    /// Created at timestamp
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Created by
+    pub created_by: crate::UserId,
+    /// Updated at
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<ServerGroup> for crate::GroupId {
+    fn from(group: ServerGroup) -> Self {
+        group.id
+    }
+}
+
+/// A channel category in a group
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerCategory {
+    /// The id of the category
+    pub id: crate::CategoryId,
+    /// The server the category is in
+    pub server_id: crate::ServerId,
+    /// The group the category is in
+    pub group_id: crate::GroupId,
+    /// The name of the category
+    pub name: String,
+    /// Created at timestamp
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Updated at
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<ServerCategory> for crate::CategoryId {
+    fn from(category: ServerCategory) -> Self {
+        category.id
+    }
+}
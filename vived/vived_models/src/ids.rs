@@ -1,4 +1,32 @@
 //! Ids are used to identify specific resources on Guilded
+//!
+//! Guilded uses both string and numeric ids, and they are not interchangeable.
+//! A numeric id will refuse to deserialize from a json string and vice versa,
+//! so if guilded ever changes the type of an id we get an error instead of silently mangled data.
+//!
+//! ```
+//! use vived_models::{ChannelId, RoleId};
+//!
+//! assert!(serde_json::from_str::<RoleId>("123").is_ok());
+//! assert!(serde_json::from_str::<RoleId>(r#""123""#).is_err());
+//!
+//! assert!(serde_json::from_str::<ChannelId>(r#""123""#).is_ok());
+//! assert!(serde_json::from_str::<ChannelId>("123").is_err());
+//!
+//! assert_eq!(ChannelId::new("abc-123"), ChannelId::from("abc-123"));
+//! ```
+//!
+//! Numeric ids convert from `u64` and `usize`, checking that the value fits:
+//! ```
+//! use vived_models::{DocId, RoleId};
+//!
+//! assert_eq!(RoleId::from(7_usize), RoleId(7));
+//! assert_eq!(RoleId::try_from(7_u64), Ok(RoleId(7)));
+//!
+//! assert_eq!(DocId::try_from(7_usize), Ok(DocId(7)));
+//! assert_eq!(DocId::try_from(7_u64), Ok(DocId(7)));
+//! assert!(DocId::try_from(u64::from(u32::MAX) + 1).is_err());
+//! ```
 
 
 // We don't really need docs for each specific id
@@ -10,10 +38,18 @@ use serde::{Deserialize, Serialize};
 /// They all consist of strings 
 macro_rules! define_string_id {
     (pub struct  $id:ident(String)) => {
-            #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq)]
+            #[derive(Debug, Serialize, Deserialize, Clone, Eq, PartialEq, Hash)]
             #[serde(transparent)]
             pub struct $id(pub String);
 
+            impl $id {
+                /// Create a new id
+                #[must_use]
+                pub fn new(id: impl Into<String>) -> Self {
+                    Self(id.into())
+                }
+            }
+
             impl ::std::fmt::Display for $id {
                 fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
                     self.0.fmt(f)
@@ -41,22 +77,49 @@ define_string_id!(pub struct UserId(String));
 define_string_id!(pub struct WebhookId(String));
 define_string_id!(pub struct GroupId(String));
 
-// For some reason RoleId uses a `usize` instead of a String
-// So we need to special case it
+define_string_id!(pub struct ListItemId(String));
 
-/// A role id
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, Eq, PartialEq)]
-#[serde(transparent)]
-pub struct RoleId(pub usize);
+/// Define the numeric ids used in the guilded api
+///
+/// Unlike string ids these are `Copy` and ordered.
+/// They convert from their own integer type, and with `TryFrom` from the wider ones listed in `try_from(..)`.
+macro_rules! define_numeric_id {
+    (pub struct $id:ident($inner:ty) $(, try_from($($wider:ty),+))?) => {
+            #[derive(Debug, Serialize, Deserialize, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+            #[serde(transparent)]
+            pub struct $id(pub $inner);
 
-impl ::std::fmt::Display for RoleId {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        self.0.fmt(f)
-    }
+            impl ::std::fmt::Display for $id {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    self.0.fmt(f)
+                }
+            }
+
+            impl ::std::convert::From<$inner> for $id {
+                fn from(id: $inner) -> Self {
+                    Self(id)
+                }
+            }
+
+            $($(
+                impl ::std::convert::TryFrom<$wider> for $id {
+                    type Error = ::std::num::TryFromIntError;
+
+                    fn try_from(id: $wider) -> Result<Self, Self::Error> {
+                        <$inner>::try_from(id).map(Self)
+                    }
+                }
+            )+)?
+    };
 }
 
-impl ::std::convert::From<usize> for RoleId {
-    fn from(id: usize) -> Self {
-        Self(id)
-    }
-}
\ No newline at end of file
+// For some reason some ids use numbers instead of strings
+// We keep RoleId as a `usize` for backwards compatibility
+define_numeric_id!(pub struct RoleId(usize), try_from(u64));
+define_numeric_id!(pub struct CalendarEventId(u32), try_from(u64, usize));
+define_numeric_id!(pub struct DocId(u32), try_from(u64, usize));
+define_numeric_id!(pub struct ForumTopicId(u32), try_from(u64, usize));
+define_numeric_id!(pub struct EmoteId(u32), try_from(u64, usize));
+define_numeric_id!(pub struct CategoryId(u32), try_from(u64, usize));
+define_numeric_id!(pub struct SchedulingAvailabilityId(u32), try_from(u64, usize));
+define_numeric_id!(pub struct MediaId(u32), try_from(u64, usize));
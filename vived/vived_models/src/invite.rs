@@ -0,0 +1,45 @@
+//! Server invites
+
+use serde::{Deserialize, Serialize};
+
+/// An invite link to a server
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Invite {
+    /// The id of the invite, this is the code at the end of the invite link
+    pub id: String,
+    /// The server the invite is for
+    pub server_id: crate::ServerId,
+    /// The channel people land in after joining, if the invite was made for one
+    pub channel_id: Option<crate::ChannelId>,
+    /// Who created the invite
+    pub created_by: crate::UserId,
+    /// Created at timestamp
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// When the invite stops working, `None` if it never expires
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// How many times the invite was used
+    #[serde(default)]
+    pub use_count: u32,
+}
+
+impl Invite {
+    /// Has the invite expired at `now`
+    ///
+    /// ```
+    /// use vived_models::Invite;
+    ///
+    /// let invite: Invite = serde_json::from_str(r#"{
+    ///     "id": "abc123", "serverId": "s", "createdBy": "u",
+    ///     "createdAt": "2022-10-01T12:00:00Z", "expiresAt": "2022-10-02T12:00:00Z", "useCount": 3
+    /// }"#).unwrap();
+    ///
+    /// assert!(!invite.is_expired("2022-10-02T11:00:00Z".parse().unwrap()));
+    /// assert!(invite.is_expired("2022-10-02T12:00:00Z".parse().unwrap()));
+    /// assert_eq!(invite.use_count, 3);
+    /// ```
+    #[must_use]
+    pub fn is_expired(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
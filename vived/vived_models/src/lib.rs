@@ -44,12 +44,48 @@ pub mod message;
 pub mod ids;
 pub mod embed;
 pub mod color;
+pub mod markdown;
+pub mod limits;
 mod channel;
 mod server;
+mod group;
+mod tree;
+mod member;
+mod scheduling;
+mod media;
+mod role;
+mod creator;
+mod forum;
+mod list;
+mod calendar;
+mod invite;
+mod subscription;
+mod emote;
+mod diff;
+mod webhook;
+mod permission;
+mod paginator;
 
 pub use message::Message;
-pub use color::Color;
+pub use color::{color_hex, Color};
 pub use ids::*;
 pub use embed::*;
 pub use server::*;
-pub use channel::*;
\ No newline at end of file
+pub use channel::*;
+pub use group::*;
+pub use tree::*;
+pub use member::*;
+pub use scheduling::*;
+pub use media::*;
+pub use role::*;
+pub use creator::*;
+pub use forum::*;
+pub use list::*;
+pub use calendar::*;
+pub use invite::*;
+pub use subscription::*;
+pub use emote::*;
+pub use diff::*;
+pub use webhook::*;
+pub use permission::*;
+pub use paginator::*;
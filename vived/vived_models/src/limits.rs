@@ -0,0 +1,167 @@
+//! Limits guilded puts on requests
+//!
+//! Text limits count characters (unicode scalar values, what [`str::chars`] gives), not bytes.
+//! This means an emoji made of multiple code points, like a flag or a skin toned emoji, counts as more than one character.
+//! ```
+//! use vived_models::limits::{char_count, fits_message_content, MESSAGE_CONTENT};
+//!
+//! // 4000 emoji are 16000 bytes, but still fit
+//! let emoji = "😀".repeat(MESSAGE_CONTENT);
+//! assert_eq!(emoji.len(), 4 * MESSAGE_CONTENT);
+//! assert!(fits_message_content(&emoji));
+//! assert!(!fits_message_content(&format!("{emoji}😀")));
+//!
+//! // same for cjk, 3 bytes a character
+//! let cjk = "漢".repeat(MESSAGE_CONTENT);
+//! assert!(fits_message_content(&cjk));
+//! assert!(!fits_message_content(&format!("{cjk}a")));
+//!
+//! // a flag is two code points
+//! assert_eq!(char_count("🇳🇴"), 2);
+//! ```
+
+use crate::Embed;
+
+/// Max characters in the content of a message
+pub const MESSAGE_CONTENT: usize = 4000;
+/// Max number of messages a message can reply to
+pub const REPLY_MESSAGE_IDS: usize = 5;
+/// Max number of messages returned by one request
+pub const MESSAGES_PER_REQUEST: u8 = 100;
+/// Number of messages returned by one request that doesn't set a limit
+pub const DEFAULT_MESSAGES_PER_REQUEST: u8 = 50;
+/// Max characters in a nickname
+pub const NICKNAME: usize = 32;
+/// Max amount of xp that can be awarded or taken at once
+pub const XP_AWARD: i32 = 1000;
+/// Max characters in the content of a user's status
+///
+/// Guilded's api reference doesn't list this one, so it hasn't been checked against the api.
+pub const USER_STATUS: usize = 100;
+
+/// Max characters in an embed title
+pub const EMBED_TITLE: usize = 256;
+/// Max characters in an embed description
+pub const EMBED_DESCRIPTION: usize = 2048;
+/// Max number of fields in an embed
+pub const EMBED_FIELDS: usize = 25;
+/// Max characters in the name of an embed field
+pub const EMBED_FIELD_NAME: usize = 256;
+/// Max characters in the value of an embed field
+pub const EMBED_FIELD_VALUE: usize = 1024;
+/// Max characters in an embed footer
+pub const EMBED_FOOTER: usize = 2048;
+/// Max characters in the name of an embed author
+pub const EMBED_AUTHOR_NAME: usize = 256;
+/// Max characters in all the text of an embed together
+pub const EMBED_TOTAL: usize = 6000;
+
+/// Number of characters in `text`, as guilded counts them
+#[must_use]
+pub fn char_count(text: &str) -> usize {
+    text.chars().count()
+}
+
+/// Is `text` at most `max` characters long
+#[must_use]
+pub fn fits(text: &str, max: usize) -> bool {
+    // no need to count every character of very long text
+    text.chars().nth(max).is_none()
+}
+
+/// Cut `text` down to at most `max` characters, ending it with `…` if anything was cut
+///
+/// ```
+/// use vived_models::limits::truncate;
+///
+/// assert_eq!(truncate("hello world", 5), "hell…");
+/// assert_eq!(truncate("hello", 5), "hello");
+/// assert_eq!(truncate("漢字漢字", 3), "漢字…");
+/// assert_eq!(truncate("hello", 0), "");
+/// ```
+#[must_use]
+pub fn truncate(text: &str, max: usize) -> String {
+    if fits(text, max) {
+        text.to_owned()
+    } else if max == 0 {
+        String::new()
+    } else {
+        let mut truncated: String = text.chars().take(max - 1).collect();
+        truncated.push('…');
+        truncated
+    }
+}
+
+/// Does `content` fit in a message
+#[must_use]
+pub fn fits_message_content(content: &str) -> bool {
+    fits(content, MESSAGE_CONTENT)
+}
+
+/// Is `nickname` a valid nickname, between 1 and [`NICKNAME`] characters
+#[must_use]
+pub fn fits_nickname(nickname: &str) -> bool {
+    !nickname.is_empty() && fits(nickname, NICKNAME)
+}
+
+/// Does `content` fit in a user's status, see [`USER_STATUS`]
+///
+/// ```
+/// use vived_models::limits::{fits_user_status, USER_STATUS};
+///
+/// assert!(fits_user_status(""));
+/// assert!(fits_user_status(&"🎮".repeat(USER_STATUS)));
+/// assert!(!fits_user_status(&"🎮".repeat(USER_STATUS + 1)));
+/// ```
+#[must_use]
+pub fn fits_user_status(content: &str) -> bool {
+    fits(content, USER_STATUS)
+}
+
+/// Total characters of all text in an embed, see [`EMBED_TOTAL`]
+#[must_use]
+pub fn embed_char_count(embed: &Embed) -> usize {
+    let optional = [
+        embed.title.as_deref(),
+        embed.description.as_deref(),
+        embed.footer.as_ref().map(|footer| footer.text.as_str()),
+        embed.author.as_ref().map(|author| author.name.as_str()),
+    ];
+    let fields = embed
+        .fields
+        .iter()
+        .map(|field| char_count(&field.name) + char_count(&field.value));
+
+    optional.into_iter().flatten().map(char_count).chain(fields).sum()
+}
+
+/// Does `embed` stay within every embed limit
+///
+/// ```
+/// use vived_models::{limits::{fits_embed, EMBED_FIELDS}, Embed, EmbedField};
+///
+/// assert!(fits_embed(&Embed::new().title("漢".repeat(256))));
+/// assert!(!fits_embed(&Embed::new().title("漢".repeat(257))));
+///
+/// let full = (0..EMBED_FIELDS).fold(Embed::new(), |embed, i| embed.field(EmbedField::new(i.to_string(), "value")));
+/// assert!(fits_embed(&full));
+/// assert!(!fits_embed(&full.field(EmbedField::new("one", "too many"))));
+/// ```
+#[must_use]
+pub fn fits_embed(embed: &Embed) -> bool {
+    let text = [
+        (embed.title.as_deref(), EMBED_TITLE),
+        (embed.description.as_deref(), EMBED_DESCRIPTION),
+        (embed.footer.as_ref().map(|footer| footer.text.as_str()), EMBED_FOOTER),
+        (embed.author.as_ref().map(|author| author.name.as_str()), EMBED_AUTHOR_NAME),
+    ];
+
+    text.into_iter()
+        .all(|(text, max)| text.is_none_or(|text| fits(text, max)))
+        && embed.fields.len() <= EMBED_FIELDS
+        && embed
+            .fields
+            .iter()
+            .all(|field| fits(&field.name, EMBED_FIELD_NAME) && fits(&field.value, EMBED_FIELD_VALUE))
+        && embed_char_count(embed) <= EMBED_TOTAL
+}
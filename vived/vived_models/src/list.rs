@@ -0,0 +1,86 @@
+//! Items in list channels
+//! <https://www.guilded.gg/docs/api/listItems/ListItem>
+
+use serde::{Deserialize, Serialize};
+
+/// A note attached to a list item
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListItemNote {
+    /// The content of the note
+    pub content: String,
+    /// Created at timestamp
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Who created the note
+    pub created_by: crate::UserId,
+    /// Updated at
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Who last updated the note
+    pub updated_by: Option<crate::UserId>,
+}
+
+/// An item in a list channel
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ListItem {
+    /// The id of the item
+    pub id: crate::ListItemId,
+    /// The server the item is in
+    pub server_id: crate::ServerId,
+    /// The list channel the item is in
+    pub channel_id: crate::ChannelId,
+    /// The text of the item
+    pub message: String,
+    /// Created at timestamp
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Created by, for items created by a webhook this is a static id
+    ///
+    /// Use [`ListItem::creator`] to get the actual creator
+    pub created_by: crate::UserId,
+    /// The webhook that created the item, if it was created by one
+    pub created_by_webhook_id: Option<crate::WebhookId>,
+    /// Updated at
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Who last updated the item
+    pub updated_by: Option<crate::UserId>,
+    /// The item this one is nested under
+    pub parent_list_item_id: Option<crate::ListItemId>,
+    /// When the item was completed, `None` if it isn't
+    pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Who completed the item, `None` if it isn't completed
+    pub completed_by: Option<crate::UserId>,
+    /// Note attached to the item
+    pub note: Option<ListItemNote>,
+}
+
+impl ListItem {
+    /// Who created this item
+    #[must_use]
+    pub fn creator(&self) -> crate::Creator {
+        crate::Creator::from_fields(&self.created_by, self.created_by_webhook_id.as_ref())
+    }
+
+    /// Is the item completed
+    #[must_use]
+    pub fn is_completed(&self) -> bool {
+        self.completed_at.is_some()
+    }
+
+    /// The user that completed the item, `None` if it isn't completed
+    #[must_use]
+    pub fn completed_by_user(&self) -> Option<&crate::UserId> {
+        self.completed_by.as_ref()
+    }
+
+    /// When the item was completed, `None` if it isn't completed
+    #[must_use]
+    pub fn completed_at(&self) -> Option<&chrono::DateTime<chrono::Utc>> {
+        self.completed_at.as_ref()
+    }
+}
+
+impl From<ListItem> for crate::ListItemId {
+    fn from(item: ListItem) -> Self {
+        item.id
+    }
+}
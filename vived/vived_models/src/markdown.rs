@@ -0,0 +1,51 @@
+//! Helpers for guilded flavoured markdown
+
+/// Characters used for inline formatting (bold, italic, strikethrough, code and spoilers)
+const FORMATTING_CHARS: [char; 4] = ['*', '~', '`', '|'];
+
+/// Characters [`escape`] puts a backslash in front of
+const ESCAPED_CHARS: [char; 13] = [
+    '\\', '*', '_', '~', '`', '|', '>', '#', '[', ']', '(', ')', '<',
+];
+
+/// Remove markdown formatting, leaving the plain text
+///
+/// This is meant for previews and logs, so it is simple rather than exact.
+/// Underscores are kept since they are common in plain text.
+/// ```
+/// use vived_models::markdown::strip;
+///
+/// assert_eq!(strip("# Hello **world**\n> ~~quote~~ `code`"), "Hello world\nquote code");
+/// ```
+#[must_use]
+pub fn strip(text: &str) -> String {
+    text.lines()
+        .map(|line| {
+            let line = line.trim_start_matches(['#', '>']).trim_start();
+            line.replace(FORMATTING_CHARS, "")
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Escape markdown, so `text` shows up as written, for example a user's name in a message
+///
+/// ```
+/// use vived_models::markdown::escape;
+///
+/// assert_eq!(escape("**bold** _name_"), r"\*\*bold\*\* \_name\_");
+/// assert_eq!(escape("[link](url) <@user>"), r"\[link\]\(url\) \<@user\>");
+/// assert_eq!(escape(r"back\slash"), r"back\\slash");
+/// assert_eq!(escape("plain text"), "plain text");
+/// ```
+#[must_use]
+pub fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for character in text.chars() {
+        if ESCAPED_CHARS.contains(&character) {
+            escaped.push('\\');
+        }
+        escaped.push(character);
+    }
+    escaped
+}
@@ -0,0 +1,46 @@
+//! Posts in media channels
+
+use serde::{Deserialize, Serialize};
+
+/// The kind of media
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum MediaType {
+    /// A still image
+    Image,
+    /// A video
+    Video,
+    /// An animated gif
+    Gif,
+}
+
+/// A media post in a media channel
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Media {
+    /// The id of the media
+    pub id: crate::MediaId,
+    /// The media channel the media is in
+    pub channel_id: crate::ChannelId,
+    /// The server the media is in
+    pub server_id: crate::ServerId,
+    /// The title of the media
+    pub title: Option<String>,
+    /// The description of the media
+    pub description: Option<String>,
+    /// Url of the media itself
+    pub src: String,
+    /// The kind of media
+    #[serde(rename = "type")]
+    pub media_type: MediaType,
+    /// Who posted the media
+    pub created_by: crate::UserId,
+    /// Created at timestamp
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<Media> for crate::MediaId {
+    fn from(media: Media) -> Self {
+        media.id
+    }
+}
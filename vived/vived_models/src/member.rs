@@ -0,0 +1,121 @@
+//! Users and server members
+//! <https://www.guilded.gg/docs/api/members/ServerMember>
+
+use serde::{Deserialize, Serialize};
+
+/// The type of a user
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum UserType {
+    /// A normal user
+    #[default]
+    User,
+    /// A bot
+    Bot,
+}
+
+/// A guilded user
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct User {
+    /// The id of the user
+    pub id: crate::UserId,
+    /// If this is a bot or a normal user
+    #[serde(default, rename = "type")]
+    pub user_type: UserType,
+    /// The name of the user
+    pub name: String,
+    /// The avatar of the user
+    /// A media-uri string
+    pub avatar: Option<String>,
+    /// The profile banner of the user
+    /// A media-uri string
+    pub banner: Option<String>,
+    /// When the account was created
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<User> for crate::UserId {
+    fn from(user: User) -> Self {
+        user.id
+    }
+}
+
+/// A user as a member of a server
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerMember {
+    /// The user
+    pub user: User,
+    /// The roles the member has
+    pub role_ids: Vec<crate::RoleId>,
+    /// The nickname of the member in the server
+    pub nickname: Option<String>,
+    /// When the member joined the server
+    pub joined_at: chrono::DateTime<chrono::Utc>,
+    /// Is the member the owner of the server
+    #[serde(default)]
+    pub is_owner: bool,
+}
+
+impl ServerMember {
+    /// The name shown in the server, the nickname if set otherwise the username
+    #[must_use]
+    pub fn display_name(&self) -> &str {
+        self.nickname.as_deref().unwrap_or(&self.user.name)
+    }
+}
+
+impl From<ServerMember> for crate::UserId {
+    fn from(member: ServerMember) -> Self {
+        member.user.id
+    }
+}
+
+/// The parts of a user that are sent along with lists, like the members of a server
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct UserSummary {
+    /// The id of the user
+    pub id: crate::UserId,
+    /// If this is a bot or a normal user
+    #[serde(default, rename = "type")]
+    pub user_type: UserType,
+    /// The name of the user
+    pub name: String,
+    /// The avatar of the user
+    /// A media-uri string
+    pub avatar: Option<String>,
+}
+
+/// A member as returned when listing the members of a server
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerMemberSummary {
+    /// The user
+    pub user: UserSummary,
+    /// The roles the member has
+    pub role_ids: Vec<crate::RoleId>,
+}
+
+impl ServerMemberSummary {
+    /// Does the member have the role
+    #[must_use]
+    pub fn has_role(&self, role: crate::RoleId) -> bool {
+        self.role_ids.contains(&role)
+    }
+}
+
+impl From<ServerMember> for ServerMemberSummary {
+    fn from(member: ServerMember) -> Self {
+        Self {
+            user: UserSummary {
+                id: member.user.id,
+                user_type: member.user.user_type,
+                name: member.user.name,
+                avatar: member.user.avatar,
+            },
+            role_ids: member.role_ids,
+        }
+    }
+}
@@ -1,9 +1,9 @@
 //! Guilded messages are like the text stuff
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
 /// The type of message
-#[derive(Deserialize, Debug, Clone, Copy)]
+#[derive(Deserialize, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum MessageType {
     /// Your normal everyday message
@@ -15,7 +15,7 @@ pub enum MessageType {
 impl MessageType {
     /// Returns `true` if the type is [`Default`].
     ///
-    /// [`Default`]: Type::Default
+    /// [`Default`]: MessageType::Default
     #[must_use]
     pub fn is_default(&self) -> bool {
         matches!(self, &Self::Default)
@@ -23,7 +23,7 @@ impl MessageType {
 
     /// Returns `true` if the type is [`System`].
     ///
-    /// [`System`]: Type::System
+    /// [`System`]: MessageType::System
     #[must_use]
     pub fn is_system(&self) -> bool {
         matches!(self, &Self::System)
@@ -33,14 +33,14 @@ impl MessageType {
 // The api lists mention ids using {id: ...} so we need to convert from that
 
 /// Wraps an id in a objects
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Deserialize, Serialize, Debug, Clone)]
 struct WrappedId<T> {
     /// The id
     id: T,
 }
 
 /// Raw mentions
-#[derive(Deserialize, Default, Debug, Clone)]
+#[derive(Deserialize, Serialize, Default, Debug, Clone)]
 #[serde(default)]
 struct RawMentions {
     /// The mentioned users
@@ -67,9 +67,23 @@ impl From<RawMentions> for Mentions {
     }
 }
 
+impl From<Mentions> for RawMentions {
+    fn from(mentions: Mentions) -> Self {
+        Self {
+            users: mentions.users.into_iter().map(|id| WrappedId { id }).collect(),
+            channels: mentions.channels.into_iter().map(|id| WrappedId { id }).collect(),
+            roles: mentions.roles.into_iter().map(|id| WrappedId { id }).collect(),
+            everyone: mentions.everyone,
+            here: mentions.here,
+        }
+    }
+}
+
 /// Who was mentioned in a message
-#[derive(Deserialize, Debug, Default, Clone)]
-#[serde(from = "RawMentions")]
+///
+/// Compared in order, use [`Mentions::same_targets`] to ignore the order.
+#[derive(Deserialize, Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(from = "RawMentions", into = "RawMentions")]
 pub struct Mentions {
     /// What users were mentioned
     pub users: Vec<crate::UserId>,
@@ -83,77 +97,34 @@ pub struct Mentions {
     pub here: bool,
 }
 
-
-/// Fields used by the api to represent who created a message
-/// They use 2 redundant fields
-/// 
-/// There is a more convenient structure the [`CreatedBy`] enum
-/// You can translate this into that using `.into()` which gives you a nicer interface
-/// 
-/// I did try to make this deserialize into that automatically,
-/// but because of limitations on serde flatten we cant 
-#[derive(Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-pub struct CreatedByRawFields {
-    /// What user created this message, for a webhook this is the static id "Ann6LewA"
-    created_by: crate::UserId,
-    /// Potential id of webhook that created message, if present ignore `created_by`
-    created_by_webhook_id: Option<crate::WebhookId>,    
-}
-
-/// Who created this message?
-#[derive(Deserialize, Debug, Clone)]
-#[serde(from = "CreatedByRawFields")]
-pub enum CreatedBy {
-    /// Message was sent by a webhook
-    Webhook(crate::WebhookId),
-    /// Message was sent by a normal user
-    User(crate::UserId),
-}
-
-impl CreatedByRawFields {
-    /// Convert this to the `CreatedBy` enum
+impl Mentions {
+    /// Do both mention the same users, channels and roles, in any order
     #[must_use]
-    pub fn into_enum(self) -> CreatedBy {
-        self.into()
-    }
-}
-
-impl From<CreatedByRawFields> for CreatedBy {
-    fn from(raw: CreatedByRawFields) -> Self {
-        if let Some(webhook_id) = raw.created_by_webhook_id {
-            Self::Webhook(webhook_id)
-        } else {
-            Self::User(raw.created_by)
+    pub fn same_targets(&self, other: &Self) -> bool {
+        /// Compare two lists ignoring order and duplicates
+        fn same<T: Eq + std::hash::Hash>(a: &[T], b: &[T]) -> bool {
+            a.iter().collect::<std::collections::HashSet<_>>()
+                == b.iter().collect::<std::collections::HashSet<_>>()
         }
+
+        same(&self.users, &other.users)
+            && same(&self.channels, &other.channels)
+            && same(&self.roles, &other.roles)
+            && self.everyone == other.everyone
+            && self.here == other.here
     }
 }
 
-impl CreatedBy {
-    /// Return the user id if this was sent by a user
-    #[must_use]
-    pub fn as_user(&self) -> Option<&crate::UserId> {
-        if let &Self::User(ref v) = self {
-            Some(v)
-        } else {
-            None
-        }
-    }
 
-    /// Return the webhook id if this was sent by a webhook
-    #[must_use]
-    pub fn as_webhook(&self) -> Option<&crate::WebhookId> {
-        if let &Self::Webhook(ref v) = self {
-            Some(v)
-        } else {
-            None
-        }
-    }
-}
+/// Fields used by the api to represent who created a message, see [`crate::CreatorRawFields`]
+pub type CreatedByRawFields = crate::CreatorRawFields;
+
+/// Who created this message? see [`crate::Creator`]
+pub type CreatedBy = crate::Creator;
 
 /// A guilded message!
-#[derive(Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
+#[derive(Deserialize, Serialize, Debug, Clone)]
+#[serde(rename_all = "camelCase", from = "RawMessage")]
 pub struct Message {
     /// The id of this message
     pub id: crate::MessageId,
@@ -181,6 +152,9 @@ pub struct Message {
     /// If it is silent would not ping users
     #[serde(default)]
     pub is_silent: bool,
+    /// Is the message pinned in its channel
+    #[serde(default)]
+    pub is_pinned: bool,
     /// Describes who and what was mentioned in this message
     #[serde(default)]
     pub mentions: Mentions,
@@ -191,6 +165,222 @@ pub struct Message {
     pub created_by: CreatedByRawFields,
     /// Updated at
     pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Fields guilded sent with a system message that this model doesn't know about, `None` if there were none
+    ///
+    /// Guilded doesn't document what system messages carry besides the usual fields,
+    /// so instead of dropping it whatever else they send ends up here, as a json object.
+    /// Other messages never have this, unknown fields on those are dropped like on every other model.
+    ///
+    /// The extra fields in this example are made up to show how they are kept, they are not captured from guilded.
+    /// ```
+    /// use vived_models::Message;
+    ///
+    /// let message = |extra: &str| -> Message {
+    ///     serde_json::from_str(&format!(r#"{{
+    ///         "id": "m", "type": "system", "serverId": "s", "channelId": "c",
+    ///         "createdAt": "2022-10-01T12:00:00.000Z", "createdBy": "u"{extra}
+    ///     }}"#)).unwrap()
+    /// };
+    ///
+    /// let joined = message(r#", "content": "joined the server", "systemType": "member_joined""#);
+    /// assert!(joined.message_type.is_system());
+    /// assert_eq!(joined.system_meta.unwrap()["systemType"], "member_joined");
+    ///
+    /// let renamed = message(r#", "systemType": "channel_renamed", "details": {"from": "old", "to": "new"}"#);
+    /// assert_eq!(renamed.system_meta.as_ref().unwrap()["details"]["to"], "new");
+    /// // the extra fields survive a round trip
+    /// let json = serde_json::to_value(&renamed).unwrap();
+    /// assert_eq!(json["details"]["from"], "old");
+    /// assert_eq!(json["createdBy"], "u");
+    ///
+    /// // known fields, like who created it, are never part of it
+    /// assert!(message("").system_meta.is_none());
+    ///
+    /// let default: Message = serde_json::from_str(r#"{
+    ///     "id": "m", "type": "default", "serverId": "s", "channelId": "c", "groupId": "g",
+    ///     "createdAt": "2022-10-01T12:00:00.000Z", "createdBy": "u"
+    /// }"#).unwrap();
+    /// assert!(default.system_meta.is_none());
+    /// ```
+    #[serde(flatten, skip_serializing_if = "Option::is_none")]
+    pub system_meta: Option<serde_json::Value>,
+}
+
+/// A [`Message`] as it is deserialized, with the fields it doesn't know about kept apart
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct RawMessage {
+    /// The id of this message
+    id: crate::MessageId,
+    /// What types of message is it?
+    #[serde(rename = "type")]
+    message_type: MessageType,
+    /// Id of server it was sent in
+    /// In the future this will be `None` in dms, but atm dms are not supported by the api
+    /// meaning this field is always a `Some`
+    server_id: Option<crate::ServerId>,
+    /// Channel message was sent in
+    channel_id: crate::ChannelId,
+    /// Content of the message
+    content: Option<String>,
+    ///  Message embeds, currently only supports up to one embed,
+    /// but it is still a list
+    #[serde(default)]
+    embeds: Vec<crate::Embed>,
+    /// Message ids replied to
+    /// if present will contain between 1 and 5 elements
+    reply_message_ids: Option<Vec<crate::MessageId>>,
+    /// If message is private only people mentioned or replied to can see it (and mods)
+    #[serde(default)]
+    is_private: bool,
+    /// If it is silent would not ping users
+    #[serde(default)]
+    is_silent: bool,
+    /// Is the message pinned in its channel
+    #[serde(default)]
+    is_pinned: bool,
+    /// Describes who and what was mentioned in this message
+    #[serde(default)]
+    mentions: Mentions,
+    /// When was this message sent?
+    created_at: chrono::DateTime<chrono::Utc>,
+    /// Who sent this message?
+    #[serde(flatten)]
+    created_by: CreatedByRawFields,
+    /// Updated at
+    updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Every other field
+    #[serde(flatten)]
+    unknown: serde_json::Map<String, serde_json::Value>,
+}
+
+impl From<RawMessage> for Message {
+    fn from(raw: RawMessage) -> Self {
+        // only system messages keep them, see `Message::system_meta`
+        let system_meta = (raw.message_type.is_system() && !raw.unknown.is_empty())
+            .then_some(serde_json::Value::Object(raw.unknown));
+
+        Self {
+            id: raw.id,
+            message_type: raw.message_type,
+            server_id: raw.server_id,
+            channel_id: raw.channel_id,
+            content: raw.content,
+            embeds: raw.embeds,
+            reply_message_ids: raw.reply_message_ids,
+            is_private: raw.is_private,
+            is_silent: raw.is_silent,
+            is_pinned: raw.is_pinned,
+            mentions: raw.mentions,
+            created_at: raw.created_at,
+            created_by: raw.created_by,
+            updated_at: raw.updated_at,
+            system_meta,
+        }
+    }
+}
+
+/// Messages are equal if they have the same id, even if one of them is an older version
+///
+/// Use [`crate::MessageDiff`] to find out if two versions differ.
+/// ```
+/// use vived_models::Message;
+///
+/// let message = |content: &str| -> Message {
+///     serde_json::from_str(&format!(r#"{{
+///         "id": "m", "type": "default", "serverId": "s", "channelId": "c",
+///         "content": "{content}", "createdAt": "2022-10-01T12:00:00Z", "createdBy": "u"
+///     }}"#)).unwrap()
+/// };
+/// assert_eq!(message("before edit"), message("after edit"));
+/// ```
+impl PartialEq for Message {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl Eq for Message {}
+
+impl Message {
+    /// How long ago this message was sent
+    #[must_use]
+    pub fn age(&self) -> chrono::Duration {
+        chrono::Utc::now() - self.created_at
+    }
+
+    /// Was this message sent more than `duration` ago
+    #[must_use]
+    pub fn is_older_than(&self, duration: chrono::Duration) -> bool {
+        self.age() > duration
+    }
+
+    /// How long ago this message was last edited, `None` if it never was
+    #[must_use]
+    pub fn time_since_update(&self) -> Option<chrono::Duration> {
+        self.updated_at.map(|updated_at| chrono::Utc::now() - updated_at)
+    }
+
+    /// The content of the message, or an empty string for messages without content
+    ///
+    /// System messages and embed only messages have no content.
+    #[must_use]
+    pub fn content_or_empty(&self) -> &str {
+        self.content.as_deref().unwrap_or_default()
+    }
+
+    /// Does the message have non empty content
+    #[must_use]
+    pub fn has_content(&self) -> bool {
+        !self.content_or_empty().is_empty()
+    }
+
+    /// A single line preview of the message without markdown, at most `max_len` characters long
+    ///
+    /// Messages without content fall back to the title (or description) of the first embed.
+    /// ```
+    /// use vived_models::Message;
+    ///
+    /// let embed_only: Message = serde_json::from_str(r#"{
+    ///     "id": "1", "type": "default", "serverId": "s", "channelId": "c",
+    ///     "embeds": [{"title": "Daily **report**"}],
+    ///     "createdAt": "2022-10-01T12:00:00.000Z", "createdBy": "u"
+    /// }"#).unwrap();
+    /// assert!(!embed_only.has_content());
+    /// assert_eq!(embed_only.text_preview(50), "Daily report");
+    ///
+    /// let system_join: Message = serde_json::from_str(r#"{
+    ///     "id": "2", "type": "system", "serverId": "s", "channelId": "c",
+    ///     "content": null, "createdAt": "2022-10-01T12:00:00.000Z", "createdBy": "u"
+    /// }"#).unwrap();
+    /// assert_eq!(system_join.content_or_empty(), "");
+    /// assert_eq!(system_join.text_preview(50), "");
+    ///
+    /// let long: Message = serde_json::from_str(r#"{
+    ///     "id": "3", "type": "default", "serverId": "s", "channelId": "c",
+    ///     "content": "first line\n*second* line", "createdAt": "2022-10-01T12:00:00.000Z", "createdBy": "u"
+    /// }"#).unwrap();
+    /// assert_eq!(long.text_preview(50), "first line second line");
+    /// assert_eq!(long.text_preview(8), "first l…");
+    /// ```
+    #[must_use]
+    pub fn text_preview(&self, max_len: usize) -> String {
+        let text = if self.has_content() {
+            self.content_or_empty()
+        } else {
+            self.embeds
+                .first()
+                .and_then(|embed| embed.title.as_deref().or(embed.description.as_deref()))
+                .unwrap_or_default()
+        };
+
+        let text = crate::markdown::strip(text)
+            .split_whitespace()
+            .collect::<Vec<_>>()
+            .join(" ");
+
+        crate::limits::truncate(&text, max_len)
+    }
 }
 
 // You should be able to construct ids from the objects
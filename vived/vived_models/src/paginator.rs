@@ -0,0 +1,216 @@
+//! Split long lists over several embeds
+
+use crate::limits::{
+    char_count, embed_char_count, truncate, EMBED_AUTHOR_NAME, EMBED_DESCRIPTION, EMBED_FIELDS,
+    EMBED_FIELD_NAME, EMBED_FIELD_VALUE, EMBED_FOOTER, EMBED_TITLE, EMBED_TOTAL,
+};
+use crate::{Embed, EmbedField, EmbedFooter};
+
+/// Characters kept free in the footer for the page number, enough for `" • page 9999/9999"`
+const PAGE_FOOTER_RESERVE: usize = 24;
+
+/// Content of a single page
+#[derive(Debug, Clone, Default)]
+struct Page {
+    /// Lines of the description, joined by newlines
+    description: String,
+    /// Fields of the page
+    fields: Vec<EmbedField>,
+    /// Characters used by the description and fields
+    chars: usize,
+}
+
+impl Page {
+    /// Is there nothing on this page
+    fn is_empty(&self) -> bool {
+        self.description.is_empty() && self.fields.is_empty()
+    }
+}
+
+/// Split lines and fields over as many embeds as needed to stay within the [embed limits](crate::limits)
+///
+/// Lines end up in the description and fields after it, a new page is started when
+/// the description, the field count or the total characters would go over the limit.
+/// A single line or field that is too big for a page on its own is cut short with `…`.
+///
+/// Every page is a copy of the template (title, color, footer and so on) with its own description and fields,
+/// when there is more than one page the footer gets `page i/n` added.
+/// ```
+/// use vived_models::{limits::fits_embed, Color, Embed, EmbedPaginator};
+///
+/// let mut paginator = EmbedPaginator::new(Embed::new().title("Leaderboard").color(Color::from_rgb(255, 0, 0)).footer("Season 3"));
+/// for i in 0..1000 {
+///     // 20 characters, so 97 lines fit in a description
+///     paginator.push_line(format!("{i:04} {}", "x".repeat(15)));
+/// }
+/// let pages = paginator.build();
+///
+/// assert_eq!(pages.len(), 11);
+/// assert!(pages.iter().all(fits_embed));
+/// assert!(pages.iter().all(|page| page.title.as_deref() == Some("Leaderboard")));
+/// assert_eq!(pages[0].footer.as_ref().unwrap().text, "Season 3 • page 1/11");
+/// assert_eq!(pages[10].footer.as_ref().unwrap().text, "Season 3 • page 11/11");
+///
+/// let lines: Vec<&str> = pages.iter().flat_map(|page| page.description.as_deref().unwrap().lines()).collect();
+/// assert_eq!(lines.len(), 1000);
+/// assert!(lines[999].starts_with("0999 "));
+/// ```
+/// A field that is too big is cut short:
+/// ```
+/// use vived_models::{limits::{fits_embed, EMBED_FIELD_VALUE}, Embed, EmbedPaginator};
+///
+/// let mut paginator = EmbedPaginator::new(Embed::new());
+/// paginator.push_field("huge", "x".repeat(5000));
+/// let pages = paginator.build();
+///
+/// assert_eq!(pages.len(), 1);
+/// assert!(fits_embed(&pages[0]));
+/// assert!(pages[0].footer.is_none());
+/// let value = &pages[0].fields[0].value;
+/// assert_eq!(value.chars().count(), EMBED_FIELD_VALUE);
+/// assert!(value.ends_with('…'));
+/// ```
+/// Fields are split by count and by the total characters of an embed:
+/// ```
+/// use vived_models::{limits::fits_embed, Embed, EmbedPaginator};
+///
+/// let mut by_count = EmbedPaginator::new(Embed::new()).max_fields(10);
+/// for i in 0..25 {
+///     by_count.push_field(i.to_string(), "value");
+/// }
+/// let pages = by_count.build();
+/// assert_eq!(pages.iter().map(|page| page.fields.len()).collect::<Vec<_>>(), [10, 10, 5]);
+///
+/// let mut by_size = EmbedPaginator::new(Embed::new().title("Leaderboard"));
+/// for i in 0..12 {
+///     by_size.push_field(format!("f{i}"), "x".repeat(1000));
+/// }
+/// let pages = by_size.build();
+/// assert_eq!(pages.iter().map(|page| page.fields.len()).collect::<Vec<_>>(), [5, 5, 2]);
+/// assert!(pages.iter().all(fits_embed));
+/// ```
+#[derive(Debug, Clone)]
+#[must_use]
+pub struct EmbedPaginator {
+    /// Embed every page starts from, without description or fields
+    template: Embed,
+    /// Most fields on a single page
+    max_fields: usize,
+    /// Characters left for the description and fields of a page
+    available: usize,
+    /// Pages that are full
+    pages: Vec<Page>,
+    /// Page being filled
+    current: Page,
+}
+
+impl EmbedPaginator {
+    /// Create a paginator, every page is a copy of `template`
+    ///
+    /// The description and fields of the template are dropped, parts that are too long are cut short.
+    pub fn new(mut template: Embed) -> Self {
+        template.description = None;
+        template.fields.clear();
+        if let Some(ref mut title) = template.title {
+            *title = truncate(title, EMBED_TITLE);
+        }
+        if let Some(ref mut author) = template.author {
+            author.name = truncate(&author.name, EMBED_AUTHOR_NAME);
+        }
+        if let Some(ref mut footer) = template.footer {
+            footer.text = truncate(&footer.text, EMBED_FOOTER - PAGE_FOOTER_RESERVE);
+        }
+
+        let used = embed_char_count(&template) + PAGE_FOOTER_RESERVE;
+        Self {
+            template,
+            max_fields: EMBED_FIELDS,
+            available: EMBED_TOTAL.saturating_sub(used),
+            pages: Vec::new(),
+            current: Page::default(),
+        }
+    }
+
+    /// Put at most `max_fields` fields on a page, capped at [`EMBED_FIELDS`]
+    pub fn max_fields(mut self, max_fields: usize) -> Self {
+        self.max_fields = max_fields.clamp(1, EMBED_FIELDS);
+        self
+    }
+
+    /// Add a line to the description
+    pub fn push_line(&mut self, line: impl AsRef<str>) {
+        let line = truncate(line.as_ref(), EMBED_DESCRIPTION.min(self.available));
+        let separator = usize::from(!self.current.description.is_empty());
+        let description = char_count(&self.current.description);
+        let cost = char_count(&line) + separator;
+
+        if description + cost > EMBED_DESCRIPTION || self.current.chars + cost > self.available {
+            self.next_page();
+        } else if separator == 1 {
+            self.current.description.push('\n');
+            self.current.chars += 1;
+        }
+
+        self.current.chars += char_count(&line);
+        self.current.description.push_str(&line);
+    }
+
+    /// Add a field that isn't inline
+    pub fn push_field(&mut self, name: impl AsRef<str>, value: impl AsRef<str>) {
+        let name = truncate(name.as_ref(), EMBED_FIELD_NAME.min(self.available));
+        let value_max = EMBED_FIELD_VALUE.min(self.available.saturating_sub(char_count(&name)));
+        let value = truncate(value.as_ref(), value_max);
+        let cost = char_count(&name) + char_count(&value);
+
+        if self.current.fields.len() >= self.max_fields
+            || self.current.chars + cost > self.available
+        {
+            self.next_page();
+        }
+
+        self.current.chars += cost;
+        self.current.fields.push(EmbedField::new(name, value));
+    }
+
+    /// Start a new page, unless the current one is still empty
+    fn next_page(&mut self) {
+        if !self.current.is_empty() {
+            self.pages.push(std::mem::take(&mut self.current));
+        }
+    }
+
+    /// Turn the pages into embeds, no lines or fields means no embeds
+    #[must_use]
+    pub fn build(mut self) -> Vec<Embed> {
+        self.next_page();
+        let total = self.pages.len();
+
+        self.pages
+            .into_iter()
+            .enumerate()
+            .map(|(index, page)| {
+                let mut embed = self.template.clone();
+                if !page.description.is_empty() {
+                    embed.description = Some(page.description);
+                }
+                embed.fields = page.fields;
+
+                if total > 1 {
+                    let number = format!("page {}/{total}", index + 1);
+                    embed.footer = Some(match embed.footer {
+                        Some(footer) if !footer.text.is_empty() => EmbedFooter {
+                            text: format!("{} • {number}", footer.text),
+                            ..footer
+                        },
+                        Some(footer) => EmbedFooter {
+                            text: number,
+                            ..footer
+                        },
+                        None => EmbedFooter::from(number),
+                    });
+                }
+                embed
+            })
+            .collect()
+    }
+}
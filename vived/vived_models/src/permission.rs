@@ -0,0 +1,287 @@
+//! Permissions of roles and members
+//! <https://www.guilded.gg/docs/api/Permissions>
+
+use serde::{Deserialize, Serialize};
+
+/// A permission, as used by roles and returned for members
+///
+/// Permissions are matched exactly as guilded sends them, like `CanUpdateServer`.
+/// Permissions vived doesn't know about yet end up in [`Permission::Other`],
+/// and [`Permission::as_str`] always gives back the raw name.
+/// ```
+/// use vived_models::Permission;
+///
+/// let permissions: Vec<Permission> = serde_json::from_str(r#"["CanUpdateServer", "CanDoSomethingNew"]"#).unwrap();
+/// assert_eq!(permissions, [Permission::UpdateServer, Permission::Other("CanDoSomethingNew".to_owned())]);
+/// assert_eq!(serde_json::to_string(&permissions).unwrap(), r#"["CanUpdateServer","CanDoSomethingNew"]"#);
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize)]
+#[serde(from = "String")]
+pub enum Permission {
+    /// Manage the server settings, `CanUpdateServer`
+    UpdateServer,
+    /// Manage roles, `CanManageRoles`
+    ManageRoles,
+    /// Invite members, `CanInviteMembers`
+    InviteMembers,
+    /// Kick and ban members, `CanKickMembers`
+    KickMembers,
+    /// Manage groups, `CanManageGroups`
+    ManageGroups,
+    /// Manage channels, `CanManageChannels`
+    ManageChannels,
+    /// Manage webhooks, `CanManageWebhooks`
+    ManageWebhooks,
+    /// Mention `@everyone` and `@here`, `CanMentionEveryone`
+    MentionEveryone,
+    /// Moderate channels, `CanModerateChannels`
+    ModerateChannels,
+    /// Ignore slow mode, `CanBypassSlowMode`
+    BypassSlowMode,
+    /// Read chat messages, `CanReadChats`
+    ReadChats,
+    /// Send chat messages, `CanCreateChats`
+    CreateChats,
+    /// Upload media in chat, `CanUploadChatMedia`
+    UploadChatMedia,
+    /// Create threads, `CanCreateThreads`
+    CreateThreads,
+    /// Send messages in threads, `CanCreateThreadMessages`
+    CreateThreadMessages,
+    /// Send private messages, `CanCreatePrivateMessages`
+    CreatePrivateMessages,
+    /// Delete and pin messages of others, `CanManageChats`
+    ManageChats,
+    /// Manage threads, `CanManageThreads`
+    ManageThreads,
+    /// Read forum topics, `CanReadForums`
+    ReadForums,
+    /// Create forum topics, `CanCreateTopics`
+    CreateTopics,
+    /// Reply to forum topics, `CanCreateTopicReplies`
+    CreateTopicReplies,
+    /// Give and take xp, `CanManageServerXp`
+    ManageServerXp,
+    /// Change their own nickname, `CanChangeNickname`
+    ChangeNickname,
+    /// Change the nicknames of others, `CanManageNicknames`
+    ManageNicknames,
+    /// Manage custom emotes, `CanManageEmotes`
+    ManageEmotes,
+    /// A permission vived doesn't know about
+    Other(String),
+}
+
+impl Permission {
+    /// The permission as guilded names it
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        match *self {
+            Self::UpdateServer => "CanUpdateServer",
+            Self::ManageRoles => "CanManageRoles",
+            Self::InviteMembers => "CanInviteMembers",
+            Self::KickMembers => "CanKickMembers",
+            Self::ManageGroups => "CanManageGroups",
+            Self::ManageChannels => "CanManageChannels",
+            Self::ManageWebhooks => "CanManageWebhooks",
+            Self::MentionEveryone => "CanMentionEveryone",
+            Self::ModerateChannels => "CanModerateChannels",
+            Self::BypassSlowMode => "CanBypassSlowMode",
+            Self::ReadChats => "CanReadChats",
+            Self::CreateChats => "CanCreateChats",
+            Self::UploadChatMedia => "CanUploadChatMedia",
+            Self::CreateThreads => "CanCreateThreads",
+            Self::CreateThreadMessages => "CanCreateThreadMessages",
+            Self::CreatePrivateMessages => "CanCreatePrivateMessages",
+            Self::ManageChats => "CanManageChats",
+            Self::ManageThreads => "CanManageThreads",
+            Self::ReadForums => "CanReadForums",
+            Self::CreateTopics => "CanCreateTopics",
+            Self::CreateTopicReplies => "CanCreateTopicReplies",
+            Self::ManageServerXp => "CanManageServerXp",
+            Self::ChangeNickname => "CanChangeNickname",
+            Self::ManageNicknames => "CanManageNicknames",
+            Self::ManageEmotes => "CanManageEmotes",
+            Self::Other(ref permission) => permission,
+        }
+    }
+}
+
+impl From<String> for Permission {
+    fn from(permission: String) -> Self {
+        match permission.as_str() {
+            "CanUpdateServer" => Self::UpdateServer,
+            "CanManageRoles" => Self::ManageRoles,
+            "CanInviteMembers" => Self::InviteMembers,
+            "CanKickMembers" => Self::KickMembers,
+            "CanManageGroups" => Self::ManageGroups,
+            "CanManageChannels" => Self::ManageChannels,
+            "CanManageWebhooks" => Self::ManageWebhooks,
+            "CanMentionEveryone" => Self::MentionEveryone,
+            "CanModerateChannels" => Self::ModerateChannels,
+            "CanBypassSlowMode" => Self::BypassSlowMode,
+            "CanReadChats" => Self::ReadChats,
+            "CanCreateChats" => Self::CreateChats,
+            "CanUploadChatMedia" => Self::UploadChatMedia,
+            "CanCreateThreads" => Self::CreateThreads,
+            "CanCreateThreadMessages" => Self::CreateThreadMessages,
+            "CanCreatePrivateMessages" => Self::CreatePrivateMessages,
+            "CanManageChats" => Self::ManageChats,
+            "CanManageThreads" => Self::ManageThreads,
+            "CanReadForums" => Self::ReadForums,
+            "CanCreateTopics" => Self::CreateTopics,
+            "CanCreateTopicReplies" => Self::CreateTopicReplies,
+            "CanManageServerXp" => Self::ManageServerXp,
+            "CanChangeNickname" => Self::ChangeNickname,
+            "CanManageNicknames" => Self::ManageNicknames,
+            "CanManageEmotes" => Self::ManageEmotes,
+            _ => Self::Other(permission),
+        }
+    }
+}
+
+impl From<&str> for Permission {
+    fn from(permission: &str) -> Self {
+        Self::from(permission.to_owned())
+    }
+}
+
+impl Serialize for Permission {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl std::fmt::Display for Permission {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A set of permissions, so checking for one doesn't mean scanning a list
+///
+/// Duplicates are dropped and the order permissions were given in doesn't matter.
+/// ```
+/// use vived_models::{Permission, PermissionSet};
+///
+/// let admin: PermissionSet = [Permission::UpdateServer, Permission::ManageRoles, Permission::ReadChats].into_iter().collect();
+/// let member: PermissionSet = [Permission::ReadChats, Permission::CreateChats, Permission::ReadChats].into_iter().collect();
+///
+/// assert!(admin.contains(&Permission::UpdateServer));
+/// assert!(!member.contains(&Permission::UpdateServer));
+/// assert_eq!(member.len(), 2);
+///
+/// assert_eq!(admin.intersection(&member), PermissionSet::from(vec![Permission::ReadChats]));
+/// assert_eq!(admin.union(&member).len(), 4);
+/// assert_eq!(member.difference(&admin), PermissionSet::from(vec![Permission::CreateChats]));
+/// assert!(PermissionSet::from(vec![Permission::ReadChats]).is_subset(&member));
+/// assert_eq!(
+///     PermissionSet::from(vec![Permission::CreateChats, Permission::ReadChats]),
+///     PermissionSet::from(vec![Permission::ReadChats, Permission::CreateChats]),
+/// );
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(from = "Vec<Permission>")]
+pub struct PermissionSet(Vec<Permission>);
+
+impl PermissionSet {
+    /// Create an empty set
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Is the permission in the set
+    #[must_use]
+    pub fn contains(&self, permission: &Permission) -> bool {
+        self.0.binary_search(permission).is_ok()
+    }
+
+    /// Is every permission of this set in `other`
+    #[must_use]
+    pub fn is_subset(&self, other: &Self) -> bool {
+        self.0.iter().all(|permission| other.contains(permission))
+    }
+
+    /// Permissions in either set
+    #[must_use]
+    pub fn union(&self, other: &Self) -> Self {
+        self.0.iter().chain(&other.0).cloned().collect()
+    }
+
+    /// Permissions in both sets
+    #[must_use]
+    pub fn intersection(&self, other: &Self) -> Self {
+        self.0
+            .iter()
+            .filter(|&permission| other.contains(permission))
+            .cloned()
+            .collect()
+    }
+
+    /// Permissions in this set but not in `other`
+    #[must_use]
+    pub fn difference(&self, other: &Self) -> Self {
+        self.0
+            .iter()
+            .filter(|&permission| !other.contains(permission))
+            .cloned()
+            .collect()
+    }
+
+    /// Iterate over the permissions
+    pub fn iter(&self) -> std::slice::Iter<'_, Permission> {
+        self.0.iter()
+    }
+
+    /// Number of permissions in the set
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// Is the set empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// The permissions, sorted
+    #[must_use]
+    pub fn into_vec(self) -> Vec<Permission> {
+        self.0
+    }
+}
+
+impl From<Vec<Permission>> for PermissionSet {
+    fn from(mut permissions: Vec<Permission>) -> Self {
+        permissions.sort();
+        permissions.dedup();
+        Self(permissions)
+    }
+}
+
+impl FromIterator<Permission> for PermissionSet {
+    fn from_iter<I: IntoIterator<Item = Permission>>(iter: I) -> Self {
+        Self::from(iter.into_iter().collect::<Vec<_>>())
+    }
+}
+
+impl IntoIterator for PermissionSet {
+    type Item = Permission;
+    type IntoIter = std::vec::IntoIter<Permission>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a PermissionSet {
+    type Item = &'a Permission;
+    type IntoIter = std::slice::Iter<'a, Permission>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.0.iter()
+    }
+}
@@ -0,0 +1,49 @@
+//! Server roles
+//! <https://www.guilded.gg/docs/api/roles/Role>
+
+use serde::{Deserialize, Serialize};
+
+/// A role in a server
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Role {
+    /// The id of the role
+    pub id: crate::RoleId,
+    /// The server the role is in
+    pub server_id: crate::ServerId,
+    /// The name of the role
+    pub name: String,
+    /// Is the role shown separately in the member list
+    #[serde(default)]
+    pub is_displayed_separately: bool,
+    /// Can members give themselves this role
+    #[serde(default)]
+    pub is_self_assignable: bool,
+    /// Can anyone mention this role
+    #[serde(default)]
+    pub is_mentionable: bool,
+    /// The permissions the role grants
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    /// The colors of the role, more than one color is shown as a gradient
+    #[serde(default)]
+    pub colors: Vec<crate::Color>,
+    /// The icon of the role
+    /// A media-uri string
+    pub icon: Option<String>,
+    /// Position of the role in the role list, higher is more important
+    #[serde(default)]
+    pub position: i32,
+    /// The bot this role belongs to, if it was created for a bot
+    pub bot_user_id: Option<crate::UserId>,
+    /// Created at timestamp
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Updated at
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl From<Role> for crate::RoleId {
+    fn from(role: Role) -> Self {
+        role.id
+    }
+}
@@ -0,0 +1,29 @@
+//! Availabilities in scheduling channels
+
+use serde::{Deserialize, Serialize};
+
+/// A time range a user is available, posted in a scheduling channel
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SchedulingAvailability {
+    /// The id of the availability
+    pub id: crate::SchedulingAvailabilityId,
+    /// The scheduling channel the availability is in
+    pub channel_id: crate::ChannelId,
+    /// The server the availability is in
+    pub server_id: crate::ServerId,
+    /// The user that is available
+    pub user_id: crate::UserId,
+    /// Start of the availability
+    pub start_date: chrono::DateTime<chrono::Utc>,
+    /// End of the availability
+    pub end_date: chrono::DateTime<chrono::Utc>,
+    /// Created at timestamp
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<SchedulingAvailability> for crate::SchedulingAvailabilityId {
+    fn from(availability: SchedulingAvailability) -> Self {
+        availability.id
+    }
+}
@@ -1,10 +1,31 @@
 //! Information about guilded servers
 //! <https://www.guilded.gg/docs/api/servers/Server>
 
-use serde::Deserialize;
+use chrono::Offset;
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Parse the timezone of a server, unknown timezones become `None`
+///
+/// Guilded sends timezones like `"America/Los Angeles (PST/PDT)"`,
+/// so the abbreviations are dropped and spaces replaced before parsing.
+fn deserialize_timezone<'de, D>(deserializer: D) -> Result<Option<chrono_tz::Tz>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    let Some(raw) = Option::<String>::deserialize(deserializer)? else {
+        return Ok(None);
+    };
+
+    let name = raw.split(" (").next().unwrap_or_default().trim().replace(' ', "_");
+    let timezone = name.parse().ok();
+    if timezone.is_none() {
+        log::warn!("unrecognized server timezone {raw:?}");
+    }
+    Ok(timezone)
+}
 
 /// The type of the server
-#[derive(Debug, Deserialize, Clone, Copy)]
+#[derive(Debug, Deserialize, Serialize, Clone, Copy, PartialEq, Eq)]
 #[serde(rename_all = "lowercase")]
 pub enum ServerType {
     /// A Team server
@@ -26,7 +47,7 @@ pub enum ServerType {
 }
 
 /// Information about a guilded server
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
 #[serde(rename_all = "camelCase")]
 pub struct Server {
     /// The id of the server
@@ -47,8 +68,9 @@ pub struct Server {
     /// The banner of the server
     /// A media-uri string
     pub banner: Option<String>,
-    /// The timezone of the server
-    pub timezone: Option<String>,
+    /// The timezone of the server, `None` if not set or not recognized
+    #[serde(default, deserialize_with = "deserialize_timezone")]
+    pub timezone: Option<chrono_tz::Tz>,
     /// The verified status of the server
     #[serde(default)]
     pub verified: bool,
@@ -56,6 +78,12 @@ pub struct Server {
     pub default_channel_id: Option<crate::ChannelId>,
     /// Created at timestamp
     pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Number of members in the server, only present on some responses
+    #[serde(default)]
+    pub member_count: Option<u32>,
+    /// Number of members currently online, only present on some responses
+    #[serde(default)]
+    pub online_count: Option<u32>,
 }
 
 impl Server {
@@ -64,6 +92,43 @@ impl Server {
     pub fn url(&self) -> String {
         format!("https://www.guilded.gg/{}", self.url)
     }
+
+    /// The current time in the timezone of the server, if it has one
+    ///
+    /// ```
+    /// use vived_models::Server;
+    ///
+    /// let server: Server = serde_json::from_str(r#"{
+    ///     "id": "s", "ownerId": "u", "name": "Vived", "url": "vived",
+    ///     "timezone": "America/Los Angeles (PST/PDT)", "createdAt": "2022-10-01T12:00:00.000Z"
+    /// }"#).unwrap();
+    /// assert_eq!(server.timezone, Some(chrono_tz::America::Los_Angeles));
+    /// assert!(server.local_time().is_some());
+    ///
+    /// let json = serde_json::to_value(&server).unwrap();
+    /// assert_eq!(json["timezone"], "America/Los_Angeles");
+    /// let again: Server = serde_json::from_value(json).unwrap();
+    /// assert_eq!(again.timezone, server.timezone);
+    /// ```
+    #[must_use]
+    pub fn local_time(&self) -> Option<chrono::DateTime<chrono::FixedOffset>> {
+        self.timezone.map(|timezone| {
+            let local = chrono::Utc::now().with_timezone(&timezone);
+            local.with_timezone(&local.offset().fix())
+        })
+    }
+
+    /// Get the member count, if guilded provided it
+    #[must_use]
+    pub fn member_count(&self) -> Option<u32> {
+        self.member_count
+    }
+
+    /// Get the online member count, if guilded provided it
+    #[must_use]
+    pub fn online_count(&self) -> Option<u32> {
+        self.online_count
+    }
 }
 
 impl From<Server> for crate::ServerId {
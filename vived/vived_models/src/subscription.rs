@@ -0,0 +1,43 @@
+//! Server subscriptions
+//! <https://www.guilded.gg/docs/api/serverSubscriptions/ServerSubscriptionTier>
+
+use serde::{Deserialize, Serialize};
+
+/// A tier people can subscribe to in a server
+/// ```
+/// use vived_models::SubscriptionTier;
+///
+/// let tier: SubscriptionTier = serde_json::from_str(r#"{"type": "Gold", "roleId": 7, "cost": 499}"#).unwrap();
+/// assert_eq!(tier.type_, "Gold");
+/// assert_eq!(tier.role_id.unwrap().0, 7);
+/// assert!(tier.description.is_none());
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionTier {
+    /// The type of the tier, like `Gold`, `Silver` or `Copper`
+    #[serde(rename = "type")]
+    pub type_: String,
+    /// Description shown for the tier
+    pub description: Option<String>,
+    /// Role given to subscribers of the tier
+    pub role_id: Option<crate::RoleId>,
+    /// Monthly cost in USD cents
+    pub cost: u32,
+}
+
+/// A user subscribed to a tier of a server
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerSubscription {
+    /// The tier subscribed to
+    pub tier: SubscriptionTier,
+    /// The subscriber
+    pub user_id: crate::UserId,
+    /// The server the tier is in
+    pub server_id: crate::ServerId,
+    /// When the user first subscribed
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// When the subscription was last renewed, `None` if it hasn't been yet
+    pub renewed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
@@ -0,0 +1,125 @@
+//! The full group -> category -> channel structure of a server
+
+use serde::Serialize;
+
+use crate::{Channel, ServerCategory, ServerGroup};
+
+/// A category and the channels in it
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct CategoryNode {
+    /// The category
+    pub category: ServerCategory,
+    /// Channels in the category
+    pub channels: Vec<Channel>,
+}
+
+/// A group and everything in it
+#[derive(Debug, Serialize, Clone, PartialEq, Eq)]
+pub struct GroupNode {
+    /// The group
+    pub group: ServerGroup,
+    /// Categories in the group
+    pub categories: Vec<CategoryNode>,
+    /// Channels in the group that are not in a category
+    pub channels: Vec<Channel>,
+}
+
+/// Categories and channels whose group is not in the list of groups
+#[derive(Debug, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct UnknownGroupNode {
+    /// Categories in an unknown group
+    pub categories: Vec<CategoryNode>,
+    /// Channels in an unknown group, or without a group, that are not in one of those categories
+    pub channels: Vec<Channel>,
+}
+
+/// The structure of a server
+#[derive(Debug, Serialize, Clone, Default, PartialEq, Eq)]
+pub struct ServerTree {
+    /// The groups of the server
+    pub groups: Vec<GroupNode>,
+    /// Everything whose group we don't know about
+    pub unknown_group: UnknownGroupNode,
+}
+
+impl ServerTree {
+    /// Build the tree from flat lists of groups, categories and channels
+    ///
+    /// Categories and channels in an unknown group are put in [`ServerTree::unknown_group`],
+    /// channels in an unknown category are put directly in their group.
+    #[must_use]
+    pub fn new(
+        groups: Vec<ServerGroup>,
+        categories: Vec<ServerCategory>,
+        channels: Vec<Channel>,
+    ) -> Self {
+        let mut groups: Vec<GroupNode> = groups
+            .into_iter()
+            .map(|group| GroupNode {
+                group,
+                categories: Vec::new(),
+                channels: Vec::new(),
+            })
+            .collect();
+        let mut unknown_group = UnknownGroupNode::default();
+
+        for category in categories {
+            let group = groups
+                .iter_mut()
+                .find(|node| node.group.id == category.group_id);
+            let node = CategoryNode {
+                category,
+                channels: Vec::new(),
+            };
+            match group {
+                Some(group) => group.categories.push(node),
+                None => unknown_group.categories.push(node),
+            }
+        }
+
+        for channel in channels {
+            let group = channel
+                .group_id
+                .as_ref()
+                .and_then(|id| groups.iter_mut().find(|node| &node.group.id == id));
+            let (group_categories, loose) = match group {
+                Some(group) => (&mut group.categories, &mut group.channels),
+                None => (&mut unknown_group.categories, &mut unknown_group.channels),
+            };
+
+            let category = channel.category_id.and_then(|id| {
+                group_categories
+                    .iter_mut()
+                    .find(|node| node.category.id == id)
+            });
+            match category {
+                Some(category) => category.channels.push(channel),
+                None => loose.push(channel),
+            }
+        }
+
+        Self {
+            groups,
+            unknown_group,
+        }
+    }
+
+    /// Iterate over every channel in the tree
+    pub fn channels(&self) -> impl Iterator<Item = &Channel> {
+        let known = self.groups.iter().flat_map(|group| {
+            group
+                .categories
+                .iter()
+                .flat_map(|category| category.channels.iter())
+                .chain(group.channels.iter())
+        });
+        let unknown = self
+            .unknown_group
+            .categories
+            .iter()
+            .flat_map(|category| category.channels.iter())
+            .chain(self.unknown_group.channels.iter());
+
+        known.chain(unknown)
+    }
+}
@@ -0,0 +1,76 @@
+//! Webhooks
+//! <https://www.guilded.gg/docs/api/webhook/Webhook>
+
+use serde::{Deserialize, Serialize};
+
+/// A webhook that can post in a channel
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    /// The id of the webhook
+    pub id: crate::WebhookId,
+    /// The name of the webhook
+    pub name: String,
+    /// The server the webhook is in
+    pub server_id: crate::ServerId,
+    /// The channel the webhook posts in
+    pub channel_id: crate::ChannelId,
+    /// Created at timestamp
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    /// Who created the webhook
+    pub created_by: crate::UserId,
+    /// When the webhook was deleted, if it was
+    pub deleted_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// The token used to execute the webhook, guilded only sends it when the webhook is created
+    pub token: Option<String>,
+}
+
+impl Webhook {
+    /// The url to execute the webhook at, `None` if the token is unknown
+    ///
+    /// ```
+    /// use vived_models::Webhook;
+    ///
+    /// let webhook = |extra: &str| -> Webhook {
+    ///     serde_json::from_str(&format!(r#"{{
+    ///         "id": "w", "name": "Alerts", "serverId": "s", "channelId": "c",
+    ///         "createdAt": "2022-10-01T12:00:00Z", "createdBy": "u"{extra}
+    ///     }}"#)).unwrap()
+    /// };
+    ///
+    /// let created = webhook(r#", "token": "secret""#);
+    /// assert_eq!(created.execute_url().unwrap(), "https://media.guilded.gg/webhooks/w/secret");
+    /// assert!(!created.is_deleted());
+    ///
+    /// let fetched = webhook(r#", "deletedAt": "2022-10-02T12:00:00Z""#);
+    /// assert!(fetched.execute_url().is_none());
+    /// assert!(fetched.is_deleted());
+    /// ```
+    #[must_use]
+    pub fn execute_url(&self) -> Option<String> {
+        if let Some(ref token) = self.token {
+            Some(format!(
+                "https://media.guilded.gg/webhooks/{}/{token}",
+                self.id
+            ))
+        } else {
+            log::warn!(
+                "webhook {} has no token, guilded only includes it in the response to creating the webhook",
+                self.id
+            );
+            None
+        }
+    }
+
+    /// Was the webhook deleted
+    #[must_use]
+    pub fn is_deleted(&self) -> bool {
+        self.deleted_at.is_some()
+    }
+}
+
+impl From<Webhook> for crate::WebhookId {
+    fn from(webhook: Webhook) -> Self {
+        webhook.id
+    }
+}
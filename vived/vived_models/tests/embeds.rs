@@ -0,0 +1,131 @@
+//! Embeds as other bots and bridges send them
+//!
+//! The fixtures under `fixtures/embeds` are reconstructed from the shapes seen in messages of other bots,
+//! with the content replaced, they are not verbatim captures.
+
+use vived_models::{Color, Embed, EmbedField, EmbedImage};
+
+fn fixture(name: &str) -> Embed {
+    let path = format!(
+        "{}/tests/fixtures/embeds/{name}.json",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    let raw = std::fs::read_to_string(&path).unwrap();
+    serde_json::from_str(&raw).unwrap_or_else(|error| panic!("{name}: {error}"))
+}
+
+const FIXTURES: [&str; 5] = [
+    "bridge_rich_embed",
+    "wide_color",
+    "null_parts",
+    "missing_fields",
+    "null_text",
+];
+
+#[test]
+fn bridge_rich_embed() {
+    let embed = fixture("bridge_rich_embed");
+
+    assert_eq!(embed.title.as_deref(), Some("New release: v2.4.0"));
+    assert_eq!(embed.color, Some(Color::from(0x58_B9_FF)));
+    assert_eq!(
+        embed.timestamp.unwrap().to_rfc3339(),
+        "2022-10-26T16:04:11.512+00:00"
+    );
+    assert_eq!(embed.footer.unwrap().icon_url, None);
+    assert_eq!(embed.thumbnail.width, Some(128));
+    assert_eq!(embed.image, EmbedImage::default());
+    assert_eq!(
+        embed.author.unwrap().icon_url.as_deref(),
+        Some("https://example.com/avatar.png")
+    );
+    let inline: Vec<bool> = embed.fields.iter().map(|field| field.inline).collect();
+    assert_eq!(inline, [true, true, false]);
+}
+
+#[test]
+fn top_byte_of_color_is_ignored() {
+    assert_eq!(
+        fixture("wide_color").color,
+        Some(Color::from_rgb(255, 0, 0))
+    );
+}
+
+#[test]
+fn null_parts_are_left_out() {
+    let embed = fixture("null_parts");
+
+    assert_eq!(
+        embed,
+        Embed::new().description("Only a description, everything else null")
+    );
+}
+
+#[test]
+fn missing_fields_are_empty() {
+    let embed = fixture("missing_fields");
+
+    assert!(embed.fields.is_empty());
+    assert_eq!(
+        embed.timestamp.unwrap().to_rfc3339(),
+        "2022-10-26T16:04:11+00:00"
+    );
+}
+
+#[test]
+fn null_text_is_empty() {
+    let embed = fixture("null_text");
+
+    assert_eq!(embed.footer.unwrap().text, "");
+    assert_eq!(embed.author.unwrap().name, "");
+    assert_eq!(embed.thumbnail.url, None);
+    assert_eq!(embed.fields, [EmbedField::new("Status", "Online")]);
+}
+
+#[test]
+fn fixtures_survive_a_round_trip() {
+    for name in FIXTURES {
+        let embed = fixture(name);
+        let json = serde_json::to_string(&embed).unwrap();
+        let again: Embed = serde_json::from_str(&json).unwrap();
+        assert_eq!(again, embed, "{name}");
+    }
+}
+
+#[test]
+fn built_embeds_round_trip_cleanly() {
+    let embed = Embed::new()
+        .title("Title")
+        .description("Description")
+        .url("https://example.com")
+        .color(Color::GUILDED_GOLD)
+        .footer("Footer")
+        .timestamp(
+            chrono::DateTime::parse_from_rfc3339("2022-10-26T16:04:11Z")
+                .unwrap()
+                .with_timezone(&chrono::Utc),
+        )
+        .thumbnail(EmbedImage::with_dimensions(
+            "https://example.com/a.png",
+            64,
+            64,
+        ))
+        .author("Author")
+        .field(("Name", "Value"))
+        .field(EmbedField::new("Inline", "Value").inline(true));
+
+    let json = serde_json::to_value(&embed).unwrap();
+    assert_eq!(json["color"], 0xF5_C4_00);
+    assert_eq!(json["timestamp"], "2022-10-26T16:04:11Z");
+    assert!(json["image"].get("url").is_none());
+
+    let again: Embed = serde_json::from_value(json).unwrap();
+    assert_eq!(again, embed);
+}
+
+#[test]
+fn empty_embed_serializes_without_optional_keys() {
+    let json = serde_json::to_value(Embed::new()).unwrap();
+    let keys: Vec<&String> = json.as_object().unwrap().keys().collect();
+    assert_eq!(keys, ["fields", "image", "thumbnail"]);
+}
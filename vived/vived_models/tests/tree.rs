@@ -0,0 +1,110 @@
+//! Building a server tree out of flat lists
+
+use serde_json::json;
+use vived_models::{Channel, ServerCategory, ServerGroup, ServerTree};
+
+fn group(id: &str) -> ServerGroup {
+    serde_json::from_value(json!({
+        "id": id, "serverId": "s", "name": id,
+        "createdAt": "2022-10-26T00:00:00Z", "createdBy": "u"
+    }))
+    .unwrap()
+}
+
+fn category(id: u32, group: &str) -> ServerCategory {
+    serde_json::from_value(json!({
+        "id": id, "serverId": "s", "groupId": group, "name": id.to_string(),
+        "createdAt": "2022-10-26T00:00:00Z"
+    }))
+    .unwrap()
+}
+
+fn channel(id: &str, group: Option<&str>, category: Option<u32>) -> Channel {
+    serde_json::from_value(json!({
+        "id": id, "type": "chat", "name": id, "serverId": "s",
+        "groupId": group, "categoryId": category,
+        "createdAt": "2022-10-26T00:00:00Z", "createdBy": "u"
+    }))
+    .unwrap()
+}
+
+fn names(channels: &[Channel]) -> Vec<&str> {
+    channels
+        .iter()
+        .map(|channel| channel.name.as_str())
+        .collect()
+}
+
+#[test]
+fn channels_end_up_in_their_category() {
+    let tree = ServerTree::new(
+        vec![group("a"), group("b")],
+        vec![category(1, "a"), category(2, "b")],
+        vec![
+            channel("in-1", Some("a"), Some(1)),
+            channel("loose-a", Some("a"), None),
+            channel("in-2", Some("b"), Some(2)),
+            channel("other-category", Some("a"), Some(2)),
+        ],
+    );
+
+    let [ref a, ref b] = tree.groups[..] else {
+        panic!("{tree:?}")
+    };
+    assert_eq!(names(&a.categories[0].channels), ["in-1"]);
+    assert_eq!(names(&a.channels), ["loose-a", "other-category"]);
+    assert_eq!(names(&b.categories[0].channels), ["in-2"]);
+    assert!(b.channels.is_empty());
+    assert_eq!(tree.unknown_group, Default::default());
+}
+
+#[test]
+fn categories_in_unknown_groups_are_kept() {
+    let tree = ServerTree::new(
+        vec![group("a")],
+        vec![category(1, "a"), category(2, "gone")],
+        vec![
+            channel("in-2", Some("gone"), Some(2)),
+            channel("loose-gone", Some("gone"), None),
+            channel("no-group", None, None),
+        ],
+    );
+
+    assert_eq!(tree.groups[0].categories.len(), 1);
+    let unknown = &tree.unknown_group;
+    assert_eq!(unknown.categories.len(), 1);
+    assert_eq!(unknown.categories[0].category.id.0, 2);
+    assert_eq!(names(&unknown.categories[0].channels), ["in-2"]);
+    assert_eq!(names(&unknown.channels), ["loose-gone", "no-group"]);
+}
+
+#[test]
+fn no_channel_is_lost() {
+    let channels = vec![
+        channel("1", Some("a"), Some(1)),
+        channel("2", Some("a"), None),
+        channel("3", Some("gone"), Some(2)),
+        channel("4", Some("gone"), Some(3)),
+        channel("5", None, Some(1)),
+    ];
+    let tree = ServerTree::new(
+        vec![group("a")],
+        vec![category(1, "a"), category(2, "gone")],
+        channels,
+    );
+
+    let mut seen: Vec<&str> = tree
+        .channels()
+        .map(|channel| channel.name.as_str())
+        .collect();
+    seen.sort_unstable();
+    assert_eq!(seen, ["1", "2", "3", "4", "5"]);
+}
+
+#[test]
+fn empty_server() {
+    assert_eq!(
+        ServerTree::new(vec![], vec![], vec![]),
+        ServerTree::default()
+    );
+}
@@ -1,15 +1,28 @@
 //! Websocket client
 
+use std::future::Future;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::Duration;
+
 use futures_util::{SinkExt, StreamExt};
-use tokio::sync::broadcast;
+use tokio::sync::{broadcast, mpsc, watch};
+use tokio::time::{Instant, Interval};
 
-/// Where to connect to.
-const WEBSOCKET_ENDPOINT: &str = "wss://www.guilded.gg/websocket/v1";
-// const WEBSOCKET_ENDPOINT: &str = "wss://gateway.discord.gg/?v=10&encoding=json";
+use crate::close::{GatewayClose, Reconnect};
+use crate::config::MIN_PING_INTERVAL;
+use crate::dispatcher::Dispatcher;
+use crate::events::GuildedEvent;
+use crate::metrics::{ConnectionMetrics, EventEnvelope};
+use crate::{ParseFailureHook, WebsocketConfig};
 
 use tokio_tungstenite::tungstenite::{self, client::IntoClientRequest};
 
+/// Event type parse failures are counted under when the frame has no readable `t` field
+pub const UNKNOWN_EVENT_TYPE: &str = "unknown";
+
 /// Websocket stream
+///
+/// This doesn't implement `Debug`, so nothing holding it can derive it.
 type WebStream =
     tokio_tungstenite::WebSocketStream<tokio_tungstenite::MaybeTlsStream<tokio::net::TcpStream>>;
 
@@ -21,49 +34,531 @@ async fn create_connection(
     Ok(connection)
 }
 
+/// Build the request used to connect, resuming after `last_message_id` if given.
+///
+/// The error is boxed since it is much bigger than the request.
+fn build_request(
+    endpoint: &str,
+    token: &str,
+    last_message_id: Option<&str>,
+) -> Result<tungstenite::handshake::client::Request, Box<tungstenite::Error>> {
+    let user_agent = format!(
+        "library: vived, version: {}, rustc version: {}",
+        version::version!(),
+        rustc_version_runtime::version()
+    );
+
+    let mut request = endpoint.into_client_request()?;
+    let headers = request.headers_mut();
+    headers.insert(
+        "Authorization",
+        format!("Bearer {token}")
+            .parse()
+            .map_err(tungstenite::Error::from)?,
+    );
+    headers.insert(
+        "User-Agent",
+        user_agent.parse().map_err(tungstenite::Error::from)?,
+    );
+    if let Some(last_message_id) = last_message_id {
+        headers.insert(
+            "guilded-last-message-id",
+            last_message_id.parse().map_err(tungstenite::Error::from)?,
+        );
+    }
+
+    Ok(request)
+}
+
 /// Connect to the websocket with the provided token.
 ///
 /// `event_capacity` is the capacity of the event queue.
 /// see [`tokio::sync::broadcast::channel`] for more info.
 ///
+/// Shorthand for [`connect_to_websocket_with_config`] with the default config.
+///
 /// # Errors
 /// If the token is an invalid header value or the connection fails.
 pub async fn connect_to_websocket(
     token: &str,
     event_capacity: usize,
 ) -> Result<broadcast::Receiver<crate::events::GuildedEvent>, tungstenite::Error> {
-    let user_agent = format!(
-        "library: vived, version: {}, rustc version: {}",
-        version::version!(),
-        rustc_version_runtime::version()
-    );
+    connect_to_websocket_with_config(
+        token,
+        WebsocketConfig::default().event_capacity(event_capacity),
+    )
+    .await
+}
 
-    let mut request = WEBSOCKET_ENDPOINT.into_client_request()?;
-    let headers = request.headers_mut();
-    headers.insert("Authorization", format!("Bearer {token}").parse()?);
-    headers.insert("User-Agent", user_agent.parse()?);
+/// Connect to the websocket with the provided token and config.
+///
+/// If the connection drops we reconnect according to the config,
+/// the returned receiver keeps working across reconnects.
+///
+/// # Errors
+/// If the token is an invalid header value or the first connection fails.
+pub async fn connect_to_websocket_with_config(
+    token: &str,
+    config: WebsocketConfig,
+) -> Result<broadcast::Receiver<crate::events::GuildedEvent>, tungstenite::Error> {
+    WebSocketClient::connect(token, config)
+        .await
+        .map(|client| client.events)
+}
+
+/// Where the event loop delivers events
+#[derive(Debug)]
+struct Outputs {
+    /// Plain events
+    events: broadcast::Sender<GuildedEvent>,
+    /// Events with receipt metadata, only sent while someone listens
+    envelopes: broadcast::Sender<EventEnvelope>,
+    /// Metrics of the connection
+    metrics: Arc<Mutex<ConnectionMetrics>>,
+    /// Called with frames that couldn't be parsed
+    on_parse_failure: Option<ParseFailureHook>,
+    /// Dispatcher fed straight from the connection, and its inbox
+    dispatcher: Option<(Dispatcher, mpsc::Sender<GuildedEvent>)>,
+}
+
+impl Outputs {
+    /// Is anyone still listening for events
+    fn has_receivers(&self) -> bool {
+        self.events.receiver_count() > 0
+            || self
+                .dispatcher
+                .as_ref()
+                .is_some_and(|dispatcher| dispatcher.0.subscriber_count() > 0)
+    }
+
+    /// Log and count a frame that couldn't be parsed, and hand it to the hook
+    fn parse_failed(&self, raw_frame: &str, event_type: Option<&str>, error: &serde_json::Error) {
+        let event_type = event_type.unwrap_or(UNKNOWN_EVENT_TYPE);
+        log::error!("error deserializing {event_type} event: {error}");
+        log::debug!("raw event: {raw_frame}");
+
+        crate::instrument::parse_failure(event_type);
+        self.metrics
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .record_parse_failure(event_type);
+
+        if let Some(ref hook) = self.on_parse_failure {
+            hook.call(raw_frame, error);
+        }
+    }
+}
+
+/// Why the connection stopped for good
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum StopReason {
+    /// Guilded rejected the token, with the close frame it sent
+    ///
+    /// `None` if the token was rejected while reconnecting, before a connection was made.
+    AuthenticationFailed(Option<GatewayClose>),
+    /// Reconnecting failed [`WebsocketConfig::max_reconnect_attempts`] times in a row
+    ReconnectAttemptsExhausted,
+    /// No one was listening for events anymore
+    NoReceivers,
+}
+
+/// State of the connection, see [`WebSocketClient::watch_status`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum ConnectionStatus {
+    /// Connected and receiving events
+    Connected,
+    /// The connection dropped and we are reconnecting, with the close frame guilded sent if there was one
+    Reconnecting(Option<GatewayClose>),
+    /// The connection is gone and won't come back
+    Stopped(StopReason),
+}
+
+/// A websocket connection, that also tracks the state of the connection
+///
+/// The connection stays open as long as the client or any receiver from [`WebSocketClient::subscribe`] is alive.
+pub struct WebSocketClient {
+    /// Kept so new receivers can be made
+    events: broadcast::Receiver<crate::events::GuildedEvent>,
+    /// Used to make envelope receivers, a sender so no envelopes are made while no one listens
+    envelopes: broadcast::Sender<EventEnvelope>,
+    /// State of the connection, updated by the connection loop
+    status: watch::Receiver<ConnectionStatus>,
+    /// Metrics of the connection
+    metrics: Arc<Mutex<ConnectionMetrics>>,
+}
+
+impl Clone for WebSocketClient {
+    fn clone(&self) -> Self {
+        Self {
+            events: self.events.resubscribe(),
+            envelopes: self.envelopes.clone(),
+            status: self.status.clone(),
+            metrics: Arc::clone(&self.metrics),
+        }
+    }
+}
+
+/// Shows the state of the connection instead of the channel internals,
+/// like `WebSocketClient { connected: true, event_count: 42 }`
+impl std::fmt::Debug for WebSocketClient {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("WebSocketClient")
+            .field("connected", &self.is_connected())
+            .field("event_count", &self.event_count())
+            .finish()
+    }
+}
+
+impl WebSocketClient {
+    /// Connect to the websocket with the provided token and config, see [`connect_to_websocket_with_config`]
+    ///
+    /// # Errors
+    /// If the token is an invalid header value or the first connection fails.
+    pub async fn connect(token: &str, config: WebsocketConfig) -> Result<Self, tungstenite::Error> {
+        // fail early on a bad token, instead of in the reconnect loop
+        let request = build_request(&config.endpoint, token, config.last_message_id.as_deref())
+            .map_err(|error| *error)?;
+
+        log::debug!("connecting to websocket");
+        let connection = create_connection(request).await?;
+        let (tx, rx) = broadcast::channel(config.event_capacity);
+        let (envelopes, _) = broadcast::channel(config.event_capacity);
+        let (status_tx, status) = watch::channel(ConnectionStatus::Connected);
+        crate::instrument::describe();
+        crate::instrument::connected(true);
+        let metrics = Arc::new(Mutex::new(ConnectionMetrics::new(config.latency_window)));
+
+        let outputs = Outputs {
+            events: tx,
+            envelopes: envelopes.clone(),
+            metrics: Arc::clone(&metrics),
+            on_parse_failure: config.on_parse_failure.clone(),
+            dispatcher: config.dispatcher.clone().map(|(dispatcher, capacity)| {
+                let (inbox, receiver) = mpsc::channel(capacity);
+                dispatcher.feed(receiver);
+                (dispatcher, inbox)
+            }),
+        };
+        tokio::spawn(connection_loop(
+            token.to_owned(),
+            config,
+            connection,
+            outputs,
+            status_tx,
+        ));
+
+        Ok(Self {
+            events: rx,
+            envelopes,
+            status,
+            metrics,
+        })
+    }
+
+    /// Get a new receiver for the events, it only gets events sent after this is called
+    #[must_use]
+    pub fn subscribe(&self) -> broadcast::Receiver<crate::events::GuildedEvent> {
+        self.events.resubscribe()
+    }
+
+    /// Get a receiver for the events together with when they were received, see [`EventEnvelope`]
+    ///
+    /// Envelopes are only made while at least one of these receivers is alive.
+    #[must_use]
+    pub fn subscribe_envelopes(&self) -> broadcast::Receiver<EventEnvelope> {
+        self.envelopes.subscribe()
+    }
+
+    /// A snapshot of the metrics of this connection, kept across reconnects
+    #[must_use]
+    pub fn metrics(&self) -> ConnectionMetrics {
+        self.metrics
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+
+    /// How many events were received over this connection, across reconnects
+    #[must_use]
+    pub fn event_count(&self) -> u64 {
+        self.metrics
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .total_events()
+    }
+
+    /// Average latency of recent events, see [`ConnectionMetrics::average_latency`]
+    #[must_use]
+    pub fn average_latency(&self) -> Option<Duration> {
+        self.metrics
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .average_latency(std::time::Instant::now())
+    }
+
+    /// Is the websocket currently connected
+    ///
+    /// This is `false` while reconnecting, and stays `false` if we gave up reconnecting.
+    #[must_use]
+    pub fn is_connected(&self) -> bool {
+        *self.status.borrow() == ConnectionStatus::Connected
+    }
+
+    /// The current state of the connection
+    #[must_use]
+    pub fn status(&self) -> ConnectionStatus {
+        self.status.borrow().clone()
+    }
+
+    /// Get a receiver that is notified whenever the state of the connection changes
+    ///
+    /// How the client reconnects depends on the close frame guilded sent, see [`crate::close`].
+    /// ```no_run
+    /// use vived_websocket::client::ConnectionStatus;
+    /// # async fn run(client: vived_websocket::WebSocketClient) {
+    /// let mut status = client.watch_status();
+    /// while status.changed().await.is_ok() {
+    ///     if let ConnectionStatus::Reconnecting(ref close) = *status.borrow() {
+    ///         log::warn!("connection lost: {close:?}");
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn watch_status(&self) -> watch::Receiver<ConnectionStatus> {
+        self.status.clone()
+    }
 
-    log::debug!("connecting to websocket");
-    let connection = create_connection(request).await?;
-    let (tx, rx) = tokio::sync::broadcast::channel(event_capacity);
+    /// Wait until the websocket is connected, returns right away if it already is
+    ///
+    /// This never finishes if we gave up reconnecting, use [`WebSocketClient::stopped`] to find out about that.
+    pub fn wait_until_connected(&self) -> impl Future<Output = ()> {
+        let mut status = self.status.clone();
+        async move {
+            loop {
+                if *status.borrow_and_update() == ConnectionStatus::Connected {
+                    return;
+                }
+                if status.changed().await.is_err() {
+                    std::future::pending::<()>().await;
+                }
+            }
+        }
+    }
+
+    /// Wait until the connection is gone for good, and get why
+    ///
+    /// A rejected token stops the connection right away, so there is no point reconnecting with it:
+    /// ```no_run
+    /// use vived_websocket::client::StopReason;
+    /// use vived_websocket::{WebSocketClient, WebsocketConfig};
+    ///
+    /// # async fn run() -> Result<(), Box<dyn std::error::Error>> {
+    /// let client = WebSocketClient::connect("token", WebsocketConfig::default()).await?;
+    /// let mut events = client.subscribe();
+    /// tokio::select! {
+    ///     reason = client.stopped() => match reason {
+    ///         StopReason::AuthenticationFailed(close) => log::error!("token rejected: {close:?}"),
+    ///         reason => log::error!("websocket stopped: {reason:?}"),
+    ///     },
+    ///     event = events.recv() => println!("{event:?}"),
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn stopped(&self) -> impl Future<Output = StopReason> {
+        let mut status = self.status.clone();
+        async move {
+            loop {
+                if let ConnectionStatus::Stopped(ref reason) = *status.borrow_and_update() {
+                    return reason.clone();
+                }
+                if status.changed().await.is_err() {
+                    // the connection loop always says why it stopped before it ends
+                    return StopReason::NoReceivers;
+                }
+            }
+        }
+    }
+}
+
+/// Run the event loop, reconnecting when the connection drops.
+///
+/// How to reconnect depends on the close frame guilded sent, see [`crate::close`].
+async fn connection_loop(
+    token: String,
+    mut config: WebsocketConfig,
+    mut connection: WebStream,
+    outputs: Outputs,
+    status: watch::Sender<ConnectionStatus>,
+) {
+    loop {
+        let close = event_loop(
+            connection,
+            &outputs,
+            &mut config.last_message_id,
+            config.ping_interval,
+        )
+        .await;
+        crate::instrument::connected(false);
+
+        if !outputs.has_receivers() {
+            log::debug!("no one is listening for events anymore, closing websocket");
+            status.send_replace(ConnectionStatus::Stopped(StopReason::NoReceivers));
+            return;
+        }
+
+        let reconnect_how = match close {
+            Some(ref close) => {
+                log::warn!("websocket closed by guilded: {close}");
+                close.reason.reconnect()
+            }
+            None => Reconnect::WithBackoff,
+        };
+        if reconnect_how == Reconnect::Never {
+            log::error!("guilded rejected the token, not reconnecting");
+            let reason = StopReason::AuthenticationFailed(close);
+            status.send_replace(ConnectionStatus::Stopped(reason));
+            return;
+        }
+        status.send_replace(ConnectionStatus::Reconnecting(close));
+
+        let immediately = reconnect_how == Reconnect::Immediately;
+        connection = match reconnect(&token, &config, immediately).await {
+            Ok(reconnected) => reconnected,
+            Err(reason) => {
+                status.send_replace(ConnectionStatus::Stopped(reason));
+                return;
+            }
+        };
+        status.send_replace(ConnectionStatus::Connected);
+        crate::instrument::reconnected();
+        crate::instrument::connected(true);
+    }
+}
+
+/// Does the error mean guilded rejected the token during the handshake
+fn is_unauthorized(error: &tungstenite::Error) -> bool {
+    match *error {
+        tungstenite::Error::Http(ref response) => matches!(response.status().as_u16(), 401 | 403),
+        _ => false,
+    }
+}
+
+/// Try to reconnect, skipping the delay before the first attempt if `immediately` is set.
+///
+/// # Errors
+/// If we ran out of attempts, or guilded rejected the token.
+async fn reconnect(
+    token: &str,
+    config: &WebsocketConfig,
+    immediately: bool,
+) -> Result<WebStream, StopReason> {
+    let mut attempt = 0;
+    loop {
+        if config
+            .max_reconnect_attempts
+            .is_some_and(|max| attempt >= max)
+        {
+            log::error!("websocket connection lost, giving up after {attempt} reconnect attempts");
+            return Err(StopReason::ReconnectAttemptsExhausted);
+        }
+
+        if immediately && attempt == 0 {
+            log::warn!("websocket connection lost, reconnecting right away");
+        } else {
+            let delay = config.reconnect_backoff.delay(attempt);
+            log::warn!("websocket connection lost, reconnecting in {delay:?}");
+            tokio::time::sleep(delay).await;
+        }
+
+        let request = build_request(&config.endpoint, token, config.last_message_id.as_deref());
+        let result = match request {
+            Ok(request) => create_connection(request).await,
+            Err(error) => Err(*error),
+        };
+        match result {
+            Ok(connection) => {
+                log::info!("reconnected to websocket");
+                return Ok(connection);
+            }
+            Err(error) if is_unauthorized(&error) => {
+                log::error!("guilded rejected the token while reconnecting: {error}");
+                return Err(StopReason::AuthenticationFailed(None));
+            }
+            Err(error) => log::error!("error reconnecting to websocket: {error}"),
+        }
 
-    tokio::spawn(event_loop(connection, tx));
+        attempt += 1;
+    }
+}
 
-    Ok(rx)
+/// Wait for the next ping, forever if pings are turned off.
+async fn next_ping(pings: &mut Option<Interval>) {
+    match *pings {
+        Some(ref mut pings) => {
+            pings.tick().await;
+        }
+        None => std::future::pending().await,
+    }
 }
 
-/// The event loop for the websocket.
-async fn event_loop(connection: WebStream, tx: broadcast::Sender<crate::events::GuildedEvent>) {
+/// The event loop for the websocket, returns when the connection is closed.
+///
+/// Returns the close frame if guilded closed the connection, `None` if it dropped some other way.
+/// If `ping_interval` is set we ping guilded on that interval,
+/// and treat the connection as dropped if no pong came back within two intervals.
+/// Time spent waiting for room in the dispatcher's inbox is left out of that.
+async fn event_loop(
+    connection: WebStream,
+    outputs: &Outputs,
+    last_message_id: &mut Option<String>,
+    ping_interval: Option<Duration>,
+) -> Option<GatewayClose> {
     let (mut write, mut read) = connection.split();
 
-    while let Some(message) = read.next().await {
+    // the field can be set directly, so the config can't be trusted to have clamped it
+    let ping_interval = ping_interval.map(|interval| interval.max(MIN_PING_INTERVAL));
+    // an interval too far in the future to represent would never tick anyway
+    let mut pings = ping_interval.and_then(|interval| {
+        let start = Instant::now().checked_add(interval)?;
+        Some(tokio::time::interval_at(start, interval))
+    });
+    let mut last_pong = Instant::now();
+
+    loop {
+        let message = tokio::select! {
+            message = read.next() => match message {
+                Some(message) => message,
+                None => return None,
+            },
+            () = next_ping(&mut pings) => {
+                let timeout = ping_interval.unwrap_or_default().saturating_mul(2);
+                if last_pong.elapsed() > timeout {
+                    log::warn!("no pong from websocket in {timeout:?}, assuming the connection dropped");
+                    return None;
+                }
+
+                if let Err(e) = write.send(tungstenite::Message::Ping(Vec::new())).await {
+                    log::error!("error sending ping: {e}");
+                    return None;
+                }
+                continue;
+            }
+        };
+
         let message = match message {
             Ok(message) => message,
             Err(e) => {
                 log::error!("error reading from websocket: {}", e);
-                continue;
+                return None;
             }
         };
+        // the only clock reads for events, everything after is handed these
+        let received_at = chrono::Utc::now();
+        let received_instant = std::time::Instant::now();
 
         let message = match message {
             tungstenite::Message::Text(text) => text,
@@ -80,53 +575,112 @@ async fn event_loop(connection: WebStream, tx: broadcast::Sender<crate::events::
                 }
                 continue;
             }
-            _ => {
-                log::error!("received non-text message from websocket");
+            tungstenite::Message::Pong(_) => {
+                last_pong = Instant::now();
                 continue;
             }
-        };
-
-        let raw_event_data: serde_json::Value = match serde_json::from_str(&message) {
-            Ok(raw_event_data) => raw_event_data,
-            Err(e) => {
-                log::error!("error deserializing event data: {}", e);
-                continue;
+            tungstenite::Message::Close(frame) => {
+                // sends the answer tungstenite queued, the connection is going away either way
+                let _ = write.close().await;
+                return Some(GatewayClose::from_frame(frame.as_ref()));
             }
-        };
-
-        let opcode = match raw_event_data.get("op").and_then(|v| v.as_u64()) {
-            Some(opcode) => opcode,
-            None => {
-                log::error!("received event without opcode");
+            tungstenite::Message::Frame(_) => {
+                log::error!("received non-text message from websocket");
                 continue;
             }
         };
 
-        match opcode {
-            0 => {
-                let event: crate::events::GuildedEvent = match serde_json::from_str(&message) {
-                    Ok(event) => event,
-                    Err(e) => {
-                        log::error!("error deserializing event: {e}");
-                        log::debug!("raw event: {message}");
-                        continue;
-                    }
-                };
-
-                log::debug!("received event: {:?}", event);
-
-                if let Err(e) = tx.send(event) {
-                    log::error!("error sending event: {}", e);
+        // pongs can't be read while the dispatcher is full, so that wait doesn't count against the deadline
+        let handling = Instant::now();
+        handle_frame(
+            &message,
+            outputs,
+            last_message_id,
+            received_at,
+            received_instant,
+        )
+        .await;
+        last_pong += handling.elapsed();
+    }
+}
+
+/// Parse a text frame and hand the event in it to `outputs`, remembering its sequence id
+///
+/// Waits while the inbox of the dispatcher is full.
+async fn handle_frame(
+    message: &str,
+    outputs: &Outputs,
+    last_message_id: &mut Option<String>,
+    received_at: chrono::DateTime<chrono::Utc>,
+    received_instant: std::time::Instant,
+) {
+    let raw_event_data: serde_json::Value = match serde_json::from_str(message) {
+        Ok(raw_event_data) => raw_event_data,
+        Err(e) => {
+            outputs.parse_failed(message, None, &e);
+            return;
+        }
+    };
+
+    let sequence = raw_event_data
+        .get("s")
+        .and_then(serde_json::Value::as_str)
+        .map(str::to_owned);
+    if sequence.is_some() {
+        last_message_id.clone_from(&sequence);
+    }
+
+    let Some(opcode) = raw_event_data.get("op").and_then(serde_json::Value::as_u64) else {
+        log::error!("received event without opcode");
+        return;
+    };
+
+    match opcode {
+        0 => {
+            let event: crate::events::GuildedEvent = match serde_json::from_str(message) {
+                Ok(event) => event,
+                Err(e) => {
+                    // the frame is valid json, so the type can be read even if the event doesn't match our models
+                    let event_type = raw_event_data.get("t").and_then(serde_json::Value::as_str);
+                    outputs.parse_failed(message, event_type, &e);
+                    return;
                 }
+            };
+
+            log::debug!("received event: {:?}", event);
+            crate::instrument::event(event.kind());
+
+            let envelope = EventEnvelope {
+                event,
+                sequence,
+                received_at,
+                received_instant,
+            };
+            outputs
+                .metrics
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .record_envelope(&envelope);
+
+            if outputs.envelopes.receiver_count() > 0 {
+                // can only fail if every receiver was dropped in the meantime
+                let _ = outputs.envelopes.send(envelope.clone());
             }
-            1 => {
-                // TODO: Heartbeat? I don't actually know if this is handled by the library or the user 
-                // TODO: I didn't find anything when searching online
+            if let Some((_, ref inbox)) = outputs.dispatcher {
+                // the dispatcher only stops reading once this sender is dropped
+                let _ = inbox.send(envelope.event.clone()).await;
             }
-            _ => {
-                log::error!("received unknown opcode: {}", opcode);
-                log::debug!("raw event: {message}");
+            if let Err(e) = outputs.events.send(envelope.event) {
+                log::error!("error sending event: {}", e);
             }
         }
+        1 => {
+            // TODO: Heartbeat? I don't actually know if this is handled by the library or the user
+            // TODO: I didn't find anything when searching online
+        }
+        _ => {
+            log::error!("received unknown opcode: {}", opcode);
+            log::debug!("raw event: {message}");
+        }
     }
 }
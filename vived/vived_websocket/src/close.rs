@@ -0,0 +1,133 @@
+//! Why guilded closed the websocket, and whether reconnecting makes sense
+//!
+//! Guilded doesn't document its close codes, so [`GatewayCloseReason`] maps the standard codes (RFC 6455)
+//! and the 4xxx codes gateways commonly use for a rejected token.
+//! The raw code and reason are always kept in [`GatewayClose`], for the cases that end up as [`GatewayCloseReason::Other`].
+//! ```
+//! use vived_websocket::close::{GatewayCloseReason, Reconnect};
+//!
+//! let fixtures = [
+//!     (1000, GatewayCloseReason::Normal, Reconnect::WithBackoff),
+//!     (1001, GatewayCloseReason::Restart, Reconnect::Immediately),
+//!     (1012, GatewayCloseReason::Restart, Reconnect::Immediately),
+//!     (1013, GatewayCloseReason::TryAgainLater, Reconnect::WithBackoff),
+//!     (1008, GatewayCloseReason::PolicyViolation, Reconnect::WithBackoff),
+//!     (4001, GatewayCloseReason::AuthenticationFailed, Reconnect::Never),
+//!     (4003, GatewayCloseReason::AuthenticationFailed, Reconnect::Never),
+//!     (4004, GatewayCloseReason::AuthenticationFailed, Reconnect::Never),
+//!     (1011, GatewayCloseReason::ServerError, Reconnect::WithBackoff),
+//!     (1006, GatewayCloseReason::Other, Reconnect::WithBackoff),
+//!     (4999, GatewayCloseReason::Other, Reconnect::WithBackoff),
+//! ];
+//! for (code, reason, reconnect) in fixtures {
+//!     assert_eq!(GatewayCloseReason::from_code(code), reason, "{code}");
+//!     assert_eq!(reason.reconnect(), reconnect, "{code}");
+//! }
+//! ```
+
+use tokio_tungstenite::tungstenite::protocol::CloseFrame;
+
+/// Code used when the close frame had no code, as RFC 6455 reserves it for
+const NO_STATUS_RECEIVED: u16 = 1005;
+
+/// What a close code means, see the [module docs](self)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum GatewayCloseReason {
+    /// `1000`, the connection was closed on purpose
+    Normal,
+    /// `1001` or `1012`, guilded is restarting or going away for a moment
+    Restart,
+    /// `1013`, guilded is overloaded and asks to come back later
+    TryAgainLater,
+    /// `1008`, guilded didn't like something the connection did, not necessarily the token
+    PolicyViolation,
+    /// `4001`, `4003` or `4004`, the token was rejected
+    AuthenticationFailed,
+    /// `1011`, something went wrong on guilded's side
+    ServerError,
+    /// Any other code
+    Other,
+}
+
+impl GatewayCloseReason {
+    /// What a close code means
+    #[must_use]
+    pub fn from_code(code: u16) -> Self {
+        match code {
+            1000 => Self::Normal,
+            1001 | 1012 => Self::Restart,
+            1013 => Self::TryAgainLater,
+            1008 => Self::PolicyViolation,
+            4001 | 4003 | 4004 => Self::AuthenticationFailed,
+            1011 => Self::ServerError,
+            _ => Self::Other,
+        }
+    }
+
+    /// How to reconnect after a close for this reason
+    #[must_use]
+    pub fn reconnect(self) -> Reconnect {
+        match self {
+            Self::AuthenticationFailed => Reconnect::Never,
+            Self::Restart => Reconnect::Immediately,
+            Self::Normal
+            | Self::TryAgainLater
+            | Self::PolicyViolation
+            | Self::ServerError
+            | Self::Other => Reconnect::WithBackoff,
+        }
+    }
+}
+
+/// How to reconnect after the connection was closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reconnect {
+    /// Don't, it won't work with the same token
+    Never,
+    /// Right away, further attempts use the backoff
+    Immediately,
+    /// After waiting according to [`crate::WebsocketConfig::reconnect_backoff`]
+    WithBackoff,
+}
+
+/// A close frame guilded sent
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GatewayClose {
+    /// What the code means
+    pub reason: GatewayCloseReason,
+    /// The raw close code
+    pub code: u16,
+    /// The reason guilded gave, can be empty
+    pub message: String,
+}
+
+impl GatewayClose {
+    /// A close with the given code and reason
+    #[must_use]
+    pub fn new(code: u16, message: impl Into<String>) -> Self {
+        Self {
+            reason: GatewayCloseReason::from_code(code),
+            code,
+            message: message.into(),
+        }
+    }
+
+    /// Read a close frame, a close without a frame gets code `1005`
+    pub(crate) fn from_frame(frame: Option<&CloseFrame<'_>>) -> Self {
+        match frame {
+            Some(frame) => Self::new(u16::from(frame.code), frame.reason.as_ref()),
+            None => Self::new(NO_STATUS_RECEIVED, ""),
+        }
+    }
+}
+
+impl std::fmt::Display for GatewayClose {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.message.is_empty() {
+            write!(f, "{:?} ({})", self.reason, self.code)
+        } else {
+            write!(f, "{:?} ({}): {}", self.reason, self.code, self.message)
+        }
+    }
+}
@@ -0,0 +1,276 @@
+//! Settings for the websocket connection
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::dispatcher::Dispatcher;
+
+/// Where to connect to by default
+const WEBSOCKET_ENDPOINT: &str = "wss://www.guilded.gg/websocket/v1";
+/// Default capacity of the event queue
+const EVENT_CAPACITY: usize = 100;
+/// Default delay before the first reconnect attempt, in milliseconds
+const INITIAL_BACKOFF_MS: u64 = 500;
+/// Default max delay between reconnect attempts, in seconds
+const MAX_BACKOFF_SECS: u64 = 60;
+/// Shortest interval between pings, shorter ones are raised to this
+pub(crate) const MIN_PING_INTERVAL: Duration = Duration::from_secs(1);
+/// Default window latency is averaged over, in seconds
+const LATENCY_WINDOW_SECS: u64 = 60;
+
+/// How long to wait between reconnect attempts
+///
+/// The delay starts at `initial` and is multiplied by `multiplier` after each failed attempt, up to `max`.
+/// ```
+/// use std::time::Duration;
+/// use vived_websocket::BackoffConfig;
+///
+/// let backoff = BackoffConfig::default()
+///     .initial(Duration::from_secs(1))
+///     .max(Duration::from_secs(5));
+///
+/// assert_eq!(backoff.delay(0), Duration::from_secs(1));
+/// assert_eq!(backoff.delay(2), Duration::from_secs(4));
+/// assert_eq!(backoff.delay(10), Duration::from_secs(5));
+/// ```
+#[derive(Debug, Clone, Copy)]
+#[non_exhaustive]
+pub struct BackoffConfig {
+    /// Delay before the first attempt
+    pub initial: Duration,
+    /// Longest delay between attempts
+    pub max: Duration,
+    /// What the delay is multiplied by after each failed attempt
+    pub multiplier: u32,
+}
+
+impl Default for BackoffConfig {
+    fn default() -> Self {
+        Self {
+            initial: Duration::from_millis(INITIAL_BACKOFF_MS),
+            max: Duration::from_secs(MAX_BACKOFF_SECS),
+            multiplier: 2,
+        }
+    }
+}
+
+impl BackoffConfig {
+    /// Set the delay before the first attempt
+    #[must_use]
+    pub fn initial(mut self, initial: Duration) -> Self {
+        self.initial = initial;
+        self
+    }
+
+    /// Set the longest delay between attempts
+    #[must_use]
+    pub fn max(mut self, max: Duration) -> Self {
+        self.max = max;
+        self
+    }
+
+    /// Set what the delay is multiplied by after each failed attempt
+    #[must_use]
+    pub fn multiplier(mut self, multiplier: u32) -> Self {
+        self.multiplier = multiplier;
+        self
+    }
+
+    /// The delay before the given attempt, starting at 0
+    #[must_use]
+    pub fn delay(&self, attempt: usize) -> Duration {
+        let mut delay = self.initial;
+        for _ in 0..attempt {
+            if delay >= self.max {
+                break;
+            }
+            delay = delay.saturating_mul(self.multiplier);
+        }
+        delay.min(self.max)
+    }
+}
+
+/// Signature of a [`ParseFailureHook`]
+type ParseFailureFn = dyn Fn(&str, &serde_json::Error) + Send + Sync;
+
+/// Called with the raw frame when an event can't be parsed, see [`WebsocketConfig::on_parse_failure`]
+#[derive(Clone)]
+pub struct ParseFailureHook(Arc<ParseFailureFn>);
+
+impl ParseFailureHook {
+    /// Call the hook
+    pub(crate) fn call(&self, raw_frame: &str, error: &serde_json::Error) {
+        (self.0)(raw_frame, error);
+    }
+}
+
+impl std::fmt::Debug for ParseFailureHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("ParseFailureHook")
+    }
+}
+
+/// Settings for the websocket connection, see [`crate::connect_to_websocket_with_config`]
+#[derive(Debug, Clone)]
+#[non_exhaustive]
+pub struct WebsocketConfig {
+    /// Capacity of the event queue, see [`tokio::sync::broadcast::channel`]
+    pub event_capacity: usize,
+    /// How many times in a row to try reconnecting after the connection drops
+    ///
+    /// `None` (the default) keeps trying forever, `Some(0)` never reconnects.
+    pub max_reconnect_attempts: Option<usize>,
+    /// How long to wait between reconnect attempts
+    pub reconnect_backoff: BackoffConfig,
+    /// Id of the last event that was received, guilded replays the events after it on connect
+    ///
+    /// This is kept up to date while connected, so events missed during a reconnect are replayed too.
+    pub last_message_id: Option<String>,
+    /// How often to ping guilded to check the connection is still alive, `None` to never ping
+    ///
+    /// If no pong comes back within two intervals the connection is treated as dropped and we reconnect.
+    /// Intervals shorter than a second are treated as a second.
+    pub ping_interval: Option<Duration>,
+    /// How far back [`crate::metrics::ConnectionMetrics`] averages the latency of events
+    pub latency_window: Duration,
+    /// Called when an event frame can't be parsed, see [`WebsocketConfig::on_parse_failure`]
+    pub on_parse_failure: Option<ParseFailureHook>,
+    /// Url of the websocket, guilded's by default
+    pub endpoint: String,
+    /// Dispatcher fed straight from the connection, with the capacity of its inbox, see [`WebsocketConfig::dispatcher`]
+    pub dispatcher: Option<(Dispatcher, usize)>,
+}
+
+impl Default for WebsocketConfig {
+    fn default() -> Self {
+        Self {
+            event_capacity: EVENT_CAPACITY,
+            max_reconnect_attempts: None,
+            reconnect_backoff: BackoffConfig::default(),
+            last_message_id: None,
+            ping_interval: None,
+            latency_window: Duration::from_secs(LATENCY_WINDOW_SECS),
+            on_parse_failure: None,
+            endpoint: WEBSOCKET_ENDPOINT.to_owned(),
+            dispatcher: None,
+        }
+    }
+}
+
+impl WebsocketConfig {
+    /// Set the capacity of the event queue
+    #[must_use]
+    pub fn event_capacity(mut self, event_capacity: usize) -> Self {
+        self.event_capacity = event_capacity;
+        self
+    }
+
+    /// Set how many times in a row to try reconnecting, `None` for forever
+    #[must_use]
+    pub fn max_reconnect_attempts(mut self, attempts: Option<usize>) -> Self {
+        self.max_reconnect_attempts = attempts;
+        self
+    }
+
+    /// Set how long to wait between reconnect attempts
+    #[must_use]
+    pub fn reconnect_backoff(mut self, backoff: BackoffConfig) -> Self {
+        self.reconnect_backoff = backoff;
+        self
+    }
+
+    /// Set the id of the last event that was received, to replay the events after it
+    #[must_use]
+    pub fn last_message_id(mut self, id: impl Into<String>) -> Self {
+        self.last_message_id = Some(id.into());
+        self
+    }
+
+    /// Set how often to ping guilded, `None` to never ping
+    ///
+    /// Intervals shorter than a second, including zero, are raised to a second.
+    /// ```
+    /// use std::time::Duration;
+    /// use vived_websocket::WebsocketConfig;
+    ///
+    /// let config = WebsocketConfig::default().ping_interval(Some(Duration::ZERO));
+    /// assert_eq!(config.ping_interval, Some(Duration::from_secs(1)));
+    /// ```
+    #[must_use]
+    pub fn ping_interval(mut self, interval: Option<Duration>) -> Self {
+        self.ping_interval = interval.map(|interval| interval.max(MIN_PING_INTERVAL));
+        self
+    }
+
+    /// Set how far back the latency of events is averaged
+    #[must_use]
+    pub fn latency_window(mut self, window: Duration) -> Self {
+        self.latency_window = window;
+        self
+    }
+
+    /// Call `hook` with the raw frame and the error whenever an event frame can't be parsed
+    ///
+    /// The failure is logged and counted in [`crate::metrics::ConnectionMetrics::parse_failures`] either way,
+    /// this is for keeping the payload around, for example to open an issue with it.
+    /// The hook runs on the event loop, so hand slow work off to another task.
+    /// ```
+    /// use std::sync::{Arc, Mutex};
+    /// use vived_websocket::WebsocketConfig;
+    ///
+    /// let failed = Arc::new(Mutex::new(Vec::new()));
+    /// let store = Arc::clone(&failed);
+    /// let config = WebsocketConfig::default().on_parse_failure(move |raw, error| {
+    ///     store.lock().unwrap().push(format!("{error}: {raw}"));
+    /// });
+    /// assert!(config.on_parse_failure.is_some());
+    /// ```
+    #[must_use]
+    pub fn on_parse_failure(
+        mut self,
+        hook: impl Fn(&str, &serde_json::Error) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_parse_failure = Some(ParseFailureHook(Arc::new(hook)));
+        self
+    }
+
+    /// Connect to another url than guilded's, for example a proxy or a local server in tests
+    #[must_use]
+    pub fn endpoint(mut self, endpoint: impl Into<String>) -> Self {
+        self.endpoint = endpoint.into();
+        self
+    }
+
+    /// Hand every event to `dispatcher` straight from the connection, through an inbox of `capacity` events
+    ///
+    /// Unlike [`Dispatcher::spawn`] no events are lost when the dispatcher falls behind,
+    /// once the inbox is full the connection stops reading until there is room again.
+    /// So a subscriber with [`OverflowPolicy::Block`](crate::dispatcher::OverflowPolicy::Block) holds up the websocket itself,
+    /// and no pings are sent while it does.
+    /// That wait doesn't count against the pong deadline, so a slow subscriber doesn't cause a reconnect.
+    /// The dispatcher is closed when the connection stops for good.
+    /// A capacity of 0 is treated as 1.
+    /// ```no_run
+    /// use vived_websocket::dispatcher::{Dispatcher, OverflowPolicy};
+    /// use vived_websocket::{WebSocketClient, WebsocketConfig};
+    ///
+    /// # async fn run() -> Result<(), vived_websocket::tungstenite::Error> {
+    /// let dispatcher = Dispatcher::new();
+    /// let mut moderation = dispatcher.subscribe(100, OverflowPolicy::Block);
+    /// let mut logging = dispatcher.subscribe(100, OverflowPolicy::DropOldest);
+    ///
+    /// let config = WebsocketConfig::default().dispatcher(&dispatcher, 16);
+    /// let _client = WebSocketClient::connect("token", config).await?;
+    ///
+    /// while let Some(event) = moderation.recv().await {
+    ///     println!("{event:?}");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn dispatcher(mut self, dispatcher: &Dispatcher, capacity: usize) -> Self {
+        self.dispatcher = Some((dispatcher.clone(), capacity.max(1)));
+        self
+    }
+}
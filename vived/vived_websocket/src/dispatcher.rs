@@ -0,0 +1,368 @@
+//! Share one websocket connection between consumers that each get their own queue
+//!
+//! With a plain [`broadcast`] receiver every consumer shares the same capacity,
+//! so one slow consumer makes everyone lag.
+//! A [`Dispatcher`] instead gives every [`Subscription`] its own queue, with its own capacity and [`OverflowPolicy`].
+//!
+//! ```
+//! use vived_models::{RoleId, ServerId};
+//! use vived_websocket::dispatcher::{Dispatcher, OverflowPolicy};
+//! use vived_websocket::events::{GuildedEvent, RoleDeleteData};
+//!
+//! fn event(id: usize) -> GuildedEvent {
+//!     GuildedEvent::RoleDeleted { server_id: ServerId::new("s"), role: RoleDeleteData { id: RoleId(id) } }
+//! }
+//!
+//! fn id(event: GuildedEvent) -> usize {
+//!     match event {
+//!         GuildedEvent::RoleDeleted { role, .. } => role.id.0,
+//!         _ => unreachable!(),
+//!     }
+//! }
+//!
+//! # #[tokio::main(flavor = "current_thread")]
+//! # async fn main() {
+//! let dispatcher = Dispatcher::new();
+//! let mut newest = dispatcher.subscribe(2, OverflowPolicy::DropOldest);
+//! let mut oldest = dispatcher.subscribe(2, OverflowPolicy::DropNewest);
+//!
+//! // neither subscriber reads while the events come in
+//! for i in 0..5 {
+//!     dispatcher.dispatch(&event(i)).await;
+//! }
+//! dispatcher.close();
+//!
+//! assert_eq!(id(newest.recv().await.unwrap()), 3);
+//! assert_eq!(id(newest.recv().await.unwrap()), 4);
+//! assert!(newest.recv().await.is_none());
+//! assert_eq!(newest.dropped(), 3);
+//!
+//! assert_eq!(id(oldest.recv().await.unwrap()), 0);
+//! assert_eq!(id(oldest.recv().await.unwrap()), 1);
+//! assert!(oldest.recv().await.is_none());
+//! assert_eq!(oldest.dropped(), 3);
+//! # }
+//! ```
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, PoisonError};
+
+use tokio::sync::{broadcast, mpsc, Notify};
+
+use crate::events::GuildedEvent;
+use crate::message_cache::{MessageCache, MessageUpdate};
+
+/// An event as it is queued, with the edit it is if the dispatcher caches messages
+type Dispatched = (GuildedEvent, Option<Arc<MessageUpdate>>);
+
+/// What to do with a new event when a subscriber's queue is full
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Drop the oldest queued event to make room, good for consumers that only care about recent events
+    DropOldest,
+    /// Drop the new event, good for consumers that want to finish what they started
+    DropNewest,
+    /// Wait until the subscriber has room, this holds up every other subscriber too
+    ///
+    /// With [`WebsocketConfig::dispatcher`](crate::WebsocketConfig::dispatcher) it holds up the websocket as well,
+    /// so no events are lost.
+    Block,
+}
+
+/// Queue of a single subscriber
+#[derive(Debug)]
+struct Queue {
+    /// Queued events
+    events: Mutex<VecDeque<Dispatched>>,
+    /// Max number of queued events
+    capacity: usize,
+    /// What to do when full
+    policy: OverflowPolicy,
+    /// Notified when an event is queued or the queue is closed
+    pushed: Notify,
+    /// Notified when an event is taken or the subscription is dropped
+    popped: Notify,
+    /// Number of events dropped because the queue was full
+    dropped: AtomicU64,
+    /// No more events will be queued
+    closed: AtomicBool,
+    /// The subscription was dropped
+    unsubscribed: AtomicBool,
+}
+
+impl Queue {
+    /// Queue an event following the overflow policy
+    async fn push(&self, event: Dispatched) {
+        loop {
+            {
+                let mut events = self.events.lock().unwrap_or_else(PoisonError::into_inner);
+                if events.len() < self.capacity {
+                    events.push_back(event);
+                    self.pushed.notify_one();
+                    return;
+                }
+
+                match self.policy {
+                    OverflowPolicy::DropOldest => {
+                        events.pop_front();
+                        events.push_back(event);
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        self.pushed.notify_one();
+                        return;
+                    }
+                    OverflowPolicy::DropNewest => {
+                        self.dropped.fetch_add(1, Ordering::Relaxed);
+                        return;
+                    }
+                    OverflowPolicy::Block => {}
+                }
+            }
+
+            if self.unsubscribed.load(Ordering::Acquire) {
+                return;
+            }
+            self.popped.notified().await;
+        }
+    }
+
+    /// Stop queueing events, the subscriber still gets the already queued ones
+    fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        self.pushed.notify_one();
+    }
+}
+
+/// One consumer's view of the events, see [`Dispatcher::subscribe`]
+#[derive(Debug)]
+pub struct Subscription {
+    /// The queue shared with the dispatcher
+    queue: Arc<Queue>,
+}
+
+impl Subscription {
+    /// Get the next event, `None` once the dispatcher is closed and every queued event was received
+    pub async fn recv(&mut self) -> Option<GuildedEvent> {
+        self.recv_with_update().await.map(|(event, _)| event)
+    }
+
+    /// Get the next event, with the edit it is if it is a [`GuildedEvent::ChatMessageUpdated`]
+    ///
+    /// The update is only there if the dispatcher was made with [`Dispatcher::with_message_cache`],
+    /// its old version and diff are only there if the message was still cached.
+    pub async fn recv_with_update(&mut self) -> Option<(GuildedEvent, Option<Arc<MessageUpdate>>)> {
+        loop {
+            {
+                let mut events = self.queue.events.lock().unwrap_or_else(PoisonError::into_inner);
+                if let Some(event) = events.pop_front() {
+                    self.queue.popped.notify_one();
+                    return Some(event);
+                }
+                if self.queue.closed.load(Ordering::Acquire) {
+                    return None;
+                }
+            }
+            self.queue.pushed.notified().await;
+        }
+    }
+
+    /// How many events were dropped because this subscriber's queue was full
+    #[must_use]
+    pub fn dropped(&self) -> u64 {
+        self.queue.dropped.load(Ordering::Relaxed)
+    }
+
+    /// How many events are waiting to be received
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.queue.events.lock().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    /// Are there no events waiting to be received
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        self.queue.unsubscribed.store(true, Ordering::Release);
+        // wake the dispatcher if it is blocked on us
+        self.queue.popped.notify_one();
+    }
+}
+
+/// Hands out events to any number of subscriptions, see the [module docs](self)
+///
+/// Cloning gives another handle to the same dispatcher.
+#[derive(Debug, Clone, Default)]
+pub struct Dispatcher {
+    /// Queues of every subscriber
+    queues: Arc<Mutex<Vec<Arc<Queue>>>>,
+    /// Rotates which subscriber gets each event first, so no one is always last behind a blocking subscriber
+    next_first: Arc<AtomicUsize>,
+    /// Set once the dispatcher is closed, later subscriptions start out closed
+    closed: Arc<AtomicBool>,
+    /// Remembers messages so edits can be handed out with the version before them
+    cache: Option<Arc<Mutex<MessageCache>>>,
+}
+
+impl Dispatcher {
+    /// Create a dispatcher without a source, events are handed out with [`Dispatcher::dispatch`]
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a dispatcher that remembers the last `capacity` messages,
+    /// so edits are handed out with the version before them, see [`Subscription::recv_with_update`]
+    ///
+    /// Like [`Dispatcher::new`] it has no source, give it one with [`WebsocketConfig::dispatcher`](crate::WebsocketConfig::dispatcher).
+    /// ```
+    /// use vived_models::Message;
+    /// use vived_websocket::dispatcher::{Dispatcher, OverflowPolicy};
+    /// use vived_websocket::events::GuildedEvent;
+    ///
+    /// fn message(content: &str) -> Message {
+    ///     serde_json::from_str(&format!(r#"{{
+    ///         "id": "a", "type": "default", "serverId": "s", "channelId": "c",
+    ///         "content": "{content}", "createdAt": "2022-10-01T12:00:00Z", "createdBy": "u"
+    ///     }}"#)).unwrap()
+    /// }
+    ///
+    /// # #[tokio::main(flavor = "current_thread")]
+    /// # async fn main() {
+    /// let dispatcher = Dispatcher::with_message_cache(100);
+    /// let mut subscription = dispatcher.subscribe(10, OverflowPolicy::Block);
+    ///
+    /// dispatcher.dispatch(&GuildedEvent::ChatMessageCreated { server_id: "s".into(), message: message("hi").into() }).await;
+    /// dispatcher.dispatch(&GuildedEvent::ChatMessageUpdated { server_id: "s".into(), message: message("hello").into() }).await;
+    ///
+    /// let (_, update) = subscription.recv_with_update().await.unwrap();
+    /// assert!(update.is_none());
+    /// let (_, update) = subscription.recv_with_update().await.unwrap();
+    /// let update = update.unwrap();
+    /// assert_eq!(update.old.as_ref().unwrap().content.as_deref(), Some("hi"));
+    /// assert!(update.diff.as_ref().unwrap().is_content_edit());
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn with_message_cache(capacity: usize) -> Self {
+        Self {
+            cache: Some(Arc::new(Mutex::new(MessageCache::new(capacity)))),
+            ..Self::default()
+        }
+    }
+
+    /// Create a dispatcher that hands out every event from `events`, see [`crate::connect_to_websocket`]
+    ///
+    /// The dispatcher is closed once the websocket is.
+    /// A dispatcher that falls too far behind lags `events` and misses events, even if every subscriber uses [`OverflowPolicy::Block`].
+    /// Use [`WebsocketConfig::dispatcher`](crate::WebsocketConfig::dispatcher) to hold up the websocket instead.
+    ///
+    /// # Panics
+    /// If called outside of a tokio runtime
+    #[must_use]
+    pub fn spawn(mut events: broadcast::Receiver<GuildedEvent>) -> Self {
+        let dispatcher = Self::new();
+
+        let task_dispatcher = dispatcher.clone();
+        tokio::spawn(async move {
+            loop {
+                match events.recv().await {
+                    Ok(event) => task_dispatcher.dispatch(&event).await,
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        log::warn!("dispatcher lagged behind the websocket, skipped {skipped} events");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => break,
+                }
+            }
+            task_dispatcher.close();
+        });
+
+        dispatcher
+    }
+
+    /// Hand out every event from `inbox`, and close once every sender of it is dropped
+    pub(crate) fn feed(&self, mut inbox: mpsc::Receiver<GuildedEvent>) {
+        let dispatcher = self.clone();
+        tokio::spawn(async move {
+            while let Some(event) = inbox.recv().await {
+                dispatcher.dispatch(&event).await;
+            }
+            dispatcher.close();
+        });
+    }
+
+    /// Add a subscriber with its own queue of `capacity` events
+    ///
+    /// A capacity of 0 is treated as 1.
+    /// The subscriber only gets events dispatched after this is called.
+    pub fn subscribe(&self, capacity: usize, policy: OverflowPolicy) -> Subscription {
+        let queue = Arc::new(Queue {
+            events: Mutex::new(VecDeque::new()),
+            capacity: capacity.max(1),
+            policy,
+            pushed: Notify::new(),
+            popped: Notify::new(),
+            dropped: AtomicU64::new(0),
+            closed: AtomicBool::new(false),
+            unsubscribed: AtomicBool::new(false),
+        });
+
+        let mut queues = self.queues.lock().unwrap_or_else(PoisonError::into_inner);
+        if self.closed.load(Ordering::Acquire) {
+            queue.close();
+        } else {
+            queues.push(Arc::clone(&queue));
+        }
+
+        Subscription { queue }
+    }
+
+    /// Hand out an event to every subscriber
+    pub async fn dispatch(&self, event: &GuildedEvent) {
+        // observed even without subscribers, so later ones still get the old versions
+        let update = self.cache.as_ref().and_then(|cache| {
+            cache
+                .lock()
+                .unwrap_or_else(PoisonError::into_inner)
+                .observe(event)
+                .map(Arc::new)
+        });
+
+        let queues = {
+            let mut queues = self.queues.lock().unwrap_or_else(PoisonError::into_inner);
+            queues.retain(|queue| !queue.unsubscribed.load(Ordering::Acquire));
+            queues.clone()
+        };
+        if queues.is_empty() {
+            return;
+        }
+
+        let first = self.next_first.fetch_add(1, Ordering::Relaxed) % queues.len();
+        for queue in queues.iter().cycle().skip(first).take(queues.len()) {
+            queue.push((event.clone(), update.clone())).await;
+        }
+    }
+
+    /// Close every subscription, they still get the events that are already queued
+    pub fn close(&self) {
+        self.closed.store(true, Ordering::Release);
+        for queue in self.queues.lock().unwrap_or_else(PoisonError::into_inner).drain(..) {
+            queue.close();
+        }
+    }
+
+    /// Number of subscriptions that are still alive
+    #[must_use]
+    pub fn subscriber_count(&self) -> usize {
+        self.queues
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .filter(|queue| !queue.unsubscribed.load(Ordering::Acquire))
+            .count()
+    }
+}
@@ -1,9 +1,14 @@
 //! Guilded websocket events.
+//!
+//! Events are cloned for every receiver, so the large models in them are behind an [`Arc`] to keep that cheap.
+//! Fields can be used through the [`Arc`] as normal, clone the model itself if you need to own it.
 
-use serde::Deserialize;
+use std::sync::Arc;
+
+use serde::{Deserialize, Serialize};
 
 /// `MessageDeleteData` is the data for a message delete event.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(rename_all = "camelCase")]
 pub struct MessageDeleteData {
     /// The id of the message that was deleted.
@@ -18,17 +23,58 @@ pub struct MessageDeleteData {
     pub is_private: bool,
 }
 
+impl From<MessageDeleteData> for vived_models::MessageId {
+    fn from(v: MessageDeleteData) -> Self {
+        v.id
+    }
+}
+
+impl From<&MessageDeleteData> for vived_models::MessageId {
+    fn from(v: &MessageDeleteData) -> Self {
+        v.id.clone()
+    }
+}
+
+/// `RoleDeleteData` is the data for a role delete event.
+///
+/// Only the id is kept, the rest of the role is gone anyway.
+/// ```
+/// use vived_websocket::events::GuildedEvent;
+///
+/// let event: GuildedEvent = serde_json::from_str(r#"{
+///     "t": "RoleDeleted",
+///     "d": {
+///         "serverId": "wlVr3Ggl",
+///         "role": {
+///             "id": 28086957,
+///             "serverId": "wlVr3Ggl",
+///             "name": "Moderators",
+///             "createdAt": "2022-10-26T00:00:00.000Z"
+///         }
+///     }
+/// }"#).unwrap();
+///
+/// let GuildedEvent::RoleDeleted { role, .. } = event else { panic!("wrong event") };
+/// assert_eq!(role.id.0, 28086957);
+/// ```
+#[derive(Debug, Deserialize, Serialize, Clone, Copy)]
+#[serde(rename_all = "camelCase")]
+pub struct RoleDeleteData {
+    /// The id of the role that was deleted.
+    pub id: vived_models::RoleId,
+}
+
 /// A Guilded event.
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Serialize, Clone)]
 #[serde(tag = "t", content = "d")]
 pub enum GuildedEvent {
     /// A message was created.
-    ChatMessageCreated { 
+    ChatMessageCreated {
         /// What server the message was created in.
         #[serde(rename = "serverId")]
         server_id: vived_models::ServerId,
         /// Message data.
-        message: vived_models::Message
+        message: Arc<vived_models::Message>,
     },
     /// Chat message was updated.
     ChatMessageUpdated {
@@ -36,7 +82,7 @@ pub enum GuildedEvent {
         #[serde(rename = "serverId")]
         server_id: vived_models::ServerId,
         /// Message data.
-        message: vived_models::Message
+        message: Arc<vived_models::Message>,
     },
     /// Chat message was deleted.
     ChatMessageDeleted {
@@ -45,5 +91,228 @@ pub enum GuildedEvent {
         server_id: vived_models::ServerId,
         /// Message data.
         message: MessageDeleteData
-    }, 
+    },
+    /// A role was created.
+    RoleCreated {
+        /// What server the role was created in.
+        #[serde(rename = "serverId")]
+        server_id: vived_models::ServerId,
+        /// Role data.
+        role: Arc<vived_models::Role>,
+    },
+    /// A role was updated.
+    RoleUpdated {
+        /// What server the role was updated in.
+        #[serde(rename = "serverId")]
+        server_id: vived_models::ServerId,
+        /// Role data.
+        role: Arc<vived_models::Role>,
+    },
+    /// A role was deleted.
+    RoleDeleted {
+        /// What server the role was deleted in.
+        #[serde(rename = "serverId")]
+        server_id: vived_models::ServerId,
+        /// Role data.
+        role: RoleDeleteData,
+    },
+    /// Someone marked themselves available in a scheduling channel.
+    ///
+    /// ```
+    /// use vived_websocket::events::{EventKind, GuildedEvent};
+    ///
+    /// let event: GuildedEvent = serde_json::from_str(r#"{
+    ///     "t": "SchedulingAvailabilityCreated",
+    ///     "d": {
+    ///         "serverId": "wlVr3Ggl",
+    ///         "availability": {
+    ///             "id": 1,
+    ///             "channelId": "00000000-0000-0000-0000-000000000001",
+    ///             "serverId": "wlVr3Ggl",
+    ///             "userId": "Ann6LewA",
+    ///             "startDate": "2022-10-27T18:00:00.000Z",
+    ///             "endDate": "2022-10-27T20:00:00.000Z",
+    ///             "createdAt": "2022-10-26T00:00:00.000Z"
+    ///         }
+    ///     }
+    /// }"#).unwrap();
+    ///
+    /// assert_eq!(event.kind(), EventKind::SchedulingAvailabilityCreated);
+    /// assert_eq!(event.channel_id().unwrap().0, "00000000-0000-0000-0000-000000000001");
+    /// assert!(event.timestamp().is_some());
+    /// ```
+    SchedulingAvailabilityCreated {
+        /// What server the availability was created in.
+        #[serde(rename = "serverId")]
+        server_id: vived_models::ServerId,
+        /// Availability data.
+        availability: vived_models::SchedulingAvailability,
+    },
+    /// An availability in a scheduling channel was changed.
+    SchedulingAvailabilityUpdated {
+        /// What server the availability was updated in.
+        #[serde(rename = "serverId")]
+        server_id: vived_models::ServerId,
+        /// Availability data.
+        availability: vived_models::SchedulingAvailability,
+    },
+    /// An availability in a scheduling channel was removed.
+    SchedulingAvailabilityDeleted {
+        /// What server the availability was deleted in.
+        #[serde(rename = "serverId")]
+        server_id: vived_models::ServerId,
+        /// Availability data.
+        availability: vived_models::SchedulingAvailability,
+    },
+}
+
+/// The kind of a [`GuildedEvent`], without any of its data.
+///
+/// Useful for filtering and metrics where the payload itself isn't needed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum EventKind {
+    /// [`GuildedEvent::ChatMessageCreated`]
+    ChatMessageCreated,
+    /// [`GuildedEvent::ChatMessageUpdated`]
+    ChatMessageUpdated,
+    /// [`GuildedEvent::ChatMessageDeleted`]
+    ChatMessageDeleted,
+    /// [`GuildedEvent::RoleCreated`]
+    RoleCreated,
+    /// [`GuildedEvent::RoleUpdated`]
+    RoleUpdated,
+    /// [`GuildedEvent::RoleDeleted`]
+    RoleDeleted,
+    /// [`GuildedEvent::SchedulingAvailabilityCreated`]
+    SchedulingAvailabilityCreated,
+    /// [`GuildedEvent::SchedulingAvailabilityUpdated`]
+    SchedulingAvailabilityUpdated,
+    /// [`GuildedEvent::SchedulingAvailabilityDeleted`]
+    SchedulingAvailabilityDeleted,
+}
+
+impl EventKind {
+    /// The name guilded uses for the event, like `ChatMessageCreated`
+    #[must_use]
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::ChatMessageCreated => "ChatMessageCreated",
+            Self::ChatMessageUpdated => "ChatMessageUpdated",
+            Self::ChatMessageDeleted => "ChatMessageDeleted",
+            Self::RoleCreated => "RoleCreated",
+            Self::RoleUpdated => "RoleUpdated",
+            Self::RoleDeleted => "RoleDeleted",
+            Self::SchedulingAvailabilityCreated => "SchedulingAvailabilityCreated",
+            Self::SchedulingAvailabilityUpdated => "SchedulingAvailabilityUpdated",
+            Self::SchedulingAvailabilityDeleted => "SchedulingAvailabilityDeleted",
+        }
+    }
+}
+
+// These helpers intentionally match on every variant without a `_` arm,
+// so adding a new event forces them to be updated.
+impl GuildedEvent {
+    /// The server this event happened in
+    #[must_use]
+    pub fn server_id(&self) -> Option<&vived_models::ServerId> {
+        match *self {
+            Self::ChatMessageCreated { ref server_id, .. }
+            | Self::ChatMessageUpdated { ref server_id, .. }
+            | Self::ChatMessageDeleted { ref server_id, .. }
+            | Self::RoleCreated { ref server_id, .. }
+            | Self::RoleUpdated { ref server_id, .. }
+            | Self::RoleDeleted { ref server_id, .. }
+            | Self::SchedulingAvailabilityCreated { ref server_id, .. }
+            | Self::SchedulingAvailabilityUpdated { ref server_id, .. }
+            | Self::SchedulingAvailabilityDeleted { ref server_id, .. } => Some(server_id),
+        }
+    }
+
+    /// The channel this event happened in
+    #[must_use]
+    pub fn channel_id(&self) -> Option<&vived_models::ChannelId> {
+        match *self {
+            Self::ChatMessageCreated { ref message, .. }
+            | Self::ChatMessageUpdated { ref message, .. } => Some(&message.channel_id),
+            Self::ChatMessageDeleted { ref message, .. } => Some(&message.channel_id),
+            Self::SchedulingAvailabilityCreated { ref availability, .. }
+            | Self::SchedulingAvailabilityUpdated { ref availability, .. }
+            | Self::SchedulingAvailabilityDeleted { ref availability, .. } => {
+                Some(&availability.channel_id)
+            }
+            Self::RoleCreated { .. } | Self::RoleUpdated { .. } | Self::RoleDeleted { .. } => None,
+        }
+    }
+
+    /// The message carried by this event, if it has a full one
+    #[must_use]
+    pub fn message(&self) -> Option<&vived_models::Message> {
+        match *self {
+            Self::ChatMessageCreated { ref message, .. }
+            | Self::ChatMessageUpdated { ref message, .. } => Some(message.as_ref()),
+            Self::ChatMessageDeleted { .. }
+            | Self::RoleCreated { .. }
+            | Self::RoleUpdated { .. }
+            | Self::RoleDeleted { .. }
+            | Self::SchedulingAvailabilityCreated { .. }
+            | Self::SchedulingAvailabilityUpdated { .. }
+            | Self::SchedulingAvailabilityDeleted { .. } => None,
+        }
+    }
+
+    /// When guilded says this event happened, `None` for events that don't carry a timestamp
+    #[must_use]
+    pub fn timestamp(&self) -> Option<chrono::DateTime<chrono::Utc>> {
+        match *self {
+            Self::ChatMessageCreated { ref message, .. } => Some(message.created_at),
+            Self::ChatMessageUpdated { ref message, .. } => message.updated_at,
+            Self::ChatMessageDeleted { ref message, .. } => Some(message.deleted_at),
+            Self::RoleCreated { ref role, .. } => Some(role.created_at),
+            Self::RoleUpdated { ref role, .. } => role.updated_at,
+            Self::SchedulingAvailabilityCreated { ref availability, .. } => Some(availability.created_at),
+            Self::RoleDeleted { .. }
+            | Self::SchedulingAvailabilityUpdated { .. }
+            | Self::SchedulingAvailabilityDeleted { .. } => None,
+        }
+    }
+
+    /// The kind of this event
+    ///
+    /// # Example
+    /// ```
+    /// use vived_websocket::events::{EventKind, GuildedEvent};
+    ///
+    /// let event: GuildedEvent = serde_json::from_str(r#"{
+    ///     "t": "ChatMessageDeleted",
+    ///     "d": {
+    ///         "serverId": "wlVr3Ggl",
+    ///         "message": {
+    ///             "id": "00000000-0000-0000-0000-000000000000",
+    ///             "serverId": "wlVr3Ggl",
+    ///             "channelId": "00000000-0000-0000-0000-000000000001",
+    ///             "deletedAt": "2022-10-26T00:00:00.000Z",
+    ///             "isPrivate": false
+    ///         }
+    ///     }
+    /// }"#).unwrap();
+    ///
+    /// assert_eq!(event.kind(), EventKind::ChatMessageDeleted);
+    /// assert_eq!(event.server_id().unwrap().0, "wlVr3Ggl");
+    /// assert_eq!(event.channel_id().unwrap().0, "00000000-0000-0000-0000-000000000001");
+    /// assert!(event.message().is_none());
+    /// ```
+    #[must_use]
+    pub fn kind(&self) -> EventKind {
+        match *self {
+            Self::ChatMessageCreated { .. } => EventKind::ChatMessageCreated,
+            Self::ChatMessageUpdated { .. } => EventKind::ChatMessageUpdated,
+            Self::ChatMessageDeleted { .. } => EventKind::ChatMessageDeleted,
+            Self::RoleCreated { .. } => EventKind::RoleCreated,
+            Self::RoleUpdated { .. } => EventKind::RoleUpdated,
+            Self::RoleDeleted { .. } => EventKind::RoleDeleted,
+            Self::SchedulingAvailabilityCreated { .. } => EventKind::SchedulingAvailabilityCreated,
+            Self::SchedulingAvailabilityUpdated { .. } => EventKind::SchedulingAvailabilityUpdated,
+            Self::SchedulingAvailabilityDeleted { .. } => EventKind::SchedulingAvailabilityDeleted,
+        }
+    }
 }
@@ -0,0 +1,80 @@
+//! Metrics recorded with the [`metrics`](https://docs.rs/metrics) crate, behind the `metrics` feature
+//!
+//! These are process wide, unlike [`crate::metrics::ConnectionMetrics`] which belongs to one client.
+//! The names are part of the public api and only change in a breaking release.
+//! Without the feature nothing is recorded, and the calls compile to nothing.
+
+use crate::events::EventKind;
+
+/// Counter of received events, labeled with `event`, the name of the event like `ChatMessageCreated`
+pub const EVENTS_TOTAL: &str = "vived_websocket_events_total";
+
+/// Counter of event frames that couldn't be parsed, labeled with `event`, the `t` field of the frame or `unknown`
+pub const PARSE_FAILURES_TOTAL: &str = "vived_websocket_parse_failures_total";
+
+/// Counter of successful reconnects after the connection dropped
+pub const RECONNECTS_TOTAL: &str = "vived_websocket_reconnects_total";
+
+/// Gauge that is 1 while the websocket is connected, and 0 while it isn't
+pub const CONNECTED: &str = "vived_websocket_connected";
+
+/// Describe every metric to the recorder, only done once
+#[cfg(feature = "metrics")]
+pub(crate) fn describe() {
+    static DESCRIBED: std::sync::Once = std::sync::Once::new();
+    DESCRIBED.call_once(|| {
+        ::metrics::describe_counter!(EVENTS_TOTAL, "Events received over the websocket");
+        ::metrics::describe_counter!(PARSE_FAILURES_TOTAL, "Event frames that couldn't be parsed");
+        ::metrics::describe_counter!(RECONNECTS_TOTAL, "Reconnects after the websocket dropped");
+        ::metrics::describe_gauge!(CONNECTED, "Is the websocket connected");
+    });
+}
+
+/// Describe every metric to the recorder, only done once
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn describe() {}
+
+/// Record a received event
+#[cfg(feature = "metrics")]
+pub(crate) fn event(kind: EventKind) {
+    ::metrics::increment_counter!(EVENTS_TOTAL, "event" => kind.name());
+}
+
+/// Record a received event
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn event(_kind: EventKind) {}
+
+/// Record an event frame that couldn't be parsed
+#[cfg(feature = "metrics")]
+pub(crate) fn parse_failure(event_type: &str) {
+    ::metrics::increment_counter!(PARSE_FAILURES_TOTAL, "event" => event_type.to_owned());
+}
+
+/// Record an event frame that couldn't be parsed
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn parse_failure(_event_type: &str) {}
+
+/// Record a successful reconnect
+#[cfg(feature = "metrics")]
+pub(crate) fn reconnected() {
+    ::metrics::increment_counter!(RECONNECTS_TOTAL);
+}
+
+/// Record a successful reconnect
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn reconnected() {}
+
+/// Record the connection going up or down
+#[cfg(feature = "metrics")]
+pub(crate) fn connected(connected: bool) {
+    ::metrics::gauge!(CONNECTED, if connected { 1.0 } else { 0.0 });
+}
+
+/// Record the connection going up or down
+#[cfg(not(feature = "metrics"))]
+#[inline(always)]
+pub(crate) fn connected(_connected: bool) {}
@@ -38,5 +38,19 @@
 
 pub mod events;
 pub mod client;
+pub mod close;
+pub mod config;
+pub mod dispatcher;
+pub mod instrument;
+pub mod message_cache;
+pub mod metrics;
+pub mod wait;
 
-pub use client::connect_to_websocket;
\ No newline at end of file
+pub use client::{
+    connect_to_websocket, connect_to_websocket_with_config, ConnectionStatus, StopReason,
+    WebSocketClient,
+};
+pub use close::{GatewayClose, GatewayCloseReason};
+pub use config::{BackoffConfig, ParseFailureHook, WebsocketConfig};
+/// Re-exported since connecting returns its errors
+pub use tokio_tungstenite::tungstenite;
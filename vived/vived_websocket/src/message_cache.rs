@@ -0,0 +1,149 @@
+//! Remember recent messages, so edits can be compared with the version before them
+//!
+//! [`GuildedEvent::ChatMessageUpdated`] only carries the new version of a message.
+//! Run events through a [`MessageCache`], and it hands back the previous version and a [`MessageDiff`] whenever it still has it.
+//! A dispatcher made with [`Dispatcher::with_message_cache`](crate::dispatcher::Dispatcher::with_message_cache)
+//! does this for every event, see [`Subscription::recv_with_update`](crate::dispatcher::Subscription::recv_with_update).
+//!
+//! ```
+//! use vived_models::Message;
+//! use vived_websocket::events::GuildedEvent;
+//! use vived_websocket::message_cache::MessageCache;
+//!
+//! fn message(id: &str, content: &str) -> Message {
+//!     serde_json::from_str(&format!(r#"{{
+//!         "id": "{id}", "type": "default", "serverId": "s", "channelId": "c",
+//!         "content": "{content}", "createdAt": "2022-10-01T12:00:00Z", "createdBy": "u"
+//!     }}"#)).unwrap()
+//! }
+//!
+//! let mut cache = MessageCache::new(1);
+//! cache.observe(&GuildedEvent::ChatMessageCreated { server_id: "s".into(), message: message("a", "hi").into() });
+//!
+//! let update = cache
+//!     .observe(&GuildedEvent::ChatMessageUpdated { server_id: "s".into(), message: message("a", "hello").into() })
+//!     .unwrap();
+//! assert_eq!(update.old.unwrap().content.as_deref(), Some("hi"));
+//! assert!(update.diff.unwrap().is_content_edit());
+//!
+//! // "a" is pushed out by "b", so its next edit comes without the old version
+//! cache.observe(&GuildedEvent::ChatMessageCreated { server_id: "s".into(), message: message("b", "other").into() });
+//! let update = cache
+//!     .observe(&GuildedEvent::ChatMessageUpdated { server_id: "s".into(), message: message("a", "bye").into() })
+//!     .unwrap();
+//! assert!(update.old.is_none() && update.diff.is_none());
+//! assert_eq!(update.new.content.as_deref(), Some("bye"));
+//! ```
+
+use std::collections::{BTreeMap, HashMap};
+
+use vived_models::{Message, MessageDiff, MessageId};
+
+use crate::events::GuildedEvent;
+
+/// An edited message, with the version before it if it was cached
+#[derive(Debug, Clone)]
+pub struct MessageUpdate {
+    /// The previous version, `None` if it wasn't cached
+    pub old: Option<Message>,
+    /// The new version
+    pub new: Message,
+    /// What changed, `None` if the previous version wasn't cached
+    pub diff: Option<MessageDiff>,
+}
+
+/// The latest version of recently seen messages, forgetting the least recently seen once full
+#[derive(Debug)]
+pub struct MessageCache {
+    /// Most messages kept
+    capacity: usize,
+    /// Cached messages, with when they were last seen
+    messages: HashMap<MessageId, (u64, Message)>,
+    /// Ids of the cached messages by when they were last seen, least recently seen first
+    order: BTreeMap<u64, MessageId>,
+    /// Counts up every time a message is seen
+    clock: u64,
+}
+
+impl MessageCache {
+    /// Create a cache keeping at most `capacity` messages, at least 1
+    #[must_use]
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            messages: HashMap::new(),
+            order: BTreeMap::new(),
+            clock: 0,
+        }
+    }
+
+    /// Update the cache with an event, returning the update if it was an edit
+    pub fn observe(&mut self, event: &GuildedEvent) -> Option<MessageUpdate> {
+        match *event {
+            GuildedEvent::ChatMessageCreated { ref message, .. } => {
+                self.insert(Message::clone(message));
+                None
+            }
+            GuildedEvent::ChatMessageUpdated { ref message, .. } => {
+                let old = self.insert(Message::clone(message));
+                let diff = old.as_ref().map(|old| MessageDiff::between(old, message));
+                Some(MessageUpdate {
+                    old,
+                    new: Message::clone(message),
+                    diff,
+                })
+            }
+            GuildedEvent::ChatMessageDeleted { ref message, .. } => {
+                if let Some((seen, _)) = self.messages.remove(&message.id) {
+                    self.order.remove(&seen);
+                }
+                None
+            }
+            GuildedEvent::RoleCreated { .. }
+            | GuildedEvent::RoleUpdated { .. }
+            | GuildedEvent::RoleDeleted { .. }
+            | GuildedEvent::SchedulingAvailabilityCreated { .. }
+            | GuildedEvent::SchedulingAvailabilityUpdated { .. }
+            | GuildedEvent::SchedulingAvailabilityDeleted { .. } => None,
+        }
+    }
+
+    /// The cached version of a message
+    #[must_use]
+    pub fn get(&self, id: &MessageId) -> Option<&Message> {
+        self.messages.get(id).map(|cached| &cached.1)
+    }
+
+    /// How many messages are cached
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.messages.len()
+    }
+
+    /// Is the cache empty
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.messages.is_empty()
+    }
+
+    /// Cache a message, returning the version it replaced
+    fn insert(&mut self, message: Message) -> Option<Message> {
+        self.clock += 1;
+        let id = message.id.clone();
+        let old = self.messages.insert(id.clone(), (self.clock, message));
+        if let Some((seen, _)) = old {
+            self.order.remove(&seen);
+        }
+        self.order.insert(self.clock, id);
+
+        while self.messages.len() > self.capacity {
+            match self.order.pop_first() {
+                Some((_, oldest)) => {
+                    self.messages.remove(&oldest);
+                }
+                None => break,
+            }
+        }
+        old.map(|(_, replaced)| replaced)
+    }
+}
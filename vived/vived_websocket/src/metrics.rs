@@ -0,0 +1,197 @@
+//! Receipt metadata and metrics for delivered events
+//!
+//! The event loop reads the clock once for every frame, right after it is read, and stamps the event with it.
+//! Everything in this module is handed those times instead of reading the clock itself,
+//! so it behaves the same in tests with made up times.
+//!
+//! ```
+//! use std::time::{Duration, Instant};
+//! use vived_websocket::events::EventKind;
+//! use vived_websocket::metrics::ConnectionMetrics;
+//!
+//! let start = Instant::now();
+//! let mut metrics = ConnectionMetrics::new(Duration::from_secs(10));
+//!
+//! metrics.record(EventKind::ChatMessageCreated, start, Some(chrono::Duration::milliseconds(100)));
+//! metrics.record(EventKind::ChatMessageCreated, start + Duration::from_secs(5), Some(chrono::Duration::milliseconds(300)));
+//! metrics.record(EventKind::RoleDeleted, start + Duration::from_secs(6), None);
+//!
+//! assert_eq!(metrics.events_received(EventKind::ChatMessageCreated), 2);
+//! assert_eq!(metrics.total_events(), 3);
+//! assert_eq!(metrics.average_latency(start + Duration::from_secs(6)), Some(Duration::from_millis(200)));
+//!
+//! // the first sample falls out of the window
+//! assert_eq!(metrics.average_latency(start + Duration::from_secs(11)), Some(Duration::from_millis(300)));
+//! assert_eq!(metrics.average_latency(start + Duration::from_secs(20)), None);
+//!
+//! metrics.record_parse_failure("ChatMessageCreated");
+//! metrics.record_parse_failure("ChatMessageCreated");
+//! metrics.record_parse_failure("SomethingNew");
+//! assert_eq!(metrics.parse_failures("ChatMessageCreated"), 2);
+//! assert_eq!(metrics.parse_failures("RoleDeleted"), 0);
+//! assert_eq!(metrics.total_parse_failures(), 3);
+//! assert_eq!(metrics.total_events(), 3);
+//! ```
+
+use std::collections::{HashMap, VecDeque};
+use std::time::{Duration, Instant};
+
+use crate::events::{EventKind, GuildedEvent};
+
+/// An event together with when and how it was received
+#[derive(Debug, Clone)]
+pub struct EventEnvelope {
+    /// The event
+    pub event: GuildedEvent,
+    /// Id guilded gave the frame, pass it as [`crate::WebsocketConfig::last_message_id`] to resume after it
+    pub sequence: Option<String>,
+    /// Wall clock time the frame was read, comparable with the timestamps guilded sends
+    pub received_at: chrono::DateTime<chrono::Utc>,
+    /// Monotonic time the frame was read, for measuring time spent after receiving it
+    pub received_instant: Instant,
+}
+
+impl EventEnvelope {
+    /// Time between guilded saying the event happened and us receiving it
+    ///
+    /// `None` for events without a timestamp, see [`GuildedEvent::timestamp`].
+    /// This can be negative if the clocks of guilded and this machine disagree.
+    #[must_use]
+    pub fn latency(&self) -> Option<chrono::Duration> {
+        self.event
+            .timestamp()
+            .map(|timestamp| self.received_at - timestamp)
+    }
+}
+
+/// Average of the latencies recorded in the last `window`
+#[derive(Debug, Clone)]
+pub struct LatencyWindow {
+    /// How far back samples count
+    window: Duration,
+    /// When each sample was recorded, and its latency, oldest first
+    samples: VecDeque<(Instant, Duration)>,
+}
+
+impl LatencyWindow {
+    /// Create an empty window covering `window`
+    #[must_use]
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            samples: VecDeque::new(),
+        }
+    }
+
+    /// Record a latency measured at `at`, samples that fell out of the window are dropped
+    pub fn record(&mut self, at: Instant, latency: Duration) {
+        self.samples.push_back((at, latency));
+        while self
+            .samples
+            .front()
+            .is_some_and(|&(sampled, _)| !self.covers(sampled, at))
+        {
+            self.samples.pop_front();
+        }
+    }
+
+    /// Average latency of the samples still in the window at `now`, `None` if there are none
+    #[must_use]
+    pub fn average(&self, now: Instant) -> Option<Duration> {
+        let (count, total) = self
+            .samples
+            .iter()
+            .filter(|&&(sampled, _)| self.covers(sampled, now))
+            .fold((0_u32, Duration::ZERO), |(count, total), &(_, latency)| {
+                (count.saturating_add(1), total.saturating_add(latency))
+            });
+
+        total.checked_div(count)
+    }
+
+    /// Does a sample taken at `sampled` still count at `now`
+    fn covers(&self, sampled: Instant, now: Instant) -> bool {
+        now.saturating_duration_since(sampled) < self.window
+    }
+}
+
+/// Counters for a single websocket connection, see [`crate::WebSocketClient::metrics`]
+#[derive(Debug, Clone)]
+pub struct ConnectionMetrics {
+    /// Events received of each kind
+    events: HashMap<EventKind, u64>,
+    /// Recent latencies
+    latency: LatencyWindow,
+    /// Frames that couldn't be parsed, by the `t` field of the frame
+    parse_failures: HashMap<String, u64>,
+}
+
+impl ConnectionMetrics {
+    /// Create empty metrics, averaging latency over `window`
+    #[must_use]
+    pub fn new(window: Duration) -> Self {
+        Self {
+            events: HashMap::new(),
+            latency: LatencyWindow::new(window),
+            parse_failures: HashMap::new(),
+        }
+    }
+
+    /// Record an event of `kind` received at `at`
+    ///
+    /// Negative latencies, from clocks that disagree, are counted as zero.
+    pub fn record(&mut self, kind: EventKind, at: Instant, latency: Option<chrono::Duration>) {
+        *self.events.entry(kind).or_default() += 1;
+        if let Some(latency) = latency {
+            self.latency
+                .record(at, latency.to_std().unwrap_or(Duration::ZERO));
+        }
+    }
+
+    /// Record a received event
+    pub fn record_envelope(&mut self, envelope: &EventEnvelope) {
+        self.record(
+            envelope.event.kind(),
+            envelope.received_instant,
+            envelope.latency(),
+        );
+    }
+
+    /// Record a frame of `event_type` that couldn't be parsed, it doesn't count as a received event
+    pub fn record_parse_failure(&mut self, event_type: &str) {
+        *self.parse_failures.entry(event_type.to_owned()).or_default() += 1;
+    }
+
+    /// How many events of `kind` were received
+    #[must_use]
+    pub fn events_received(&self, kind: EventKind) -> u64 {
+        self.events.get(&kind).copied().unwrap_or_default()
+    }
+
+    /// How many events were received in total
+    #[must_use]
+    pub fn total_events(&self) -> u64 {
+        self.events.values().sum()
+    }
+
+    /// How many frames of `event_type` couldn't be parsed
+    ///
+    /// This takes the `t` field of the frame instead of an [`EventKind`], so event types vived doesn't know are counted too.
+    /// Frames without a readable `t` field are counted as [`crate::client::UNKNOWN_EVENT_TYPE`].
+    #[must_use]
+    pub fn parse_failures(&self, event_type: &str) -> u64 {
+        self.parse_failures.get(event_type).copied().unwrap_or_default()
+    }
+
+    /// How many frames couldn't be parsed in total
+    #[must_use]
+    pub fn total_parse_failures(&self) -> u64 {
+        self.parse_failures.values().sum()
+    }
+
+    /// Average latency over the window at `now`, `None` if no event with a timestamp was received in it
+    #[must_use]
+    pub fn average_latency(&self, now: Instant) -> Option<Duration> {
+        self.latency.average(now)
+    }
+}
@@ -0,0 +1,123 @@
+//! Wait for specific events, useful for conversational flows
+
+use std::future::Future;
+use std::time::Duration;
+
+use tokio::sync::broadcast;
+
+use crate::events::GuildedEvent;
+
+/// Why waiting for an event failed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WaitError {
+    /// Nothing matched before the timeout
+    Timeout,
+    /// The websocket connection was closed
+    Closed,
+}
+
+impl std::fmt::Display for WaitError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match *self {
+            Self::Timeout => write!(f, "timed out waiting for event"),
+            Self::Closed => write!(f, "websocket closed while waiting for event"),
+        }
+    }
+}
+
+impl std::error::Error for WaitError {}
+
+/// Wait for the first event `filter` returns `Some` for
+///
+/// We subscribe to the events when this is called, not when the future is first polled,
+/// so no events that happen in between are missed.
+/// If we lag behind we skip the lost events and keep waiting.
+///
+/// # Errors
+/// [`WaitError::Timeout`] if nothing matched within `timeout`,
+/// [`WaitError::Closed`] if the websocket closed before that
+pub fn wait_for_event<T, F>(
+    events: &broadcast::Receiver<GuildedEvent>,
+    mut filter: F,
+    timeout: Duration,
+) -> impl Future<Output = Result<T, WaitError>>
+where
+    F: FnMut(&GuildedEvent) -> Option<T>,
+{
+    let mut receiver = events.resubscribe();
+
+    async move {
+        let wait = async {
+            loop {
+                match receiver.recv().await {
+                    Ok(event) => {
+                        if let Some(value) = filter(&event) {
+                            return Ok(value);
+                        }
+                    }
+                    Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                        log::warn!("lagged behind while waiting for event, skipped {skipped} events");
+                    }
+                    Err(broadcast::error::RecvError::Closed) => return Err(WaitError::Closed),
+                }
+            }
+        };
+
+        tokio::time::timeout(timeout, wait)
+            .await
+            .unwrap_or(Err(WaitError::Timeout))
+    }
+}
+
+/// Wait for the first new message `predicate` returns `true` for
+///
+/// # Errors
+/// Same as [`wait_for_event`]
+///
+/// # Example
+/// A simple confirm dialog, after the bot asked "are you sure?" about a command only its author can answer
+/// ```no_run
+/// use std::time::Duration;
+/// use tokio::sync::broadcast::Receiver;
+/// use vived_models::Message;
+/// use vived_websocket::events::GuildedEvent;
+/// use vived_websocket::wait::wait_for_message;
+///
+/// async fn confirm(events: &Receiver<GuildedEvent>, command: &Message) -> bool {
+///     let channel = command.channel_id.clone();
+///     let author = command.created_by.clone().into_enum();
+///
+///     let answer = wait_for_message(
+///         events,
+///         move |message| {
+///             message.channel_id == channel
+///                 && message.created_by.clone().into_enum().as_user() == author.as_user()
+///                 && matches!(message.content.as_deref(), Some("yes" | "no"))
+///         },
+///         Duration::from_secs(30),
+///     )
+///     .await;
+///
+///     // No answer in time counts as a no
+///     matches!(answer, Ok(message) if message.content.as_deref() == Some("yes"))
+/// }
+/// ```
+pub fn wait_for_message<F>(
+    events: &broadcast::Receiver<GuildedEvent>,
+    mut predicate: F,
+    timeout: Duration,
+) -> impl Future<Output = Result<vived_models::Message, WaitError>>
+where
+    F: FnMut(&vived_models::Message) -> bool,
+{
+    wait_for_event(
+        events,
+        move |event| match *event {
+            GuildedEvent::ChatMessageCreated { ref message, .. } if predicate(message) => {
+                Some(vived_models::Message::clone(message))
+            }
+            _ => None,
+        },
+        timeout,
+    )
+}
@@ -0,0 +1,176 @@
+//! A dispatcher fed straight from the connection, against a local websocket server
+
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::protocol::{frame::coding::CloseCode, CloseFrame};
+use tokio_tungstenite::tungstenite::Message;
+use vived_websocket::dispatcher::{Dispatcher, OverflowPolicy, Subscription};
+use vived_websocket::events::GuildedEvent;
+use vived_websocket::{WebSocketClient, WebsocketConfig};
+
+/// Longest any step of a test may take
+const TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A role deleted frame for the role with id `id`
+fn event(id: usize) -> Message {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/roles/role_deleted.json"
+    );
+    let mut frame: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    frame["s"] = format!("event-{id}").into();
+    frame["d"]["role"]["id"] = id.into();
+    Message::Text(frame.to_string())
+}
+
+/// Id of the role in an event made by [`event`]
+fn role_id(event: GuildedEvent) -> usize {
+    match event {
+        GuildedEvent::RoleDeleted { role, .. } => role.id.0,
+        event => panic!("unexpected event {event:?}"),
+    }
+}
+
+/// Receive every event until the subscription is closed
+async fn drain(mut subscription: Subscription, delay: Duration) -> Vec<usize> {
+    let mut ids = Vec::new();
+    while let Some(event) = tokio::time::timeout(TIMEOUT, subscription.recv())
+        .await
+        .unwrap()
+    {
+        ids.push(role_id(event));
+        tokio::time::sleep(delay).await;
+    }
+    ids
+}
+
+/// Connect a client feeding `dispatcher`, send `count` events, then reject the token so the client stops
+async fn run_server(dispatcher: &Dispatcher, count: usize) -> WebSocketClient {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    // the broadcast is far smaller than the burst, only the dispatcher inbox can keep up
+    let config = WebsocketConfig::default()
+        .endpoint(format!("ws://{}", listener.local_addr().unwrap()))
+        .event_capacity(1)
+        .dispatcher(dispatcher, 1);
+
+    let server = async {
+        let (stream, _) = listener.accept().await.unwrap();
+        let mut socket = tokio_tungstenite::accept_async(stream).await.unwrap();
+        for id in 0..count {
+            socket.send(event(id)).await.unwrap();
+        }
+        let frame = CloseFrame {
+            code: CloseCode::from(4001),
+            reason: "bye".into(),
+        };
+        socket.send(Message::Close(Some(frame))).await.unwrap();
+        while let Some(Ok(_)) = socket.next().await {}
+    };
+    let (client, ()) = tokio::join!(WebSocketClient::connect("token", config), server);
+    client.unwrap()
+}
+
+#[tokio::test]
+async fn a_blocking_slow_consumer_gets_every_event() {
+    let dispatcher = Dispatcher::new();
+    let slow = dispatcher.subscribe(1, OverflowPolicy::Block);
+    let fast = dispatcher.subscribe(1, OverflowPolicy::DropNewest);
+
+    let count = 50;
+    let slow = tokio::spawn(drain(slow, Duration::from_millis(5)));
+    let fast = tokio::spawn(drain(fast, Duration::ZERO));
+    let client = run_server(&dispatcher, count).await;
+
+    assert_eq!(slow.await.unwrap(), (0..count).collect::<Vec<_>>());
+    // the other subscriber drops whatever doesn't fit, but what it gets is in order
+    let fast = fast.await.unwrap();
+    assert!(fast.windows(2).all(|pair| pair[0] < pair[1]), "{fast:?}");
+    assert_eq!(client.event_count(), count as u64);
+}
+
+#[tokio::test]
+async fn a_dropping_slow_consumer_does_not_hold_up_the_others() {
+    let dispatcher = Dispatcher::new();
+    let slow = dispatcher.subscribe(2, OverflowPolicy::DropOldest);
+    let fast = dispatcher.subscribe(100, OverflowPolicy::Block);
+
+    let count = 50;
+    let slow = tokio::spawn(drain(slow, Duration::from_millis(50)));
+    let fast = tokio::spawn(drain(fast, Duration::ZERO));
+    run_server(&dispatcher, count).await;
+
+    assert_eq!(fast.await.unwrap(), (0..count).collect::<Vec<_>>());
+    let slow = slow.await.unwrap();
+    assert!(slow.len() < count, "the slow subscriber kept up: {slow:?}");
+    // the newest events are kept
+    assert_eq!(slow.last(), Some(&(count - 1)));
+}
+
+#[tokio::test]
+async fn subscriptions_close_when_the_connection_stops() {
+    let dispatcher = Dispatcher::new();
+    let subscription = dispatcher.subscribe(10, OverflowPolicy::Block);
+    let client = run_server(&dispatcher, 3).await;
+
+    assert_eq!(drain(subscription, Duration::ZERO).await, [0, 1, 2]);
+    tokio::time::timeout(TIMEOUT, client.stopped())
+        .await
+        .unwrap();
+
+    let late = dispatcher.subscribe(10, OverflowPolicy::Block);
+    assert!(drain(late, Duration::ZERO).await.is_empty());
+}
+
+#[tokio::test]
+async fn a_blocked_dispatcher_does_not_miss_the_pong_deadline() {
+    let dispatcher = Dispatcher::new();
+    let mut slow = dispatcher.subscribe(1, OverflowPolicy::Block);
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let config = WebsocketConfig::default()
+        .endpoint(format!("ws://{}", listener.local_addr().unwrap()))
+        .ping_interval(Some(Duration::from_secs(1)))
+        .dispatcher(&dispatcher, 1);
+    let server = async {
+        let (stream, _) = listener.accept().await.unwrap();
+        tokio_tungstenite::accept_async(stream).await.unwrap()
+    };
+    let (client, socket) = tokio::join!(WebSocketClient::connect("token", config), server);
+    let client = client.unwrap();
+    let mut status = client.watch_status();
+
+    // the server keeps reading, so every ping is answered once the client sends it
+    let (mut write, mut read) = socket.split();
+    for id in 0..5 {
+        write.send(event(id)).await.unwrap();
+    }
+    let reader = tokio::spawn(async move { while let Some(Ok(_)) = read.next().await {} });
+
+    // blocked for well over the two ping intervals a pong may take
+    tokio::time::sleep(Duration::from_millis(3500)).await;
+    let mut ids = Vec::new();
+    for _ in 0..5 {
+        let event = tokio::time::timeout(TIMEOUT, slow.recv()).await.unwrap();
+        ids.push(role_id(event.unwrap()));
+    }
+    assert_eq!(ids, [0, 1, 2, 3, 4]);
+
+    // give a wrongly missed deadline the chance to show
+    tokio::time::sleep(Duration::from_millis(1500)).await;
+    assert!(
+        !status.has_changed().unwrap(),
+        "{:?}",
+        *status.borrow_and_update()
+    );
+    assert!(client.is_connected());
+    assert!(
+        tokio::time::timeout(Duration::from_millis(200), listener.accept())
+            .await
+            .is_err(),
+        "the client reconnected"
+    );
+    reader.abort();
+}
@@ -0,0 +1,189 @@
+//! Every `GuildedEvent` variant mapped through the common-field helpers
+
+use serde_json::json;
+use vived_websocket::events::{EventKind, GuildedEvent};
+
+const SERVER: &str = "wlVr3Ggl";
+const CHANNEL: &str = "00000000-0000-0000-0000-000000000001";
+
+fn event(kind: &str, data: serde_json::Value) -> GuildedEvent {
+    serde_json::from_value(json!({"t": kind, "d": data})).unwrap()
+}
+
+fn message() -> serde_json::Value {
+    json!({
+        "id": "00000000-0000-0000-0000-000000000000",
+        "type": "default",
+        "serverId": SERVER,
+        "channelId": CHANNEL,
+        "content": "hello",
+        "createdAt": "2022-10-26T00:00:00.000Z",
+        "createdBy": "Ann6LewA",
+        "updatedAt": "2022-10-26T00:05:00.000Z"
+    })
+}
+
+fn role() -> serde_json::Value {
+    json!({
+        "id": 28086957,
+        "serverId": SERVER,
+        "name": "Moderators",
+        "createdAt": "2022-10-26T00:00:00.000Z",
+        "updatedAt": "2022-10-26T00:05:00.000Z"
+    })
+}
+
+fn availability() -> serde_json::Value {
+    json!({
+        "id": 1,
+        "channelId": CHANNEL,
+        "serverId": SERVER,
+        "userId": "Ann6LewA",
+        "startDate": "2022-10-27T18:00:00.000Z",
+        "endDate": "2022-10-27T20:00:00.000Z",
+        "createdAt": "2022-10-26T00:00:00.000Z"
+    })
+}
+
+/// Every variant, with the kind, channel, message and timestamp it should report
+fn all_events() -> Vec<(GuildedEvent, EventKind, Option<&'static str>, bool, bool)> {
+    let deleted = json!({
+        "id": "00000000-0000-0000-0000-000000000000",
+        "serverId": SERVER,
+        "channelId": CHANNEL,
+        "deletedAt": "2022-10-26T00:10:00.000Z",
+        "isPrivate": false
+    });
+
+    vec![
+        (
+            event(
+                "ChatMessageCreated",
+                json!({"serverId": SERVER, "message": message()}),
+            ),
+            EventKind::ChatMessageCreated,
+            Some(CHANNEL),
+            true,
+            true,
+        ),
+        (
+            event(
+                "ChatMessageUpdated",
+                json!({"serverId": SERVER, "message": message()}),
+            ),
+            EventKind::ChatMessageUpdated,
+            Some(CHANNEL),
+            true,
+            true,
+        ),
+        (
+            event(
+                "ChatMessageDeleted",
+                json!({"serverId": SERVER, "message": deleted}),
+            ),
+            EventKind::ChatMessageDeleted,
+            Some(CHANNEL),
+            false,
+            true,
+        ),
+        (
+            event("RoleCreated", json!({"serverId": SERVER, "role": role()})),
+            EventKind::RoleCreated,
+            None,
+            false,
+            true,
+        ),
+        (
+            event("RoleUpdated", json!({"serverId": SERVER, "role": role()})),
+            EventKind::RoleUpdated,
+            None,
+            false,
+            true,
+        ),
+        (
+            event("RoleDeleted", json!({"serverId": SERVER, "role": role()})),
+            EventKind::RoleDeleted,
+            None,
+            false,
+            false,
+        ),
+        (
+            event(
+                "SchedulingAvailabilityCreated",
+                json!({"serverId": SERVER, "availability": availability()}),
+            ),
+            EventKind::SchedulingAvailabilityCreated,
+            Some(CHANNEL),
+            false,
+            true,
+        ),
+        (
+            event(
+                "SchedulingAvailabilityUpdated",
+                json!({"serverId": SERVER, "availability": availability()}),
+            ),
+            EventKind::SchedulingAvailabilityUpdated,
+            Some(CHANNEL),
+            false,
+            false,
+        ),
+        (
+            event(
+                "SchedulingAvailabilityDeleted",
+                json!({"serverId": SERVER, "availability": availability()}),
+            ),
+            EventKind::SchedulingAvailabilityDeleted,
+            Some(CHANNEL),
+            false,
+            false,
+        ),
+    ]
+}
+
+#[test]
+fn kind_matches_the_event_name() {
+    for (event, kind, ..) in all_events() {
+        assert_eq!(event.kind(), kind);
+        let raw = serde_json::to_value(&event).unwrap();
+        assert_eq!(raw["t"], kind.name());
+    }
+}
+
+#[test]
+fn every_event_has_its_server() {
+    for (event, kind, ..) in all_events() {
+        assert_eq!(
+            event.server_id().map(|id| id.0.as_str()),
+            Some(SERVER),
+            "{kind:?}"
+        );
+    }
+}
+
+#[test]
+fn channel_only_for_channel_events() {
+    for (event, kind, channel, ..) in all_events() {
+        assert_eq!(
+            event.channel_id().map(|id| id.0.as_str()),
+            channel,
+            "{kind:?}"
+        );
+    }
+}
+
+#[test]
+fn message_only_for_full_messages() {
+    for (event, kind, _, has_message, _) in all_events() {
+        assert_eq!(event.message().is_some(), has_message, "{kind:?}");
+        if let Some(message) = event.message() {
+            assert_eq!(message.content.as_deref(), Some("hello"));
+        }
+    }
+}
+
+#[test]
+fn timestamp_only_when_guilded_sends_one() {
+    for (event, kind, _, _, has_timestamp) in all_events() {
+        assert_eq!(event.timestamp().is_some(), has_timestamp, "{kind:?}");
+    }
+}
@@ -0,0 +1,83 @@
+//! Which messages a message cache keeps once it is full
+
+use vived_models::{Message, MessageId};
+use vived_websocket::events::{GuildedEvent, MessageDeleteData};
+use vived_websocket::message_cache::MessageCache;
+
+fn message(id: &str) -> Message {
+    serde_json::from_str(&format!(
+        r#"{{
+            "id": "{id}", "type": "default", "serverId": "s", "channelId": "c",
+            "content": "{id}", "createdAt": "2022-10-01T12:00:00Z", "createdBy": "u"
+        }}"#
+    ))
+    .unwrap()
+}
+
+fn created(id: &str) -> GuildedEvent {
+    GuildedEvent::ChatMessageCreated {
+        server_id: "s".into(),
+        message: message(id).into(),
+    }
+}
+
+fn updated(id: &str) -> GuildedEvent {
+    GuildedEvent::ChatMessageUpdated {
+        server_id: "s".into(),
+        message: message(id).into(),
+    }
+}
+
+fn cached(cache: &MessageCache, id: &str) -> bool {
+    cache.get(&MessageId::from(id)).is_some()
+}
+
+#[test]
+fn the_least_recently_seen_message_is_forgotten() {
+    let mut cache = MessageCache::new(2);
+    cache.observe(&created("a"));
+    cache.observe(&created("b"));
+    // seeing "a" again makes "b" the least recently seen
+    cache.observe(&updated("a"));
+    cache.observe(&created("c"));
+
+    assert!(cached(&cache, "a"));
+    assert!(!cached(&cache, "b"));
+    assert!(cached(&cache, "c"));
+    assert_eq!(cache.len(), 2);
+}
+
+#[test]
+fn deleted_messages_make_room() {
+    let mut cache = MessageCache::new(2);
+    cache.observe(&created("a"));
+    cache.observe(&created("b"));
+    cache.observe(&GuildedEvent::ChatMessageDeleted {
+        server_id: "s".into(),
+        message: MessageDeleteData {
+            id: "a".into(),
+            server_id: "s".into(),
+            channel_id: "c".into(),
+            deleted_at: "2022-10-01T12:00:00Z".parse().unwrap(),
+            is_private: false,
+        },
+    });
+    cache.observe(&created("c"));
+
+    assert!(!cached(&cache, "a"));
+    assert!(cached(&cache, "b"));
+    assert!(cached(&cache, "c"));
+}
+
+#[test]
+fn many_edits_of_one_message_keep_the_others() {
+    let mut cache = MessageCache::new(3);
+    cache.observe(&created("a"));
+    cache.observe(&created("b"));
+    for _ in 0..1000 {
+        cache.observe(&updated("c"));
+    }
+
+    assert_eq!(cache.len(), 3);
+    assert!(cached(&cache, "a") && cached(&cache, "b") && cached(&cache, "c"));
+}
@@ -0,0 +1,69 @@
+//! Pinging the connection, against a local websocket server
+
+use std::time::Duration;
+
+use futures_util::StreamExt;
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::Message;
+use tokio_tungstenite::WebSocketStream;
+use vived_websocket::client::ConnectionStatus;
+use vived_websocket::{WebSocketClient, WebsocketConfig};
+
+/// Connect a client with `ping_interval` set on the field directly, skipping the builder
+async fn connect(
+    ping_interval: Duration,
+) -> (
+    WebSocketClient,
+    WebSocketStream<tokio::net::TcpStream>,
+    TcpListener,
+) {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let mut config =
+        WebsocketConfig::default().endpoint(format!("ws://{}", listener.local_addr().unwrap()));
+    config.ping_interval = Some(ping_interval);
+
+    let server = async {
+        let (stream, _) = listener.accept().await.unwrap();
+        tokio_tungstenite::accept_async(stream).await.unwrap()
+    };
+    let (client, socket) = tokio::join!(WebSocketClient::connect("token", config), server);
+    (client.unwrap(), socket, listener)
+}
+
+#[tokio::test]
+async fn a_zero_interval_pings_every_second() {
+    let (client, mut socket, _listener) = connect(Duration::ZERO).await;
+
+    let message = tokio::time::timeout(Duration::from_secs(3), socket.next())
+        .await
+        .unwrap()
+        .unwrap()
+        .unwrap();
+    assert!(matches!(message, Message::Ping(_)), "{message:?}");
+    assert!(client.is_connected());
+}
+
+#[tokio::test]
+async fn a_huge_interval_never_pings() {
+    let (client, mut socket, _listener) = connect(Duration::MAX).await;
+
+    assert!(
+        tokio::time::timeout(Duration::from_millis(500), socket.next())
+            .await
+            .is_err()
+    );
+    assert!(client.is_connected());
+}
+
+#[tokio::test]
+async fn no_pong_counts_as_a_dropped_connection() {
+    // the server never reads, so it never answers the pings
+    let (client, _socket, _listener) = connect(Duration::from_secs(1)).await;
+    let mut status = client.watch_status();
+
+    tokio::time::timeout(Duration::from_secs(5), status.changed())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(*status.borrow(), ConnectionStatus::Reconnecting(None));
+}
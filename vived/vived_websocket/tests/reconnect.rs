@@ -0,0 +1,293 @@
+//! Reconnecting after the connection drops, against a local websocket server
+
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use futures_util::{SinkExt, StreamExt};
+use tokio::net::TcpListener;
+use tokio_tungstenite::tungstenite::handshake::server::{ErrorResponse, Request, Response};
+use tokio_tungstenite::tungstenite::protocol::{frame::coding::CloseCode, CloseFrame};
+use tokio_tungstenite::tungstenite::{http, Message};
+use tokio_tungstenite::WebSocketStream;
+use vived_websocket::client::{ConnectionStatus, StopReason};
+use vived_websocket::close::GatewayClose;
+use vived_websocket::events::EventKind;
+use vived_websocket::{BackoffConfig, WebSocketClient, WebsocketConfig};
+
+/// Longest any step of a test may take
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// A role event frame with the given sequence id
+fn event(sequence: &str) -> Message {
+    let path = concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/tests/fixtures/roles/role_deleted.json"
+    );
+    let mut frame: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(path).unwrap()).unwrap();
+    frame["s"] = sequence.into();
+    Message::Text(frame.to_string())
+}
+
+/// Accept a connection, returning it with the `guilded-last-message-id` header it was made with
+async fn accept(
+    listener: &TcpListener,
+) -> (WebSocketStream<tokio::net::TcpStream>, Option<String>) {
+    let (stream, _) = tokio::time::timeout(TIMEOUT, listener.accept())
+        .await
+        .expect("no connection")
+        .unwrap();
+
+    let last_message_id = Arc::new(Mutex::new(None));
+    let header = Arc::clone(&last_message_id);
+    // The error type is set by tungstenite's handshake callback
+    #[allow(clippy::result_large_err)]
+    let callback =
+        move |request: &Request, response: Response| -> Result<Response, ErrorResponse> {
+            *header.lock().unwrap() = request
+                .headers()
+                .get("guilded-last-message-id")
+                .map(|value| value.to_str().unwrap().to_owned());
+            Ok(response)
+        };
+    let socket = tokio_tungstenite::accept_hdr_async(stream, callback)
+        .await
+        .unwrap();
+
+    let last_message_id = last_message_id.lock().unwrap().clone();
+    (socket, last_message_id)
+}
+
+/// Connect a client to `listener`, returning it with the server side of the connection
+async fn connect(
+    listener: &TcpListener,
+    config: WebsocketConfig,
+) -> (WebSocketClient, WebSocketStream<tokio::net::TcpStream>) {
+    let config = config.endpoint(format!("ws://{}", listener.local_addr().unwrap()));
+    let (client, (socket, _)) =
+        tokio::join!(WebSocketClient::connect("token", config), accept(listener));
+    (client.unwrap(), socket)
+}
+
+fn backoff(initial: Duration) -> BackoffConfig {
+    BackoffConfig::default().initial(initial)
+}
+
+/// Close the connection with `code`, and wait for the client to answer
+async fn close_with(mut socket: WebSocketStream<tokio::net::TcpStream>, code: u16) {
+    let frame = CloseFrame {
+        code: CloseCode::from(code),
+        reason: "bye".into(),
+    };
+    socket.send(Message::Close(Some(frame))).await.unwrap();
+    while let Some(Ok(_)) = socket.next().await {}
+}
+
+/// Accept a connection, but reject the token during the handshake
+async fn reject(listener: &TcpListener) {
+    let (stream, _) = tokio::time::timeout(TIMEOUT, listener.accept())
+        .await
+        .expect("no connection")
+        .unwrap();
+    // The error type is set by tungstenite's handshake callback
+    #[allow(clippy::result_large_err)]
+    let unauthorized = |_: &Request, _: Response| -> Result<Response, ErrorResponse> {
+        Err(http::Response::builder().status(401).body(None).unwrap())
+    };
+    assert!(tokio_tungstenite::accept_hdr_async(stream, unauthorized)
+        .await
+        .is_err());
+}
+
+/// A backoff so long that any reconnect during a test was immediate
+fn an_hour() -> BackoffConfig {
+    backoff(Duration::from_secs(3600))
+}
+
+#[tokio::test]
+async fn dropped_connections_resume_after_the_last_event() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let config = WebsocketConfig::default().reconnect_backoff(backoff(Duration::from_millis(10)));
+    let (client, mut socket) = connect(&listener, config).await;
+    let mut events = client.subscribe();
+    let mut status = client.watch_status();
+
+    socket.send(event("first")).await.unwrap();
+    let received = tokio::time::timeout(TIMEOUT, events.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(received.kind(), EventKind::RoleDeleted);
+
+    // no close frame, the connection just goes away
+    drop(socket);
+    status.changed().await.unwrap();
+    assert_eq!(*status.borrow(), ConnectionStatus::Reconnecting(None));
+
+    let (mut socket, last_message_id) = accept(&listener).await;
+    assert_eq!(last_message_id.as_deref(), Some("first"));
+    tokio::time::timeout(TIMEOUT, client.wait_until_connected())
+        .await
+        .unwrap();
+
+    // the receiver from before the reconnect keeps working
+    socket.send(event("second")).await.unwrap();
+    let received = tokio::time::timeout(TIMEOUT, events.recv())
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(received.kind(), EventKind::RoleDeleted);
+    assert_eq!(client.event_count(), 2);
+}
+
+#[tokio::test]
+async fn reconnects_wait_for_the_backoff() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let delay = Duration::from_millis(300);
+    let (_client, socket) = connect(
+        &listener,
+        WebsocketConfig::default().reconnect_backoff(backoff(delay)),
+    )
+    .await;
+
+    let dropped = Instant::now();
+    drop(socket);
+    accept(&listener).await;
+    assert!(
+        dropped.elapsed() >= delay,
+        "reconnected after {:?}",
+        dropped.elapsed()
+    );
+}
+
+#[tokio::test]
+async fn gives_up_after_the_max_attempts() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let config = WebsocketConfig::default()
+        .reconnect_backoff(backoff(Duration::from_millis(10)))
+        .max_reconnect_attempts(Some(3));
+    let (client, socket) = connect(&listener, config).await;
+
+    // nothing is listening anymore, so every attempt is refused
+    drop(listener);
+    drop(socket);
+
+    let reason = tokio::time::timeout(TIMEOUT, client.stopped())
+        .await
+        .unwrap();
+    assert_eq!(reason, StopReason::ReconnectAttemptsExhausted);
+    assert!(!client.is_connected());
+}
+
+#[tokio::test]
+async fn zero_attempts_never_reconnect() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let config = WebsocketConfig::default()
+        .reconnect_backoff(backoff(Duration::ZERO))
+        .max_reconnect_attempts(Some(0));
+    let (client, socket) = connect(&listener, config).await;
+
+    drop(socket);
+    let reason = tokio::time::timeout(TIMEOUT, client.stopped())
+        .await
+        .unwrap();
+    assert_eq!(reason, StopReason::ReconnectAttemptsExhausted);
+    assert!(
+        tokio::time::timeout(Duration::from_millis(200), listener.accept())
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn stops_once_no_one_listens() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let config = WebsocketConfig::default().reconnect_backoff(backoff(Duration::ZERO));
+    let (client, socket) = connect(&listener, config).await;
+    let mut status = client.watch_status();
+
+    drop(client);
+    drop(socket);
+
+    // the status is kept after the connection loop is gone
+    let _ = tokio::time::timeout(TIMEOUT, status.changed())
+        .await
+        .unwrap();
+    assert_eq!(
+        *status.borrow(),
+        ConnectionStatus::Stopped(StopReason::NoReceivers)
+    );
+    assert!(
+        tokio::time::timeout(Duration::from_millis(200), listener.accept())
+            .await
+            .is_err()
+    );
+}
+
+#[tokio::test]
+async fn closes_that_may_pass_reconnect_with_backoff() {
+    for code in [1011, 1013, 1008] {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let config = WebsocketConfig::default().reconnect_backoff(an_hour());
+        let (client, socket) = connect(&listener, config).await;
+        let mut status = client.watch_status();
+
+        close_with(socket, code).await;
+        status.changed().await.unwrap();
+        assert_eq!(
+            *status.borrow(),
+            ConnectionStatus::Reconnecting(Some(GatewayClose::new(code, "bye"))),
+            "{code}"
+        );
+        assert!(
+            tokio::time::timeout(Duration::from_millis(200), listener.accept())
+                .await
+                .is_err(),
+            "{code} reconnected without waiting"
+        );
+    }
+}
+
+#[tokio::test]
+async fn restarts_reconnect_right_away() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let config = WebsocketConfig::default().reconnect_backoff(an_hour());
+    let (client, socket) = connect(&listener, config).await;
+
+    close_with(socket, 1012).await;
+    let (_socket, _) = accept(&listener).await;
+    tokio::time::timeout(TIMEOUT, client.wait_until_connected())
+        .await
+        .unwrap();
+}
+
+#[tokio::test]
+async fn a_rejected_token_stops_the_client() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let config = WebsocketConfig::default().reconnect_backoff(backoff(Duration::ZERO));
+    let (client, socket) = connect(&listener, config).await;
+
+    close_with(socket, 4001).await;
+    let reason = tokio::time::timeout(TIMEOUT, client.stopped())
+        .await
+        .unwrap();
+    assert_eq!(
+        reason,
+        StopReason::AuthenticationFailed(Some(GatewayClose::new(4001, "bye")))
+    );
+    assert!(!client.is_connected());
+}
+
+#[tokio::test]
+async fn a_token_rejected_while_reconnecting_stops_the_client() {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let config = WebsocketConfig::default().reconnect_backoff(an_hour());
+    let (client, socket) = connect(&listener, config).await;
+
+    close_with(socket, 1001).await;
+    reject(&listener).await;
+    let reason = tokio::time::timeout(TIMEOUT, client.stopped())
+        .await
+        .unwrap();
+    assert_eq!(reason, StopReason::AuthenticationFailed(None));
+}
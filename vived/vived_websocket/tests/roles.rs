@@ -0,0 +1,66 @@
+//! Role gateway events, from fixtures in `tests/fixtures/roles`
+//!
+//! The fixtures are reconstructed from the payloads in guilded's api documentation, not captured from the gateway.
+
+use vived_models::{Color, RoleId, ServerId};
+use vived_websocket::events::{EventKind, GuildedEvent};
+
+fn fixture(name: &str) -> GuildedEvent {
+    let path = format!("{}/tests/fixtures/roles/{name}.json", env!("CARGO_MANIFEST_DIR"));
+    let raw = std::fs::read_to_string(&path).unwrap();
+    serde_json::from_str(&raw).unwrap_or_else(|error| panic!("{path}: {error}"))
+}
+
+#[test]
+fn role_created() {
+    let event = fixture("role_created");
+    assert_eq!(event.kind(), EventKind::RoleCreated);
+    assert_eq!(event.server_id(), Some(&ServerId::from("wlVr3Ggl")));
+
+    let GuildedEvent::RoleCreated { ref role, .. } = event else {
+        panic!("wrong event {event:?}")
+    };
+    assert_eq!(role.id, RoleId(28086957));
+    assert_eq!(role.name, "Moderators");
+    assert!(role.is_mentionable);
+    assert_eq!(role.colors, [Color::GUILDED_GOLD]);
+    assert_eq!(role.updated_at, None);
+}
+
+#[test]
+fn role_updated() {
+    let event = fixture("role_updated");
+    assert_eq!(event.kind(), EventKind::RoleUpdated);
+
+    let GuildedEvent::RoleUpdated { ref role, .. } = event else {
+        panic!("wrong event {event:?}")
+    };
+    assert_eq!(role.name, "Senior Moderators");
+    assert_eq!(role.permissions.len(), 3);
+    assert_eq!(role.colors.len(), 2);
+    assert!(role.icon.is_some());
+    assert!(role.updated_at.is_some());
+}
+
+#[test]
+fn role_deleted_only_keeps_the_id() {
+    let event = fixture("role_deleted");
+    assert_eq!(event.kind(), EventKind::RoleDeleted);
+    assert_eq!(event.server_id(), Some(&ServerId::from("wlVr3Ggl")));
+
+    let GuildedEvent::RoleDeleted { role, .. } = event else {
+        panic!("wrong event {event:?}")
+    };
+    assert_eq!(role.id, RoleId(28086957));
+}
+
+#[test]
+fn role_events_round_trip() {
+    for name in ["role_created", "role_updated", "role_deleted"] {
+        let event = fixture(name);
+        let again: GuildedEvent =
+            serde_json::from_value(serde_json::to_value(&event).unwrap()).unwrap();
+        assert_eq!(again.kind(), event.kind());
+        assert_eq!(again.server_id(), event.server_id());
+    }
+}